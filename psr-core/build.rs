@@ -0,0 +1,15 @@
+// With the ebpf feature, compile psr-ebpf for the BPF target so src/ebpf.rs
+// can embed the programs
+fn main() {
+    #[cfg(feature = "ebpf")]
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("linux") {
+        let package = aya_build::Package {
+            name: "psr-ebpf",
+            root_dir: concat!(env!("CARGO_MANIFEST_DIR"), "/../psr-ebpf"),
+            ..Default::default()
+        };
+        if let Err(e) = aya_build::build_ebpf([package], aya_build::Toolchain::Nightly) {
+            panic!("Failed to build the eBPF programs: {:#}", e);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::process::Command;
+
+// Cumulative (received, sent) network bytes per PID, attributed from the byte
+// counters of each process's open sockets. Returns None when no attribution
+// tool is available, so callers can tell "no traffic" from "unknown".
+//
+// Only sockets that are currently open are counted, which makes this an
+// estimate: traffic on connections that closed between samples is lost.
+#[cfg(target_os = "linux")]
+pub fn read_process_net_totals() -> Option<HashMap<u32, (u64, u64)>> {
+    // `ss -tinp` reports tcp_info (bytes_received/bytes_sent) with the owning PIDs
+    let output = Command::new("ss").args(["-tinpH"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut totals: HashMap<u32, (u64, u64)> = HashMap::new();
+    let mut current_pid = None;
+
+    for line in stdout.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            // Socket line; the detail line that follows belongs to this PID
+            current_pid = parse_ss_pid(line);
+            continue;
+        }
+
+        let Some(pid) = current_pid.take() else {
+            continue;
+        };
+
+        let received = parse_ss_field(line, "bytes_received:").unwrap_or(0);
+        let sent = parse_ss_field(line, "bytes_sent:").unwrap_or(0);
+        let entry = totals.entry(pid).or_default();
+        entry.0 += received;
+        entry.1 += sent;
+    }
+
+    Some(totals)
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn parse_ss_pid(line: &str) -> Option<u32> {
+    let start = line.find("pid=")? + "pid=".len();
+    let digits: String = line[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+#[cfg(target_os = "linux")]
+fn parse_ss_field(line: &str, key: &str) -> Option<u64> {
+    line.split_whitespace()
+        .find_map(|field| field.strip_prefix(key))
+        .and_then(|value| value.parse().ok())
+}
+
+// nettop reports per-process byte counters directly
+#[cfg(target_os = "macos")]
+pub fn read_process_net_totals() -> Option<HashMap<u32, (u64, u64)>> {
+    let output = Command::new("nettop")
+        .args(["-P", "-L", "1", "-x", "-J", "bytes_in,bytes_out"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut totals = HashMap::new();
+
+    // Rows look like "name.1234,bytes_in,bytes_out," after a header line
+    for line in stdout.lines().skip(1) {
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() < 3 {
+            continue;
+        }
+
+        let Some(pid) = fields[0]
+            .rsplit('.')
+            .next()
+            .and_then(|pid| pid.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        let received = fields[1].trim().parse().unwrap_or(0);
+        let sent = fields[2].trim().parse().unwrap_or(0);
+        totals.insert(pid, (received, sent));
+    }
+
+    Some(totals)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn read_process_net_totals() -> Option<HashMap<u32, (u64, u64)>> {
+    None
+}
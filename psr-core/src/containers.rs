@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+// Container detection from a process's cgroup path. Docker, containerd, CRI-O
+// and podman put the 64-character container ID in the path; LXC uses the
+// container name.
+
+#[cfg(target_os = "linux")]
+pub fn container_id(pid: u32) -> Option<String> {
+    let cgroups = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    cgroups.lines().find_map(|line| {
+        // hierarchy-ID:controllers:path
+        let path = line.splitn(3, ':').nth(2)?;
+        id_from_cgroup_path(path)
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn container_id(_pid: u32) -> Option<String> {
+    None
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn id_from_cgroup_path(path: &str) -> Option<String> {
+    let segments: Vec<&str> = path.split('/').collect();
+    for (index, segment) in segments.iter().enumerate().rev() {
+        if let Some(name) = segment.strip_prefix("lxc.payload.") {
+            return Some(name.to_string());
+        }
+        if index > 0 && segments[index - 1] == "lxc" {
+            return Some(segment.to_string());
+        }
+
+        let id = segment.strip_suffix(".scope").unwrap_or(segment);
+        let id = ["docker-", "cri-containerd-", "crio-", "libpod-"]
+            .iter()
+            .find_map(|prefix| id.strip_prefix(prefix))
+            .unwrap_or(id);
+        if id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Some(id.to_string());
+        }
+    }
+    None
+}
+
+// Container names by ID, looked up from the Docker API when an unknown ID
+// shows up (at most every few seconds). The lookup runs on the blocking pool,
+// so a slow Docker daemon doesn't hold up the collector; until it answers,
+// and for IDs without a name, IDs are shortened the way `docker ps` shows
+// them.
+pub struct ContainerNames {
+    names: HashMap<String, String>,
+    fetched_at: Option<Instant>,
+    // The lookup under way, if any
+    pending: Option<Receiver<Option<HashMap<String, String>>>>,
+}
+
+impl ContainerNames {
+    pub fn new() -> Self {
+        Self {
+            names: HashMap::new(),
+            fetched_at: None,
+            pending: None,
+        }
+    }
+
+    pub fn name(&mut self, id: &str) -> String {
+        if id.len() != 64 {
+            // Already a name (LXC)
+            return id.to_string();
+        }
+
+        if let Some(pending) = &self.pending {
+            match pending.try_recv() {
+                Ok(names) => {
+                    if let Some(names) = names {
+                        self.names = names;
+                    }
+                    self.pending = None;
+                }
+                Err(TryRecvError::Disconnected) => self.pending = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+
+        if !self.names.contains_key(id)
+            && self.pending.is_none()
+            && self
+                .fetched_at
+                .is_none_or(|at| at.elapsed() > Duration::from_secs(5))
+        {
+            self.fetched_at = Some(Instant::now());
+            let (tx, rx) = mpsc::channel();
+            tokio::task::spawn_blocking(move || {
+                let _ = tx.send(docker_container_names());
+            });
+            self.pending = Some(rx);
+        }
+
+        self.names
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.get(..12).unwrap_or(id).to_string())
+    }
+}
+
+#[cfg(unix)]
+fn docker_container_names() -> Option<HashMap<String, String>> {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect("/var/run/docker.sock").ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(1))).ok()?;
+    // HTTP/1.0 so the reply isn't chunked and ends when the socket closes
+    stream
+        .write_all(b"GET /containers/json HTTP/1.0\r\nHost: docker\r\n\r\n")
+        .ok()?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    let (_, body) = response.split_once("\r\n\r\n")?;
+
+    let containers: Vec<serde_json::Value> = serde_json::from_str(body).ok()?;
+    let names = containers
+        .iter()
+        .filter_map(|container| {
+            let id = container["Id"].as_str()?;
+            let name = container["Names"][0].as_str()?;
+            Some((id.to_string(), name.trim_start_matches('/').to_string()))
+        })
+        .collect();
+    Some(names)
+}
+
+#[cfg(not(unix))]
+fn docker_container_names() -> Option<HashMap<String, String>> {
+    None
+}
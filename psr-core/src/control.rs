@@ -0,0 +1,509 @@
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::Duration;
+
+use crate::processes::SchedPolicy;
+
+// Actions psr takes on other processes
+
+/// Current nice value of a process. getpriority's -1 error return is
+/// indistinguishable from a real nice of -1, but only for processes that exited
+/// since the listing, which drop out on the next refresh anyway.
+#[cfg(unix)]
+pub fn get_nice(pid: u32) -> i32 {
+    unsafe { libc::getpriority(libc::PRIO_PROCESS as _, pid as libc::id_t) }
+}
+
+#[cfg(not(unix))]
+pub fn get_nice(_pid: u32) -> i32 {
+    0
+}
+
+/// Scheduling policy and realtime priority of a process
+#[cfg(target_os = "linux")]
+pub fn get_scheduling(pid: u32) -> Option<(SchedPolicy, i32)> {
+    let policy = match unsafe { libc::sched_getscheduler(pid as libc::pid_t) } {
+        libc::SCHED_OTHER => SchedPolicy::Other,
+        libc::SCHED_BATCH => SchedPolicy::Batch,
+        libc::SCHED_IDLE => SchedPolicy::Idle,
+        libc::SCHED_FIFO => SchedPolicy::Fifo,
+        libc::SCHED_RR => SchedPolicy::RoundRobin,
+        libc::SCHED_DEADLINE => SchedPolicy::Deadline,
+        // -1 for a process that exited, or a policy newer than this list
+        _ => return None,
+    };
+    let mut param = libc::sched_param { sched_priority: 0 };
+    if unsafe { libc::sched_getparam(pid as libc::pid_t, &mut param) } == -1 {
+        return None;
+    }
+    Some((policy, param.sched_priority))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get_scheduling(_pid: u32) -> Option<(SchedPolicy, i32)> {
+    None
+}
+
+#[cfg(unix)]
+pub fn set_nice(pid: u32, nice: i32) -> io::Result<()> {
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS as _, pid as libc::id_t, nice) };
+    if result == -1 {
+        return Err(last_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn set_nice(_pid: u32, _nice: i32) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "changing priority is not supported on this platform",
+    ))
+}
+
+/// Signals that can be sent to a process, by name
+#[cfg(unix)]
+pub const SIGNALS: &[(&str, i32)] = &[
+    ("TERM", libc::SIGTERM),
+    ("KILL", libc::SIGKILL),
+    ("HUP", libc::SIGHUP),
+    ("INT", libc::SIGINT),
+    ("QUIT", libc::SIGQUIT),
+    ("STOP", libc::SIGSTOP),
+    ("CONT", libc::SIGCONT),
+    ("USR1", libc::SIGUSR1),
+    ("USR2", libc::SIGUSR2),
+    ("CHLD", libc::SIGCHLD),
+];
+
+#[cfg(not(unix))]
+pub const SIGNALS: &[(&str, i32)] = &[];
+
+#[cfg(unix)]
+pub fn send_signal(pid: u32, signal: i32) -> io::Result<()> {
+    // kill(2) takes 0 and negative PIDs as process groups, and -1 as every
+    // process we're allowed to signal
+    if pid == 0 || pid > i32::MAX as u32 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid PID {}", pid),
+        ));
+    }
+    if unsafe { libc::kill(pid as libc::pid_t, signal) } == -1 {
+        return Err(last_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn send_signal(_pid: u32, _signal: i32) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "signals are not supported on this platform",
+    ))
+}
+
+/// Force-kill a process
+#[cfg(unix)]
+pub fn kill(pid: u32) -> io::Result<()> {
+    send_signal(pid, libc::SIGKILL)
+}
+
+/// Force-kill a process with the platform's own command
+#[cfg(not(unix))]
+pub fn kill(pid: u32) -> io::Result<()> {
+    let status = std::process::Command::new("taskkill")
+        .args(["/F", "/PID", &pid.to_string()])
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("taskkill exited with {}", status)));
+    }
+    Ok(())
+}
+
+/// Start a shell command with no terminal I/O, since the UI owns the terminal.
+/// A detached command gets a process group of its own, out of reach of
+/// signals meant for psr's. A simple command replaces the shell, so the
+/// returned child is the command itself.
+#[cfg(unix)]
+pub fn spawn(command: &str, detach: bool) -> io::Result<Child> {
+    use std::os::unix::process::CommandExt;
+
+    let mut shell = Command::new("sh");
+    if command.contains([';', '&', '|', '(', ')', '\n']) {
+        shell.arg("-c").arg(command);
+    } else {
+        shell.arg("-c").arg(format!("exec {}", command));
+    }
+    if detach {
+        shell.process_group(0);
+    }
+    quiet(&mut shell).spawn()
+}
+
+/// Start a command through cmd.exe with no terminal I/O
+#[cfg(not(unix))]
+pub fn spawn(command: &str, _detach: bool) -> io::Result<Child> {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    quiet(&mut shell).spawn()
+}
+
+/// How a process was started, for starting it again: its executable, argv
+/// (including argv[0]), environment, working directory and owner
+pub struct Launch {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub env: Vec<(OsString, OsString)>,
+    pub cwd: PathBuf,
+    pub uid: u32,
+    pub gid: u32,
+}
+
+/// Read how a running process was started from /proc
+#[cfg(target_os = "linux")]
+pub fn launch_of(pid: u32) -> io::Result<Launch> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let proc = PathBuf::from(format!("/proc/{}", pid));
+    let cmdline = std::fs::read(proc.join("cmdline"))?;
+    if cmdline.is_empty() {
+        // Kernel threads have no command line
+        return Err(io::Error::other("no command line to start again"));
+    }
+    // Each argument ends in a NUL. Empty arguments are kept: `cmd -o ''`
+    // isn't `cmd -o`.
+    let args: Vec<String> = cmdline
+        .strip_suffix(&[0])
+        .unwrap_or(&cmdline)
+        .split(|&b| b == 0)
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect();
+    // NAME=value entries, also NUL-terminated
+    let environ = std::fs::read(proc.join("environ"))?;
+    let env = environ
+        .split(|&b| b == 0)
+        .filter_map(|entry| {
+            let split = entry.iter().position(|&b| b == b'=')?;
+            Some((
+                OsStr::from_bytes(&entry[..split]).to_os_string(),
+                OsStr::from_bytes(&entry[split + 1..]).to_os_string(),
+            ))
+        })
+        .collect();
+    // The executable as it's linked now, unless it was replaced since (by an
+    // upgrade, say), in which case argv[0] finds the new one
+    let program = std::fs::read_link(proc.join("exe"))
+        .ok()
+        .filter(|exe| !exe.to_string_lossy().ends_with(" (deleted)"))
+        .unwrap_or_else(|| PathBuf::from(&args[0]));
+    let cwd = std::fs::read_link(proc.join("cwd"))?;
+    // "Uid:" and "Gid:" lines list the real ID first
+    let status = std::fs::read_to_string(proc.join("status"))?;
+    let id = |field: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(field))
+            .and_then(|ids| ids.split_whitespace().next()?.parse().ok())
+            .ok_or_else(|| io::Error::other(format!("no {} in /proc/{}/status", field, pid)))
+    };
+    Ok(Launch {
+        program,
+        args,
+        env,
+        cwd,
+        uid: id("Uid:")?,
+        gid: id("Gid:")?,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn launch_of(_pid: u32) -> io::Result<Launch> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "restarting is not supported on this platform",
+    ))
+}
+
+/// Stop a process and start it again as `launch` describes, in a process
+/// group of its own. It's asked to exit with SIGTERM first and killed if it
+/// hasn't within `grace`, so this blocks for up to that long and a little
+/// more: run it off the UI thread. Running as root, the new process gets the
+/// old one's user and group; otherwise it runs as psr's user, which can only
+/// have stopped its own processes anyway.
+#[cfg(target_os = "linux")]
+pub fn restart(pid: u32, launch: &Launch, grace: Duration) -> io::Result<Child> {
+    use std::os::unix::process::CommandExt;
+
+    send_signal(pid, libc::SIGTERM)?;
+    if !wait_for_exit(pid, grace) {
+        match kill(pid) {
+            // It made it out between the last look and the kill
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            result => result?,
+        }
+        wait_for_exit(pid, Duration::from_secs(2));
+    }
+
+    let mut command = Command::new(&launch.program);
+    command
+        .arg0(&launch.args[0])
+        .args(&launch.args[1..])
+        .env_clear()
+        .envs(launch.env.iter().map(|(name, value)| (name, value)))
+        .current_dir(&launch.cwd)
+        .process_group(0);
+    if unsafe { libc::geteuid() } == 0 {
+        command.uid(launch.uid).gid(launch.gid);
+    }
+    quiet(&mut command).spawn()
+}
+
+// Wait up to `timeout` for a process to let go of its ports and files. It may
+// linger as a zombie until its parent gets to it, which is dead enough.
+#[cfg(target_os = "linux")]
+fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    use std::time::Instant;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let alive = std::fs::read_to_string(format!("/proc/{}/stat", pid))
+            .ok()
+            .and_then(|stat| Some(stat.rsplit_once(')')?.1.trim_start().starts_with('Z')))
+            .is_some_and(|zombie| !zombie);
+        if !alive {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn restart(_pid: u32, _launch: &Launch, _grace: Duration) -> io::Result<Child> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "restarting is not supported on this platform",
+    ))
+}
+
+/// Attach a system call tracer to a process on psr's terminal and wait for it
+/// to exit: strace on Linux, dtruss on macOS
+#[cfg(unix)]
+pub fn trace(pid: u32) -> io::Result<ExitStatus> {
+    let program = if cfg!(target_os = "macos") {
+        "dtruss"
+    } else {
+        "strace"
+    };
+    let mut tracer = Command::new(program);
+    tracer.arg("-p").arg(pid.to_string());
+    foreground(tracer).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} is not installed", program),
+        ),
+        _ => e,
+    })
+}
+
+#[cfg(not(unix))]
+pub fn trace(_pid: u32) -> io::Result<ExitStatus> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "tracing is not supported on this platform",
+    ))
+}
+
+/// The debugger command line used unless the config names another
+pub const DEFAULT_DEBUGGER: &str = if cfg!(target_os = "macos") {
+    "lldb -p $PSR_PID"
+} else {
+    "gdb -p $PSR_PID"
+};
+
+/// Run a debugger command line through the shell on psr's terminal, with the
+/// process to attach to in PSR_PID, and wait for it to exit
+#[cfg(unix)]
+pub fn debug(pid: u32, debugger: &str) -> io::Result<ExitStatus> {
+    let mut shell = Command::new("sh");
+    shell
+        .arg("-c")
+        .arg(debugger)
+        .env("PSR_PID", pid.to_string());
+    foreground(shell)
+}
+
+#[cfg(not(unix))]
+pub fn debug(_pid: u32, _debugger: &str) -> io::Result<ExitStatus> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "attaching a debugger is not supported on this platform",
+    ))
+}
+
+/// Start `perf record` on a process for a number of seconds, writing to
+/// `output`, with `args` (e.g. `-g -F 99`) passed along. Its progress isn't
+/// shown, but its stderr is piped so the reason it failed can be read.
+#[cfg(target_os = "linux")]
+pub fn profile(pid: u32, seconds: u64, args: &str, output: &Path) -> io::Result<Child> {
+    Command::new("perf")
+        .arg("record")
+        .arg("-p")
+        .arg(pid.to_string())
+        .arg("-o")
+        .arg(output)
+        .args(args.split_whitespace())
+        // With -p, the command only sets how long to record for
+        .args(["--", "sleep", &seconds.to_string()])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => {
+                io::Error::new(io::ErrorKind::NotFound, "perf is not installed")
+            }
+            _ => e,
+        })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn profile(_pid: u32, _seconds: u64, _args: &str, _output: &Path) -> io::Result<Child> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "perf is only available on Linux",
+    ))
+}
+
+/// Write a core dump of a running process into `dir` with gdb's gcore, which
+/// stops the process only while it's being written. Returns the core's path.
+#[cfg(unix)]
+pub fn dump_core(pid: u32, dir: &Path) -> io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let prefix = dir.join("core");
+    let result = Command::new("gcore")
+        .arg("-o")
+        .arg(&prefix)
+        .arg(pid.to_string())
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => {
+                io::Error::new(io::ErrorKind::NotFound, "gcore (from gdb) is not installed")
+            }
+            _ => e,
+        })?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        let reason = stderr
+            .lines()
+            .rev()
+            .map(str::trim)
+            .find(|line| !line.is_empty());
+        return Err(io::Error::other(
+            reason.map_or_else(|| result.status.to_string(), str::to_string),
+        ));
+    }
+    Ok(PathBuf::from(format!("{}.{}", prefix.display(), pid)))
+}
+
+#[cfg(not(unix))]
+pub fn dump_core(_pid: u32, _dir: &Path) -> io::Result<PathBuf> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "core dumps are not supported on this platform",
+    ))
+}
+
+/// Abort a process with SIGABRT so the kernel dumps its core, wherever the
+/// system's core_pattern says. Its core size limit is raised to the hard
+/// limit first, as it's often 0 and would make the kernel skip the dump.
+#[cfg(target_os = "linux")]
+pub fn abort(pid: u32) -> io::Result<()> {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    let pid_t = pid as libc::pid_t;
+    if unsafe { libc::prlimit(pid_t, libc::RLIMIT_CORE, std::ptr::null(), &mut limit) } == 0
+        && limit.rlim_cur != limit.rlim_max
+    {
+        limit.rlim_cur = limit.rlim_max;
+        // Only possible for our own processes, or as root; the dump may
+        // still happen without it
+        unsafe { libc::prlimit(pid_t, libc::RLIMIT_CORE, &limit, std::ptr::null_mut()) };
+    }
+    send_signal(pid, libc::SIGABRT)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn abort(pid: u32) -> io::Result<()> {
+    send_signal(pid, libc::SIGABRT)
+}
+
+/// Where the kernel writes core dumps: a file name pattern, or a program
+/// they're piped to when it starts with `|`
+#[cfg(target_os = "linux")]
+pub fn core_pattern() -> Option<String> {
+    let pattern = std::fs::read_to_string("/proc/sys/kernel/core_pattern").ok()?;
+    Some(pattern.trim().to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn core_pattern() -> Option<String> {
+    None
+}
+
+// Run a command on the terminal and wait for it. psr ignores SIGINT and
+// SIGQUIT meanwhile, so Ctrl+C reaches the command without taking psr down
+// with it.
+#[cfg(unix)]
+fn foreground(mut command: Command) -> io::Result<ExitStatus> {
+    use std::os::unix::process::CommandExt;
+
+    // Ignored signals stay ignored across exec, so the command takes them back
+    unsafe {
+        command.pre_exec(|| {
+            libc::signal(libc::SIGINT, libc::SIG_DFL);
+            libc::signal(libc::SIGQUIT, libc::SIG_DFL);
+            Ok(())
+        });
+    }
+
+    let (interrupt, quit) = unsafe {
+        (
+            libc::signal(libc::SIGINT, libc::SIG_IGN),
+            libc::signal(libc::SIGQUIT, libc::SIG_IGN),
+        )
+    };
+    let status = command.status();
+    unsafe {
+        libc::signal(libc::SIGINT, interrupt);
+        libc::signal(libc::SIGQUIT, quit);
+    }
+    status
+}
+
+fn quiet(command: &mut Command) -> &mut Command {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+}
+
+// The last OS error, worded for the status line for the usual failures
+#[cfg(unix)]
+fn last_error() -> io::Error {
+    let error = io::Error::last_os_error();
+    match error.raw_os_error() {
+        Some(libc::EPERM) => io::Error::new(io::ErrorKind::PermissionDenied, "permission denied"),
+        Some(libc::ESRCH) => io::Error::new(io::ErrorKind::NotFound, "no such process"),
+        _ => error,
+    }
+}
@@ -0,0 +1,613 @@
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::gpu::{GpuInfo, GpuVendor};
+use crate::numa::NumaNode;
+use crate::pressure::{Pressure, PressureStall};
+use crate::processes::{
+    CoreInfo, DiskInfo, NetworkInterface, ProcessChange, ProcessDelta, ProcessInfo, ProcessStatus,
+    ProcessUpdate, SchedPolicy, SystemLoad,
+};
+use crate::source::ProcessSource;
+
+// Long-running processes every demo starts with: (name, user, parent, base
+// CPU %, base memory in MB, threads, command line)
+const RESIDENTS: &[(&str, &str, u32, f32, u64, usize, &str)] = &[
+    ("systemd", "root", 0, 0.1, 12, 1, "/sbin/init splash"),
+    (
+        "systemd-journald",
+        "root",
+        1,
+        0.2,
+        48,
+        1,
+        "/lib/systemd/systemd-journald",
+    ),
+    ("sshd", "root", 1, 0.0, 8, 1, "sshd: /usr/sbin/sshd -D"),
+    ("cron", "root", 1, 0.0, 3, 1, "/usr/sbin/cron -f"),
+    (
+        "dockerd",
+        "root",
+        1,
+        1.2,
+        96,
+        24,
+        "/usr/bin/dockerd -H fd://",
+    ),
+    (
+        "nginx",
+        "root",
+        1,
+        0.1,
+        6,
+        1,
+        "nginx: master process /usr/sbin/nginx",
+    ),
+    ("nginx", "www-data", 6, 2.5, 18, 1, "nginx: worker process"),
+    ("nginx", "www-data", 6, 2.0, 18, 1, "nginx: worker process"),
+    (
+        "postgres",
+        "postgres",
+        1,
+        3.0,
+        210,
+        1,
+        "/usr/lib/postgresql/16/bin/postgres -D /var/lib/postgresql/16/main",
+    ),
+    (
+        "redis-server",
+        "redis",
+        1,
+        0.8,
+        64,
+        5,
+        "/usr/bin/redis-server 127.0.0.1:6379",
+    ),
+    ("node", "app", 5, 6.0, 340, 11, "node /srv/app/server.js"),
+    (
+        "python3",
+        "app",
+        5,
+        4.0,
+        180,
+        4,
+        "python3 -m worker --queue default",
+    ),
+    (
+        "java",
+        "app",
+        1,
+        12.0,
+        1450,
+        48,
+        "java -Xmx2g -jar /opt/search/search.jar",
+    ),
+    ("bash", "alice", 3, 0.0, 5, 1, "-bash"),
+    ("vim", "alice", 14, 0.3, 22, 1, "vim src/main.rs"),
+    (
+        "firefox",
+        "alice",
+        1,
+        8.0,
+        620,
+        72,
+        "/usr/lib/firefox/firefox",
+    ),
+    (
+        "Web Content",
+        "alice",
+        16,
+        5.0,
+        280,
+        28,
+        "/usr/lib/firefox/firefox -contentproc",
+    ),
+];
+
+// Short-lived processes that come and go: (name, user, CPU %, memory in MB,
+// command line)
+const WORKERS: &[(&str, &str, f32, u64, &str)] = &[
+    (
+        "rustc",
+        "alice",
+        95.0,
+        420,
+        "rustc --crate-name psr --edition=2021 src/main.rs",
+    ),
+    (
+        "cc1",
+        "alice",
+        80.0,
+        160,
+        "/usr/lib/gcc/x86_64-linux-gnu/13/cc1 -quiet vendor.c",
+    ),
+    (
+        "curl",
+        "app",
+        1.5,
+        9,
+        "curl -s https://api.example.com/health",
+    ),
+    ("pg_dump", "postgres", 35.0, 60, "pg_dump -Fc app"),
+    ("gzip", "root", 60.0, 4, "gzip -9 /var/log/syslog.1"),
+    ("git", "alice", 20.0, 30, "git status"),
+];
+
+const CORES: f32 = 4.0;
+const TOTAL_MEMORY: u64 = 16 * 1024 * 1024 * 1024;
+const TOTAL_SWAP: u64 = 4 * 1024 * 1024 * 1024;
+const TOTAL_GPU_MEMORY: u64 = 12 * 1024 * 1024 * 1024;
+const MB: u64 = 1024 * 1024;
+
+struct Simulated {
+    info: ProcessInfo,
+    base_cpu: f32,
+    base_memory: u64,
+    // Offset into the load curve so processes don't peak together
+    phase: f32,
+    // Tick after which the process exits, for workers
+    exits_at: Option<u64>,
+}
+
+/// Made-up but plausible data: a fixed set of services with CPU and memory
+/// following slow load curves, plus short-lived workers that come and go. The
+/// same seed always produces the same sequence of updates, one sample per
+/// refresh interval, which makes it useful for screenshots and for working on
+/// the UI away from a busy machine.
+pub struct DemoSource {
+    interval: Duration,
+    rng: Rng,
+    tick: u64,
+    next_pid: u32,
+    processes: Vec<Simulated>,
+    // PIDs already sent, which later samples only send changes for
+    listed: HashSet<u32>,
+    network_totals: [(u64, u64); 2],
+    // 1, 5 and 15 minute load averages, decayed towards the busy cores
+    load: [f64; 3],
+    pending: VecDeque<ProcessUpdate>,
+    due: Option<Instant>,
+}
+
+impl DemoSource {
+    pub fn new(interval: Duration, seed: u64) -> Self {
+        let mut source = Self {
+            interval,
+            rng: Rng(seed.max(1)),
+            tick: 0,
+            next_pid: 1,
+            processes: Vec::new(),
+            listed: HashSet::new(),
+            network_totals: [(0, 0); 2],
+            load: [1.0; 3],
+            pending: VecDeque::new(),
+            due: None,
+        };
+        for &(name, user, parent, cpu, memory, threads, cmd) in RESIDENTS {
+            let uptime = Duration::from_secs(86_400 - source.next_pid as u64 * 600);
+            source.spawn(
+                name,
+                user,
+                parent,
+                cpu,
+                memory * MB,
+                Some(threads),
+                cmd,
+                uptime,
+                None,
+            );
+        }
+        source
+    }
+
+    /// Wait for the next update, sleeping until the next sample is due. The
+    /// demo never runs out, so this always returns one.
+    pub async fn recv(&mut self) -> Option<ProcessUpdate> {
+        loop {
+            if let Some(update) = self.try_next() {
+                return Some(update);
+            }
+            if let Some(due) = self.due {
+                tokio::time::sleep_until(due.into()).await;
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn spawn(
+        &mut self,
+        name: &str,
+        user: &str,
+        parent: u32,
+        cpu: f32,
+        memory: u64,
+        threads: Option<usize>,
+        cmd: &str,
+        uptime: Duration,
+        exits_at: Option<u64>,
+    ) {
+        let pid = self.next_pid;
+        // Low PIDs for the residents, then jumps like a real system's churn
+        self.next_pid += if pid < 20 {
+            1
+        } else {
+            1 + self.rng.below(40) as u32
+        };
+        let mut info = ProcessInfo::new(
+            pid,
+            name.to_string(),
+            cpu,
+            memory,
+            ProcessStatus::Sleeping,
+            user.to_string(),
+            uptime,
+            cmd.split_whitespace().map(str::to_string).collect(),
+            threads,
+            (parent > 0).then_some(parent),
+        );
+        info.started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs())
+            .saturating_sub(uptime.as_secs());
+        info.nice = if user == "root" || user == "alice" {
+            0
+        } else {
+            5
+        };
+        info.set_scheduling(Some((SchedPolicy::Other, 0)));
+        info.update_memory_breakdown(memory * 3, Some(demo_memory_breakdown(name, memory)));
+        info.net_rx_rate = Some(0);
+        info.net_tx_rate = Some(0);
+        self.processes.push(Simulated {
+            info,
+            base_cpu: cpu,
+            base_memory: memory,
+            phase: self.rng.unit() * std::f32::consts::TAU,
+            exits_at,
+        });
+    }
+
+    // Advance the simulation by one refresh interval
+    fn step(&mut self) {
+        self.tick += 1;
+        let tick = self.tick;
+        self.processes
+            .retain(|p| p.exits_at.is_none_or(|exits_at| tick < exits_at));
+
+        // Roughly one worker every few refreshes, living a handful of them
+        if self.rng.below(3) == 0 {
+            let (name, user, cpu, memory, cmd) =
+                WORKERS[self.rng.below(WORKERS.len() as u64) as usize];
+            let lifetime = 2 + self.rng.below(8);
+            let parent = if user == "alice" { 14 } else { 1 };
+            self.spawn(
+                name,
+                user,
+                parent,
+                cpu,
+                memory * MB,
+                Some(1),
+                cmd,
+                Duration::ZERO,
+                Some(tick + lifetime),
+            );
+        }
+
+        let seconds = self.interval.as_secs_f32().max(0.1);
+        let t = tick as f32 * seconds;
+        for process in &mut self.processes {
+            let wave = ((t / 60.0 + process.phase).sin() + 1.0) / 2.0;
+            let jitter = self.rng.unit() * 0.3;
+            let cpu = (process.base_cpu * (0.4 + wave + jitter)).min(100.0);
+            // The search service leaks a little, so something climbs on the charts
+            let memory = if process.info.name == "java" {
+                process.base_memory + tick * 256 * 1024
+            } else {
+                (process.base_memory as f32 * (0.9 + wave * 0.2)) as u64
+            };
+            let breakdown = demo_memory_breakdown(&process.info.name, memory);
+            process
+                .info
+                .update_memory_breakdown(process.base_memory * 3, Some(breakdown));
+            process.info.status = if cpu > 10.0 {
+                ProcessStatus::Running
+            } else {
+                ProcessStatus::Sleeping
+            };
+            process.info.start_time += self.interval;
+
+            // Rates are set directly rather than derived from totals over
+            // wall-clock time, which would make the output depend on timing
+            let info = &mut process.info;
+            info.disk_read_rate = (cpu as f64 * 20_000.0 * self.rng.unit() as f64) as u64;
+            info.disk_write_rate = info.disk_read_rate / 2;
+            info.disk_read_total += (info.disk_read_rate as f32 * seconds) as u64;
+            info.disk_write_total += (info.disk_write_rate as f32 * seconds) as u64;
+            info.update_history(cpu, memory);
+            if matches!(info.name.as_str(), "nginx" | "node" | "curl" | "firefox") {
+                let traffic = (cpu as f64 * 50_000.0 * (0.5 + wave as f64)) as u64;
+                info.net_rx_rate = Some(traffic);
+                info.net_tx_rate = Some(traffic / 3);
+                info.net_rx_total += (traffic as f32 * seconds) as u64;
+                info.net_tx_total += (traffic as f32 / 3.0 * seconds) as u64;
+            }
+            // Two sockets, with processes placed on them alternately
+            info.numa_node = Some(info.pid % 2);
+            // Services get systemd's generous limit; node leaks a few
+            // descriptors every refresh until it nears its 1024
+            (info.fds, info.fd_limit) = match info.name.as_str() {
+                "nginx" | "postgres" => (Some(40 + info.pid as u64 % 60), Some(524_288)),
+                "node" => (Some((600 + tick * 3).min(1019)), Some(1024)),
+                _ => (Some(4 + info.pid as u64 % 30), Some(1024)),
+            };
+            // The worker runs inference on the discrete GPU, and the browser
+            // composits on the integrated one
+            info.gpu_memory = match info.name.as_str() {
+                "python3" => Some(((4096.0 + 2048.0 * wave) as u64) * MB),
+                "firefox" => Some(310 * MB),
+                _ => None,
+            };
+        }
+    }
+
+    fn sample(&mut self) -> Vec<ProcessUpdate> {
+        let cpu: f32 = self.processes.iter().map(|p| p.info.cpu_usage).sum::<f32>() / CORES;
+        let used: u64 = self.processes.iter().map(|p| p.info.memory).sum::<u64>() + 2 * 1024 * MB;
+        let swapped: u64 = self.processes.iter().filter_map(|p| p.info.swap).sum();
+
+        let seconds = self.interval.as_secs_f64().max(0.1);
+        let rx: u64 = self
+            .processes
+            .iter()
+            .filter_map(|p| p.info.net_rx_rate)
+            .sum();
+        let tx: u64 = self
+            .processes
+            .iter()
+            .filter_map(|p| p.info.net_tx_rate)
+            .sum();
+        let loopback = 20_000 + self.rng.below(30_000);
+        let rates = [(rx, tx), (loopback, loopback)];
+        let networks = ["eth0", "lo"]
+            .iter()
+            .zip(rates)
+            .zip(&mut self.network_totals)
+            .map(|((name, (rx_rate, tx_rate)), totals)| {
+                totals.0 += (rx_rate as f64 * seconds) as u64;
+                totals.1 += (tx_rate as f64 * seconds) as u64;
+                NetworkInterface {
+                    name: name.to_string(),
+                    rx_rate,
+                    tx_rate,
+                    total_received: totals.0,
+                    total_transmitted: totals.1,
+                }
+            })
+            .collect();
+
+        let read: u64 = self.processes.iter().map(|p| p.info.disk_read_rate).sum();
+        let write: u64 = self.processes.iter().map(|p| p.info.disk_write_rate).sum();
+        let disks = vec![
+            DiskInfo {
+                name: "nvme0n1p2".to_string(),
+                mount_point: "/".to_string(),
+                file_system: "ext4".to_string(),
+                total_space: 512 * 1024 * MB,
+                available_space: (188 * 1024 * MB).saturating_sub(self.tick * 4 * MB),
+                read_rate: read * 3 / 4,
+                write_rate: write * 3 / 4,
+            },
+            DiskInfo {
+                name: "sda1".to_string(),
+                mount_point: "/home".to_string(),
+                file_system: "xfs".to_string(),
+                total_space: 2048 * 1024 * MB,
+                available_space: 1310 * 1024 * MB,
+                read_rate: read / 4,
+                write_rate: write / 4,
+            },
+        ];
+
+        let processes = if self.listed.is_empty() {
+            ProcessUpdate::ProcessList(self.processes.iter().map(|p| p.info.clone()).collect())
+        } else {
+            let mut delta = ProcessDelta::default();
+            for process in &self.processes {
+                if self.listed.remove(&process.info.pid) {
+                    delta.updated.push(ProcessChange::new(&process.info, false));
+                } else {
+                    delta.added.push(process.info.clone());
+                }
+            }
+            // Whatever wasn't seen this time has exited
+            delta.removed = self.listed.drain().collect();
+            delta.removed.sort_unstable();
+            ProcessUpdate::ProcessDelta(delta)
+        };
+        self.listed = self.processes.iter().map(|p| p.info.pid).collect();
+
+        // The kernel's exponentially-damped averages, as if every busy
+        // fraction of a core were one runnable task
+        let busy = (cpu.min(100.0) / 100.0 * CORES) as f64;
+        for (load, minutes) in self.load.iter_mut().zip([1.0, 5.0, 15.0]) {
+            let decay = (-seconds / (minutes * 60.0)).exp();
+            *load = *load * decay + busy * (1.0 - decay);
+        }
+        // Some stalls on the CPU once it's busy, and a little on memory and
+        // disk, which the swapping database keeps waiting
+        let cpu_stall = (cpu.min(100.0) - 40.0).max(0.0) * 0.5;
+        let memory_stall = 0.2 + self.rng.unit() * 0.3;
+        let io_stall = 1.0 + self.rng.unit() * 2.0;
+        let pressure = Pressure {
+            cpu: PressureStall {
+                some: cpu_stall,
+                full: 0.0,
+            },
+            memory: PressureStall {
+                some: memory_stall,
+                full: memory_stall / 3.0,
+            },
+            io: PressureStall {
+                some: io_stall,
+                full: io_stall / 3.0,
+            },
+        };
+
+        // The total spread unevenly over the cores, each clocking up from an
+        // 800 MHz idle floor as it gets busy. The last one runs hot and is
+        // held to 2 GHz, so throttling shows up next to its neighbours.
+        let cores: Vec<CoreInfo> = (0..CORES as usize)
+            .map(|core| {
+                let usage = (cpu * (0.5 + self.rng.unit())).clamp(0.0, 100.0);
+                let ceiling = if core == CORES as usize - 1 {
+                    2000.0
+                } else {
+                    3600.0
+                };
+                CoreInfo {
+                    usage,
+                    frequency: (800.0 + (ceiling - 800.0) * usage / 100.0) as u64,
+                }
+            })
+            .collect();
+
+        // Busy while the worker is, and warming with it
+        let worker_cpu = self
+            .processes
+            .iter()
+            .find(|p| p.info.name == "python3")
+            .map_or(0.0, |p| p.info.cpu_usage);
+        let utilization = (worker_cpu * 12.0).min(100.0);
+        let holders = |integrated: bool| -> Vec<(u32, u64)> {
+            self.processes
+                .iter()
+                .filter(|p| (p.info.name == "firefox") == integrated)
+                .filter_map(|p| Some((p.info.pid, p.info.gpu_memory?)))
+                .collect()
+        };
+        let inference = holders(false);
+        let browser = holders(true);
+        let gpus = vec![
+            GpuInfo {
+                vendor: GpuVendor::Nvidia,
+                index: 0,
+                name: "NVIDIA GeForce RTX 4070".to_string(),
+                utilization: Some(utilization),
+                memory_used: inference.iter().map(|(_, memory)| memory).sum::<u64>() + 180 * MB,
+                memory_total: TOTAL_GPU_MEMORY,
+                temperature: Some(38 + (utilization * 0.4) as u32),
+                power: Some(15.0 + utilization * 1.8),
+                processes: inference,
+            },
+            // Integrated graphics share system memory and report no
+            // temperature or power of their own
+            GpuInfo {
+                vendor: GpuVendor::Intel,
+                index: 1,
+                name: "Intel Graphics [8086:a780]".to_string(),
+                utilization: Some(3.0 + self.rng.unit() * 4.0),
+                memory_used: browser.iter().map(|(_, memory)| memory).sum(),
+                memory_total: 0,
+                temperature: None,
+                power: None,
+                processes: browser,
+            },
+        ];
+
+        // Half the memory on each socket, and the base system's use split
+        // between them
+        let numa_nodes = (0..2)
+            .map(|id| NumaNode {
+                id,
+                total: TOTAL_MEMORY / 2,
+                used: (self
+                    .processes
+                    .iter()
+                    .filter(|p| p.info.numa_node == Some(id))
+                    .map(|p| p.info.memory)
+                    .sum::<u64>()
+                    + 1024 * MB)
+                    .min(TOTAL_MEMORY / 2),
+            })
+            .collect();
+
+        // The machine came up just before the oldest resident process
+        let uptime = 86_400 + (self.tick as f64 * seconds) as u64;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+
+        vec![
+            ProcessUpdate::SystemInfo(
+                cpu.min(100.0),
+                used.min(TOTAL_MEMORY),
+                TOTAL_MEMORY,
+                CORES as usize,
+            ),
+            ProcessUpdate::SwapInfo(swapped + 256 * MB, TOTAL_SWAP),
+            ProcessUpdate::CoreInfo(cores),
+            ProcessUpdate::Pressure(pressure),
+            ProcessUpdate::NumaInfo(numa_nodes),
+            ProcessUpdate::SystemLoad(SystemLoad {
+                one: self.load[0],
+                five: self.load[1],
+                fifteen: self.load[2],
+                uptime,
+                boot_time: now.saturating_sub(uptime),
+            }),
+            ProcessUpdate::NetworkInfo(networks),
+            ProcessUpdate::DiskInfo(disks),
+            ProcessUpdate::GpuInfo(gpus),
+            processes,
+        ]
+    }
+}
+
+impl ProcessSource for DemoSource {
+    fn try_next(&mut self) -> Option<ProcessUpdate> {
+        if self.pending.is_empty() && self.due.is_none_or(|due| Instant::now() >= due) {
+            self.due = Some(Instant::now() + self.interval);
+            self.step();
+            self.pending = self.sample().into();
+            if self.tick == 1 {
+                // Nothing to wait for, so the loading message goes right away
+                self.pending
+                    .push_front(ProcessUpdate::LoadingStatus(String::new()));
+            }
+        }
+        self.pending.pop_front()
+    }
+
+    fn request_refresh(&mut self) {
+        self.due = None;
+    }
+}
+
+// xorshift64: tiny, and the same on every platform, so a seed always plays
+// out the same way
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    // Uniform in [0, 1)
+    fn unit(&mut self) -> f32 {
+        (self.next() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+// A fifth of each process's memory shared, and the idle database partly
+// swapped out
+fn demo_memory_breakdown(name: &str, memory: u64) -> (u64, u64) {
+    let swap = if name == "postgres" { memory / 4 } else { 0 };
+    (memory / 5, swap)
+}
@@ -0,0 +1,134 @@
+// The eBPF collector for the kernel statistics, built with the ebpf feature
+// on Linux. The programs in psr-ebpf count every system call, the block I/O
+// requests each process submits and how long they take to complete, and TCP
+// retransmits. Without the feature, or where the kernel won't load them
+// (they need Linux 6.5 or later, and root or CAP_BPF and CAP_PERFMON), the
+// monitor falls back to what kernel_stats reads from procfs and ss.
+use std::collections::HashMap;
+
+// One process's totals since the programs were loaded
+#[derive(Clone, Copy, Default)]
+pub struct Counters {
+    pub syscalls: u64,
+    // Nanoseconds from submission to completion, summed over the requests
+    pub block_io_ns: u64,
+    pub block_io_requests: u64,
+    pub tcp_retransmits: u64,
+}
+
+#[cfg(all(feature = "ebpf", target_os = "linux"))]
+type Totals = aya::maps::PerCpuHashMap<aya::maps::MapData, u32, u64>;
+
+#[cfg(all(feature = "ebpf", target_os = "linux"))]
+pub struct KernelProbes {
+    // Owns the attached programs, which are detached when it's dropped
+    _ebpf: aya::Ebpf,
+    syscalls: Totals,
+    block_io_ns: Totals,
+    block_io_requests: Totals,
+    // None when the kernel has no kprobes to credit retransmits with
+    retransmits: Option<Totals>,
+}
+
+#[cfg(all(feature = "ebpf", target_os = "linux"))]
+impl KernelProbes {
+    // Load and attach the programs; None if the kernel refuses any of them
+    pub fn load() -> Option<Self> {
+        let mut ebpf = aya::Ebpf::load(aya::include_bytes_aligned!(concat!(
+            env!("OUT_DIR"),
+            "/psr-ebpf"
+        )))
+        .ok()?;
+        for tracepoint in ["sys_enter", "block_io_start", "block_io_done"] {
+            attach_tracepoint(&mut ebpf, tracepoint)?;
+        }
+        // Retransmits are credited through a kprobe, which kernels can be
+        // built without; the monitor then reads them from ss instead
+        let counts_retransmits = attach_kprobe(&mut ebpf, "tcp_sendmsg").is_some()
+            && attach_tracepoint(&mut ebpf, "tcp_retransmit_skb").is_some();
+
+        let mut totals = |name| Totals::try_from(ebpf.take_map(name)?).ok();
+        let syscalls = totals("SYSCALLS")?;
+        let block_io_ns = totals("BLOCK_IO_NS")?;
+        let block_io_requests = totals("BLOCK_IO_REQUESTS")?;
+        let retransmits = counts_retransmits.then(|| totals("RETRANSMITS")).flatten();
+        Some(Self {
+            _ebpf: ebpf,
+            syscalls,
+            block_io_ns,
+            block_io_requests,
+            retransmits,
+        })
+    }
+
+    pub fn counts_retransmits(&self) -> bool {
+        self.retransmits.is_some()
+    }
+
+    // Every process's totals, summed over the CPUs. Processes that have
+    // exited are dropped from the maps, so a reused PID starts from zero.
+    pub fn read(&mut self) -> HashMap<u32, Counters> {
+        let mut counters: HashMap<u32, Counters> = HashMap::new();
+        let mut add = |totals: &Totals, field: fn(&mut Counters) -> &mut u64| {
+            for (pid, values) in totals.iter().filter_map(Result::ok) {
+                *field(counters.entry(pid).or_default()) += values.iter().sum::<u64>();
+            }
+        };
+        add(&self.syscalls, |c| &mut c.syscalls);
+        add(&self.block_io_ns, |c| &mut c.block_io_ns);
+        add(&self.block_io_requests, |c| &mut c.block_io_requests);
+        if let Some(retransmits) = &self.retransmits {
+            add(retransmits, |c| &mut c.tcp_retransmits);
+        }
+
+        counters.retain(|pid, _| {
+            let running = std::path::Path::new(&format!("/proc/{}", pid)).exists();
+            if !running {
+                let _ = self.syscalls.remove(pid);
+                let _ = self.block_io_ns.remove(pid);
+                let _ = self.block_io_requests.remove(pid);
+                if let Some(retransmits) = &mut self.retransmits {
+                    let _ = retransmits.remove(pid);
+                }
+            }
+            running
+        });
+        counters
+    }
+}
+
+// The raw tracepoint programs are named after their tracepoints, and the
+// kprobes after the function they probe
+#[cfg(all(feature = "ebpf", target_os = "linux"))]
+fn attach_tracepoint(ebpf: &mut aya::Ebpf, name: &str) -> Option<()> {
+    let program: &mut aya::programs::RawTracePoint = ebpf.program_mut(name)?.try_into().ok()?;
+    program.load().ok()?;
+    program.attach(name).ok()?;
+    Some(())
+}
+
+#[cfg(all(feature = "ebpf", target_os = "linux"))]
+fn attach_kprobe(ebpf: &mut aya::Ebpf, name: &str) -> Option<()> {
+    let program: &mut aya::programs::KProbe = ebpf.program_mut(name)?.try_into().ok()?;
+    program.load().ok()?;
+    program.attach(name, 0).ok()?;
+    Some(())
+}
+
+#[cfg(not(all(feature = "ebpf", target_os = "linux")))]
+pub struct KernelProbes;
+
+#[cfg(not(all(feature = "ebpf", target_os = "linux")))]
+impl KernelProbes {
+    pub fn load() -> Option<Self> {
+        None
+    }
+
+    pub fn counts_retransmits(&self) -> bool {
+        false
+    }
+
+    pub fn read(&mut self) -> HashMap<u32, Counters> {
+        HashMap::new()
+    }
+}
@@ -0,0 +1,28 @@
+// Per-process energy counters, only available on macOS
+
+// Cumulative (billed energy in nanojoules, idle + interrupt wakeups) for a
+// process, or None when the platform or permissions don't allow reading them
+#[cfg(target_os = "macos")]
+pub fn read_energy_totals(pid: u32) -> Option<(u64, u64)> {
+    let mut info: libc::rusage_info_v4 = unsafe { std::mem::zeroed() };
+    let result = unsafe {
+        libc::proc_pid_rusage(
+            pid as libc::c_int,
+            libc::RUSAGE_INFO_V4,
+            &mut info as *mut libc::rusage_info_v4 as *mut libc::rusage_info_t,
+        )
+    };
+    if result != 0 {
+        return None;
+    }
+
+    Some((
+        info.ri_billed_energy,
+        info.ri_pkg_idle_wkups + info.ri_interrupt_wkups,
+    ))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn read_energy_totals(_pid: u32) -> Option<(u64, u64)> {
+    None
+}
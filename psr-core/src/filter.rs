@@ -0,0 +1,237 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::processes::ProcessInfo;
+
+/// A process field a filter can be limited to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Field {
+    Name,
+    Pid,
+    User,
+    Status,
+    Container,
+    Unit,
+    /// The NUMA node holding most of the process's memory
+    Node,
+    Command,
+    /// The local or remote port of any of the process's sockets
+    Port,
+    /// The local address of any of the process's sockets
+    Local,
+    /// The remote address of any of the process's connected sockets
+    Remote,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Field> {
+        Some(match name.to_lowercase().as_str() {
+            "name" => Field::Name,
+            "pid" => Field::Pid,
+            "user" => Field::User,
+            "status" => Field::Status,
+            "container" => Field::Container,
+            "unit" => Field::Unit,
+            "node" | "numa" => Field::Node,
+            "cmd" | "command" => Field::Command,
+            "port" => Field::Port,
+            "local" => Field::Local,
+            "remote" => Field::Remote,
+            _ => return None,
+        })
+    }
+
+    /// Whether the field is about the process's sockets rather than the
+    /// process itself, so is matched with [`Filter::matches_socket`].
+    pub fn is_socket(self) -> bool {
+        matches!(self, Field::Port | Field::Local | Field::Remote)
+    }
+
+    fn text(self, process: &ProcessInfo) -> Option<String> {
+        match self {
+            Field::Name => Some(process.name.clone()),
+            Field::Pid => Some(process.pid.to_string()),
+            Field::User => Some(process.user.clone()),
+            Field::Status => Some(process.status.to_string()),
+            Field::Container => process.container.clone(),
+            Field::Unit => process.unit.clone(),
+            Field::Node => process.numa_node.map(|node| node.to_string()),
+            Field::Command => Some(process.cmd.join(" ")),
+            Field::Port | Field::Local | Field::Remote => None,
+        }
+    }
+}
+
+/// The text typed into a process filter, compiled. Matching ignores case,
+/// and unless it's a regex the text is taken literally. Text like
+/// `user=root` only looks at that field (name, pid, user, status, container,
+/// unit, node or cmd); anything else is matched against the name, PID, user,
+/// container and unit.
+///
+/// `port=`, `local=` and `remote=` match the process's sockets instead, which
+/// the process table doesn't hold: [`Filter::matches`] is false for them and
+/// the caller looks the sockets up and checks each with
+/// [`Filter::matches_socket`]. Their text has to match a whole port or
+/// address, so `port=80` doesn't match 8080.
+#[derive(Clone, Debug)]
+pub struct Filter {
+    pub field: Option<Field>,
+    pub pattern: Regex,
+}
+
+impl Filter {
+    pub fn new(filter: &str, regex: bool) -> Result<Filter, regex::Error> {
+        let (field, text) = match filter.split_once('=') {
+            Some((name, text)) => match Field::parse(name.trim()) {
+                Some(field) => (Some(field), text),
+                None => (None, filter),
+            },
+            None => (None, filter),
+        };
+        let pattern = if regex {
+            text.to_string()
+        } else {
+            regex::escape(text)
+        };
+        let pattern = match field {
+            Some(field) if field.is_socket() => format!("^(?:{})$", pattern),
+            _ => pattern,
+        };
+        let pattern = RegexBuilder::new(&pattern).case_insensitive(true).build()?;
+        Ok(Filter { field, pattern })
+    }
+
+    pub fn matches(&self, process: &ProcessInfo) -> bool {
+        let matches = |text: &str| self.pattern.is_match(text);
+        match self.field {
+            Some(field) => field.text(process).is_some_and(|text| matches(&text)),
+            None => {
+                matches(&process.name)
+                    || matches(&process.pid.to_string())
+                    || matches(&process.user)
+                    || process.container.as_deref().is_some_and(matches)
+                    || process.unit.as_deref().is_some_and(matches)
+            }
+        }
+    }
+
+    /// Whether a socket with these local and remote addresses, written as
+    /// `address:port`, matches a `port=`, `local=` or `remote=` filter. An
+    /// address matches with or without its port; a remote port of 0 means the
+    /// socket isn't connected and has no remote end.
+    pub fn matches_socket(&self, local: &str, remote: &str) -> bool {
+        let split = |address: &str| -> Option<(String, String)> {
+            let (ip, port) = address.rsplit_once(':')?;
+            let ip = ip.trim_start_matches('[').trim_end_matches(']');
+            Some((ip.to_string(), port.to_string()))
+        };
+        let local = split(local);
+        let remote = split(remote).filter(|(_, port)| port != "0");
+        let address = |address: &Option<(String, String)>| {
+            address.as_ref().is_some_and(|(ip, port)| {
+                self.pattern.is_match(ip) || self.pattern.is_match(&format!("{}:{}", ip, port))
+            })
+        };
+        let port = |address: &Option<(String, String)>| {
+            address
+                .as_ref()
+                .is_some_and(|(_, port)| self.pattern.is_match(port))
+        };
+        match self.field {
+            Some(Field::Port) => port(&local) || port(&remote),
+            Some(Field::Local) => address(&local),
+            Some(Field::Remote) => address(&remote),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processes::ProcessStatus;
+    use std::time::Duration;
+
+    fn process(pid: u32, name: &str) -> ProcessInfo {
+        ProcessInfo::new(
+            pid,
+            name.to_string(),
+            0.0,
+            0,
+            ProcessStatus::Running,
+            "root".to_string(),
+            Duration::ZERO,
+            vec![format!("/usr/sbin/{}", name)],
+            Some(1),
+            Some(1),
+        )
+    }
+
+    fn filter(text: &str) -> Filter {
+        Filter::new(text, false).unwrap()
+    }
+
+    #[test]
+    fn port_matches_whole_ports_only() {
+        let port = filter("port=80");
+        assert!(port.matches_socket("0.0.0.0:80", "0.0.0.0:0"));
+        assert!(port.matches_socket("10.0.0.2:51000", "93.184.216.34:80"));
+        assert!(!port.matches_socket("0.0.0.0:8080", "0.0.0.0:0"));
+        assert!(!port.matches_socket("0.0.0.0:180", "0.0.0.0:0"));
+
+        let prefix = Filter::new("port=80.*", true).unwrap();
+        assert!(prefix.matches_socket("0.0.0.0:8080", "0.0.0.0:0"));
+        assert!(!prefix.matches_socket("0.0.0.0:443", "0.0.0.0:0"));
+    }
+
+    #[test]
+    fn unconnected_sockets_have_no_remote_end() {
+        assert!(!filter("port=0").matches_socket("0.0.0.0:22", "0.0.0.0:0"));
+        assert!(!filter("remote=0.0.0.0").matches_socket("0.0.0.0:22", "0.0.0.0:0"));
+        assert!(filter("local=0.0.0.0").matches_socket("0.0.0.0:22", "0.0.0.0:0"));
+    }
+
+    #[test]
+    fn addresses_match_with_or_without_their_port() {
+        let local = filter("local=127.0.0.1");
+        assert!(local.matches_socket("127.0.0.1:5432", "0.0.0.0:0"));
+        assert!(!local.matches_socket("127.0.0.10:5432", "0.0.0.0:0"));
+        assert!(filter("local=127.0.0.1:5432").matches_socket("127.0.0.1:5432", "0.0.0.0:0"));
+        assert!(!filter("local=127.0.0.1:543").matches_socket("127.0.0.1:5432", "0.0.0.0:0"));
+
+        let remote = filter("remote=10.0.0.5");
+        assert!(remote.matches_socket("10.0.0.2:51000", "10.0.0.5:443"));
+        assert!(!remote.matches_socket("10.0.0.5:443", "10.0.0.2:51000"));
+    }
+
+    #[test]
+    fn ipv6_addresses_drop_their_brackets() {
+        let local = "[::1]:8080";
+        let remote = "[2001:db8::7]:443";
+        assert!(filter("local=::1").matches_socket(local, remote));
+        assert!(filter("local=::1:8080").matches_socket(local, remote));
+        assert!(filter("remote=2001:DB8::7").matches_socket(local, remote));
+        assert!(filter("port=443").matches_socket(local, remote));
+        assert!(!filter("port=1").matches_socket(local, remote));
+        assert!(!filter("remote=2001:db8::").matches_socket(local, remote));
+    }
+
+    #[test]
+    fn socket_and_process_terms_stay_apart() {
+        let nginx = process(80, "nginx");
+        // A socket term is left to the caller's socket lookup
+        assert!(!filter("port=80").matches(&nginx));
+        assert!(filter("port=80").field.is_some_and(Field::is_socket));
+        // and a process term never matches a socket
+        assert!(!filter("name=nginx").matches_socket("0.0.0.0:80", "0.0.0.0:0"));
+        assert!(!filter("80").matches_socket("0.0.0.0:80", "0.0.0.0:0"));
+
+        // Without a field the text is matched against the name and PID
+        assert!(filter("80").matches(&nginx));
+        assert!(filter("NGIN").matches(&nginx));
+        assert!(filter("name=nginx").matches(&nginx));
+        assert!(!filter("name=80").matches(&nginx));
+        // and an unknown field is just text
+        assert!(!filter("socket=80").matches(&nginx));
+        assert!(filter(" Port =80").field == Some(Field::Port));
+    }
+}
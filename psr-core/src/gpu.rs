@@ -0,0 +1,474 @@
+// GPUs and the processes using them: NVIDIA's as reported by `nvidia-smi`,
+// and on Linux AMD and Intel ones from the amdgpu sysfs files and the DRM
+// usage statistics in /proc/<pid>/fdinfo, which is what intel_gpu_top reads
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+const MIB: u64 = 1024 * 1024;
+
+// How often GPUs are read. Each read forks nvidia-smi twice and walks every
+// process's open files, too much for every refresh; in between, the last
+// reading is handed out again.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Who made a GPU, which decides where its figures come from
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Intel,
+}
+
+impl fmt::Display for GpuVendor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            GpuVendor::Nvidia => "NVIDIA",
+            GpuVendor::Amd => "AMD",
+            GpuVendor::Intel => "Intel",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// One GPU's load, memory and temperature
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub vendor: GpuVendor,
+    /// The driver's index: as `nvidia-smi -i` takes it for NVIDIA GPUs, the
+    /// N of /dev/dri/cardN for the others
+    pub index: u32,
+    pub name: String,
+    /// Busy time over the driver's last sample period, in percent, None when
+    /// the GPU doesn't report it
+    pub utilization: Option<f32>,
+    /// Video memory in use and installed, in bytes. Integrated GPUs have no
+    /// memory of their own, so their total is zero and what's in use is the
+    /// system memory their clients hold.
+    pub memory_used: u64,
+    pub memory_total: u64,
+    /// Core temperature in °C
+    pub temperature: Option<u32>,
+    /// Power draw in watts
+    pub power: Option<f32>,
+    /// PIDs holding memory on this GPU, with how many bytes
+    pub processes: Vec<(u32, u64)>,
+}
+
+/// Reads every GPU it can find, keeping the engine busy times the kernel
+/// reports per DRM client between reads, to turn them into utilization for
+/// GPUs that don't report their own
+#[derive(Default)]
+pub struct GpuSampler {
+    // Busy nanoseconds per (PCI address, client ID, engine) at the last read
+    busy: HashMap<(String, u64, String), u64>,
+    sampled_at: Option<Instant>,
+    // Set once spawning nvidia-smi fails because it isn't installed, so
+    // machines without it don't pay for a failed fork on every read
+    no_nvidia_smi: bool,
+    // The last reading and when it was taken
+    last: Option<Vec<GpuInfo>>,
+    read_at: Option<Instant>,
+}
+
+impl GpuSampler {
+    /// NVIDIA GPUs followed by AMD and Intel ones, or None when there's no
+    /// way to read any of them. GPUs are only read every few seconds; calls
+    /// in between get the last reading.
+    pub fn read(&mut self) -> Option<Vec<GpuInfo>> {
+        if self
+            .read_at
+            .is_some_and(|at| at.elapsed() < SAMPLE_INTERVAL)
+        {
+            return self.last.clone();
+        }
+        let nvidia = self.read_nvidia();
+        let drm = self.read_drm();
+        self.read_at = Some(Instant::now());
+        self.last = if nvidia.is_none() && drm.is_empty() {
+            None
+        } else {
+            Some(nvidia.into_iter().flatten().chain(drm).collect())
+        };
+        self.last.clone()
+    }
+
+    // Every GPU the NVIDIA driver knows about, or None when `nvidia-smi`
+    // isn't installed or fails. Only compute processes are listed against a
+    // GPU; the driver doesn't report per-process memory for graphics clients
+    // this way.
+    fn read_nvidia(&mut self) -> Option<Vec<GpuInfo>> {
+        if self.no_nvidia_smi {
+            return None;
+        }
+        let devices = match query(&[
+            "--query-gpu=index,uuid,name,utilization.gpu,memory.used,memory.total,temperature.gpu,power.draw",
+            "--format=csv,noheader,nounits",
+        ]) {
+            Ok(devices) => devices,
+            Err(e) => {
+                self.no_nvidia_smi = e.kind() == io::ErrorKind::NotFound;
+                return None;
+            }
+        };
+        // Which processes are on which GPU comes from a second query, by UUID
+        let mut by_uuid: HashMap<String, Vec<(u32, u64)>> = HashMap::new();
+        for fields in query(&[
+            "--query-compute-apps=gpu_uuid,pid,used_memory",
+            "--format=csv,noheader,nounits",
+        ])
+        .unwrap_or_default()
+        {
+            let [uuid, pid, memory] = fields.as_slice() else {
+                continue;
+            };
+            let (Ok(pid), Ok(memory)) = (pid.parse::<u32>(), memory.parse::<u64>()) else {
+                continue;
+            };
+            by_uuid
+                .entry(uuid.clone())
+                .or_default()
+                .push((pid, memory * MIB));
+        }
+
+        let gpus = devices
+            .into_iter()
+            .filter_map(|fields| {
+                let [index, uuid, name, utilization, used, total, temperature, power] =
+                    fields.as_slice()
+                else {
+                    return None;
+                };
+                Some(GpuInfo {
+                    vendor: GpuVendor::Nvidia,
+                    index: index.parse().ok()?,
+                    name: name.clone(),
+                    // "[N/A]" and "[Not Supported]" fail to parse, leaving None
+                    utilization: utilization.parse().ok(),
+                    memory_used: used.parse::<u64>().unwrap_or(0) * MIB,
+                    memory_total: total.parse::<u64>().unwrap_or(0) * MIB,
+                    temperature: temperature.parse().ok(),
+                    power: power.parse().ok(),
+                    processes: by_uuid.remove(uuid).unwrap_or_default(),
+                })
+            })
+            .collect();
+        Some(gpus)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read_drm(&mut self) -> Vec<GpuInfo> {
+        // No AMD or Intel card, no reason to look through every process's
+        // descriptors for clients
+        let cards = drm_cards();
+        if cards.is_empty() {
+            return Vec::new();
+        }
+        let clients = drm_clients();
+
+        // What each engine did since the last read, over all of a GPU's
+        // clients; the busiest engine stands for the GPU
+        let now = Instant::now();
+        let elapsed = self
+            .sampled_at
+            .map(|at| now.duration_since(at).as_nanos() as f64);
+        let mut busy = HashMap::new();
+        let mut engine_busy: HashMap<(&str, &str), u64> = HashMap::new();
+        for client in &clients {
+            for (engine, nanos) in &client.engines {
+                let key = (client.pci.clone(), client.id, engine.clone());
+                if let Some(previous) = self.busy.get(&key) {
+                    *engine_busy
+                        .entry((client.pci.as_str(), engine.as_str()))
+                        .or_default() += nanos.saturating_sub(*previous);
+                }
+                busy.insert(key, *nanos);
+            }
+        }
+        self.busy = busy;
+        self.sampled_at = Some(now);
+
+        cards
+            .into_iter()
+            .map(|card| {
+                let mut processes: HashMap<u32, u64> = HashMap::new();
+                for client in clients.iter().filter(|c| c.pci == card.pci) {
+                    *processes.entry(client.pid).or_default() += client.memory;
+                }
+                let engines = engine_busy
+                    .iter()
+                    .filter(|((pci, _), _)| *pci == card.pci)
+                    .map(|(_, &nanos)| nanos);
+                let from_engines = elapsed.filter(|&elapsed| elapsed > 0.0).map(|elapsed| {
+                    let busiest = engines.max().unwrap_or(0);
+                    (busiest as f64 / elapsed * 100.0).min(100.0) as f32
+                });
+                let (temperature, power) = hwmon(&card.device);
+                let vram_used = read_number(&card.device.join("mem_info_vram_used"));
+                let vram_total = read_number(&card.device.join("mem_info_vram_total"));
+                GpuInfo {
+                    vendor: card.vendor,
+                    index: card.index,
+                    name: card.name,
+                    utilization: read_number(&card.device.join("gpu_busy_percent"))
+                        .map(|percent| percent as f32)
+                        .or(from_engines),
+                    memory_used: vram_used.unwrap_or_else(|| processes.values().sum()),
+                    memory_total: vram_total.unwrap_or(0),
+                    temperature,
+                    power,
+                    processes: processes.into_iter().collect(),
+                }
+            })
+            .collect()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read_drm(&mut self) -> Vec<GpuInfo> {
+        Vec::new()
+    }
+}
+
+/// GPU memory per PID, summed over every GPU a process has memory on
+pub fn memory_by_pid(gpus: &[GpuInfo]) -> HashMap<u32, u64> {
+    let mut memory: HashMap<u32, u64> = HashMap::new();
+    for (pid, bytes) in gpus.iter().flat_map(|gpu| &gpu.processes) {
+        *memory.entry(*pid).or_default() += bytes;
+    }
+    memory
+}
+
+// Rows of comma-separated fields from an `nvidia-smi` CSV query, or why
+// there are none: NotFound when it isn't installed
+fn query(args: &[&str]) -> io::Result<Vec<Vec<String>>> {
+    let output = Command::new("nvidia-smi")
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("nvidia-smi failed"));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.split(',')
+                .map(|field| field.trim().to_string())
+                .collect()
+        })
+        .collect())
+}
+
+// An AMD or Intel GPU as the DRM subsystem lists it
+#[cfg(target_os = "linux")]
+struct DrmCard {
+    vendor: GpuVendor,
+    index: u32,
+    name: String,
+    // PCI address, as DRM clients give it in `drm-pdev`
+    pci: String,
+    // The card's sysfs device directory
+    device: std::path::PathBuf,
+}
+
+// /sys/class/drm/cardN for every AMD and Intel GPU; NVIDIA's are left to
+// nvidia-smi, and connectors (card0-DP-1 and the like) are skipped
+#[cfg(target_os = "linux")]
+fn drm_cards() -> Vec<DrmCard> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+    let mut cards: Vec<DrmCard> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let index = entry
+                .file_name()
+                .to_str()?
+                .strip_prefix("card")?
+                .parse()
+                .ok()?;
+            let device = entry.path().join("device");
+            let read = |name: &str| {
+                std::fs::read_to_string(device.join(name))
+                    .ok()
+                    .map(|value| value.trim().to_string())
+            };
+            let vendor_id = read("vendor")?;
+            let vendor = match vendor_id.as_str() {
+                "0x1002" => GpuVendor::Amd,
+                "0x8086" => GpuVendor::Intel,
+                _ => return None,
+            };
+            // Only some boards name themselves; the rest go by PCI ID
+            let name = read("product_name")
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| {
+                    let device_id = read("device").unwrap_or_default();
+                    let family = match vendor {
+                        GpuVendor::Amd => "AMD Radeon",
+                        _ => "Intel Graphics",
+                    };
+                    format!(
+                        "{} [{}:{}]",
+                        family,
+                        vendor_id.trim_start_matches("0x"),
+                        device_id.trim_start_matches("0x")
+                    )
+                });
+            let pci = std::fs::canonicalize(&device)
+                .ok()?
+                .file_name()?
+                .to_str()?
+                .to_string();
+            Some(DrmCard {
+                vendor,
+                index,
+                name,
+                pci,
+                device,
+            })
+        })
+        .collect();
+    cards.sort_by_key(|card| card.index);
+    cards
+}
+
+// A process's open GPU context, from its fdinfo
+#[cfg(target_os = "linux")]
+struct DrmClient {
+    pid: u32,
+    pci: String,
+    id: u64,
+    // Cumulative busy nanoseconds per engine (render, video, gfx, ...)
+    engines: Vec<(String, u64)>,
+    // Bytes resident on the GPU's behalf
+    memory: u64,
+}
+
+// Every DRM client psr may look at, once each however many descriptors
+// share it. Processes of other users are only visible to root.
+#[cfg(target_os = "linux")]
+fn drm_clients() -> Vec<DrmClient> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    let mut seen = std::collections::HashSet::new();
+    let mut clients = Vec::new();
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            let is_drm =
+                std::fs::read_link(fd.path()).is_ok_and(|target| target.starts_with("/dev/dri/"));
+            if !is_drm {
+                continue;
+            }
+            let path = entry.path().join("fdinfo").join(fd.file_name());
+            let Some(client) = std::fs::read_to_string(path)
+                .ok()
+                .and_then(|fdinfo| parse_drm_fdinfo(pid, &fdinfo))
+            else {
+                continue;
+            };
+            if seen.insert((client.pci.clone(), client.id)) {
+                clients.push(client);
+            }
+        }
+    }
+    clients
+}
+
+// The DRM usage statistics of one descriptor, as documented in the kernel's
+// drm-usage-stats.rst. None for descriptors without them, which older
+// kernels and some drivers don't provide.
+#[cfg(target_os = "linux")]
+fn parse_drm_fdinfo(pid: u32, fdinfo: &str) -> Option<DrmClient> {
+    let mut pci = None;
+    let mut id = None;
+    let mut engines = Vec::new();
+    // Newer kernels report `drm-resident-<region>`; older amdgpu only the
+    // `drm-memory-<region>` it replaced, and some report both
+    let mut resident = None;
+    let mut legacy = None;
+    for line in fdinfo.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if key == "drm-pdev" {
+            pci = Some(value.to_string());
+        } else if key == "drm-client-id" {
+            id = value.parse::<u64>().ok();
+        } else if let Some(engine) = key.strip_prefix("drm-engine-") {
+            if engine.starts_with("capacity-") {
+                continue;
+            }
+            if let Some(nanos) = value.strip_suffix("ns").and_then(|n| n.trim().parse().ok()) {
+                engines.push((engine.to_string(), nanos));
+            }
+        } else if key.starts_with("drm-resident-") {
+            *resident.get_or_insert(0) += parse_drm_bytes(value);
+        } else if key.starts_with("drm-memory-") {
+            *legacy.get_or_insert(0) += parse_drm_bytes(value);
+        }
+    }
+    Some(DrmClient {
+        pid,
+        pci: pci?,
+        id: id?,
+        engines,
+        memory: resident.or(legacy).unwrap_or(0),
+    })
+}
+
+// "1234", "1234 KiB" or "12 MiB"
+#[cfg(target_os = "linux")]
+fn parse_drm_bytes(value: &str) -> u64 {
+    let (number, unit) = value.split_once(' ').unwrap_or((value, ""));
+    let scale = match unit.trim() {
+        "KiB" => 1024,
+        "MiB" => MIB,
+        _ => 1,
+    };
+    number.parse::<u64>().unwrap_or(0) * scale
+}
+
+// A whole number from a sysfs file
+#[cfg(target_os = "linux")]
+fn read_number(path: &std::path::Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+// Temperature and power draw from the card's hardware monitor, which
+// reports them in millidegrees and microwatts
+#[cfg(target_os = "linux")]
+fn hwmon(device: &std::path::Path) -> (Option<u32>, Option<f32>) {
+    let Some(hwmon) = std::fs::read_dir(device.join("hwmon"))
+        .ok()
+        .and_then(|mut entries| entries.next())
+        .and_then(|entry| entry.ok())
+        .map(|entry| entry.path())
+    else {
+        return (None, None);
+    };
+    let temperature = read_number(&hwmon.join("temp1_input")).map(|milli| (milli / 1000) as u32);
+    let power = read_number(&hwmon.join("power1_average"))
+        .or_else(|| read_number(&hwmon.join("power1_input")))
+        .map(|micro| micro as f32 / 1_000_000.0);
+    (temperature, power)
+}
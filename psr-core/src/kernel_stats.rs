@@ -0,0 +1,103 @@
+// Counters the kernel keeps per process anyway, for the optional collector
+// behind `ProcessMonitor::set_kernel_stats` when the eBPF programs (see
+// ebpf.rs) aren't built in or won't load: read and write system calls, time
+// spent waiting for block I/O and TCP retransmits. They're cumulative; the
+// collector turns them into rates. Linux only. What the kernel doesn't count
+// on its own (other system calls, per-request block I/O latency) isn't
+// available this way.
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::process::Command;
+
+// Read and write system calls made so far, from /proc/<pid>/io. The kernel
+// doesn't count other system calls without tracing, and the file is only
+// readable for our own processes unless we're root.
+#[cfg(target_os = "linux")]
+pub fn read_syscalls(pid: u32) -> Option<u64> {
+    let io = std::fs::read_to_string(format!("/proc/{}/io", pid)).ok()?;
+    let count = |key: &str| -> Option<u64> {
+        io.lines()
+            .find_map(|line| line.strip_prefix(key))
+            .and_then(|value| value.trim().parse().ok())
+    };
+    Some(count("syscr:")? + count("syscw:")?)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_syscalls(_pid: u32) -> Option<u64> {
+    None
+}
+
+// Whether the kernel is keeping delay accounting, without which the block
+// I/O delay in /proc/<pid>/stat stays at zero. It's off by default and
+// turned on with the kernel.task_delayacct sysctl or the delayacct boot option.
+#[cfg(target_os = "linux")]
+pub fn delay_accounting() -> bool {
+    std::fs::read_to_string("/proc/sys/kernel/task_delayacct")
+        .is_ok_and(|enabled| enabled.trim() == "1")
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn delay_accounting() -> bool {
+    false
+}
+
+// Microseconds the process has spent waiting for block I/O to complete:
+// field 42 of /proc/<pid>/stat, in clock ticks
+#[cfg(target_os = "linux")]
+pub fn read_blkio_delay(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The name in parentheses may contain spaces; fields 3 on follow it
+    let (_, fields) = stat.rsplit_once(')')?;
+    let ticks: u64 = fields.split_whitespace().nth(39)?.parse().ok()?;
+    let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks_per_second <= 0 {
+        return None;
+    }
+    Some(ticks * 1_000_000 / ticks_per_second as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_blkio_delay(_pid: u32) -> Option<u64> {
+    None
+}
+
+// TCP segments retransmitted per PID, summed over the process's open
+// sockets from the tcp_info `ss -ti` prints. Like the traffic totals, what
+// was retransmitted on connections that have since closed is lost. None when
+// ss isn't available.
+#[cfg(target_os = "linux")]
+pub fn read_tcp_retransmits() -> Option<HashMap<u32, u64>> {
+    let output = Command::new("ss").args(["-tipH"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut totals: HashMap<u32, u64> = HashMap::new();
+    let mut current_pid = None;
+    for line in stdout.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            current_pid = crate::bandwidth::parse_ss_pid(line);
+            continue;
+        }
+        let Some(pid) = current_pid.take() else {
+            continue;
+        };
+        // "retrans:0/12" is the segments outstanding now, then the total;
+        // the field is left out while nothing has been retransmitted
+        let retransmits = line
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("retrans:"))
+            .and_then(|value| value.split_once('/'))
+            .and_then(|(_, total)| total.parse().ok())
+            .unwrap_or(0);
+        *totals.entry(pid).or_default() += retransmits;
+    }
+    Some(totals)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_tcp_retransmits() -> Option<HashMap<u32, u64>> {
+    None
+}
@@ -0,0 +1,69 @@
+//! The process and system collector behind the psr process monitor.
+//!
+//! A [`ProcessMonitor`] samples processes, CPU, memory, network interfaces
+//! and disks on a background task and sends each sample as a
+//! [`ProcessUpdate`]. Fold the updates into a [`Snapshot`] to keep the latest
+//! state, then sort and filter its processes with [`sort_processes`] and
+//! [`Filter`]:
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use psr_core::{ProcessMonitor, SortBy, Snapshot};
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() {
+//!     let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+//!     let (monitor, _refresh) = ProcessMonitor::new(tx, Duration::from_secs(2));
+//!     tokio::spawn(monitor.start_monitoring());
+//!
+//!     let mut snapshot = Snapshot::default();
+//!     while let Some(update) = rx.recv().await {
+//!         snapshot.apply(update);
+//!         let top = snapshot.sorted(SortBy::Cpu, false);
+//!         if let Some(p) = top.first() {
+//!             println!("{} ({}) {:.1}%", p.name, p.pid, p.cpu_usage);
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Programs that poll from their own loop can go through [`ProcessSource`]
+//! instead, which [`ProcessMonitor::spawn`] returns for the live system and
+//! [`ReplaySource`], [`MockSource`] and [`DemoSource`] implement for recorded,
+//! fixed and simulated data.
+//!
+//! The `ebpf` feature builds in eBPF programs (Linux only) that
+//! [`ProcessMonitor::set_kernel_stats`] uses in place of the procfs counters
+//! when the kernel will load them. Building it takes a nightly toolchain with
+//! rust-src and bpf-linker.
+
+mod bandwidth;
+mod containers;
+pub mod control;
+mod demo;
+mod ebpf;
+mod energy;
+mod filter;
+mod gpu;
+mod kernel_stats;
+mod numa;
+mod pressure;
+pub mod processes;
+mod snapshot;
+mod sort;
+mod source;
+pub mod systemd;
+
+pub use demo::DemoSource;
+pub use filter::{Field, Filter};
+pub use gpu::{GpuInfo, GpuVendor};
+pub use numa::NumaNode;
+pub use pressure::{Pressure, PressureStall};
+pub use processes::{
+    push_history, CollectorStats, CoreInfo, DiskInfo, NetworkInterface, ProcessChange,
+    ProcessDelta, ProcessDetails, ProcessInfo, ProcessMonitor, ProcessStatus, ProcessUpdate,
+    SchedPolicy, SystemLoad, HISTORY_LEN,
+};
+pub use snapshot::Snapshot;
+pub use sort::{sort_processes, SortBy};
+pub use source::{ChannelSource, MockSource, ProcessSource, ReplaySource};
@@ -0,0 +1,102 @@
+// NUMA memory nodes and where processes' memory sits on them, only available
+// on Linux
+
+use serde::{Deserialize, Serialize};
+
+/// Memory installed in and used from one NUMA node, in bytes
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct NumaNode {
+    pub id: u32,
+    pub total: u64,
+    pub used: u64,
+}
+
+/// Every node with memory, or None on machines with only one of them, where
+/// there's nothing to balance
+#[cfg(target_os = "linux")]
+pub fn read_nodes() -> Option<Vec<NumaNode>> {
+    let mut nodes: Vec<NumaNode> = std::fs::read_dir("/sys/devices/system/node")
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry
+                .file_name()
+                .to_str()?
+                .strip_prefix("node")?
+                .parse()
+                .ok()?;
+            let meminfo = std::fs::read_to_string(entry.path().join("meminfo")).ok()?;
+            // Lines read "Node 0 MemTotal:       6131236 kB"
+            let field = |name: &str| {
+                meminfo.lines().find_map(|line| {
+                    let mut words = line.split_whitespace().skip(2);
+                    (words.next()? == name)
+                        .then(|| words.next()?.parse::<u64>().ok())
+                        .flatten()
+                        .map(|kb| kb * 1024)
+                })
+            };
+            let total = field("MemTotal:")?;
+            // The node's MemUsed counts page cache; leaving out that and
+            // reclaimable slab matches how used memory is counted overall
+            let reclaimable = ["MemFree:", "FilePages:", "SReclaimable:"]
+                .into_iter()
+                .filter_map(field)
+                .sum::<u64>();
+            // Memoryless nodes (CPUs only) have nothing to show
+            (total > 0).then(|| NumaNode {
+                id,
+                total,
+                used: total.saturating_sub(reclaimable),
+            })
+        })
+        .collect();
+    if nodes.len() < 2 {
+        return None;
+    }
+    nodes.sort_by_key(|node| node.id);
+    Some(nodes)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_nodes() -> Option<Vec<NumaNode>> {
+    None
+}
+
+/// The node holding most of a process's memory, from the per-node page
+/// counts in /proc/<pid>/numa_maps. Reading it walks the process's page
+/// tables, which is slow for large processes.
+#[cfg(target_os = "linux")]
+pub fn preferred_node(pid: u32) -> Option<u32> {
+    let maps = std::fs::read_to_string(format!("/proc/{}/numa_maps", pid)).ok()?;
+    let mut bytes: Vec<u64> = Vec::new();
+    for line in maps.lines() {
+        // Counts are in pages of the mapping's size, which huge pages change
+        let page_size = line
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("kernelpagesize_kB="))
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .unwrap_or(4)
+            * 1024;
+        for (node, pages) in line.split_whitespace().filter_map(|field| {
+            let (node, pages) = field.strip_prefix('N')?.split_once('=')?;
+            Some((node.parse::<usize>().ok()?, pages.parse::<u64>().ok()?))
+        }) {
+            if bytes.len() <= node {
+                bytes.resize(node + 1, 0);
+            }
+            bytes[node] += pages * page_size;
+        }
+    }
+    bytes
+        .iter()
+        .enumerate()
+        .filter(|(_, &total)| total > 0)
+        .max_by_key(|(_, &total)| total)
+        .map(|(node, _)| node as u32)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn preferred_node(_pid: u32) -> Option<u32> {
+    None
+}
@@ -0,0 +1,64 @@
+// Pressure stall information (PSI), only available on Linux 4.20 and later
+
+use serde::{Deserialize, Serialize};
+
+/// Share of the last 10 seconds, in percent, that some runnable task was
+/// stalled waiting for a resource, and that all of them were at once. CPU
+/// pressure has no meaningful `full` figure and reports it as zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PressureStall {
+    pub some: f32,
+    pub full: f32,
+}
+
+/// How contended the CPU, memory and I/O are, which shows trouble before
+/// utilization does: a machine at 60% CPU can still have tasks queueing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pressure {
+    pub cpu: PressureStall,
+    pub memory: PressureStall,
+    pub io: PressureStall,
+}
+
+impl Pressure {
+    /// CPU, memory and I/O, in that order
+    pub fn stalls(&self) -> [PressureStall; 3] {
+        [self.cpu, self.memory, self.io]
+    }
+}
+
+// None when the kernel lacks PSI or has it disabled (psi=0)
+#[cfg(target_os = "linux")]
+pub fn read_pressure() -> Option<Pressure> {
+    Some(Pressure {
+        cpu: read_stall("cpu")?,
+        memory: read_stall("memory")?,
+        io: read_stall("io")?,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_pressure() -> Option<Pressure> {
+    None
+}
+
+// Lines look like "some avg10=2.27 avg60=5.84 avg300=5.42 total=651285980"
+#[cfg(target_os = "linux")]
+fn read_stall(resource: &str) -> Option<PressureStall> {
+    let contents = std::fs::read_to_string(format!("/proc/pressure/{}", resource)).ok()?;
+    let avg10 = |kind: &str| {
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix(kind))
+            .and_then(|line| {
+                line.split_whitespace()
+                    .find_map(|field| field.strip_prefix("avg10="))
+            })
+            .and_then(|value| value.parse().ok())
+    };
+    Some(PressureStall {
+        some: avg10("some ")?,
+        // Older kernels have no "full" line for the CPU
+        full: avg10("full ").unwrap_or(0.0),
+    })
+}
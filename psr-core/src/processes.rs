@@ -0,0 +1,1734 @@
+use crate::containers::{self, ContainerNames};
+use crate::ebpf::KernelProbes;
+use crate::gpu::{self, GpuInfo, GpuSampler};
+use crate::numa::{self, NumaNode};
+use crate::pressure::{self, Pressure};
+use crate::systemd;
+use crate::{bandwidth, control, energy, kernel_stats};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sysinfo::{
+    CpuExt, CpuRefreshKind, DiskExt, NetworkExt, NetworksExt, PidExt, ProcessExt, System, SystemExt,
+};
+use tokio::sync::mpsc::{self, Sender};
+use tokio::sync::Mutex;
+use tokio::task;
+use tokio::time::interval_at;
+
+/// Samples kept in the chart histories, one per refresh.
+pub const HISTORY_LEN: usize = 60;
+
+/// Append a sample to a chart history, dropping the oldest once it holds
+/// [`HISTORY_LEN`]. Histories are ring buffers, so this never shifts or
+/// reallocates.
+pub fn push_history<T>(history: &mut VecDeque<T>, value: T) {
+    if history.len() >= HISTORY_LEN {
+        history.pop_front();
+    }
+    history.push_back(value);
+}
+
+fn new_history<T>(first: T) -> VecDeque<T> {
+    let mut history = VecDeque::with_capacity(HISTORY_LEN);
+    history.push_back(first);
+    history
+}
+
+/// Scheduler state of a process, simplified across platforms.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ProcessStatus {
+    Running,
+    Sleeping,
+    Stopped,
+    Zombie,
+    Unknown,
+}
+
+impl std::fmt::Display for ProcessStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcessStatus::Running => write!(f, "Running"),
+            ProcessStatus::Sleeping => write!(f, "Sleeping"),
+            ProcessStatus::Stopped => write!(f, "Stopped"),
+            ProcessStatus::Zombie => write!(f, "Zombie"),
+            ProcessStatus::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// Kernel scheduling policy of a process (Linux only), ordered from least to
+/// most favoured by the scheduler.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub enum SchedPolicy {
+    Idle,
+    Batch,
+    /// The default time-sharing policy
+    Other,
+    RoundRobin,
+    Fifo,
+    Deadline,
+}
+
+impl SchedPolicy {
+    /// Whether the policy preempts every time-sharing process
+    pub fn is_realtime(self) -> bool {
+        matches!(
+            self,
+            SchedPolicy::RoundRobin | SchedPolicy::Fifo | SchedPolicy::Deadline
+        )
+    }
+}
+
+impl std::fmt::Display for SchedPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchedPolicy::Idle => write!(f, "IDLE"),
+            SchedPolicy::Batch => write!(f, "BATCH"),
+            SchedPolicy::Other => write!(f, "OTHER"),
+            SchedPolicy::RoundRobin => write!(f, "RR"),
+            SchedPolicy::Fifo => write!(f, "FIFO"),
+            SchedPolicy::Deadline => write!(f, "DEADLINE"),
+        }
+    }
+}
+
+/// One process as of the latest sample. CPU usage is in percent of one core
+/// and memory in bytes.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_usage: f32,
+    /// Resident set size in bytes
+    pub memory: u64,
+    /// Address space reserved by the process, most of it usually not resident
+    pub virtual_memory: u64,
+    /// The part of `memory` backed by files or shared memory, which other
+    /// processes may map too (Linux only), None when unknown
+    pub shared_memory: Option<u64>,
+    /// Bytes swapped out (Linux only), None when unknown
+    pub swap: Option<u64>,
+    /// Proportional set size: private memory plus an equal share of each
+    /// shared page. Linux only, and only collected when asked for with
+    /// [`ProcessMonitor::set_proportional_memory`].
+    pub pss: Option<u64>,
+    /// Unique set size: memory no other process maps, so what exiting would free
+    pub uss: Option<u64>,
+    /// The NUMA node holding most of the process's memory, only collected on
+    /// Linux machines with more than one node
+    pub numa_node: Option<u32>,
+    pub status: ProcessStatus,
+    pub user: String,
+    #[serde(
+        serialize_with = "serialize_secs",
+        deserialize_with = "deserialize_secs"
+    )]
+    pub start_time: Duration,
+    /// When the process started, in seconds since the Unix epoch
+    pub started_at: u64,
+    pub cmd: Vec<String>,
+    pub threads: Option<usize>,
+    /// Open file descriptors and the soft limit on them (RLIMIT_NOFILE), Linux
+    /// only. None when unknown, or for the limit, unlimited.
+    pub fds: Option<u64>,
+    pub fd_limit: Option<u64>,
+    pub parent: Option<u32>,
+    /// Name (or short ID) of the container the process runs in
+    pub container: Option<String>,
+    /// systemd unit (service or scope) the process belongs to
+    pub unit: Option<String>,
+    pub nice: i32,
+    /// Scheduling policy and its realtime priority (1-99 for FIFO and RR, 0
+    /// otherwise), None when unknown
+    pub sched_policy: Option<SchedPolicy>,
+    pub rt_priority: Option<i32>,
+    /// Disk I/O in bytes per second, derived from the cumulative totals
+    pub disk_read_rate: u64,
+    pub disk_write_rate: u64,
+    pub disk_read_total: u64,
+    pub disk_write_total: u64,
+    /// Network bytes per second attributed from the process's sockets, None when unknown
+    pub net_rx_rate: Option<u64>,
+    pub net_tx_rate: Option<u64>,
+    pub net_rx_total: u64,
+    pub net_tx_total: u64,
+    /// Memory held on GPUs, in bytes, None when not on one
+    pub gpu_memory: Option<u64>,
+    /// Power draw in watts and wakeups per second (macOS only), None when unknown
+    pub power: Option<f64>,
+    pub wakeups_rate: Option<f64>,
+    pub energy_total: u64,
+    pub wakeups_total: u64,
+    /// System calls per second, milliseconds per second spent waiting for
+    /// block I/O, and TCP segments retransmitted per second. Linux only, and
+    /// only collected when asked for with [`ProcessMonitor::set_kernel_stats`];
+    /// None otherwise, or for the block I/O delay, while the kernel's delay
+    /// accounting is off. Without the eBPF collector (see
+    /// [`CollectorStats::ebpf`]) only read and write calls are counted.
+    pub syscall_rate: Option<u64>,
+    pub blkio_delay_rate: Option<u64>,
+    pub tcp_retransmit_rate: Option<u64>,
+    /// Average microseconds a block I/O request took to complete over the
+    /// last sample, only measured by the eBPF collector
+    pub blkio_latency: Option<u64>,
+    pub syscall_total: u64,
+    /// In microseconds
+    pub blkio_delay_total: u64,
+    pub blkio_request_total: u64,
+    pub tcp_retransmit_total: u64,
+    /// History for graphs, oldest first
+    pub cpu_history: VecDeque<f32>,
+    pub memory_history: VecDeque<u64>,
+    /// Disk read and write rates, in bytes per second
+    pub disk_read_history: VecDeque<u64>,
+    pub disk_write_history: VecDeque<u64>,
+    /// The kernel statistics' rates, zero while they aren't collected
+    pub syscall_history: VecDeque<u64>,
+    pub blkio_delay_history: VecDeque<u64>,
+    pub blkio_latency_history: VecDeque<u64>,
+    pub tcp_retransmit_history: VecDeque<u64>,
+    #[serde(skip, default = "Instant::now")]
+    pub last_updated: Instant,
+}
+
+// Durations are exported as whole seconds rather than serde's secs/nanos pair
+fn serialize_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_secs())
+}
+
+fn deserialize_secs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_secs)
+}
+
+impl ProcessInfo {
+    /// A process with the given basics; everything else starts empty or zero.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        pid: u32,
+        name: String,
+        cpu_usage: f32,
+        memory: u64,
+        status: ProcessStatus,
+        user: String,
+        start_time: Duration,
+        cmd: Vec<String>,
+        threads: Option<usize>,
+        parent: Option<u32>,
+    ) -> Self {
+        Self {
+            pid,
+            name,
+            cpu_usage,
+            memory,
+            virtual_memory: 0,
+            shared_memory: None,
+            swap: None,
+            pss: None,
+            uss: None,
+            numa_node: None,
+            status,
+            user,
+            start_time,
+            started_at: 0,
+            cmd,
+            threads,
+            fds: None,
+            fd_limit: None,
+            parent,
+            container: None,
+            unit: None,
+            nice: 0,
+            sched_policy: None,
+            rt_priority: None,
+            disk_read_rate: 0,
+            disk_write_rate: 0,
+            disk_read_total: 0,
+            disk_write_total: 0,
+            net_rx_rate: None,
+            net_tx_rate: None,
+            net_rx_total: 0,
+            net_tx_total: 0,
+            gpu_memory: None,
+            power: None,
+            wakeups_rate: None,
+            energy_total: 0,
+            wakeups_total: 0,
+            syscall_rate: None,
+            blkio_delay_rate: None,
+            tcp_retransmit_rate: None,
+            blkio_latency: None,
+            syscall_total: 0,
+            blkio_delay_total: 0,
+            blkio_request_total: 0,
+            tcp_retransmit_total: 0,
+            cpu_history: new_history(cpu_usage),
+            memory_history: new_history(memory),
+            disk_read_history: new_history(0),
+            disk_write_history: new_history(0),
+            syscall_history: new_history(0),
+            blkio_delay_history: new_history(0),
+            blkio_latency_history: new_history(0),
+            tcp_retransmit_history: new_history(0),
+            last_updated: Instant::now(),
+        }
+    }
+
+    /// Derive read/write rates from new cumulative totals; must run before
+    /// `update_history` since it measures time since the last update
+    pub fn update_disk_io(&mut self, read_total: u64, write_total: u64) {
+        let elapsed = self.last_updated.elapsed().as_secs_f64().max(0.001);
+        self.disk_read_rate =
+            (read_total.saturating_sub(self.disk_read_total) as f64 / elapsed) as u64;
+        self.disk_write_rate =
+            (write_total.saturating_sub(self.disk_write_total) as f64 / elapsed) as u64;
+        self.disk_read_total = read_total;
+        self.disk_write_total = write_total;
+    }
+
+    /// Same as `update_disk_io` for socket traffic; None means attribution is unavailable
+    pub fn update_net_io(&mut self, totals: Option<(u64, u64)>) {
+        let Some((rx_total, tx_total)) = totals else {
+            self.net_rx_rate = None;
+            self.net_tx_rate = None;
+            return;
+        };
+
+        let elapsed = self.last_updated.elapsed().as_secs_f64().max(0.001);
+        self.net_rx_rate =
+            Some((rx_total.saturating_sub(self.net_rx_total) as f64 / elapsed) as u64);
+        self.net_tx_rate =
+            Some((tx_total.saturating_sub(self.net_tx_total) as f64 / elapsed) as u64);
+        self.net_rx_total = rx_total;
+        self.net_tx_total = tx_total;
+    }
+
+    /// Same again for the energy counters, which are in nanojoules and wakeups
+    pub fn update_energy(&mut self, totals: Option<(u64, u64)>) {
+        let Some((energy_total, wakeups_total)) = totals else {
+            self.power = None;
+            self.wakeups_rate = None;
+            return;
+        };
+
+        let elapsed = self.last_updated.elapsed().as_secs_f64().max(0.001);
+        self.power = Some(energy_total.saturating_sub(self.energy_total) as f64 / 1e9 / elapsed);
+        self.wakeups_rate = Some(wakeups_total.saturating_sub(self.wakeups_total) as f64 / elapsed);
+        self.energy_total = energy_total;
+        self.wakeups_total = wakeups_total;
+    }
+
+    /// Same again for the kernel statistics, each None when it isn't
+    /// collected. The block I/O delay is in microseconds.
+    pub fn update_kernel_stats(
+        &mut self,
+        syscalls: Option<u64>,
+        blkio_delay: Option<u64>,
+        blkio_requests: Option<u64>,
+        tcp_retransmits: Option<u64>,
+    ) {
+        let elapsed = self.last_updated.elapsed().as_secs_f64().max(0.001);
+        let rate = |total: Option<u64>, previous: &mut u64| {
+            let total = total?;
+            let rate = (total.saturating_sub(*previous) as f64 / elapsed) as u64;
+            *previous = total;
+            Some(rate)
+        };
+        let previous_delay = self.blkio_delay_total;
+        self.syscall_rate = rate(syscalls, &mut self.syscall_total);
+        self.blkio_delay_rate =
+            rate(blkio_delay, &mut self.blkio_delay_total).map(|micros| micros / 1000);
+        self.tcp_retransmit_rate = rate(tcp_retransmits, &mut self.tcp_retransmit_total);
+
+        // The delay over the requests finished since the last sample
+        self.blkio_latency = blkio_requests.map(|requests| {
+            let finished = requests.saturating_sub(self.blkio_request_total);
+            self.blkio_request_total = requests;
+            self.blkio_delay_total
+                .saturating_sub(previous_delay)
+                .checked_div(finished)
+                .unwrap_or(0)
+        });
+    }
+
+    pub fn set_scheduling(&mut self, scheduling: Option<(SchedPolicy, i32)>) {
+        self.sched_policy = scheduling.map(|(policy, _)| policy);
+        self.rt_priority = scheduling.map(|(_, priority)| priority);
+    }
+
+    /// Virtual size plus the shared and swapped bytes, when known
+    pub fn update_memory_breakdown(&mut self, virtual_memory: u64, breakdown: Option<(u64, u64)>) {
+        self.virtual_memory = virtual_memory;
+        self.shared_memory = breakdown.map(|(shared, _)| shared);
+        self.swap = breakdown.map(|(_, swap)| swap);
+    }
+
+    /// Record a new sample, along with the disk rates already set for it
+    pub fn update_history(&mut self, cpu: f32, memory: u64) {
+        self.cpu_usage = cpu;
+        self.memory = memory;
+        push_history(&mut self.cpu_history, cpu);
+        push_history(&mut self.memory_history, memory);
+        push_history(&mut self.disk_read_history, self.disk_read_rate);
+        push_history(&mut self.disk_write_history, self.disk_write_rate);
+        push_history(&mut self.syscall_history, self.syscall_rate.unwrap_or(0));
+        push_history(
+            &mut self.blkio_delay_history,
+            self.blkio_delay_rate.unwrap_or(0),
+        );
+        push_history(
+            &mut self.blkio_latency_history,
+            self.blkio_latency.unwrap_or(0),
+        );
+        push_history(
+            &mut self.tcp_retransmit_history,
+            self.tcp_retransmit_rate.unwrap_or(0),
+        );
+        self.last_updated = Instant::now();
+    }
+
+    /// Fold in the latest sample of this process, extending its histories
+    pub fn apply(&mut self, change: ProcessChange) {
+        self.virtual_memory = change.virtual_memory;
+        self.shared_memory = change.shared_memory;
+        self.swap = change.swap;
+        self.status = change.status;
+        self.start_time = change.start_time;
+        self.nice = change.nice;
+        self.sched_policy = change.sched_policy;
+        self.rt_priority = change.rt_priority;
+        self.disk_read_rate = change.disk_read_rate;
+        self.disk_write_rate = change.disk_write_rate;
+        self.disk_read_total = change.disk_read_total;
+        self.disk_write_total = change.disk_write_total;
+        self.net_rx_rate = change.net_rx_rate;
+        self.net_tx_rate = change.net_tx_rate;
+        self.net_rx_total = change.net_rx_total;
+        self.net_tx_total = change.net_tx_total;
+        self.gpu_memory = change.gpu_memory;
+        self.power = change.power;
+        self.wakeups_rate = change.wakeups_rate;
+        self.energy_total = change.energy_total;
+        self.wakeups_total = change.wakeups_total;
+        self.syscall_rate = change.syscall_rate;
+        self.blkio_delay_rate = change.blkio_delay_rate;
+        self.tcp_retransmit_rate = change.tcp_retransmit_rate;
+        self.blkio_latency = change.blkio_latency;
+        self.syscall_total = change.syscall_total;
+        self.blkio_delay_total = change.blkio_delay_total;
+        self.blkio_request_total = change.blkio_request_total;
+        self.tcp_retransmit_total = change.tcp_retransmit_total;
+        self.update_history(change.cpu_usage, change.memory);
+        if let Some(details) = change.details {
+            self.user = details.user;
+            self.threads = details.threads;
+            self.fds = details.fds;
+            self.fd_limit = details.fd_limit;
+            self.pss = details.pss;
+            self.uss = details.uss;
+            self.numa_node = details.numa_node;
+            self.parent = details.parent;
+            self.container = details.container;
+            self.unit = details.unit;
+            self.cmd = details.cmd;
+        }
+    }
+}
+
+/// The latest sample of a process the receiver already knows. Histories
+/// aren't sent; [`ProcessInfo::apply`] extends them from the sample.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProcessChange {
+    pub pid: u32,
+    pub cpu_usage: f32,
+    pub memory: u64,
+    pub virtual_memory: u64,
+    pub shared_memory: Option<u64>,
+    pub swap: Option<u64>,
+    pub status: ProcessStatus,
+    #[serde(
+        serialize_with = "serialize_secs",
+        deserialize_with = "deserialize_secs"
+    )]
+    pub start_time: Duration,
+    pub nice: i32,
+    pub sched_policy: Option<SchedPolicy>,
+    pub rt_priority: Option<i32>,
+    pub disk_read_rate: u64,
+    pub disk_write_rate: u64,
+    pub disk_read_total: u64,
+    pub disk_write_total: u64,
+    pub net_rx_rate: Option<u64>,
+    pub net_tx_rate: Option<u64>,
+    pub net_rx_total: u64,
+    pub net_tx_total: u64,
+    pub gpu_memory: Option<u64>,
+    pub power: Option<f64>,
+    pub wakeups_rate: Option<f64>,
+    pub energy_total: u64,
+    pub wakeups_total: u64,
+    pub syscall_rate: Option<u64>,
+    pub blkio_delay_rate: Option<u64>,
+    pub tcp_retransmit_rate: Option<u64>,
+    pub blkio_latency: Option<u64>,
+    pub syscall_total: u64,
+    pub blkio_delay_total: u64,
+    pub blkio_request_total: u64,
+    pub tcp_retransmit_total: u64,
+    /// Fields that are only looked up again on a full refresh
+    pub details: Option<ProcessDetails>,
+}
+
+/// The slowly changing fields of a process
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProcessDetails {
+    pub user: String,
+    pub threads: Option<usize>,
+    pub fds: Option<u64>,
+    pub fd_limit: Option<u64>,
+    pub pss: Option<u64>,
+    pub uss: Option<u64>,
+    pub numa_node: Option<u32>,
+    pub parent: Option<u32>,
+    pub container: Option<String>,
+    pub unit: Option<String>,
+    pub cmd: Vec<String>,
+}
+
+impl ProcessChange {
+    /// The current sample of `process`, with its details when they may have changed
+    pub fn new(process: &ProcessInfo, with_details: bool) -> Self {
+        Self {
+            pid: process.pid,
+            cpu_usage: process.cpu_usage,
+            memory: process.memory,
+            virtual_memory: process.virtual_memory,
+            shared_memory: process.shared_memory,
+            swap: process.swap,
+            status: process.status.clone(),
+            start_time: process.start_time,
+            nice: process.nice,
+            sched_policy: process.sched_policy,
+            rt_priority: process.rt_priority,
+            disk_read_rate: process.disk_read_rate,
+            disk_write_rate: process.disk_write_rate,
+            disk_read_total: process.disk_read_total,
+            disk_write_total: process.disk_write_total,
+            net_rx_rate: process.net_rx_rate,
+            net_tx_rate: process.net_tx_rate,
+            net_rx_total: process.net_rx_total,
+            net_tx_total: process.net_tx_total,
+            gpu_memory: process.gpu_memory,
+            power: process.power,
+            wakeups_rate: process.wakeups_rate,
+            energy_total: process.energy_total,
+            wakeups_total: process.wakeups_total,
+            syscall_rate: process.syscall_rate,
+            blkio_delay_rate: process.blkio_delay_rate,
+            tcp_retransmit_rate: process.tcp_retransmit_rate,
+            blkio_latency: process.blkio_latency,
+            syscall_total: process.syscall_total,
+            blkio_delay_total: process.blkio_delay_total,
+            blkio_request_total: process.blkio_request_total,
+            tcp_retransmit_total: process.tcp_retransmit_total,
+            details: with_details.then(|| ProcessDetails {
+                user: process.user.clone(),
+                threads: process.threads,
+                fds: process.fds,
+                fd_limit: process.fd_limit,
+                pss: process.pss,
+                uss: process.uss,
+                numa_node: process.numa_node,
+                parent: process.parent,
+                container: process.container.clone(),
+                unit: process.unit.clone(),
+                cmd: process.cmd.clone(),
+            }),
+        }
+    }
+}
+
+/// How the process table changed since the previous update
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ProcessDelta {
+    /// Processes seen for the first time
+    pub added: Vec<ProcessInfo>,
+    /// New samples of processes already known
+    pub updated: Vec<ProcessChange>,
+    /// PIDs of processes that have exited
+    pub removed: Vec<u32>,
+}
+
+impl ProcessDelta {
+    /// Bring a table of processes by PID up to date, returning the ones that exited
+    pub fn apply(self, processes: &mut HashMap<u32, ProcessInfo>) -> Vec<ProcessInfo> {
+        let exited = self
+            .removed
+            .iter()
+            .filter_map(|pid| processes.remove(pid))
+            .collect();
+        for change in self.updated {
+            if let Some(process) = processes.get_mut(&change.pid) {
+                process.apply(change);
+            }
+        }
+        for process in self.added {
+            processes.insert(process.pid, process);
+        }
+        exited
+    }
+}
+
+/// Per-interface network throughput, rates in bytes per second
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NetworkInterface {
+    pub name: String,
+    pub rx_rate: u64,
+    pub tx_rate: u64,
+    pub total_received: u64,
+    pub total_transmitted: u64,
+}
+
+/// What the last refresh cost the collector, so a monitor can show that it
+/// isn't the thing slowing the machine down
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct CollectorStats {
+    /// PID of the process running the collector, to find its CPU and memory
+    pub pid: u32,
+    /// How long the processes took to collect
+    pub collect_time: Duration,
+    /// Whether the kernel statistics come from the eBPF collector rather
+    /// than procfs
+    pub ebpf: bool,
+}
+
+/// Utilization and clock speed of one logical CPU core
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct CoreInfo {
+    /// Busy time, in percent of this core
+    pub usage: f32,
+    /// Current clock in MHz, zero when the platform doesn't report it
+    pub frequency: u64,
+}
+
+impl CoreInfo {
+    fn read_all(system: &System) -> Vec<Self> {
+        system
+            .cpus()
+            .iter()
+            .map(|cpu| Self {
+                usage: cpu.cpu_usage(),
+                frequency: cpu.frequency(),
+            })
+            .collect()
+    }
+}
+
+/// Load averages and how long the machine has been up
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct SystemLoad {
+    /// Runnable (and on Linux, uninterruptible) tasks averaged over 1, 5 and
+    /// 15 minutes; zero on Windows, which has no load average
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+    /// Seconds since boot
+    pub uptime: u64,
+    /// When the machine booted, as a Unix timestamp
+    pub boot_time: u64,
+}
+
+impl SystemLoad {
+    fn read(system: &System) -> Self {
+        let load = system.load_average();
+        Self {
+            one: load.one,
+            five: load.five,
+            fifteen: load.fifteen,
+            uptime: system.uptime(),
+            boot_time: system.boot_time(),
+        }
+    }
+}
+
+/// Updates that can be sent from the background task, or streamed from an agent
+#[derive(Clone, Serialize, Deserialize)]
+pub enum ProcessUpdate {
+    /// Every process, replacing whatever the receiver had
+    ProcessList(Vec<ProcessInfo>),
+    /// Changes to the last list, sent on regular refreshes so unchanged
+    /// fields and histories aren't copied every time
+    ProcessDelta(ProcessDelta),
+    SystemInfo(f32, u64, u64, usize), // cpu, used_mem, total_mem, cores
+    SwapInfo(u64, u64),               // used_swap, total_swap
+    /// Memory of each NUMA node, sent only on machines with several
+    NumaInfo(Vec<NumaNode>),
+    /// GPUs, sent only where there's a way to read them
+    GpuInfo(Vec<GpuInfo>),
+    /// Every logical core, in the order the OS numbers them
+    CoreInfo(Vec<CoreInfo>),
+    NetworkInfo(Vec<NetworkInterface>),
+    DiskInfo(Vec<DiskInfo>),
+    LoadingStatus(String),
+    CollectorStats(CollectorStats),
+    SystemLoad(SystemLoad),
+    /// Pressure stall figures, on systems that report them
+    Pressure(Pressure),
+}
+
+// Cumulative (in, out) counters from the previous sample, used to derive
+// per-second rates independently of how often sysinfo refreshes its data
+struct RateCounters {
+    taken_at: Instant,
+    totals: HashMap<String, (u64, u64)>,
+}
+
+impl RateCounters {
+    fn new() -> Self {
+        Self {
+            taken_at: Instant::now(),
+            totals: HashMap::new(),
+        }
+    }
+
+    // Rates for `key` since the previous sample; keys seen for the first time report none
+    fn rates(&self, key: &str, totals: (u64, u64)) -> (u64, u64) {
+        let elapsed = self.taken_at.elapsed().as_secs_f64().max(0.001);
+        match self.totals.get(key) {
+            Some(&(previous_in, previous_out)) => (
+                (totals.0.saturating_sub(previous_in) as f64 / elapsed) as u64,
+                (totals.1.saturating_sub(previous_out) as f64 / elapsed) as u64,
+            ),
+            None => (0, 0),
+        }
+    }
+
+    fn record(&mut self, totals: HashMap<String, (u64, u64)>) {
+        self.taken_at = Instant::now();
+        self.totals = totals;
+    }
+}
+
+/// Mounted filesystem with capacity and read/write throughput in bytes per second
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DiskInfo {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total_space: u64,
+    pub available_space: u64,
+    pub read_rate: u64,
+    pub write_rate: u64,
+}
+
+impl DiskInfo {
+    pub fn used_space(&self) -> u64 {
+        self.total_space.saturating_sub(self.available_space)
+    }
+}
+
+// Cumulative bytes read/written per block device, keyed by kernel device name
+#[cfg(target_os = "linux")]
+fn read_disk_io_totals() -> HashMap<String, (u64, u64)> {
+    const SECTOR_SIZE: u64 = 512;
+
+    let Ok(content) = std::fs::read_to_string("/proc/diskstats") else {
+        return HashMap::new();
+    };
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let sectors_read = fields.get(5)?.parse::<u64>().ok()?;
+            let sectors_written = fields.get(9)?.parse::<u64>().ok()?;
+            Some((
+                fields[2].to_string(),
+                (sectors_read * SECTOR_SIZE, sectors_written * SECTOR_SIZE),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_disk_io_totals() -> HashMap<String, (u64, u64)> {
+    HashMap::new()
+}
+
+// Kernel device name for a disk, resolving symlinks such as /dev/mapper/* -> dm-N
+fn disk_device_name(name: &str) -> String {
+    std::fs::canonicalize(name)
+        .ok()
+        .and_then(|path| path.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| name.trim_start_matches("/dev/").to_string())
+}
+
+// Cache for user information to reduce system calls
+struct UserCache {
+    cache: HashMap<u32, String>,
+    last_refresh: Instant,
+}
+
+impl UserCache {
+    fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            last_refresh: Instant::now(),
+        }
+    }
+
+    // Refresh cache every 30 seconds
+    fn expire(&mut self) {
+        if self.last_refresh.elapsed() > Duration::from_secs(30) {
+            self.cache.clear();
+            self.last_refresh = Instant::now();
+        }
+    }
+
+    fn contains(&self, pid: u32) -> bool {
+        self.cache.contains_key(&pid)
+    }
+
+    // Users that `ps` listed; processes it didn't list stay unknown until the
+    // cache expires
+    fn get_user(&mut self, pid: u32) -> String {
+        self.cache
+            .entry(pid)
+            .or_insert_with(|| "unknown".to_string())
+            .clone()
+    }
+}
+
+// Thread cache to avoid expensive operations
+struct ThreadCache {
+    cache: HashMap<u32, usize>,
+    last_refresh: Instant,
+}
+
+impl ThreadCache {
+    fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            last_refresh: Instant::now(),
+        }
+    }
+
+    // Only refresh thread counts every 5 seconds
+    fn expire(&mut self) {
+        if self.last_refresh.elapsed() > Duration::from_secs(5) {
+            self.cache.clear();
+            self.last_refresh = Instant::now();
+        }
+    }
+
+    fn get_thread_count(&mut self, pid: u32) -> Option<usize> {
+        self.expire();
+
+        if let Some(count) = self.cache.get(&pid) {
+            return Some(*count);
+        }
+
+        let thread_count = read_thread_count(pid);
+        if let Some(count) = thread_count {
+            self.cache.insert(pid, count);
+        }
+
+        thread_count
+    }
+}
+
+// The user and thread count of every process from a single `ps` call, rather
+// than one per process. Some `ps` (macOS, the BSDs) have no thread count
+// column, in which case only users are listed.
+#[cfg(unix)]
+fn read_ps_table() -> HashMap<u32, (String, Option<usize>)> {
+    let output = ["pid=,user=,nlwp=", "pid=,user="]
+        .iter()
+        .find_map(|format| {
+            Command::new("ps")
+                .args(["-eo", format])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+        });
+    let Some(output) = output else {
+        return HashMap::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let user = fields.next()?.to_string();
+            let threads = fields.next().and_then(|count| count.parse().ok());
+            Some((pid, (user, threads)))
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+fn read_ps_table() -> HashMap<u32, (String, Option<usize>)> {
+    HashMap::new()
+}
+
+// Shared (file-backed plus shmem) resident bytes and swapped-out bytes, from
+// the kB figures in /proc/<pid>/status
+#[cfg(target_os = "linux")]
+fn read_memory_breakdown(pid: u32) -> Option<(u64, u64)> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let field = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|value| {
+                value
+                    .trim()
+                    .trim_end_matches("kB")
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+            })
+            .map(|kb| kb * 1024)
+    };
+    let shared = field("RssFile:")? + field("RssShmem:")?;
+    // Kernel threads have no memory lines at all, but a process without swap
+    // still reports VmSwap as zero
+    Some((shared, field("VmSwap:")?))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_memory_breakdown(_pid: u32) -> Option<(u64, u64)> {
+    None
+}
+
+// PSS and USS (private clean plus private dirty) in bytes. Other users'
+// processes can't be read without ptrace access, so they come back as None.
+#[cfg(target_os = "linux")]
+fn read_proportional_memory(pid: u32) -> Option<(u64, u64)> {
+    let rollup = std::fs::read_to_string(format!("/proc/{}/smaps_rollup", pid)).ok()?;
+    let field = |name: &str| {
+        rollup
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|value| {
+                value
+                    .trim()
+                    .trim_end_matches("kB")
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+            })
+            .map(|kb| kb * 1024)
+    };
+    let uss = field("Private_Clean:")? + field("Private_Dirty:")?;
+    Some((field("Pss:")?, uss))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_proportional_memory(_pid: u32) -> Option<(u64, u64)> {
+    None
+}
+
+// Open descriptors are the entries of /proc/<pid>/fd, and the limit is the
+// soft one from /proc/<pid>/limits. Other users' processes hide their
+// descriptors, so they come back as None.
+#[cfg(target_os = "linux")]
+fn read_fd_usage(pid: u32) -> (Option<u64>, Option<u64>) {
+    let fds = std::fs::read_dir(format!("/proc/{}/fd", pid))
+        .ok()
+        .map(|entries| entries.count() as u64);
+    // "Max open files            1024                 524288               files"
+    let limit = std::fs::read_to_string(format!("/proc/{}/limits", pid))
+        .ok()
+        .and_then(|limits| {
+            let line = limits
+                .lines()
+                .find_map(|line| line.strip_prefix("Max open files"))?;
+            line.split_whitespace().next()?.parse().ok()
+        });
+    (fds, limit)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_fd_usage(_pid: u32) -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+// Thread counts come straight from the OS rather than from `ps`
+#[cfg(target_os = "linux")]
+fn read_thread_count(pid: u32) -> Option<usize> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Threads:"))
+        .and_then(|count| count.trim().parse().ok())
+}
+
+#[cfg(target_os = "macos")]
+fn read_thread_count(pid: u32) -> Option<usize> {
+    let mut info: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid as libc::c_int,
+            libc::PROC_PIDTASKINFO,
+            0,
+            &mut info as *mut libc::proc_taskinfo as *mut libc::c_void,
+            size,
+        )
+    };
+    (written == size).then_some(info.pti_threadnum as usize)
+}
+
+#[cfg(windows)]
+fn read_thread_count(pid: u32) -> Option<usize> {
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::tlhelp32::{
+        CreateToolhelp32Snapshot, Process32First, Process32Next, PROCESSENTRY32, TH32CS_SNAPPROCESS,
+    };
+
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return None;
+        }
+
+        let mut entry: PROCESSENTRY32 = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32>() as u32;
+
+        let mut count = None;
+        let mut more = Process32First(snapshot, &mut entry) != 0;
+        while more {
+            if entry.th32ProcessID == pid {
+                count = Some(entry.cntThreads as usize);
+                break;
+            }
+            more = Process32Next(snapshot, &mut entry) != 0;
+        }
+
+        CloseHandle(snapshot);
+        count
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn read_thread_count(_pid: u32) -> Option<usize> {
+    None
+}
+
+/// Samples the system on a background task, sending a [`ProcessUpdate`] for
+/// each part of every sample.
+pub struct ProcessMonitor {
+    system: Arc<Mutex<System>>,
+    user_cache: Arc<Mutex<UserCache>>,
+    thread_cache: Arc<Mutex<ThreadCache>>,
+    container_names: Arc<Mutex<ContainerNames>>,
+    process_cache: Arc<Mutex<HashMap<u32, ProcessInfo>>>,
+    last_full_refresh: Arc<Mutex<Instant>>,
+    tx: Sender<ProcessUpdate>,
+    refresh_receiver: mpsc::Receiver<()>,
+    refresh_interval: Duration,
+    csv_log: Option<Arc<Mutex<BufWriter<File>>>>,
+    proportional_memory: bool,
+    kernel_stats: bool,
+    // The eBPF programs counting the kernel statistics, when they loaded,
+    // and whether they count TCP retransmits too
+    kernel_probes: Option<Arc<Mutex<KernelProbes>>>,
+    probed_retransmits: bool,
+    // Whether the machine has several NUMA nodes to place processes on
+    numa: bool,
+    network_counters: Arc<Mutex<RateCounters>>,
+    disk_counters: Arc<Mutex<RateCounters>>,
+    gpu_sampler: Arc<Mutex<GpuSampler>>,
+}
+
+const BATCH_SIZE: usize = 50; // Process information in batches
+
+// Raw per-process data copied out of sysinfo while the system lock is held
+struct RawProcess {
+    pid: sysinfo::Pid,
+    cmd: Vec<String>,
+    name: String,
+    cpu_usage: f32,
+    memory: u64,
+    virtual_memory: u64,
+    // Shared and swapped bytes
+    memory_breakdown: Option<(u64, u64)>,
+    status: sysinfo::ProcessStatus,
+    run_time: u64,
+    started_at: u64,
+    parent: Option<sysinfo::Pid>,
+    nice: i32,
+    scheduling: Option<(SchedPolicy, i32)>,
+    energy: Option<(u64, u64)>,
+    disk_read_total: u64,
+    disk_write_total: u64,
+}
+
+impl ProcessMonitor {
+    /// A monitor that sends to `tx` every `refresh_interval`, plus the sender
+    /// that asks it for an immediate refresh. Must be called inside a Tokio
+    /// runtime.
+    pub fn new(tx: Sender<ProcessUpdate>, refresh_interval: Duration) -> (Self, mpsc::Sender<()>) {
+        let mut system = System::new_all();
+        system.refresh_all();
+
+        // Create a channel for requesting refreshes
+        let (refresh_tx, refresh_rx) = mpsc::channel(10);
+
+        // Store the refresh sender in the app
+        let clone_tx = tx.clone();
+        tokio::spawn(async move {
+            // Provide the refresh sender to the app
+            let update = ProcessUpdate::LoadingStatus("Starting up...".to_string());
+            let _ = clone_tx.send(update).await;
+        });
+
+        let monitor = Self {
+            system: Arc::new(Mutex::new(system)),
+            user_cache: Arc::new(Mutex::new(UserCache::new())),
+            thread_cache: Arc::new(Mutex::new(ThreadCache::new())),
+            container_names: Arc::new(Mutex::new(ContainerNames::new())),
+            process_cache: Arc::new(Mutex::new(HashMap::new())),
+            last_full_refresh: Arc::new(Mutex::new(Instant::now())),
+            tx,
+            refresh_receiver: refresh_rx,
+            refresh_interval,
+            csv_log: None,
+            proportional_memory: false,
+            kernel_stats: false,
+            kernel_probes: None,
+            probed_retransmits: false,
+            numa: numa::read_nodes().is_some(),
+            network_counters: Arc::new(Mutex::new(RateCounters::new())),
+            disk_counters: Arc::new(Mutex::new(RateCounters::new())),
+            gpu_sampler: Arc::new(Mutex::new(GpuSampler::default())),
+        };
+
+        (monitor, refresh_tx)
+    }
+
+    /// Append one CSV row per process to `path` on every refresh
+    pub fn set_csv_log(&mut self, path: &Path) -> io::Result<()> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let is_empty = file.metadata()?.len() == 0;
+
+        let mut writer = BufWriter::new(file);
+        if is_empty {
+            writeln!(writer, "timestamp,pid,name,cpu_usage,memory,status")?;
+            writer.flush()?;
+        }
+
+        self.csv_log = Some(Arc::new(Mutex::new(writer)));
+        Ok(())
+    }
+
+    /// Also report PSS and USS, read from `/proc/<pid>/smaps_rollup` on full
+    /// refreshes. The kernel walks every mapping to produce it, which costs
+    /// noticeably more than the rest of a sample, so it's off by default.
+    pub fn set_proportional_memory(&mut self, enabled: bool) {
+        self.proportional_memory = enabled;
+    }
+
+    /// Also report system call rates, block I/O delay and TCP retransmits
+    /// (Linux only). With the `ebpf` feature they're counted by eBPF
+    /// programs, which also time each block I/O request, if the kernel will
+    /// load them. Otherwise they come from `/proc/<pid>/io` (read and write
+    /// calls only), the delay accounting in `/proc/<pid>/stat` and `ss`, which
+    /// costs a couple more reads per process and another `ss` run each
+    /// sample. Either way it's off by default.
+    pub fn set_kernel_stats(&mut self, enabled: bool) {
+        self.kernel_stats = enabled;
+        let probes = enabled.then(KernelProbes::load).flatten();
+        self.probed_retransmits = probes
+            .as_ref()
+            .is_some_and(KernelProbes::counts_retransmits);
+        self.kernel_probes = probes.map(|probes| Arc::new(Mutex::new(probes)));
+    }
+
+    /// Sample forever; spawn it on its own task.
+    pub async fn start_monitoring(mut self) {
+        // First, send initial loading message
+        let _ = self
+            .tx
+            .send(ProcessUpdate::LoadingStatus(
+                "Initializing system monitor...".to_string(),
+            ))
+            .await;
+
+        // Start with a system info update
+        {
+            let system = self.system.lock().await;
+            let cpu_usage = system.global_cpu_info().cpu_usage();
+            let total_memory = system.total_memory();
+            let used_memory = system.used_memory();
+            let _ = self
+                .tx
+                .send(ProcessUpdate::SystemInfo(
+                    cpu_usage,
+                    used_memory,
+                    total_memory,
+                    system.cpus().len(),
+                ))
+                .await;
+            let _ = self
+                .tx
+                .send(ProcessUpdate::SwapInfo(
+                    system.used_swap(),
+                    system.total_swap(),
+                ))
+                .await;
+            let _ = self
+                .tx
+                .send(ProcessUpdate::CoreInfo(CoreInfo::read_all(&system)))
+                .await;
+            let _ = self
+                .tx
+                .send(ProcessUpdate::SystemLoad(SystemLoad::read(&system)))
+                .await;
+            if let Some(nodes) = numa::read_nodes() {
+                let _ = self.tx.send(ProcessUpdate::NumaInfo(nodes)).await;
+            }
+            if let Some(pressure) = pressure::read_pressure() {
+                let _ = self.tx.send(ProcessUpdate::Pressure(pressure)).await;
+            }
+        }
+
+        // Initial process list
+        self.collect_and_send_processes(true).await;
+        self.collect_and_send_networks().await;
+        self.collect_and_send_disks().await;
+
+        // Clear loading status after initial load is complete
+        let _ = self
+            .tx
+            .send(ProcessUpdate::LoadingStatus("".to_string()))
+            .await;
+
+        // Now start regular monitoring
+        // The initial list was just sent, so the first tick waits a full interval
+        let mut interval_timer = interval_at(
+            tokio::time::Instant::now() + self.refresh_interval,
+            self.refresh_interval,
+        );
+
+        loop {
+            tokio::select! {
+                // Check for refresh requests
+                _ = self.refresh_receiver.recv() => {
+                    let _ = self.tx.send(ProcessUpdate::LoadingStatus("Manual refresh requested...".to_string())).await;
+                    self.collect_and_send_processes(true).await;
+                }
+
+                // Regular timer-based updates
+                _ = interval_timer.tick() => {
+                    self.collect_and_send_processes(false).await;
+                    self.collect_and_send_networks().await;
+                    self.collect_and_send_disks().await;
+
+                    // Update system info every tick
+                    let system = self.system.lock().await;
+                    let cpu_usage = system.global_cpu_info().cpu_usage();
+                    let total_memory = system.total_memory();
+                    let used_memory = system.used_memory();
+                    let cores = system.cpus().len();
+                    let _ = self.tx.send(ProcessUpdate::SystemInfo(cpu_usage, used_memory, total_memory, cores)).await;
+                    let _ = self.tx.send(ProcessUpdate::SwapInfo(system.used_swap(), system.total_swap())).await;
+                    let _ = self.tx.send(ProcessUpdate::CoreInfo(CoreInfo::read_all(&system))).await;
+                    let _ = self.tx.send(ProcessUpdate::SystemLoad(SystemLoad::read(&system))).await;
+                    if let Some(nodes) = numa::read_nodes() {
+                        let _ = self.tx.send(ProcessUpdate::NumaInfo(nodes)).await;
+                    }
+                    if let Some(pressure) = pressure::read_pressure() {
+                        let _ = self.tx.send(ProcessUpdate::Pressure(pressure)).await;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn collect_and_send_processes(&self, force_full_refresh: bool) {
+        let started = Instant::now();
+
+        // Determine if we need a full refresh
+        let mut last_full_refresh = self.last_full_refresh.lock().await;
+        let is_full_refresh =
+            force_full_refresh || last_full_refresh.elapsed() > Duration::from_secs(10);
+
+        if is_full_refresh {
+            let _ = self
+                .tx
+                .send(ProcessUpdate::LoadingStatus(
+                    "Collecting process data...".to_string(),
+                ))
+                .await;
+
+            // Full refresh of system data
+            {
+                let mut system = self.system.lock().await;
+                system.refresh_all();
+                system.refresh_networks_list();
+                system.refresh_disks_list();
+            }
+            *last_full_refresh = Instant::now();
+        } else {
+            // Partial refresh
+            let mut system = self.system.lock().await;
+            // Every core's usage and frequency, which can drop from one
+            // refresh to the next when a core throttles. This goes first: the
+            // process refresh updates only the total, after which sysinfo
+            // skips the cores as refreshed too recently.
+            system.refresh_cpu_specifics(CpuRefreshKind::everything());
+            system.refresh_processes();
+            system.refresh_memory();
+            system.refresh_networks();
+        }
+
+        // GPUs come from an external tool and a walk of every process's open
+        // files, and give per-process GPU memory. The sampler only reads them
+        // every few seconds and repeats its last reading in between.
+        let sampler = self.gpu_sampler.clone();
+        let gpus = task::spawn_blocking(move || sampler.blocking_lock().read())
+            .await
+            .ok()
+            .flatten();
+        let gpu_memory = gpus.as_deref().map(gpu::memory_by_pid);
+
+        // Process information
+        let delta = self.get_processes(is_full_refresh, gpu_memory).await;
+        let stats = CollectorStats {
+            pid: std::process::id(),
+            collect_time: started.elapsed(),
+            ebpf: self.kernel_probes.is_some(),
+        };
+
+        // Forced refreshes (the first one and those asked for) send the whole
+        // table, so receivers can start over; otherwise just what changed
+        let update = {
+            let process_cache = self.process_cache.lock().await;
+            if let Some(csv_log) = &self.csv_log {
+                let mut writer = csv_log.lock().await;
+                let _ = write_csv_rows(&mut *writer, process_cache.values());
+            }
+            if force_full_refresh {
+                ProcessUpdate::ProcessList(process_cache.values().cloned().collect())
+            } else {
+                ProcessUpdate::ProcessDelta(delta)
+            }
+        };
+        let _ = self.tx.send(update).await;
+        let _ = self.tx.send(ProcessUpdate::CollectorStats(stats)).await;
+        if let Some(gpus) = gpus {
+            let _ = self.tx.send(ProcessUpdate::GpuInfo(gpus)).await;
+        }
+
+        // Clear loading status once done
+        if is_full_refresh {
+            let _ = self
+                .tx
+                .send(ProcessUpdate::LoadingStatus("".to_string()))
+                .await;
+        }
+    }
+
+    async fn collect_and_send_networks(&self) {
+        let mut counters = self.network_counters.lock().await;
+
+        let mut interfaces: Vec<NetworkInterface> = {
+            let system = self.system.lock().await;
+            system
+                .networks()
+                .iter()
+                .map(|(name, data)| {
+                    let total_received = data.total_received();
+                    let total_transmitted = data.total_transmitted();
+                    let (rx_rate, tx_rate) =
+                        counters.rates(name, (total_received, total_transmitted));
+
+                    NetworkInterface {
+                        name: name.clone(),
+                        rx_rate,
+                        tx_rate,
+                        total_received,
+                        total_transmitted,
+                    }
+                })
+                .collect()
+        };
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+        counters.record(
+            interfaces
+                .iter()
+                .map(|i| (i.name.clone(), (i.total_received, i.total_transmitted)))
+                .collect(),
+        );
+
+        let _ = self.tx.send(ProcessUpdate::NetworkInfo(interfaces)).await;
+    }
+
+    async fn collect_and_send_disks(&self) {
+        let mut counters = self.disk_counters.lock().await;
+        let io_totals = read_disk_io_totals();
+
+        let mut disks: Vec<DiskInfo> = {
+            let system = self.system.lock().await;
+            system
+                .disks()
+                .iter()
+                .map(|disk| {
+                    let name = disk.name().to_string_lossy().to_string();
+                    let device = disk_device_name(&name);
+                    let (read_rate, write_rate) = io_totals
+                        .get(&device)
+                        .map(|&totals| counters.rates(&device, totals))
+                        .unwrap_or((0, 0));
+
+                    DiskInfo {
+                        name,
+                        mount_point: disk.mount_point().to_string_lossy().to_string(),
+                        file_system: String::from_utf8_lossy(disk.file_system()).to_string(),
+                        total_space: disk.total_space(),
+                        available_space: disk.available_space(),
+                        read_rate,
+                        write_rate,
+                    }
+                })
+                .collect()
+        };
+        disks.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+
+        counters.record(io_totals);
+
+        let _ = self.tx.send(ProcessUpdate::DiskInfo(disks)).await;
+    }
+
+    // Get processes in an async-friendly way
+    async fn get_processes(
+        &self,
+        is_full_refresh: bool,
+        gpu_memory: Option<HashMap<u32, u64>>,
+    ) -> ProcessDelta {
+        let mut process_cache = self.process_cache.lock().await;
+        let mut delta = ProcessDelta::default();
+        let mut active_pids = HashSet::new();
+
+        // Collect process data first while holding the lock
+        let system_processes: Vec<RawProcess> = {
+            let system = self.system.lock().await;
+            system
+                .processes()
+                .iter()
+                .map(|(pid, process)| {
+                    let disk_usage = process.disk_usage();
+                    RawProcess {
+                        pid: *pid,
+                        cmd: process.cmd().to_vec(),
+                        name: process.name().to_string(),
+                        cpu_usage: process.cpu_usage(),
+                        memory: process.memory(),
+                        virtual_memory: process.virtual_memory(),
+                        memory_breakdown: read_memory_breakdown(pid.as_u32()),
+                        status: process.status(),
+                        run_time: process.run_time(),
+                        started_at: process.start_time(),
+                        parent: process.parent(),
+                        nice: control::get_nice(pid.as_u32()),
+                        scheduling: control::get_scheduling(pid.as_u32()),
+                        energy: energy::read_energy_totals(pid.as_u32()),
+                        disk_read_total: disk_usage.total_read_bytes,
+                        disk_write_total: disk_usage.total_written_bytes,
+                    }
+                })
+                .collect()
+        };
+
+        // Users missing from the cache (and thread counts, while at it) come
+        // from a single `ps` call for every process
+        if is_full_refresh {
+            let mut user_cache = self.user_cache.lock().await;
+            user_cache.expire();
+            if system_processes
+                .iter()
+                .any(|raw| !user_cache.contains(raw.pid.as_u32()))
+            {
+                let table = task::spawn_blocking(read_ps_table)
+                    .await
+                    .unwrap_or_default();
+                let mut thread_cache = self.thread_cache.lock().await;
+                thread_cache.expire();
+                for (pid, (user, threads)) in table {
+                    user_cache.cache.insert(pid, user);
+                    if let Some(threads) = threads {
+                        thread_cache.cache.insert(pid, threads);
+                    }
+                }
+            }
+        }
+
+        // Per-process socket traffic comes from a single external tool call
+        let net_totals = task::spawn_blocking(bandwidth::read_process_net_totals)
+            .await
+            .ok()
+            .flatten();
+        // The kernel statistics come from the eBPF programs when they're
+        // loaded, or procfs and ss otherwise
+        let probed = match &self.kernel_probes {
+            Some(probes) => {
+                let probes = probes.clone();
+                task::spawn_blocking(move || probes.blocking_lock().read())
+                    .await
+                    .ok()
+            }
+            None => None,
+        };
+        let retransmits = if self.kernel_stats && !self.probed_retransmits {
+            task::spawn_blocking(kernel_stats::read_tcp_retransmits)
+                .await
+                .ok()
+                .flatten()
+        } else {
+            None
+        };
+        let delay_accounting =
+            self.kernel_stats && probed.is_none() && kernel_stats::delay_accounting();
+
+        // Process in batches to avoid blocking for too long
+        for chunk in system_processes.chunks(BATCH_SIZE) {
+            for raw in chunk {
+                let pid_u32 = raw.pid.as_u32();
+                active_pids.insert(pid_u32);
+                let net_io = net_totals
+                    .as_ref()
+                    .map(|totals| totals.get(&pid_u32).copied().unwrap_or((0, 0)));
+                let gpu_memory = gpu_memory
+                    .as_ref()
+                    .and_then(|memory| memory.get(&pid_u32).copied());
+                let counters = probed
+                    .as_ref()
+                    .map(|probed| probed.get(&pid_u32).copied().unwrap_or_default());
+                let (syscalls, blkio_delay, blkio_requests) = match counters {
+                    Some(counters) => (
+                        Some(counters.syscalls),
+                        Some(counters.block_io_ns / 1000),
+                        Some(counters.block_io_requests),
+                    ),
+                    None if self.kernel_stats => (
+                        kernel_stats::read_syscalls(pid_u32),
+                        delay_accounting
+                            .then(|| kernel_stats::read_blkio_delay(pid_u32))
+                            .flatten(),
+                        None,
+                    ),
+                    None => (None, None, None),
+                };
+                let tcp_retransmits = match counters {
+                    Some(counters) if self.probed_retransmits => Some(counters.tcp_retransmits),
+                    _ => retransmits
+                        .as_ref()
+                        .map(|totals| totals.get(&pid_u32).copied().unwrap_or(0)),
+                };
+
+                // Convert status
+                let status = match raw.status {
+                    sysinfo::ProcessStatus::Run => ProcessStatus::Running,
+                    sysinfo::ProcessStatus::Sleep => ProcessStatus::Sleeping,
+                    sysinfo::ProcessStatus::Stop => ProcessStatus::Stopped,
+                    sysinfo::ProcessStatus::Zombie => ProcessStatus::Zombie,
+                    _ => ProcessStatus::Unknown,
+                };
+
+                // Only fetch expensive information on full refresh
+                let (user, threads, parent_pid, container, unit) =
+                    if is_full_refresh || !process_cache.contains_key(&pid_u32) {
+                        let user = if is_full_refresh {
+                            self.user_cache.lock().await.get_user(pid_u32)
+                        } else {
+                            "fetching...".to_string()
+                        };
+
+                        let threads = if is_full_refresh {
+                            let mut thread_cache = self.thread_cache.lock().await;
+                            thread_cache.get_thread_count(pid_u32)
+                        } else {
+                            None
+                        };
+
+                        let container = match containers::container_id(pid_u32) {
+                            Some(id) => Some(self.container_names.lock().await.name(&id)),
+                            None => None,
+                        };
+
+                        let unit = systemd::unit(pid_u32).map(|u| u.name);
+
+                        (
+                            user,
+                            threads,
+                            raw.parent.map(|p| p.as_u32()),
+                            container,
+                            unit,
+                        )
+                    } else if let Some(cached) = process_cache.get(&pid_u32) {
+                        (
+                            cached.user.clone(),
+                            cached.threads,
+                            cached.parent,
+                            cached.container.clone(),
+                            cached.unit.clone(),
+                        )
+                    } else {
+                        ("unknown".to_string(), None, None, None, None)
+                    };
+
+                let proportional = if self.proportional_memory && is_full_refresh {
+                    read_proportional_memory(pid_u32)
+                } else {
+                    None
+                };
+                let numa_node = if self.numa && is_full_refresh {
+                    numa::preferred_node(pid_u32)
+                } else {
+                    None
+                };
+                let (fds, fd_limit) = if is_full_refresh {
+                    read_fd_usage(pid_u32)
+                } else {
+                    (None, None)
+                };
+
+                // Update existing process or create new
+                if let Some(cached_process) = process_cache.get_mut(&pid_u32) {
+                    cached_process.update_disk_io(raw.disk_read_total, raw.disk_write_total);
+                    cached_process.update_net_io(net_io);
+                    cached_process.update_energy(raw.energy);
+                    cached_process.update_kernel_stats(
+                        syscalls,
+                        blkio_delay,
+                        blkio_requests,
+                        tcp_retransmits,
+                    );
+                    cached_process.gpu_memory = gpu_memory;
+                    cached_process.update_history(raw.cpu_usage, raw.memory);
+                    cached_process
+                        .update_memory_breakdown(raw.virtual_memory, raw.memory_breakdown);
+                    cached_process.start_time = Duration::from_secs(raw.run_time);
+                    cached_process.nice = raw.nice;
+                    cached_process.set_scheduling(raw.scheduling);
+
+                    // Only update these fields on full refresh
+                    if is_full_refresh {
+                        cached_process.status = status;
+                        cached_process.user = user;
+                        cached_process.threads = threads;
+                        cached_process.pss = proportional.map(|(pss, _)| pss);
+                        cached_process.uss = proportional.map(|(_, uss)| uss);
+                        cached_process.numa_node = numa_node;
+                        cached_process.fds = fds;
+                        cached_process.fd_limit = fd_limit;
+                        cached_process.parent = parent_pid;
+                        cached_process.container = container;
+                        cached_process.unit = unit;
+                        cached_process.cmd = raw.cmd.clone();
+                    }
+
+                    delta
+                        .updated
+                        .push(ProcessChange::new(cached_process, is_full_refresh));
+                } else {
+                    // New process
+                    let mut process_info = ProcessInfo::new(
+                        pid_u32,
+                        raw.name.clone(),
+                        raw.cpu_usage,
+                        raw.memory,
+                        status,
+                        user,
+                        Duration::from_secs(raw.run_time),
+                        raw.cmd.clone(),
+                        threads,
+                        parent_pid,
+                    );
+                    process_info.started_at = raw.started_at;
+                    process_info.update_memory_breakdown(raw.virtual_memory, raw.memory_breakdown);
+                    process_info.pss = proportional.map(|(pss, _)| pss);
+                    process_info.uss = proportional.map(|(_, uss)| uss);
+                    process_info.numa_node = numa_node;
+                    process_info.fds = fds;
+                    process_info.fd_limit = fd_limit;
+                    process_info.nice = raw.nice;
+                    process_info.set_scheduling(raw.scheduling);
+                    process_info.container = container;
+                    process_info.unit = unit;
+                    process_info.gpu_memory = gpu_memory;
+                    process_info.disk_read_total = raw.disk_read_total;
+                    process_info.disk_write_total = raw.disk_write_total;
+                    if let Some((energy_total, wakeups_total)) = raw.energy {
+                        process_info.power = Some(0.0);
+                        process_info.wakeups_rate = Some(0.0);
+                        process_info.energy_total = energy_total;
+                        process_info.wakeups_total = wakeups_total;
+                    }
+                    if let Some((rx_total, tx_total)) = net_io {
+                        process_info.net_rx_rate = Some(0);
+                        process_info.net_tx_rate = Some(0);
+                        process_info.net_rx_total = rx_total;
+                        process_info.net_tx_total = tx_total;
+                    }
+                    // Rates start from the next sample, as the totals do
+                    process_info.syscall_rate = syscalls.map(|_| 0);
+                    process_info.blkio_delay_rate = blkio_delay.map(|_| 0);
+                    process_info.tcp_retransmit_rate = tcp_retransmits.map(|_| 0);
+                    process_info.blkio_latency = blkio_requests.map(|_| 0);
+                    process_info.syscall_total = syscalls.unwrap_or(0);
+                    process_info.blkio_delay_total = blkio_delay.unwrap_or(0);
+                    process_info.blkio_request_total = blkio_requests.unwrap_or(0);
+                    process_info.tcp_retransmit_total = tcp_retransmits.unwrap_or(0);
+                    process_cache.insert(pid_u32, process_info.clone());
+                    delta.added.push(process_info);
+                }
+            }
+
+            // Small delay between batches to avoid blocking UI
+            if chunk.len() == BATCH_SIZE {
+                // No need to drop system here anymore
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }
+
+        // Clean up processes that no longer exist
+        process_cache.retain(|pid, _| {
+            let alive = active_pids.contains(pid);
+            if !alive {
+                delta.removed.push(*pid);
+            }
+            alive
+        });
+
+        delta
+    }
+}
+
+fn write_csv_rows<'a>(
+    writer: &mut impl Write,
+    processes: impl IntoIterator<Item = &'a ProcessInfo>,
+) -> io::Result<()> {
+    let timestamp = chrono::Local::now().to_rfc3339();
+    for p in processes {
+        writeln!(
+            writer,
+            "{},{},\"{}\",{:.2},{},{}",
+            timestamp,
+            p.pid,
+            p.name.replace('"', "\"\""),
+            p.cpu_usage,
+            p.memory,
+            p.status
+        )?;
+    }
+    writer.flush()
+}
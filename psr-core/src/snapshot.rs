@@ -0,0 +1,164 @@
+use crate::filter::Filter;
+use crate::gpu::GpuInfo;
+use crate::numa::NumaNode;
+use crate::pressure::Pressure;
+use crate::processes::{
+    CollectorStats, CoreInfo, DiskInfo, NetworkInterface, ProcessInfo, ProcessUpdate, SystemLoad,
+};
+use crate::sort::{self, SortBy};
+use std::collections::HashMap;
+
+/// The latest state reported by a [`ProcessMonitor`](crate::ProcessMonitor),
+/// built up by applying its updates as they arrive.
+#[derive(Clone, Default)]
+pub struct Snapshot {
+    /// Processes by PID
+    pub processes: HashMap<u32, ProcessInfo>,
+    /// Total CPU usage, in percent
+    pub cpu_usage: f32,
+    /// Memory in use and installed, in bytes
+    pub used_memory: u64,
+    pub total_memory: u64,
+    /// Swap in use and configured, in bytes
+    pub used_swap: u64,
+    pub total_swap: u64,
+    /// Logical CPU cores, which per-process CPU usage is spread over
+    pub cpu_count: usize,
+    /// Usage and clock speed of each core
+    pub cores: Vec<CoreInfo>,
+    /// Load averages and uptime, once the monitor has reported them
+    pub load: Option<SystemLoad>,
+    /// The latest pressure stall figures, where the system reports them
+    pub pressure: Option<Pressure>,
+    /// Memory per NUMA node, empty unless there are several
+    pub numa_nodes: Vec<NumaNode>,
+    pub networks: Vec<NetworkInterface>,
+    pub disks: Vec<DiskInfo>,
+    /// GPUs, empty where there are none
+    pub gpus: Vec<GpuInfo>,
+    /// The monitor's last progress message, if any
+    pub status: Option<String>,
+    /// What the collector's last refresh cost, if it reported it
+    pub collector: Option<CollectorStats>,
+}
+
+impl Snapshot {
+    /// Fold one update into the snapshot.
+    pub fn apply(&mut self, update: ProcessUpdate) {
+        match update {
+            ProcessUpdate::ProcessList(processes) => {
+                self.processes = processes.into_iter().map(|p| (p.pid, p)).collect();
+            }
+            ProcessUpdate::ProcessDelta(delta) => {
+                delta.apply(&mut self.processes);
+            }
+            ProcessUpdate::SystemInfo(cpu_usage, used_memory, total_memory, cpu_count) => {
+                self.cpu_usage = cpu_usage;
+                self.used_memory = used_memory;
+                self.total_memory = total_memory;
+                self.cpu_count = cpu_count;
+            }
+            ProcessUpdate::SwapInfo(used_swap, total_swap) => {
+                self.used_swap = used_swap;
+                self.total_swap = total_swap;
+            }
+            ProcessUpdate::CoreInfo(cores) => self.cores = cores,
+            ProcessUpdate::NetworkInfo(networks) => self.networks = networks,
+            ProcessUpdate::DiskInfo(disks) => self.disks = disks,
+            ProcessUpdate::GpuInfo(gpus) => self.gpus = gpus,
+            ProcessUpdate::LoadingStatus(status) => self.status = Some(status),
+            ProcessUpdate::CollectorStats(stats) => self.collector = Some(stats),
+            ProcessUpdate::SystemLoad(load) => self.load = Some(load),
+            ProcessUpdate::Pressure(pressure) => self.pressure = Some(pressure),
+            ProcessUpdate::NumaInfo(nodes) => self.numa_nodes = nodes,
+        }
+    }
+
+    /// Updates that bring a receiver with nothing up to this snapshot, as
+    /// for a client that connects after the monitor started.
+    pub fn updates(&self) -> Vec<ProcessUpdate> {
+        let mut updates: Vec<ProcessUpdate> = self
+            .status
+            .iter()
+            .cloned()
+            .map(ProcessUpdate::LoadingStatus)
+            .collect();
+        if self.total_memory > 0 {
+            updates.push(ProcessUpdate::SystemInfo(
+                self.cpu_usage,
+                self.used_memory,
+                self.total_memory,
+                self.cpu_count,
+            ));
+        }
+        if self.total_swap > 0 {
+            updates.push(ProcessUpdate::SwapInfo(self.used_swap, self.total_swap));
+        }
+        if !self.cores.is_empty() {
+            updates.push(ProcessUpdate::CoreInfo(self.cores.clone()));
+        }
+        if let Some(load) = self.load {
+            updates.push(ProcessUpdate::SystemLoad(load));
+        }
+        if let Some(pressure) = self.pressure {
+            updates.push(ProcessUpdate::Pressure(pressure));
+        }
+        if !self.numa_nodes.is_empty() {
+            updates.push(ProcessUpdate::NumaInfo(self.numa_nodes.clone()));
+        }
+        if !self.networks.is_empty() {
+            updates.push(ProcessUpdate::NetworkInfo(self.networks.clone()));
+        }
+        if !self.disks.is_empty() {
+            updates.push(ProcessUpdate::DiskInfo(self.disks.clone()));
+        }
+        if !self.gpus.is_empty() {
+            updates.push(ProcessUpdate::GpuInfo(self.gpus.clone()));
+        }
+        if !self.processes.is_empty() {
+            updates.push(ProcessUpdate::ProcessList(
+                self.processes.values().cloned().collect(),
+            ));
+        }
+        if let Some(stats) = self.collector {
+            updates.push(ProcessUpdate::CollectorStats(stats));
+        }
+        updates
+    }
+
+    /// Like [`updates`](Self::updates), but moving the processes out rather
+    /// than copying them.
+    pub fn into_updates(mut self) -> Vec<ProcessUpdate> {
+        let processes = std::mem::take(&mut self.processes);
+        let mut updates = self.updates();
+        if !processes.is_empty() {
+            // In the same place as updates() puts them, before the collector stats
+            let at = updates.len() - usize::from(self.collector.is_some());
+            updates.insert(
+                at,
+                ProcessUpdate::ProcessList(processes.into_values().collect()),
+            );
+        }
+        updates
+    }
+
+    pub fn find(&self, pid: u32) -> Option<&ProcessInfo> {
+        self.processes.get(&pid)
+    }
+
+    /// The processes ordered by one field.
+    pub fn sorted(&self, by: SortBy, ascending: bool) -> Vec<ProcessInfo> {
+        let mut processes: Vec<ProcessInfo> = self.processes.values().cloned().collect();
+        sort::sort_processes(&mut processes, by, ascending);
+        processes
+    }
+
+    /// The processes a filter matches.
+    pub fn filtered(&self, filter: &Filter) -> Vec<ProcessInfo> {
+        self.processes
+            .values()
+            .filter(|p| filter.matches(p))
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,115 @@
+use std::cmp::Ordering;
+
+use crate::processes::ProcessInfo;
+
+/// A process field to order by.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortBy {
+    Pid,
+    Name,
+    Cpu,
+    Memory,
+    VirtualMemory,
+    SharedMemory,
+    Swap,
+    Pss,
+    Uss,
+    Status,
+    User,
+    StartTime,
+    /// When the process started, earliest first: the reverse of
+    /// `StartTime`, which orders by how long it has run
+    StartedAt,
+    DiskRead,
+    DiskWrite,
+    NetDown,
+    NetUp,
+    GpuMemory,
+    /// NUMA node holding most of the memory
+    NumaNode,
+    Nice,
+    /// Open file descriptors
+    Fds,
+    /// Scheduling policy, then realtime priority within it
+    Scheduling,
+    Power,
+    /// System calls per second
+    Syscalls,
+    /// Time spent waiting for block I/O per second
+    BlkioDelay,
+    /// Average time a block I/O request takes
+    BlkioLatency,
+    TcpRetransmits,
+    Threads,
+    /// Wakeups per second (macOS only)
+    Wakeups,
+    /// Parent PID
+    Parent,
+    /// The command line, argument by argument
+    Command,
+    Container,
+    /// systemd unit
+    Unit,
+}
+
+impl SortBy {
+    /// How `a` orders against `b`, smallest first.
+    pub fn compare(self, a: &ProcessInfo, b: &ProcessInfo) -> Ordering {
+        match self {
+            SortBy::Pid => a.pid.cmp(&b.pid),
+            SortBy::Name => a.name.cmp(&b.name),
+            SortBy::Cpu => a
+                .cpu_usage
+                .partial_cmp(&b.cpu_usage)
+                .unwrap_or(Ordering::Equal),
+            SortBy::Memory => a.memory.cmp(&b.memory),
+            SortBy::VirtualMemory => a.virtual_memory.cmp(&b.virtual_memory),
+            SortBy::SharedMemory => a.shared_memory.cmp(&b.shared_memory),
+            SortBy::Swap => a.swap.cmp(&b.swap),
+            SortBy::Pss => a.pss.cmp(&b.pss),
+            SortBy::Uss => a.uss.cmp(&b.uss),
+            SortBy::Status => a.status.cmp(&b.status),
+            SortBy::User => a.user.cmp(&b.user),
+            SortBy::StartTime => a.start_time.cmp(&b.start_time),
+            SortBy::StartedAt => a.started_at.cmp(&b.started_at),
+            SortBy::DiskRead => a.disk_read_rate.cmp(&b.disk_read_rate),
+            SortBy::DiskWrite => a.disk_write_rate.cmp(&b.disk_write_rate),
+            SortBy::NetDown => a.net_rx_rate.cmp(&b.net_rx_rate),
+            SortBy::NetUp => a.net_tx_rate.cmp(&b.net_tx_rate),
+            SortBy::GpuMemory => a.gpu_memory.cmp(&b.gpu_memory),
+            SortBy::NumaNode => a.numa_node.cmp(&b.numa_node),
+            SortBy::Nice => a.nice.cmp(&b.nice),
+            SortBy::Fds => a.fds.cmp(&b.fds),
+            SortBy::Scheduling => {
+                (a.sched_policy, a.rt_priority).cmp(&(b.sched_policy, b.rt_priority))
+            }
+            SortBy::Power => a.power.partial_cmp(&b.power).unwrap_or(Ordering::Equal),
+            SortBy::Syscalls => a.syscall_rate.cmp(&b.syscall_rate),
+            SortBy::BlkioDelay => a.blkio_delay_rate.cmp(&b.blkio_delay_rate),
+            SortBy::BlkioLatency => a.blkio_latency.cmp(&b.blkio_latency),
+            SortBy::TcpRetransmits => a.tcp_retransmit_rate.cmp(&b.tcp_retransmit_rate),
+            SortBy::Threads => a.threads.cmp(&b.threads),
+            SortBy::Wakeups => a
+                .wakeups_rate
+                .partial_cmp(&b.wakeups_rate)
+                .unwrap_or(Ordering::Equal),
+            SortBy::Parent => a.parent.cmp(&b.parent),
+            SortBy::Command => a.cmd.cmp(&b.cmd),
+            SortBy::Container => a.container.cmp(&b.container),
+            SortBy::Unit => a.unit.cmp(&b.unit),
+        }
+    }
+}
+
+/// Sort `processes` by one field. The sort is stable, so processes that
+/// compare equal keep their relative order.
+pub fn sort_processes(processes: &mut [ProcessInfo], by: SortBy, ascending: bool) {
+    processes.sort_by(|a, b| {
+        let ordering = by.compare(a, b);
+        if ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    });
+}
@@ -0,0 +1,197 @@
+use std::collections::VecDeque;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::processes::{ProcessInfo, ProcessMonitor, ProcessUpdate};
+
+/// Somewhere process updates come from: the live system, a recording, or
+/// made-up data. Consumers poll it from their own loop rather than waiting
+/// on it, so a UI stays responsive while nothing new has arrived.
+pub trait ProcessSource: Send {
+    /// The next update if one is ready, without waiting.
+    fn try_next(&mut self) -> Option<ProcessUpdate>;
+
+    /// Ask for a fresh sample as soon as possible. Sources that can't sample
+    /// on demand ignore it.
+    fn request_refresh(&mut self) {}
+}
+
+/// Updates arriving over a channel, from a [`ProcessMonitor`] running on its
+/// own task or anything else that produces them in the background.
+pub struct ChannelSource {
+    updates: mpsc::Receiver<ProcessUpdate>,
+    refresh: Option<mpsc::Sender<()>>,
+}
+
+impl ChannelSource {
+    /// A source for `updates`, where sending on `refresh` (if there is one)
+    /// asks the producer for a fresh sample.
+    pub fn new(updates: mpsc::Receiver<ProcessUpdate>, refresh: Option<mpsc::Sender<()>>) -> Self {
+        Self { updates, refresh }
+    }
+
+    /// Wait for the next update; None once the producer has gone away.
+    pub async fn recv(&mut self) -> Option<ProcessUpdate> {
+        self.updates.recv().await
+    }
+}
+
+impl ProcessSource for ChannelSource {
+    fn try_next(&mut self) -> Option<ProcessUpdate> {
+        self.updates.try_recv().ok()
+    }
+
+    fn request_refresh(&mut self) {
+        if let Some(refresh) = &self.refresh {
+            let _ = refresh.try_send(());
+        }
+    }
+}
+
+impl ProcessMonitor {
+    /// Sample this machine every `refresh_interval` on a background task,
+    /// optionally appending each sample to a CSV file (see
+    /// [`set_csv_log`](ProcessMonitor::set_csv_log)), reading PSS/USS (see
+    /// [`set_proportional_memory`](ProcessMonitor::set_proportional_memory))
+    /// and kernel statistics (see
+    /// [`set_kernel_stats`](ProcessMonitor::set_kernel_stats)).
+    /// Must be called inside a Tokio runtime.
+    pub fn spawn(
+        refresh_interval: Duration,
+        csv_log: Option<&Path>,
+        proportional_memory: bool,
+        kernel_stats: bool,
+    ) -> io::Result<ChannelSource> {
+        let (tx, rx) = mpsc::channel(100);
+        let (mut monitor, refresh) = ProcessMonitor::new(tx, refresh_interval);
+        if let Some(path) = csv_log {
+            monitor.set_csv_log(path)?;
+        }
+        monitor.set_proportional_memory(proportional_memory);
+        monitor.set_kernel_stats(kernel_stats);
+        tokio::spawn(monitor.start_monitoring());
+        Ok(ChannelSource::new(rx, Some(refresh)))
+    }
+}
+
+/// Plays back recorded updates in order. With an interval, each process list
+/// or delta is held back until that long after the previous one, so a recording plays
+/// at roughly the pace it was made; without one everything is available at
+/// once.
+pub struct ReplaySource {
+    updates: VecDeque<ProcessUpdate>,
+    interval: Option<Duration>,
+    last_list: Option<Instant>,
+}
+
+impl ReplaySource {
+    pub fn new(updates: impl IntoIterator<Item = ProcessUpdate>) -> Self {
+        Self {
+            updates: updates.into_iter().collect(),
+            interval: None,
+            last_list: None,
+        }
+    }
+
+    pub fn paced(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Whether every update has been played.
+    pub fn is_finished(&self) -> bool {
+        self.updates.is_empty()
+    }
+}
+
+impl ProcessSource for ReplaySource {
+    fn try_next(&mut self) -> Option<ProcessUpdate> {
+        if let Some(ProcessUpdate::ProcessList(_) | ProcessUpdate::ProcessDelta(_)) =
+            self.updates.front()
+        {
+            let due = match (self.interval, self.last_list) {
+                (Some(interval), Some(last)) => last.elapsed() >= interval,
+                _ => true,
+            };
+            if !due {
+                return None;
+            }
+            self.last_list = Some(Instant::now());
+        }
+        self.updates.pop_front()
+    }
+}
+
+/// A fixed set of processes and system figures, for tests. It reports them
+/// on the first poll and again after every refresh request or change.
+pub struct MockSource {
+    processes: Vec<ProcessInfo>,
+    cpu_usage: f32,
+    used_memory: u64,
+    total_memory: u64,
+    cpu_count: usize,
+    pending: VecDeque<ProcessUpdate>,
+}
+
+impl MockSource {
+    pub fn new(processes: Vec<ProcessInfo>) -> Self {
+        let mut source = Self {
+            processes,
+            cpu_usage: 0.0,
+            used_memory: 0,
+            total_memory: 0,
+            cpu_count: 1,
+            pending: VecDeque::new(),
+        };
+        source.request_refresh();
+        source
+    }
+
+    /// Report these system-wide figures (CPU in percent, memory in bytes).
+    pub fn with_system(mut self, cpu_usage: f32, used_memory: u64, total_memory: u64) -> Self {
+        self.cpu_usage = cpu_usage;
+        self.used_memory = used_memory;
+        self.total_memory = total_memory;
+        self.pending.clear();
+        self.request_refresh();
+        self
+    }
+
+    /// Report this many CPU cores (one unless set).
+    pub fn with_cpu_count(mut self, cpu_count: usize) -> Self {
+        self.cpu_count = cpu_count;
+        self.pending.clear();
+        self.request_refresh();
+        self
+    }
+
+    /// Replace the processes, reporting the new list on the next poll.
+    pub fn set_processes(&mut self, processes: Vec<ProcessInfo>) {
+        self.processes = processes;
+        self.request_refresh();
+    }
+
+    /// Queue an arbitrary update, such as a network or disk sample.
+    pub fn push(&mut self, update: ProcessUpdate) {
+        self.pending.push_back(update);
+    }
+}
+
+impl ProcessSource for MockSource {
+    fn try_next(&mut self) -> Option<ProcessUpdate> {
+        self.pending.pop_front()
+    }
+
+    fn request_refresh(&mut self) {
+        self.pending.push_back(ProcessUpdate::SystemInfo(
+            self.cpu_usage,
+            self.used_memory,
+            self.total_memory,
+            self.cpu_count,
+        ));
+        self.pending
+            .push_back(ProcessUpdate::ProcessList(self.processes.clone()));
+    }
+}
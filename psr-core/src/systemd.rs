@@ -0,0 +1,113 @@
+use std::io;
+use std::process::{Command, Stdio};
+
+/// The systemd unit a process belongs to, from its cgroup path. Services run by
+/// a user's own systemd instance (under user@UID.service) are user units and
+/// are managed with `systemctl --user`.
+pub struct Unit {
+    pub name: String,
+    pub user: bool,
+}
+
+#[cfg(target_os = "linux")]
+pub fn unit(pid: u32) -> Option<Unit> {
+    let cgroups = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    // hierarchy-ID:controllers:path, where systemd keeps its tree in the
+    // unified hierarchy on cgroup v2 and in "name=systemd" on v1
+    cgroups.lines().find_map(|line| {
+        let mut fields = line.splitn(3, ':');
+        let controllers = fields.nth(1)?;
+        let path = fields.next()?;
+        if controllers.is_empty() || controllers == "name=systemd" {
+            unit_from_cgroup_path(path)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn unit(_pid: u32) -> Option<Unit> {
+    None
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn unit_from_cgroup_path(path: &str) -> Option<Unit> {
+    let segments: Vec<&str> = path.split('/').collect();
+    let index = segments
+        .iter()
+        .rposition(|s| s.ends_with(".service") || s.ends_with(".scope"))?;
+    let user = segments[..index].iter().any(|s| s.starts_with("user@"));
+    Some(Unit {
+        name: segments[index].to_string(),
+        user,
+    })
+}
+
+/// Run `systemctl <action>` on a unit. --no-block queues the job instead of
+/// waiting for slow services to finish stopping or starting, and
+/// --no-ask-password fails straight away when polkit would want a password,
+/// which it would otherwise ask for on the terminal underneath the TUI.
+pub fn systemctl(action: &str, unit: &Unit) -> io::Result<()> {
+    let mut command = Command::new("systemctl");
+    if unit.user {
+        command.arg("--user");
+    }
+    let output = command
+        .args(["--no-block", "--no-ask-password", action, &unit.name])
+        .stdin(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr.lines().next().unwrap_or("systemctl failed");
+        // "Failed to stop x.service: Interactive authentication required."
+        // repeats what the caller already says
+        let message = message
+            .strip_prefix("Failed to ")
+            .and_then(|rest| rest.split_once(": "))
+            .map_or(message, |(_, reason)| reason);
+        return Err(io::Error::other(message.to_string()));
+    }
+    Ok(())
+}
+
+/// Most recent journal lines, newest first, for a service unit (covering every
+/// process it has run) or otherwise for the single PID
+#[cfg(target_os = "linux")]
+pub fn journal(pid: u32, unit: Option<&str>, lines: usize) -> io::Result<Vec<String>> {
+    let mut command = Command::new("journalctl");
+    command.args(["--no-pager", "--reverse", "--output=short-iso", "--lines"]);
+    command.arg(lines.to_string());
+    match unit.filter(|u| u.ends_with(".service")) {
+        Some(unit) => command.arg(format!("_SYSTEMD_UNIT={}", unit)),
+        None => command.arg(format!("_PID={}", pid)),
+    };
+
+    let output = command.output().map_err(|e| {
+        if e.kind() == io::ErrorKind::NotFound {
+            io::Error::new(e.kind(), "journalctl is not installed")
+        } else {
+            e
+        }
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr.lines().next().unwrap_or("journalctl failed");
+        return Err(io::Error::other(message.to_string()));
+    }
+
+    // Skip journalctl's "-- No entries --" style markers
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.starts_with("-- "))
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn journal(_pid: u32, _unit: Option<&str>, _lines: usize) -> io::Result<Vec<String>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the journal is only available on Linux",
+    ))
+}
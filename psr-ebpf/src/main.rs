@@ -0,0 +1,122 @@
+// The kernel side of psr-core's optional eBPF collector: per-process counts
+// of system calls, block I/O requests and the time they took, and TCP
+// retransmits. psr-core's build script compiles this for the BPF target and
+// src/ebpf.rs there loads it; on the host it builds to an empty program so
+// the workspace still checks it.
+#![cfg_attr(target_arch = "bpf", no_std, no_main)]
+
+use aya_ebpf::{
+    helpers::{bpf_get_current_pid_tgid, bpf_ktime_get_ns},
+    macros::{kprobe, map, raw_tracepoint},
+    maps::{LruHashMap, LruPerCpuHashMap},
+    programs::{ProbeContext, RawTracePointContext},
+};
+
+// The totals are kept per CPU, so they're added to without atomics and
+// psr-core sums them. Being LRU maps, a full one drops the process that's
+// been quiet longest rather than refusing new ones.
+const PROCESSES: u32 = 32768;
+
+// Keyed by PID (the kernel's thread group ID)
+#[map]
+static SYSCALLS: LruPerCpuHashMap<u32, u64> = LruPerCpuHashMap::with_max_entries(PROCESSES, 0);
+#[map]
+static BLOCK_IO_NS: LruPerCpuHashMap<u32, u64> = LruPerCpuHashMap::with_max_entries(PROCESSES, 0);
+#[map]
+static BLOCK_IO_REQUESTS: LruPerCpuHashMap<u32, u64> =
+    LruPerCpuHashMap::with_max_entries(PROCESSES, 0);
+#[map]
+static RETRANSMITS: LruPerCpuHashMap<u32, u64> = LruPerCpuHashMap::with_max_entries(PROCESSES, 0);
+
+// Block requests in flight and the process that last sent on each TCP
+// socket, both by the kernel's address for them
+#[map]
+static REQUESTS: LruHashMap<u64, Request> = LruHashMap::with_max_entries(16384, 0);
+#[map]
+static SOCKET_OWNERS: LruHashMap<u64, u32> = LruHashMap::with_max_entries(PROCESSES, 0);
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Request {
+    started: u64,
+    pid: u32,
+}
+
+fn current_pid() -> u32 {
+    (bpf_get_current_pid_tgid() >> 32) as u32
+}
+
+fn add(totals: &LruPerCpuHashMap<u32, u64>, pid: u32, value: u64) {
+    match totals.get_ptr_mut(pid) {
+        Some(total) => unsafe { *total += value },
+        None => {
+            let _ = totals.insert(pid, value, 0);
+        }
+    }
+}
+
+#[raw_tracepoint(tracepoint = "sys_enter")]
+pub fn sys_enter(_ctx: RawTracePointContext) -> u32 {
+    add(&SYSCALLS, current_pid(), 1);
+    0
+}
+
+// A request starts in the process submitting it, but usually completes in an
+// interrupt, so it's remembered until then. These tracepoints are Linux 6.5
+// and later.
+#[raw_tracepoint(tracepoint = "block_io_start")]
+pub fn block_io_start(ctx: RawTracePointContext) -> u32 {
+    let request = Request {
+        started: unsafe { bpf_ktime_get_ns() },
+        pid: current_pid(),
+    };
+    let _ = REQUESTS.insert(ctx.arg::<u64>(0), request, 0);
+    0
+}
+
+#[raw_tracepoint(tracepoint = "block_io_done")]
+pub fn block_io_done(ctx: RawTracePointContext) -> u32 {
+    let address: u64 = ctx.arg(0);
+    let Some(request) = (unsafe { REQUESTS.get(address) }).copied() else {
+        return 0;
+    };
+    let _ = REQUESTS.remove(address);
+    let took = unsafe { bpf_ktime_get_ns() }.saturating_sub(request.started);
+    add(&BLOCK_IO_NS, request.pid, took);
+    add(&BLOCK_IO_REQUESTS, request.pid, 1);
+    0
+}
+
+// Retransmits mostly happen on a timer, outside the process, so they're
+// counted against whoever last sent on the socket
+#[kprobe(function = "tcp_sendmsg")]
+pub fn tcp_sendmsg(ctx: ProbeContext) -> u32 {
+    if let Some(socket) = ctx.arg::<u64>(0) {
+        let _ = SOCKET_OWNERS.insert(socket, current_pid(), 0);
+    }
+    0
+}
+
+#[raw_tracepoint(tracepoint = "tcp_retransmit_skb")]
+pub fn tcp_retransmit_skb(ctx: RawTracePointContext) -> u32 {
+    let socket: u64 = ctx.arg(0);
+    if let Some(&pid) = unsafe { SOCKET_OWNERS.get(socket) } {
+        add(&RETRANSMITS, pid, 1);
+    }
+    0
+}
+
+#[cfg(target_arch = "bpf")]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+// Tracing programs can only be loaded under a GPL-compatible license
+#[cfg(target_arch = "bpf")]
+#[unsafe(link_section = "license")]
+#[unsafe(no_mangle)]
+static LICENSE: [u8; 13] = *b"Dual MIT/GPL\0";
+
+#[cfg(not(target_arch = "bpf"))]
+fn main() {}
@@ -0,0 +1,232 @@
+use psr_core::{ProcessMonitor, ProcessSource, ProcessUpdate, Snapshot};
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc, Mutex};
+
+use crate::cli::Args;
+
+// Remote monitoring: an agent runs the collector headless and streams every
+// ProcessUpdate to connected TUIs. After a short handshake, each update is
+// sent as a 4-byte big-endian length followed by the bincode-encoded update.
+// Process lists after the first arrive as deltas, so a client starts from a
+// full snapshot of the agent's state.
+
+// The handshake is "PSR", a zero byte and the protocol version as a 4-byte
+// big-endian number. bincode doesn't encode field names, so a peer expecting
+// another layout would misread every update: the version goes up with every
+// change to ProcessUpdate or anything it carries.
+const MAGIC: &[u8; 4] = b"PSR\0";
+const PROTOCOL_VERSION: u32 = 1;
+
+fn handshake() -> [u8; 8] {
+    let mut handshake = [0u8; 8];
+    handshake[..4].copy_from_slice(MAGIC);
+    handshake[4..].copy_from_slice(&PROTOCOL_VERSION.to_be_bytes());
+    handshake
+}
+
+// Larger frames mean a corrupt stream rather than a real process list
+const MAX_FRAME: usize = 64 * 1024 * 1024;
+
+// An encoded update, shared between the clients it's sent to
+type Frame = Arc<Vec<u8>>;
+
+pub async fn run(addr: &str, args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("cannot listen on {}: {}", addr, e))?;
+
+    let (tx, mut rx) = mpsc::channel(100);
+    let (mut process_monitor, _refresh_sender) =
+        ProcessMonitor::new(tx, Duration::from_millis(args.refresh_ms));
+    process_monitor.set_proportional_memory(args.pss);
+    process_monitor.set_kernel_stats(args.kernel_stats);
+    tokio::spawn(async move {
+        process_monitor.start_monitoring().await;
+    });
+
+    // New clients are sent the snapshot before the deltas that follow it.
+    // Updates are applied and broadcast under its lock, so a client sees
+    // each of them exactly once.
+    let (frames, _) = broadcast::channel::<Frame>(16);
+    let snapshot: Arc<Mutex<Snapshot>> = Arc::default();
+
+    let accept_frames = frames.clone();
+    let accept_snapshot = snapshot.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let _ = stream.set_nodelay(true);
+            let subscription = accept_frames.subscribe();
+            let snapshot = accept_snapshot.clone();
+            tokio::spawn(async move {
+                let _ = stream_updates(stream, snapshot, subscription).await;
+            });
+        }
+    });
+
+    while let Some(update) = rx.recv().await {
+        // Loading messages aren't worth sending
+        if matches!(update, ProcessUpdate::LoadingStatus(_)) {
+            continue;
+        }
+        let frame = Arc::new(encode(&update)?);
+        let mut snapshot = snapshot.lock().await;
+        snapshot.apply(update);
+        // Fails only when nobody is connected
+        let _ = frames.send(frame);
+    }
+
+    Ok(())
+}
+
+// The frames that bring a new (or lagging) client up to date. The
+// subscription is restarted from here so nothing is sent twice.
+async fn catch_up(
+    snapshot: &Mutex<Snapshot>,
+    frames: &mut broadcast::Receiver<Frame>,
+) -> bincode::Result<Vec<u8>> {
+    let snapshot = snapshot.lock().await;
+    *frames = frames.resubscribe();
+    let mut replay = Vec::new();
+    for update in snapshot.updates() {
+        replay.extend(encode(&update)?);
+    }
+    Ok(replay)
+}
+
+fn encode(update: &ProcessUpdate) -> bincode::Result<Vec<u8>> {
+    let payload = bincode::serialize(update)?;
+    let mut frame = Vec::with_capacity(payload.len() + 4);
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&payload);
+    Ok(frame)
+}
+
+async fn stream_updates(
+    mut stream: TcpStream,
+    snapshot: Arc<Mutex<Snapshot>>,
+    mut frames: broadcast::Receiver<Frame>,
+) -> io::Result<()> {
+    stream.write_all(&handshake()).await?;
+    let replay = catch_up(&snapshot, &mut frames)
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    stream.write_all(&replay).await?;
+    loop {
+        match frames.recv().await {
+            Ok(frame) => stream.write_all(&frame).await?,
+            // A slow client missed deltas, so it starts over from the snapshot
+            Err(broadcast::error::RecvError::Lagged(_)) => {
+                let replay = catch_up(&snapshot, &mut frames)
+                    .await
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                stream.write_all(&replay).await?;
+            }
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        }
+    }
+}
+
+// Connect to an agent in the background and forward its updates, reconnecting
+// every few seconds when the connection fails or drops
+pub fn connect(addr: String) -> mpsc::Receiver<ProcessUpdate> {
+    let (tx, rx) = mpsc::channel(100);
+    tokio::spawn(async move {
+        loop {
+            let _ = tx
+                .send(ProcessUpdate::LoadingStatus("connecting...".to_string()))
+                .await;
+            let error = match receive_updates(&addr, &tx).await {
+                Ok(()) => "connection closed".to_string(),
+                Err(e) => e.to_string(),
+            };
+            if tx.is_closed() {
+                return;
+            }
+            let _ = tx
+                .send(ProcessUpdate::LoadingStatus(format!(
+                    "{}, retrying...",
+                    error
+                )))
+                .await;
+            tokio::time::sleep(Duration::from_secs(3)).await;
+        }
+    });
+    rx
+}
+
+async fn receive_updates(addr: &str, tx: &mpsc::Sender<ProcessUpdate>) -> io::Result<()> {
+    let mut stream = TcpStream::connect(addr).await?;
+
+    let mut received = [0u8; 8];
+    stream.read_exact(&mut received).await?;
+    if received != handshake() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not a psr agent, or an incompatible version",
+        ));
+    }
+    let _ = tx.send(ProcessUpdate::LoadingStatus(String::new())).await;
+
+    loop {
+        let mut length = [0u8; 4];
+        if let Err(e) = stream.read_exact(&mut length).await {
+            return match e.kind() {
+                io::ErrorKind::UnexpectedEof => Ok(()),
+                _ => Err(e),
+            };
+        }
+        let length = u32::from_be_bytes(length) as usize;
+        if length > MAX_FRAME {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "oversized update",
+            ));
+        }
+
+        let mut payload = vec![0u8; length];
+        stream.read_exact(&mut payload).await?;
+        let update = bincode::deserialize(&payload)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if tx.send(update).await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+// A machine the TUI can show: this one (no name) or a connected agent. While
+// another host is shown its state is kept up to date here, so switching to it
+// shows its data right away; the shown host's state lives in the App.
+pub struct Host {
+    pub name: Option<String>,
+    pub source: Box<dyn ProcessSource>,
+    snapshot: Snapshot,
+}
+
+impl Host {
+    pub fn new(name: Option<String>, source: Box<dyn ProcessSource>) -> Self {
+        Self {
+            name,
+            source,
+            snapshot: Snapshot::default(),
+        }
+    }
+
+    pub fn remember(&mut self, update: ProcessUpdate) {
+        self.snapshot.apply(update);
+    }
+
+    // Switching away: pick up from where the App left off
+    pub fn keep(&mut self, snapshot: Snapshot) {
+        self.snapshot = snapshot;
+    }
+
+    // Updates that bring a freshly selected host's view up to date, handing
+    // its state over to the App
+    pub fn replay(&mut self) -> Vec<ProcessUpdate> {
+        std::mem::take(&mut self.snapshot).into_updates()
+    }
+}
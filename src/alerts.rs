@@ -0,0 +1,265 @@
+use psr_core::ProcessInfo;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use sysinfo::{System, SystemExt};
+
+use crate::app::SystemResources;
+use crate::config::matches_pattern;
+
+// What an alert rule watches. System-wide CPU and memory and per-process CPU
+// are percentages; per-process memory is in MB.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Metric {
+    Cpu,
+    Memory,
+    ProcessCpu,
+    ProcessMemory,
+}
+
+// A threshold from the config, e.g.
+//
+//   [[alerts]]
+//   metric = "process-cpu"
+//   above = 90
+//   for_secs = 30
+//   webhook = "https://hooks.slack.com/services/..."
+//   exec = "kill -USR1 $PSR_PID"
+//
+// `name` limits process rules to processes matching the pattern, `webhook`
+// is POSTed a JSON description of each alert the rule fires, and `exec` is
+// a shell command run for each one (see run_command for its environment)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub metric: Metric,
+    pub above: f64,
+    #[serde(default)]
+    pub for_secs: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec: Option<String>,
+}
+
+impl AlertRule {
+    pub fn describe(&self) -> String {
+        let (subject, unit) = match self.metric {
+            Metric::Cpu => ("total CPU".to_string(), "%"),
+            Metric::Memory => ("total memory".to_string(), "%"),
+            Metric::ProcessCpu => (self.process_subject("CPU"), "%"),
+            Metric::ProcessMemory => (self.process_subject("memory"), "MB"),
+        };
+        let mut text = format!("{} > {}{}", subject, self.above, unit);
+        if self.for_secs > 0 {
+            text.push_str(&format!(" for {}s", self.for_secs));
+        }
+        text
+    }
+
+    fn process_subject(&self, metric: &str) -> String {
+        match &self.name {
+            Some(pattern) => format!("{} {}", pattern, metric),
+            None => format!("process {}", metric),
+        }
+    }
+
+    // Everything currently over the threshold: (pid, what, value)
+    fn breaches(
+        &self,
+        system: &SystemResources,
+        processes: &HashMap<u32, ProcessInfo>,
+    ) -> Vec<(Option<u32>, String, f64)> {
+        let system_value = match self.metric {
+            Metric::Cpu => Some(system.cpu_usage as f64),
+            Metric::Memory if system.total_memory > 0 => {
+                Some(system.used_memory as f64 / system.total_memory as f64 * 100.0)
+            }
+            Metric::Memory => Some(0.0),
+            Metric::ProcessCpu | Metric::ProcessMemory => None,
+        };
+        if let Some(value) = system_value {
+            return if value > self.above {
+                vec![(None, "system".to_string(), value)]
+            } else {
+                Vec::new()
+            };
+        }
+
+        processes
+            .values()
+            .filter(|p| {
+                self.name
+                    .as_ref()
+                    .is_none_or(|pattern| matches_pattern(pattern, &p.name))
+            })
+            .filter_map(|p| {
+                let value = match self.metric {
+                    Metric::ProcessMemory => (p.memory / 1024 / 1024) as f64,
+                    _ => p.cpu_usage as f64,
+                };
+                (value > self.above)
+                    .then(|| (Some(p.pid), format!("{} ({})", p.name, p.pid), value))
+            })
+            .collect()
+    }
+}
+
+// A rule that has held for its whole duration
+pub struct Alert {
+    pub rule: usize,
+    pub pid: Option<u32>,
+    pub subject: String,
+    pub value: f64,
+    pub since: Instant,
+    // Fired on this refresh rather than an earlier one
+    pub new: bool,
+}
+
+// Tracks since when each rule has been breached, per process for process
+// rules, so an alert only fires once the breach has lasted `for_secs`
+#[derive(Default)]
+pub struct Alerts {
+    breached_since: HashMap<(usize, Option<u32>), Instant>,
+    pub active: Vec<Alert>,
+}
+
+impl Alerts {
+    pub fn evaluate(
+        &mut self,
+        rules: &[AlertRule],
+        system: &SystemResources,
+        processes: &HashMap<u32, ProcessInfo>,
+    ) {
+        let now = Instant::now();
+        let mut breached_since = HashMap::new();
+        let firing: HashSet<(usize, Option<u32>)> =
+            self.active.drain(..).map(|a| (a.rule, a.pid)).collect();
+
+        for (index, rule) in rules.iter().enumerate() {
+            for (pid, subject, value) in rule.breaches(system, processes) {
+                let since = self
+                    .breached_since
+                    .get(&(index, pid))
+                    .copied()
+                    .unwrap_or(now);
+                breached_since.insert((index, pid), since);
+                if now.duration_since(since) >= Duration::from_secs(rule.for_secs) {
+                    self.active.push(Alert {
+                        rule: index,
+                        pid,
+                        subject,
+                        value,
+                        since,
+                        new: !firing.contains(&(index, pid)),
+                    });
+                }
+            }
+        }
+
+        // Breaches that ended start over next time
+        self.breached_since = breached_since;
+    }
+
+    pub fn is_alerting(&self, pid: u32) -> bool {
+        self.active.iter().any(|a| a.pid == Some(pid))
+    }
+
+    pub fn clear(&mut self) {
+        self.breached_since.clear();
+        self.active.clear();
+    }
+}
+
+// Show a desktop notification for a freshly fired alert. Talking to the
+// notification daemon can block, so it happens on its own thread; failures
+// (no daemon, no session bus) are ignored.
+pub fn notify(rule: &AlertRule, alert: &Alert) {
+    let summary = format!("psr alert: {}", alert.subject);
+    let body = rule.describe();
+    std::thread::spawn(move || {
+        let _ = notify_rust::Notification::new()
+            .appname("psr")
+            .summary(&summary)
+            .body(&body)
+            .show();
+    });
+}
+
+// POST a freshly fired alert to the rule's webhook. `text` is what Slack
+// shows; incident tooling can use the rest. Like notifications, delivery
+// happens on its own thread and failures are dropped.
+pub fn post_webhook(
+    url: &str,
+    rule: &AlertRule,
+    alert: &Alert,
+    process: Option<&ProcessInfo>,
+    host: Option<&str>,
+) {
+    let host = host.map_or_else(
+        || System::new().host_name().unwrap_or_default(),
+        str::to_string,
+    );
+    let payload = json!({
+        "text": format!("psr alert on {}: {} ({})", host, rule.describe(), alert.subject),
+        "host": host,
+        "rule": rule.describe(),
+        "subject": alert.subject,
+        "value": alert.value,
+        "process": process,
+    });
+    let url = url.to_string();
+    std::thread::spawn(move || {
+        let _ = ureq::post(&url)
+            .set("Content-Type", "application/json")
+            .timeout(Duration::from_secs(10))
+            .send_string(&payload.to_string());
+    });
+}
+
+// Run a rule's `exec` command through the shell for a freshly fired alert.
+// The alert is described in PSR_RULE and PSR_VALUE, and for process rules
+// the process in PSR_PID, PSR_NAME, PSR_CPU and PSR_MEMORY (bytes). Output
+// would garble the TUI, so it's discarded; the command is waited on in the
+// background.
+pub fn run_command(
+    command: &str,
+    rule: &AlertRule,
+    alert: &Alert,
+    process: Option<&ProcessInfo>,
+) -> io::Result<()> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell
+        .arg(command)
+        .env("PSR_RULE", rule.describe())
+        .env("PSR_VALUE", alert.value.to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(process) = process {
+        shell
+            .env("PSR_PID", process.pid.to_string())
+            .env("PSR_NAME", &process.name)
+            .env("PSR_CPU", process.cpu_usage.to_string())
+            .env("PSR_MEMORY", process.memory.to_string());
+    }
+
+    let mut child = shell.spawn()?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
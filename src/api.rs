@@ -0,0 +1,100 @@
+use hyper::StatusCode;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use crate::app::App;
+use crate::http::{self, Response};
+
+// Read-only JSON API over the latest refresh, for scripts and dashboards:
+// /system, /processes and /process/<pid>. Like the metrics page, each
+// process is rendered once per refresh and handed out as text.
+pub struct ApiServer {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    system: serde_json::Value,
+    // Each process as JSON, by PID
+    processes: BTreeMap<u32, String>,
+}
+
+impl ApiServer {
+    pub async fn start(addr: &str) -> io::Result<Self> {
+        let state = Arc::new(Mutex::new(State::default()));
+        let shared = state.clone();
+        http::serve(addr, move |path| match shared.lock() {
+            Ok(state) => route(&state, path),
+            Err(_) => error(StatusCode::INTERNAL_SERVER_ERROR, "state unavailable"),
+        })
+        .await?;
+
+        Ok(Self { state })
+    }
+
+    pub fn update(&self, app: &App) {
+        let system = json!({
+            "timestamp": chrono::Local::now().to_rfc3339(),
+            "resources": &app.system_resources,
+            "processes": app.all_processes.len(),
+            "networks": &app.network_stats.interfaces,
+            "disks": &app.disk_stats.disks,
+        });
+        let processes = app
+            .all_processes
+            .values()
+            .filter_map(|p| Some((p.pid, serde_json::to_string(p).ok()?)))
+            .collect();
+        if let Ok(mut state) = self.state.lock() {
+            state.system = system;
+            state.processes = processes;
+        }
+    }
+}
+
+fn route(state: &State, path: &str) -> Response {
+    let path = path.split('?').next().unwrap_or(path).trim_end_matches('/');
+    let body = match path {
+        "/system" => serde_json::to_string(&state.system),
+        "/processes" => {
+            let processes: Vec<&str> = state.processes.values().map(String::as_str).collect();
+            Ok(format!("[{}]", processes.join(",")))
+        }
+        _ => match path.strip_prefix("/process/") {
+            Some(pid) => match pid
+                .parse::<u32>()
+                .ok()
+                .and_then(|pid| state.processes.get(&pid))
+            {
+                Some(process) => Ok(process.clone()),
+                None => {
+                    return error(
+                        StatusCode::NOT_FOUND,
+                        &format!("no process with PID {}", pid),
+                    )
+                }
+            },
+            None => {
+                return error(
+                    StatusCode::NOT_FOUND,
+                    "endpoints are /system, /processes and /process/<pid>",
+                )
+            }
+        },
+    };
+
+    match body {
+        Ok(body) => Response::ok("application/json", body),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    Response {
+        status,
+        content_type: "application/json",
+        body: json!({ "error": message }).to_string(),
+    }
+}
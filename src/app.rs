@@ -1,16 +1,95 @@
-use crate::processes::ProcessInfo;
+use crate::alerts::{self, Alerts};
+use crate::cgroups::{self, CgroupInfo};
+use crate::clipboard;
+use crate::command::{self, Command};
+use crate::computed::{Evaluator, Value};
+use crate::config::{self, ColumnEntry, Config};
+use crate::flamegraph;
+use crate::history::{self, HistorySample, HistoryStore};
+use crate::inspect::{
+    self, Activity, Connection, Executable, ListeningPort, MemoryMapping, OpenFile, OutputStream,
+    ResourceLimit, Security, ThreadInfo,
+};
+use crate::keymap::{self, Keymap};
+use crate::plugins::{self, Plugin, PluginTable};
+use crate::resolve::Resolver;
+use crate::scripting::{Action, ScriptResults, Scripts};
+use clap::ValueEnum;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use psr_core::control;
+use psr_core::systemd::{self, Unit};
+use psr_core::{
+    push_history, CollectorStats, CoreInfo, DiskInfo, Filter, GpuInfo, NetworkInterface, NumaNode,
+    Pressure, ProcessDelta, ProcessInfo, ProcessStatus, ProcessUpdate, Snapshot, SortBy,
+    SystemLoad, HISTORY_LEN,
+};
+use ratatui::layout::Rect;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
+use std::path::PathBuf;
+use std::process::{Child, ExitStatus};
+use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
 pub enum SortKey {
     Pid,
     Name,
     Cpu,
+    #[value(alias = "mem")]
     Memory,
+    #[value(alias = "virt")]
+    Virtual,
+    Shared,
+    Swap,
+    Pss,
+    Uss,
     Status,
     User,
     StartTime,
+    StartedAt,
+    DiskRead,
+    DiskWrite,
+    NetDown,
+    NetUp,
+    #[value(alias = "gpu")]
+    GpuMemory,
+    #[value(alias = "numa")]
+    Node,
+    Nice,
+    #[value(alias = "fd")]
+    Fds,
+    #[value(alias = "policy")]
+    Scheduling,
+    Power,
+    #[value(alias = "syscall")]
+    Syscalls,
+    #[value(alias = "blkio")]
+    BlkioDelay,
+    #[value(alias = "latency")]
+    BlkioLatency,
+    #[value(alias = "retrans")]
+    Retransmits,
+    Threads,
+    Wakeups,
+    #[value(alias = "ppid")]
+    Parent,
+    #[value(alias = "cmd")]
+    Command,
+    Container,
+    Unit,
+    // The column set by scripts
+    Script,
+    // Number of processes, for the Users tab; process lists keep their order
+    #[value(skip)]
+    Count,
+    // A computed column from the config, by position
+    #[value(skip)]
+    Computed(usize),
+    // A plugin's column, by the plugin's position
+    #[value(skip)]
+    Plugin(usize),
 }
 
 #[allow(dead_code)]
@@ -21,19 +100,586 @@ impl SortKey {
             SortKey::Name => "Name",
             SortKey::Cpu => "CPU%",
             SortKey::Memory => "Memory",
+            SortKey::Virtual => "Virtual",
+            SortKey::Shared => "Shared",
+            SortKey::Swap => "Swap",
+            SortKey::Pss => "PSS",
+            SortKey::Uss => "USS",
             SortKey::Status => "Status",
             SortKey::User => "User",
             SortKey::StartTime => "Start Time",
+            SortKey::StartedAt => "Started At",
+            SortKey::DiskRead => "Read/s",
+            SortKey::DiskWrite => "Write/s",
+            SortKey::NetDown => "Down/s",
+            SortKey::NetUp => "Up/s",
+            SortKey::GpuMemory => "GPU Mem",
+            SortKey::Node => "Node",
+            SortKey::Nice => "Nice",
+            SortKey::Fds => "FDs",
+            SortKey::Scheduling => "Policy",
+            SortKey::Power => "Power",
+            SortKey::Syscalls => "R/W calls/s",
+            SortKey::BlkioDelay => "Blk Wait",
+            SortKey::BlkioLatency => "Blk Latency",
+            SortKey::Retransmits => "Retrans/s",
+            SortKey::Threads => "Threads",
+            SortKey::Wakeups => "Wakeups/s",
+            SortKey::Parent => "PPID",
+            SortKey::Command => "Command",
+            SortKey::Container => "Container",
+            SortKey::Unit => "Unit",
+            SortKey::Script => "Script",
+            SortKey::Plugin(_) => "Plugin",
+            SortKey::Count => "Processes",
+            SortKey::Computed(_) => "Computed",
+        }
+    }
+
+    // The process field this sorts by, for keys psr-core knows about
+    pub fn sort_by(&self) -> Option<SortBy> {
+        Some(match self {
+            SortKey::Pid => SortBy::Pid,
+            SortKey::Name => SortBy::Name,
+            SortKey::Cpu => SortBy::Cpu,
+            SortKey::Memory => SortBy::Memory,
+            SortKey::Virtual => SortBy::VirtualMemory,
+            SortKey::Shared => SortBy::SharedMemory,
+            SortKey::Swap => SortBy::Swap,
+            SortKey::Pss => SortBy::Pss,
+            SortKey::Uss => SortBy::Uss,
+            SortKey::Status => SortBy::Status,
+            SortKey::User => SortBy::User,
+            SortKey::StartTime => SortBy::StartTime,
+            SortKey::StartedAt => SortBy::StartedAt,
+            SortKey::DiskRead => SortBy::DiskRead,
+            SortKey::DiskWrite => SortBy::DiskWrite,
+            SortKey::NetDown => SortBy::NetDown,
+            SortKey::NetUp => SortBy::NetUp,
+            SortKey::GpuMemory => SortBy::GpuMemory,
+            SortKey::Node => SortBy::NumaNode,
+            SortKey::Nice => SortBy::Nice,
+            SortKey::Fds => SortBy::Fds,
+            SortKey::Scheduling => SortBy::Scheduling,
+            SortKey::Power => SortBy::Power,
+            SortKey::Syscalls => SortBy::Syscalls,
+            SortKey::BlkioDelay => SortBy::BlkioDelay,
+            SortKey::BlkioLatency => SortBy::BlkioLatency,
+            SortKey::Retransmits => SortBy::TcpRetransmits,
+            SortKey::Threads => SortBy::Threads,
+            SortKey::Wakeups => SortBy::Wakeups,
+            SortKey::Parent => SortBy::Parent,
+            SortKey::Command => SortBy::Command,
+            SortKey::Container => SortBy::Container,
+            SortKey::Unit => SortBy::Unit,
+            SortKey::Script | SortKey::Plugin(_) | SortKey::Count | SortKey::Computed(_) => {
+                return None
+            }
+        })
+    }
+}
+
+// What key presses currently feed: the filter, or a prompt opened by a command
+#[derive(Clone, Copy, PartialEq)]
+pub enum InputMode {
+    Normal,
+    Columns,
+    Search,
+    Command,
+    Service,
+}
+
+// A popup that takes all input until it's answered or dismissed
+pub struct Dialog {
+    pub title: String,
+    pub message: String,
+    pub kind: DialogKind,
+    // What answering it does
+    action: DialogAction,
+}
+
+pub enum DialogKind {
+    // Just information; any key closes it
+    Message,
+    // Enter or y goes ahead, Esc or n backs out
+    Confirm,
+    // A line of text, submitted with Enter
+    Input(String),
+    // One of several options, picked with the arrows and Enter
+    List {
+        options: Vec<String>,
+        selected: usize,
+    },
+}
+
+// The help screen: how far it's scrolled and what it's narrowed down to
+#[derive(Default)]
+pub struct Help {
+    pub scroll: usize,
+    // Lines that fit, updated on every draw, for paging
+    pub page: usize,
+    pub search: String,
+    // Typing goes into the search rather than scrolling
+    pub searching: bool,
+}
+
+// Timings for the --debug overlay, measured by the main loop over the last
+// second
+#[derive(Default)]
+pub struct DebugStats {
+    // How long the last frame took to draw
+    pub frame_time: Duration,
+    // When the frames were drawn
+    frames: VecDeque<Instant>,
+    // When each pass of the main loop ended, how long it spent before waiting
+    // for input and how many updates were waiting when it started
+    passes: VecDeque<(Instant, Duration, usize)>,
+}
+
+impl DebugStats {
+    pub fn frame_drawn(&mut self, took: Duration) {
+        self.frame_time = took;
+        self.frames.push_back(Instant::now());
+        forget_older(&mut self.frames, |&at| at);
+    }
+
+    pub fn pass_done(&mut self, took: Duration, backlog: usize) {
+        self.passes.push_back((Instant::now(), took, backlog));
+        forget_older(&mut self.passes, |&(at, ..)| at);
+    }
+
+    // The slowest pass
+    pub fn loop_time(&self) -> Duration {
+        self.passes
+            .iter()
+            .map(|&(_, took, _)| took)
+            .max()
+            .unwrap_or_default()
+    }
+
+    // The most updates that were waiting at once
+    pub fn backlog(&self) -> usize {
+        self.passes
+            .iter()
+            .map(|&(.., backlog)| backlog)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn fps(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+// Drop the entries from more than a second ago off the front
+fn forget_older<T>(entries: &mut VecDeque<T>, at: impl Fn(&T) -> Instant) {
+    let now = Instant::now();
+    while entries
+        .front()
+        .is_some_and(|entry| now.duration_since(at(entry)) > Duration::from_secs(1))
+    {
+        entries.pop_front();
+    }
+}
+
+// A perf record run started from psr
+struct Profile {
+    pid: u32,
+    // perf's data file
+    output: PathBuf,
+    // Where the flamegraph drawn from it goes, when one was asked for
+    flamegraph: Option<PathBuf>,
+    perf: Child,
+}
+
+enum DialogAction {
+    None,
+    Kill(Vec<u32>, Option<(&'static str, i32)>),
+    Signal(Vec<u32>),
+    Renice(Vec<u32>),
+    Reap(Vec<u32>),
+    Run,
+    Restart(u32, control::Launch),
+    Profile(u32, bool),
+    CoreDump(u32),
+}
+
+impl Dialog {
+    fn new(title: &str, message: String, kind: DialogKind, action: DialogAction) -> Self {
+        Self {
+            title: title.to_string(),
+            message,
+            kind,
+            action,
+        }
+    }
+}
+
+// The outcome of the last command, shown in the filter line until it's
+// dismissed by a key press or expires. Errors stay up a little longer.
+pub struct Toast {
+    pub text: String,
+    pub error: bool,
+    shown_at: Instant,
+}
+
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+const ERROR_TOAST_DURATION: Duration = Duration::from_secs(8);
+
+// Even with nothing new, redraw this often so elapsed times keep ticking
+const IDLE_REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+
+// Process starts and exits kept for the Events tab
+const EVENT_LOG_LEN: usize = 1000;
+
+// How long a restarted process gets to exit after SIGTERM before it's killed
+const RESTART_GRACE: Duration = Duration::from_secs(5);
+
+// Screen areas of the last frame that respond to the mouse
+#[derive(Default)]
+pub struct MouseRegions {
+    pub tabs: Vec<(Rect, usize)>,
+    pub headers: Vec<(Rect, SortKey)>,
+    pub rows: Vec<(Rect, u32)>,
+    // Area scrolled by the wheel: the process table, or the Detailed panel
+    pub scroll_area: Rect,
+}
+
+fn contains(rect: Rect, x: u16, y: u16) -> bool {
+    x >= rect.x && x < rect.x + rect.width && y >= rect.y && y < rect.y + rect.height
+}
+
+// A process the selection is locked to, toggled with Ctrl+f
+pub struct FollowedProcess {
+    pub pid: u32,
+    pub name: String,
+    pub exited: bool,
+}
+
+// Sub-views of the Detailed tab, cycled with Ctrl+d
+#[derive(Clone, Copy, PartialEq)]
+pub enum DetailPanel {
+    Overview,
+    Arguments,
+    OpenFiles,
+    Connections,
+    MemoryMaps,
+    Threads,
+    Security,
+    Limits,
+    Journal,
+    Output,
+}
+
+impl DetailPanel {
+    pub const ALL: [DetailPanel; 10] = [
+        DetailPanel::Overview,
+        DetailPanel::Arguments,
+        DetailPanel::OpenFiles,
+        DetailPanel::Connections,
+        DetailPanel::MemoryMaps,
+        DetailPanel::Threads,
+        DetailPanel::Security,
+        DetailPanel::Limits,
+        DetailPanel::Journal,
+        DetailPanel::Output,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            DetailPanel::Overview => "Overview",
+            DetailPanel::Arguments => "Arguments",
+            DetailPanel::OpenFiles => "Open Files",
+            DetailPanel::Connections => "Connections",
+            DetailPanel::MemoryMaps => "Memory Map",
+            DetailPanel::Threads => "Threads",
+            DetailPanel::Security => "Security",
+            DetailPanel::Limits => "Limits",
+            DetailPanel::Journal => "Journal",
+            DetailPanel::Output => "Output",
+        }
+    }
+}
+
+// A column of the processes table, as named in the config file
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Column {
+    Pid,
+    Name,
+    Cpu,
+    // Resident memory; the others break it down further
+    Memory,
+    Virtual,
+    Shared,
+    Swap,
+    // Only collected with --pss
+    Pss,
+    Uss,
+    // Only shown where a GPU is reported
+    GpuMemory,
+    // NUMA node holding most of the memory, only on machines with several
+    Node,
+    Status,
+    Nice,
+    // Scheduling policy and realtime priority
+    Policy,
+    RtPriority,
+    User,
+    Started,
+    // Wall-clock start time rather than how long ago
+    StartedAt,
+    DiskRead,
+    DiskWrite,
+    NetDown,
+    NetUp,
+    Power,
+    Wakeups,
+    // Only collected with --kernel-stats, and the latency only with eBPF
+    Syscalls,
+    BlkioDelay,
+    BlkioLatency,
+    Retransmits,
+    Parent,
+    Threads,
+    // Open file descriptors against the process's limit
+    Fds,
+    Command,
+    Container,
+    Unit,
+    Script,
+    // Defined in the config's `computed` list, by position; `columns` names
+    // them rather than holding these
+    #[serde(skip)]
+    Computed(usize),
+    // One for each plugin with a column, by its place among the plugins
+    #[serde(skip)]
+    Plugin(usize),
+}
+
+impl Column {
+    pub const ALL: [Column; 35] = [
+        Column::Pid,
+        Column::Name,
+        Column::Cpu,
+        Column::Memory,
+        Column::Virtual,
+        Column::Shared,
+        Column::Swap,
+        Column::Pss,
+        Column::Uss,
+        Column::GpuMemory,
+        Column::Node,
+        Column::Status,
+        Column::Nice,
+        Column::Policy,
+        Column::RtPriority,
+        Column::User,
+        Column::Started,
+        Column::StartedAt,
+        Column::DiskRead,
+        Column::DiskWrite,
+        Column::NetDown,
+        Column::NetUp,
+        Column::Power,
+        Column::Wakeups,
+        Column::Syscalls,
+        Column::BlkioDelay,
+        Column::BlkioLatency,
+        Column::Retransmits,
+        Column::Parent,
+        Column::Threads,
+        Column::Fds,
+        Column::Command,
+        Column::Container,
+        Column::Unit,
+        Column::Script,
+    ];
+
+    // Shown when the config doesn't list any columns
+    pub const DEFAULT: [Column; 23] = [
+        Column::Pid,
+        Column::Name,
+        Column::Cpu,
+        Column::Memory,
+        Column::GpuMemory,
+        Column::Node,
+        Column::Status,
+        Column::Nice,
+        Column::User,
+        Column::Started,
+        Column::DiskRead,
+        Column::DiskWrite,
+        Column::NetDown,
+        Column::NetUp,
+        Column::Power,
+        Column::Wakeups,
+        Column::Syscalls,
+        Column::BlkioDelay,
+        Column::BlkioLatency,
+        Column::Retransmits,
+        Column::Container,
+        Column::Unit,
+        Column::Script,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Column::Pid => "PID",
+            Column::Name => "Name",
+            Column::Cpu => "CPU%",
+            Column::Memory => "Memory",
+            Column::Virtual => "Virtual",
+            Column::Shared => "Shared",
+            Column::Swap => "Swap",
+            Column::Pss => "PSS",
+            Column::Uss => "USS",
+            Column::Status => "Status",
+            Column::Nice => "Nice",
+            Column::Policy => "Policy",
+            Column::RtPriority => "RT Prio",
+            Column::User => "User",
+            Column::Started => "Started",
+            Column::StartedAt => "Started At",
+            Column::DiskRead => "Read/s",
+            Column::DiskWrite => "Write/s",
+            Column::NetDown => "Down/s",
+            Column::NetUp => "Up/s",
+            Column::GpuMemory => "GPU Mem",
+            Column::Node => "Node",
+            Column::Power => "Power",
+            Column::Wakeups => "Wakeups/s",
+            Column::Syscalls => "R/W calls/s",
+            Column::BlkioDelay => "Blk Wait",
+            Column::BlkioLatency => "Blk Latency",
+            Column::Retransmits => "Retrans/s",
+            Column::Parent => "PPID",
+            Column::Threads => "Threads",
+            Column::Fds => "FDs",
+            Column::Command => "Command",
+            Column::Container => "Container",
+            Column::Unit => "Unit",
+            Column::Script => "Script",
+            // Titled by their config entry or plugin
+            Column::Computed(_) | Column::Plugin(_) => "",
+        }
+    }
+
+    pub fn sort_key(&self) -> Option<SortKey> {
+        match self {
+            Column::Pid => Some(SortKey::Pid),
+            Column::Name => Some(SortKey::Name),
+            Column::Cpu => Some(SortKey::Cpu),
+            Column::Memory => Some(SortKey::Memory),
+            Column::Virtual => Some(SortKey::Virtual),
+            Column::Shared => Some(SortKey::Shared),
+            Column::Swap => Some(SortKey::Swap),
+            Column::Pss => Some(SortKey::Pss),
+            Column::Uss => Some(SortKey::Uss),
+            Column::Status => Some(SortKey::Status),
+            Column::Nice => Some(SortKey::Nice),
+            Column::Policy | Column::RtPriority => Some(SortKey::Scheduling),
+            Column::User => Some(SortKey::User),
+            Column::Started => Some(SortKey::StartTime),
+            Column::StartedAt => Some(SortKey::StartedAt),
+            Column::DiskRead => Some(SortKey::DiskRead),
+            Column::DiskWrite => Some(SortKey::DiskWrite),
+            Column::NetDown => Some(SortKey::NetDown),
+            Column::NetUp => Some(SortKey::NetUp),
+            Column::GpuMemory => Some(SortKey::GpuMemory),
+            Column::Node => Some(SortKey::Node),
+            Column::Power => Some(SortKey::Power),
+            Column::Fds => Some(SortKey::Fds),
+            Column::Syscalls => Some(SortKey::Syscalls),
+            Column::BlkioDelay => Some(SortKey::BlkioDelay),
+            Column::BlkioLatency => Some(SortKey::BlkioLatency),
+            Column::Retransmits => Some(SortKey::Retransmits),
+            Column::Threads => Some(SortKey::Threads),
+            Column::Wakeups => Some(SortKey::Wakeups),
+            Column::Parent => Some(SortKey::Parent),
+            Column::Command => Some(SortKey::Command),
+            Column::Container => Some(SortKey::Container),
+            Column::Unit => Some(SortKey::Unit),
+            Column::Script => Some(SortKey::Script),
+            Column::Plugin(index) => Some(SortKey::Plugin(*index)),
+            Column::Computed(index) => Some(SortKey::Computed(*index)),
+        }
+    }
+}
+
+// How the processes tab arranges its rows
+#[derive(Clone, Copy, PartialEq)]
+pub enum ViewMode {
+    Flat,
+    // Children indented under their parent
+    Tree,
+    // One row per executable name, expandable to the individual PIDs
+    Grouped,
+}
+
+// Where a process sits in the tree or grouped view, with totals over its whole
+// subtree or group (itself included) for showing collapsed rows as one line
+pub struct TreeRow {
+    pub depth: usize,
+    pub has_children: bool,
+    pub collapsed: bool,
+    pub descendants: usize,
+    pub cpu_total: f32,
+    pub memory_total: u64,
+}
+
+// A process appearing or going away, as last seen, for the Events tab
+pub struct ProcessEvent {
+    // Seconds since the epoch
+    pub at: i64,
+    pub exited: bool,
+    pub pid: u32,
+    pub name: String,
+    pub user: String,
+    pub cpu_usage: f32,
+    pub memory: u64,
+    // How long an exited process ran, to within a refresh
+    pub lifetime: Option<Duration>,
+}
+
+impl ProcessEvent {
+    fn new(process: &ProcessInfo, exited: bool) -> Self {
+        let at = history::now();
+        Self {
+            at,
+            exited,
+            pid: process.pid,
+            name: process.name.clone(),
+            user: process.user.clone(),
+            cpu_usage: process.cpu_usage,
+            memory: process.memory,
+            lifetime: (exited && process.started_at > 0).then(|| {
+                Duration::from_secs(at.saturating_sub(process.started_at as i64).max(0) as u64)
+            }),
         }
     }
 }
 
+// What a user's or container's processes add up to, for the Users and
+// Containers tabs
+pub struct GroupSummary {
+    pub name: String,
+    pub processes: usize,
+    pub cpu_usage: f32,
+    pub memory: u64,
+}
+
+#[derive(Serialize)]
 pub struct SystemResources {
     pub cpu_usage: f32,
     pub used_memory: u64,
     pub total_memory: u64,
-    pub cpu_history: Vec<f32>,
-    pub memory_history: Vec<f32>, // Percentage of memory used
+    pub cpu_count: usize,
+    pub used_swap: u64,
+    pub total_swap: u64,
+    pub cpu_history: VecDeque<f32>,
+    pub memory_history: VecDeque<f32>, // Percentage of memory used
+    pub swap_history: VecDeque<f32>,   // Percentage of swap used
 }
 
 impl SystemResources {
@@ -42,87 +688,569 @@ impl SystemResources {
             cpu_usage: 0.0,
             used_memory: 0,
             total_memory: 1, // Avoid division by zero
-            cpu_history: vec![0.0; 60],
-            memory_history: vec![0.0; 60],
+            cpu_count: 1,
+            used_swap: 0,
+            total_swap: 0,
+            cpu_history: VecDeque::from(vec![0.0; HISTORY_LEN]),
+            memory_history: VecDeque::from(vec![0.0; HISTORY_LEN]),
+            swap_history: VecDeque::from(vec![0.0; HISTORY_LEN]),
         }
     }
 
-    pub fn update(&mut self, cpu: f32, used: u64, total: u64) {
+    pub fn update(&mut self, cpu: f32, used: u64, total: u64, cores: usize) {
         self.cpu_usage = cpu;
         self.used_memory = used;
         self.total_memory = total;
+        self.cpu_count = cores.max(1);
 
-        // Update history
-        if self.cpu_history.len() >= 60 {
-            self.cpu_history.remove(0);
-            self.memory_history.remove(0);
-        }
-
-        self.cpu_history.push(cpu);
+        push_history(&mut self.cpu_history, cpu);
         let memory_percent = (used as f32 / total as f32) * 100.0;
-        self.memory_history.push(memory_percent);
+        push_history(&mut self.memory_history, memory_percent);
+    }
+
+    pub fn update_swap(&mut self, used: u64, total: u64) {
+        self.used_swap = used;
+        self.total_swap = total;
+        let swap_percent = self.swap_percentage();
+        push_history(&mut self.swap_history, swap_percent);
     }
 
     pub fn memory_percentage(&self) -> f32 {
         (self.used_memory as f32 / self.total_memory as f32) * 100.0
     }
+
+    // Zero on machines without swap
+    pub fn swap_percentage(&self) -> f32 {
+        if self.total_swap == 0 {
+            return 0.0;
+        }
+        (self.used_swap as f32 / self.total_swap as f32) * 100.0
+    }
+}
+
+// Short in/out rate history per named item (network interface, mount point)
+pub struct RateHistory {
+    pub inbound: HashMap<String, VecDeque<u64>>,
+    pub outbound: HashMap<String, VecDeque<u64>>,
+}
+
+impl RateHistory {
+    pub fn new() -> Self {
+        Self {
+            inbound: HashMap::new(),
+            outbound: HashMap::new(),
+        }
+    }
+
+    // Record one (name, in, out) sample per item, forgetting items that disappeared
+    pub fn record(&mut self, samples: Vec<(String, u64, u64)>) {
+        self.inbound
+            .retain(|name, _| samples.iter().any(|(n, _, _)| n == name));
+        self.outbound
+            .retain(|name, _| samples.iter().any(|(n, _, _)| n == name));
+
+        for (name, inbound, outbound) in samples {
+            push_history(self.inbound.entry(name.clone()).or_default(), inbound);
+            push_history(self.outbound.entry(name).or_default(), outbound);
+        }
+    }
+}
+
+// Latest interface rates plus a short RX/TX history per interface
+pub struct NetworkStats {
+    pub interfaces: Vec<NetworkInterface>,
+    pub history: RateHistory,
+}
+
+impl NetworkStats {
+    pub fn new() -> Self {
+        Self {
+            interfaces: Vec::new(),
+            history: RateHistory::new(),
+        }
+    }
+
+    pub fn update(&mut self, interfaces: Vec<NetworkInterface>) {
+        self.history.record(
+            interfaces
+                .iter()
+                .map(|i| (i.name.clone(), i.rx_rate, i.tx_rate))
+                .collect(),
+        );
+        self.interfaces = interfaces;
+    }
+}
+
+// Latest mounted filesystems plus a short read/write history per mount point
+pub struct DiskStats {
+    pub disks: Vec<DiskInfo>,
+    pub history: RateHistory,
+}
+
+impl DiskStats {
+    pub fn new() -> Self {
+        Self {
+            disks: Vec::new(),
+            history: RateHistory::new(),
+        }
+    }
+
+    pub fn update(&mut self, disks: Vec<DiskInfo>) {
+        self.history.record(
+            disks
+                .iter()
+                .map(|d| (d.mount_point.clone(), d.read_rate, d.write_rate))
+                .collect(),
+        );
+        self.disks = disks;
+    }
 }
 
 pub struct App {
-    pub processes: Vec<ProcessInfo>,
+    // Every process from the last refresh, and the PIDs of the filtered,
+    // sorted view of it that the tables show; see process() for their data
+    pub all_processes: HashMap<u32, ProcessInfo>,
+    pub processes: Vec<u32>,
     pub selected_index: usize,
     pub previous_selected_pid: Option<u32>, // Track selected process between updates
     pub current_tab: usize,
-    pub tabs: Vec<&'static str>,
+    pub tabs: Vec<String>,
     pub sort_key: SortKey,
     pub sort_ascending: bool,
+    // Every PID in sort order (pinned first), and whether the data or the
+    // sort settings changed since it was worked out
+    sort_order: Vec<u32>,
+    sort_dirty: bool,
     pub system_resources: SystemResources,
+    pub network_stats: NetworkStats,
+    pub disk_stats: DiskStats,
+    // The screen is redrawn, at most every ui_refresh_interval, only when
+    // the generation moved on since the last frame: new data, input or an
+    // expiring toast
     last_ui_refresh: Instant,
-    last_data_refresh: Instant,
     ui_refresh_interval: Duration,
-    data_refresh_interval: Duration,
+    generation: u64,
+    drawn_generation: u64,
+    // Rows visible in the process tables, updated on every draw
+    pub page_size: usize,
+    // First table row on screen, moved only as far as needed to keep the
+    // selection visible
+    pub table_offset: usize,
     pub filter: String,
-    pub show_help: bool,
+    // Treat the filter as a case-insensitive regex instead of a substring
+    pub filter_regex: bool,
+    pub filter_error: Option<String>,
+    // The active filter compiled to a case-insensitive pattern (substring
+    // filters are escaped), used to match rows and highlight what matched
+    pub filter_pattern: Option<Filter>,
+    // Every process's sockets, read while a port=, local= or remote= filter
+    // is in use
+    sockets: HashMap<u32, Vec<Connection>>,
+    sockets_sampled_at: Option<Instant>,
+    // Tree and grouped views of the processes tab: collapsed parents,
+    // expanded groups and the layout of each listed process
+    pub view_mode: ViewMode,
+    pub collapsed: HashSet<u32>,
+    pub expanded_groups: HashSet<String>,
+    pub tree_rows: HashMap<u32, TreeRow>,
+    // Cgroups tab, and the cgroup whose members the process lists are limited
+    // to after drilling into one
+    pub cgroups: Vec<CgroupInfo>,
+    pub cgroup_selected: usize,
+    cgroups_sampled_at: Option<Instant>,
+    pub cgroup_error: Option<String>,
+    pub cgroup_filter: Option<String>,
+    // Ports tab: listening sockets and their owners
+    pub ports: Vec<ListeningPort>,
+    pub port_selected: usize,
+    ports_sampled_at: Option<Instant>,
+    pub ports_error: Option<String>,
+    // Last submitted `/` search, which n/N jump between without hiding rows
+    pub search: Option<String>,
+    search_origin: usize,
+    pub help: Option<Help>,
+    pub debug: Option<DebugStats>,
+    pub keymap: Keymap,
     pub loading_status: String,
-    pub refresh_sender: Option<mpsc::Sender<()>>,
+    // What the last refresh cost whichever psr collects the processes
+    pub collector: Option<CollectorStats>,
+    // Load averages and uptime of the host being shown
+    pub system_load: Option<SystemLoad>,
+    // Usage and clock speed of each core, for the dashboard's per-core panel
+    pub cores: Vec<CoreInfo>,
+    // GPUs of the host being shown, empty where there are none
+    pub gpus: Vec<GpuInfo>,
+    // Memory of each NUMA node, empty unless the host has several
+    pub numa_nodes: Vec<NumaNode>,
+    // Pressure stall figures over the last minute, newest last; empty where
+    // the host doesn't report them
+    pub pressure_history: VecDeque<Pressure>,
+    // Agent whose processes are shown, None for this machine
+    pub host: Option<String>,
+    // Set when a command wants fresh data; the main loop passes it on to the source
+    pub refresh_requested: bool,
+    // Prompt input and the outcome of the last command, shown in the filter line
+    pub input_mode: InputMode,
+    pub input: String,
+    pub dialog: Option<Dialog>,
+    // Set by :quit; the main loop exits
+    pub quit: bool,
+    pub status_message: Option<Toast>,
+    // PIDs tagged with Space; batch actions apply to these instead of the selection
+    pub tagged: HashSet<u32>,
+    // Processes that appeared within the config's flash duration, and ones
+    // that exited within it, whose rows stay listed until it runs out
+    pub started_at: HashMap<u32, Instant>,
+    pub exiting: HashMap<u32, (ProcessInfo, Instant)>,
+    // Starts and exits since psr started, newest last
+    pub events: VecDeque<ProcessEvent>,
+    // The highlighted row of the Events tab, which lists them newest first
+    pub event_selected: usize,
+    // Commands started from psr without detaching, stopped when it exits
+    children: Vec<Child>,
+    // perf record runs in progress
+    profiles: Vec<Profile>,
+    // Slow work on background threads, like drawing flamegraphs and dumping
+    // cores, each ending in a status line message or an error
+    tasks: Vec<JoinHandle<Result<String, String>>>,
+    // Restarts waiting for the old process to exit, by name, ending in the
+    // new one to follow
+    restarts: Vec<(String, JoinHandle<io::Result<Child>>)>,
+    pub config: Config,
+    // Process CPU% shown as a share of all cores rather than per core; the
+    // view is scaled, while alerts, scripts and exports keep per-core values
+    pub normalize_cpu: bool,
+    // Alert rules from the config that currently hold, checked every refresh,
+    // and whether newly fired ones raise a desktop notification
+    pub alerts: Alerts,
+    pub notify: bool,
+    // User scripts and what they made of the last refresh
+    pub scripts: Option<Scripts>,
+    pub script_results: ScriptResults,
+    // WebAssembly plugins, the tables their tabs show and, plugin by plugin,
+    // their column's text for each process
+    pub plugins: Vec<Plugin>,
+    pub plugin_tables: HashMap<String, Result<PluginTable, String>>,
+    plugins_collected_at: Option<Instant>,
+    pub plugin_cells: Vec<HashMap<u32, String>>,
+    // Config-defined computed columns and their values for each process
+    pub computed: Option<Evaluator>,
+    pub computed_values: HashMap<u32, Vec<Option<Value>>>,
+    // Last script or plugin error, kept so it's reported once rather than on
+    // every refresh
+    reported_error: Option<String>,
+    pub follow: Option<FollowedProcess>,
+    pub mouse: MouseRegions,
+    // Column picker rows (column, shown) and the row under the cursor
+    pub column_picker: Vec<(Column, bool)>,
+    pub column_cursor: usize,
+    // Unit the restart/stop prompt acts on
+    pub service_unit: Option<Unit>,
+    // Detailed tab sub-view and the data loaded for it
+    pub detail_panel: DetailPanel,
+    pub detail_scroll: usize,
+    detail_pid: Option<u32>,
+    pub open_files: Vec<OpenFile>,
+    pub connections: Vec<Connection>,
+    // Host and service names for the connections' addresses
+    pub resolver: Resolver,
+    pub memory_maps: Vec<MemoryMapping>,
+    pub threads: Vec<ThreadInfo>,
+    threads_sampled_at: Option<Instant>,
+    // Context switch and page fault totals of the selected process, and their
+    // per-second rates over the last minute for the Overview sparklines
+    pub activity: Option<Activity>,
+    pub activity_rates: VecDeque<Activity>,
+    activity_sampled_at: Option<Instant>,
+    // Kernel function the selected process is blocked in, when sleeping
+    pub wait_channel: Option<String>,
+    // Where the selected process was started from, and whether it's still there
+    pub executable: Option<Executable>,
+    pub security: Option<Security>,
+    pub limits: Vec<ResourceLimit>,
+    pub journal: Vec<String>,
+    journal_read_at: Option<Instant>,
+    // Why the last read of the journal failed, shown again until the next
+    journal_error: Option<String>,
+    // Where the selected process's stdout and stderr go, with their tails
+    pub output: Vec<OutputStream>,
+    output_read_at: Option<Instant>,
+    // On-disk history (--history) and the selected process's samples from it
+    pub history: Option<HistoryStore>,
+    pub process_history: Vec<HistorySample>,
+    process_history_read_at: Option<Instant>,
+    pub system_history: Vec<HistorySample>,
+    system_history_read_at: Option<Instant>,
+    // Moment the charts are scrubbed back to, None while showing live data
+    pub scrub_time: Option<i64>,
+    // How often in-memory history gains a point
+    pub refresh_interval: Duration,
+    pub detail_error: Option<String>,
 }
 
 impl App {
     pub fn new() -> Self {
         Self {
+            all_processes: HashMap::new(),
             processes: Vec::new(),
             selected_index: 0,
             previous_selected_pid: None,
             current_tab: 0,
-            tabs: vec!["Dashboard", "All Processes", "User", "System", "Detailed"],
+            tabs: [
+                "Dashboard",
+                "All Processes",
+                "User",
+                "System",
+                "Users",
+                "Containers",
+                "Cgroups",
+                "Detailed",
+                "Network",
+                "Disks",
+                "GPU",
+                "Alerts",
+                "Events",
+                "Ports",
+            ]
+            .map(String::from)
+            .to_vec(),
             sort_key: SortKey::Cpu,
             sort_ascending: false,
+            sort_order: Vec::new(),
+            sort_dirty: true,
             system_resources: SystemResources::new(),
+            network_stats: NetworkStats::new(),
+            disk_stats: DiskStats::new(),
             last_ui_refresh: Instant::now(),
-            last_data_refresh: Instant::now(),
             ui_refresh_interval: Duration::from_millis(33), // ~30fps
-            data_refresh_interval: Duration::from_millis(1000), // 1 second data updates
+            generation: 1,
+            drawn_generation: 0,
+            page_size: 10,
+            table_offset: 0,
             filter: String::new(),
-            show_help: false,
+            filter_regex: false,
+            filter_error: None,
+            filter_pattern: None,
+            sockets: HashMap::new(),
+            sockets_sampled_at: None,
+            view_mode: ViewMode::Flat,
+            collapsed: HashSet::new(),
+            expanded_groups: HashSet::new(),
+            tree_rows: HashMap::new(),
+            cgroups: Vec::new(),
+            cgroup_selected: 0,
+            ports: Vec::new(),
+            port_selected: 0,
+            ports_sampled_at: None,
+            ports_error: None,
+            cgroups_sampled_at: None,
+            cgroup_error: None,
+            cgroup_filter: None,
+            search: None,
+            search_origin: 0,
+            help: None,
+            debug: None,
+            keymap: Keymap::default(),
             loading_status: "Initializing...".to_string(),
-            refresh_sender: None,
+            collector: None,
+            system_load: None,
+            cores: Vec::new(),
+            gpus: Vec::new(),
+            numa_nodes: Vec::new(),
+            pressure_history: VecDeque::new(),
+            host: None,
+            refresh_requested: false,
+            input_mode: InputMode::Normal,
+            dialog: None,
+            quit: false,
+            input: String::new(),
+            status_message: None,
+            tagged: HashSet::new(),
+            started_at: HashMap::new(),
+            exiting: HashMap::new(),
+            events: VecDeque::new(),
+            event_selected: 0,
+            children: Vec::new(),
+            profiles: Vec::new(),
+            tasks: Vec::new(),
+            restarts: Vec::new(),
+            config: Config::default(),
+            alerts: Alerts::default(),
+            notify: false,
+            normalize_cpu: false,
+            scripts: None,
+            script_results: ScriptResults::default(),
+            plugins: Vec::new(),
+            plugin_tables: HashMap::new(),
+            plugins_collected_at: None,
+            plugin_cells: Vec::new(),
+            computed: None,
+            computed_values: HashMap::new(),
+            reported_error: None,
+            follow: None,
+            mouse: MouseRegions::default(),
+            column_picker: Vec::new(),
+            column_cursor: 0,
+            service_unit: None,
+            detail_panel: DetailPanel::Overview,
+            detail_scroll: 0,
+            detail_pid: None,
+            open_files: Vec::new(),
+            connections: Vec::new(),
+            resolver: Resolver::new(),
+            memory_maps: Vec::new(),
+            threads: Vec::new(),
+            threads_sampled_at: None,
+            activity: None,
+            activity_rates: VecDeque::new(),
+            activity_sampled_at: None,
+            wait_channel: None,
+            executable: None,
+            security: None,
+            limits: Vec::new(),
+            journal: Vec::new(),
+            journal_read_at: None,
+            journal_error: None,
+            output: Vec::new(),
+            output_read_at: None,
+            history: None,
+            process_history: Vec::new(),
+            process_history_read_at: None,
+            system_history: Vec::new(),
+            system_history_read_at: None,
+            scrub_time: None,
+            refresh_interval: Duration::from_secs(1),
+            detail_error: None,
+        }
+    }
+
+    pub fn set_status(&mut self, text: impl Into<String>) {
+        self.status_message = Some(Toast {
+            text: text.into(),
+            error: false,
+            shown_at: Instant::now(),
+        });
+    }
+
+    pub fn set_error(&mut self, text: impl Into<String>) {
+        self.status_message = Some(Toast {
+            text: text.into(),
+            error: true,
+            shown_at: Instant::now(),
+        });
+    }
+
+    pub fn expire_status(&mut self) {
+        let expired = self.status_message.as_ref().is_some_and(|toast| {
+            let duration = if toast.error {
+                ERROR_TOAST_DURATION
+            } else {
+                TOAST_DURATION
+            };
+            toast.shown_at.elapsed() >= duration
+        });
+        if expired {
+            self.status_message = None;
+            self.invalidate();
+        }
+    }
+
+    // Stop flashing rows whose time is up, dropping exited processes from the list
+    pub fn expire_flashes(&mut self) {
+        let duration = self.config.flash_duration();
+        let before = self.started_at.len() + self.exiting.len();
+        self.started_at.retain(|_, at| at.elapsed() < duration);
+        let exiting = self.exiting.len();
+        self.exiting.retain(|_, (_, at)| at.elapsed() < duration);
+        if self.exiting.len() != exiting {
+            self.sort_dirty = true;
+            self.update_selection();
         }
+        if self.started_at.len() + self.exiting.len() != before {
+            self.invalidate();
+        }
+    }
+
+    pub fn request_refresh(&mut self) {
+        self.refresh_requested = true;
     }
 
-    pub fn set_refresh_sender(&mut self, sender: mpsc::Sender<()>) {
-        self.refresh_sender = Some(sender);
+    // Fold an update from the process source into the app's state. Returns
+    // whether it brought a new process list or a change to it.
+    pub fn apply_update(&mut self, update: ProcessUpdate) -> bool {
+        self.invalidate();
+        match update {
+            ProcessUpdate::ProcessList(processes) => {
+                self.set_processes(processes);
+            }
+            ProcessUpdate::ProcessDelta(delta) => {
+                self.apply_delta(delta);
+            }
+            ProcessUpdate::SystemInfo(cpu, used, total, cores) => {
+                self.system_resources.update(cpu, used, total, cores);
+                return false;
+            }
+            ProcessUpdate::SwapInfo(used, total) => {
+                self.system_resources.update_swap(used, total);
+                return false;
+            }
+            ProcessUpdate::CoreInfo(cores) => {
+                self.cores = cores;
+                return false;
+            }
+            ProcessUpdate::GpuInfo(gpus) => {
+                self.gpus = gpus;
+                return false;
+            }
+            ProcessUpdate::NumaInfo(nodes) => {
+                self.numa_nodes = nodes;
+                return false;
+            }
+            ProcessUpdate::NetworkInfo(interfaces) => {
+                self.network_stats.update(interfaces);
+                return false;
+            }
+            ProcessUpdate::DiskInfo(disks) => {
+                self.disk_stats.update(disks);
+                return false;
+            }
+            ProcessUpdate::LoadingStatus(status) => {
+                self.loading_status = status;
+                return false;
+            }
+            ProcessUpdate::CollectorStats(stats) => {
+                self.collector = Some(stats);
+                return false;
+            }
+            ProcessUpdate::SystemLoad(load) => {
+                self.system_load = Some(load);
+                return false;
+            }
+            ProcessUpdate::Pressure(pressure) => {
+                push_history(&mut self.pressure_history, pressure);
+                return false;
+            }
+        }
+        self.evaluate_alerts();
+        self.record_history();
+        self.refresh_details();
+        self.refresh_cgroups();
+        self.refresh_ports();
+        self.refresh_plugin_tab();
+        self.refresh_system_history();
+        true
     }
 
     pub fn next(&mut self) {
-        if !self.processes.is_empty() {
-            self.previous_selected_pid = Some(self.processes[self.selected_index].pid);
+        if !self.processes.is_empty() && self.follow.is_none() {
+            self.previous_selected_pid = Some(self.processes[self.selected_index]);
             self.selected_index = (self.selected_index + 1) % self.processes.len();
         }
     }
 
     pub fn previous(&mut self) {
-        if !self.processes.is_empty() {
-            self.previous_selected_pid = Some(self.processes[self.selected_index].pid);
+        if !self.processes.is_empty() && self.follow.is_none() {
+            self.previous_selected_pid = Some(self.processes[self.selected_index]);
             self.selected_index = if self.selected_index > 0 {
                 self.selected_index - 1
             } else {
@@ -131,202 +1259,2597 @@ impl App {
         }
     }
 
-    pub fn next_tab(&mut self) {
-        self.current_tab = (self.current_tab + 1) % self.tabs.len();
+    // Jump the selection by a screenful, stopping at the ends of the list
+    pub fn page_down(&mut self) {
+        self.select_index(self.selected_index.saturating_add(self.page_size.max(1)));
     }
 
-    pub fn previous_tab(&mut self) {
-        self.current_tab = if self.current_tab > 0 {
-            self.current_tab - 1
-        } else {
-            self.tabs.len() - 1
-        };
+    pub fn page_up(&mut self) {
+        self.select_index(self.selected_index.saturating_sub(self.page_size.max(1)));
     }
 
-    pub fn toggle_help(&mut self) {
-        self.show_help = !self.show_help;
+    pub fn select_first(&mut self) {
+        self.select_index(0);
     }
 
-    // Update selection after process list changes
-    pub fn update_selection(&mut self) {
-        // If we have a previous selection, try to maintain it
-        let previous_pid = if !self.processes.is_empty() {
-            Some(self.processes[self.selected_index].pid)
-        } else {
-            self.previous_selected_pid
-        };
-
-        // If filter is active, filter the processes but don't modify the original vector
-        if !self.filter.is_empty() {
-            let filter = self.filter.to_lowercase();
-            let filtered_processes: Vec<_> = self
-                .processes
-                .iter()
-                .filter(|p| {
-                    p.name.to_lowercase().contains(&filter)
-                        || p.pid.to_string().contains(&filter)
-                        || p.user.to_lowercase().contains(&filter)
-                })
-                .cloned()
-                .collect();
+    pub fn select_last(&mut self) {
+        self.select_index(usize::MAX);
+    }
 
-            // Replace processes with filtered version
-            self.processes = filtered_processes;
+    pub fn select_pid(&mut self, pid: u32) {
+        if let Some(index) = self.processes.iter().position(|&p| p == pid) {
+            self.select_index(index);
         }
+    }
 
-        // Ensure selection is within bounds
-        if self.processes.is_empty() {
-            self.selected_index = 0;
-        } else if self.selected_index >= self.processes.len() {
-            self.selected_index = self.processes.len() - 1;
+    // Clicks select tabs, rows and sort columns; the wheel scrolls whatever
+    // list is under the pointer
+    pub fn handle_mouse(&mut self, event: MouseEvent) {
+        if let Some(help) = &mut self.help {
+            match event.kind {
+                MouseEventKind::ScrollDown => help.scroll = help.scroll.saturating_add(3),
+                MouseEventKind::ScrollUp => help.scroll = help.scroll.saturating_sub(3),
+                _ => {}
+            }
+            return;
+        }
+        if self.input_mode != InputMode::Normal || self.dialog.is_some() {
+            return;
         }
 
-        // Try to maintain previous selection if possible
-        if let Some(pid) = previous_pid {
-            if let Some(index) = self.processes.iter().position(|p| p.pid == pid) {
-                self.selected_index = index;
+        let (x, y) = (event.column, event.row);
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(&(_, tab)) = self.mouse.tabs.iter().find(|(r, _)| contains(*r, x, y)) {
+                    self.current_tab = tab;
+                    self.scrub_time = None;
+                } else if let Some(&(_, key)) =
+                    self.mouse.headers.iter().find(|(r, _)| contains(*r, x, y))
+                {
+                    self.set_sort_key(key);
+                } else if let Some(&(_, pid)) =
+                    self.mouse.rows.iter().find(|(r, _)| contains(*r, x, y))
+                {
+                    self.select_pid(pid);
+                }
+            }
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp
+                if contains(self.mouse.scroll_area, x, y) =>
+            {
+                let delta = if event.kind == MouseEventKind::ScrollDown {
+                    3
+                } else {
+                    -3
+                };
+                if self.on_tab("Detailed") {
+                    self.scroll_detail(delta);
+                } else if self.on_tab("Cgroups") {
+                    self.move_cgroup_selection(delta);
+                } else if self.on_tab("Ports") {
+                    self.move_port_selection(delta);
+                } else if self.on_tab("Events") {
+                    self.move_event_selection(delta);
+                } else {
+                    self.select_index(self.selected_index.saturating_add_signed(delta));
+                }
             }
+            _ => {}
         }
     }
 
-    pub fn clear_filter(&mut self) {
-        if !self.filter.is_empty() {
-            self.filter.clear();
-
-            // Request a full refresh to restore the full process list
-            if let Some(tx) = &self.refresh_sender {
-                let _ = tx.try_send(());
-            }
+    fn select_index(&mut self, index: usize) {
+        if self.processes.is_empty() || self.follow.is_some() {
+            return;
         }
+
+        self.previous_selected_pid = Some(self.processes[self.selected_index]);
+        self.selected_index = index.min(self.processes.len() - 1);
     }
 
-    pub fn should_refresh_ui(&self) -> bool {
-        self.last_ui_refresh.elapsed() >= self.ui_refresh_interval
+    // A process in the view: a running one, or one that just exited and is
+    // still on show
+    pub fn process(&self, pid: u32) -> Option<&ProcessInfo> {
+        self.all_processes
+            .get(&pid)
+            .or_else(|| self.exiting.get(&pid).map(|(process, _)| process))
     }
 
-    pub fn should_refresh_data(&self) -> bool {
-        self.last_data_refresh.elapsed() >= self.data_refresh_interval
+    // The processes in the view, in order
+    pub fn shown_processes(&self) -> impl Iterator<Item = &ProcessInfo> + '_ {
+        self.processes.iter().filter_map(|&pid| self.process(pid))
     }
 
-    pub fn refresh_ui(&mut self) {
-        self.last_ui_refresh = Instant::now();
+    pub fn selected_process(&self) -> Option<&ProcessInfo> {
+        self.process(*self.processes.get(self.selected_index)?)
     }
 
-    pub fn toggle_sort(&mut self) {
-        self.sort_ascending = !self.sort_ascending;
-        self.sort_processes();
+    // Processes listed on the current tab; the User and System tabs split the
+    // view by whether the current user owns the process
+    pub fn tab_processes(&self) -> Vec<&ProcessInfo> {
+        let current_user = current_user();
+        self.shown_processes()
+            .filter(|p| match self.tabs[self.current_tab].as_str() {
+                "User" => p.user == current_user,
+                "System" => p.user != current_user && p.user != "unknown",
+                _ => true,
+            })
+            .collect()
     }
 
-    pub fn set_sort_key(&mut self, key: SortKey) {
-        if self.sort_key == key {
-            self.sort_ascending = !self.sort_ascending;
-        } else {
-            self.sort_key = key;
-            self.sort_ascending = false; // Default to descending for new sort key
-        }
-        self.sort_processes();
+    // Where the selected process sits in a tab's list, if it is listed there
+    pub fn tab_selection(&self, list: &[&ProcessInfo]) -> Option<usize> {
+        let selected = *self.processes.get(self.selected_index)?;
+        list.iter().position(|p| p.pid == selected)
     }
 
-    pub fn sort_processes(&mut self) {
-        match self.sort_key {
-            SortKey::Pid => {
-                self.processes.sort_by(|a, b| {
-                    if self.sort_ascending {
-                        a.pid.cmp(&b.pid)
-                    } else {
-                        b.pid.cmp(&a.pid)
-                    }
-                });
+    // Scroll the current table just far enough to bring the selected row on
+    // screen, without leaving blank rows below the end of the list
+    pub fn update_viewport(&mut self) {
+        // This runs every frame, so only the tabs that split the list pay for
+        // building theirs
+        let (len, selected) = match self.tabs[self.current_tab].as_str() {
+            "User" | "System" => {
+                let list = self.tab_processes();
+                (list.len(), self.tab_selection(&list))
             }
-            SortKey::Name => {
-                self.processes.sort_by(|a, b| {
-                    if self.sort_ascending {
-                        a.name.cmp(&b.name)
-                    } else {
-                        b.name.cmp(&a.name)
-                    }
-                });
+            _ => (
+                self.processes.len(),
+                (!self.processes.is_empty()).then_some(self.selected_index),
+            ),
+        };
+        let page = self.page_size.max(1);
+        let mut offset = self.table_offset.min(len.saturating_sub(page));
+        if let Some(selected) = selected {
+            if selected < offset {
+                offset = selected;
+            } else if selected >= offset + page {
+                offset = selected + 1 - page;
             }
-            SortKey::Cpu => {
-                self.processes.sort_by(|a, b| {
-                    if self.sort_ascending {
-                        a.cpu_usage
-                            .partial_cmp(&b.cpu_usage)
-                            .unwrap_or(std::cmp::Ordering::Equal)
-                    } else {
-                        b.cpu_usage
-                            .partial_cmp(&a.cpu_usage)
-                            .unwrap_or(std::cmp::Ordering::Equal)
-                    }
-                });
+        }
+        self.table_offset = offset;
+    }
+
+    pub fn next_tab(&mut self) {
+        self.current_tab = (self.current_tab + 1) % self.tabs.len();
+        self.scrub_time = None;
+    }
+
+    pub fn previous_tab(&mut self) {
+        self.scrub_time = None;
+        self.current_tab = if self.current_tab > 0 {
+            self.current_tab - 1
+        } else {
+            self.tabs.len() - 1
+        };
+    }
+
+    // Switch to the tab whose title (or any word of it) matches `name`
+    pub fn select_tab(&mut self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        let position = self.tabs.iter().position(|t| {
+            let title = t.to_lowercase();
+            title == name || title.split_whitespace().any(|word| word == name)
+        });
+
+        match position {
+            Some(index) => {
+                self.current_tab = index;
+                self.scrub_time = None;
+                true
             }
-            SortKey::Memory => {
-                self.processes.sort_by(|a, b| {
-                    if self.sort_ascending {
-                        a.memory
-                            .partial_cmp(&b.memory)
-                            .unwrap_or(std::cmp::Ordering::Equal)
-                    } else {
-                        b.memory
-                            .partial_cmp(&a.memory)
-                            .unwrap_or(std::cmp::Ordering::Equal)
-                    }
+            None => false,
+        }
+    }
+
+    pub fn on_tab(&self, name: &str) -> bool {
+        self.tabs[self.current_tab] == name
+    }
+
+    pub fn next_detail_panel(&mut self) {
+        let index = DetailPanel::ALL
+            .iter()
+            .position(|p| *p == self.detail_panel)
+            .unwrap_or(0);
+        self.detail_panel = DetailPanel::ALL[(index + 1) % DetailPanel::ALL.len()];
+        self.detail_scroll = 0;
+        self.refresh_details();
+    }
+
+    pub fn scroll_detail(&mut self, delta: isize) {
+        let rows = match self.detail_panel {
+            DetailPanel::Overview | DetailPanel::Security => 0,
+            DetailPanel::Arguments => self.selected_process().map_or(0, |p| p.cmd.len()),
+            DetailPanel::OpenFiles => self.open_files.len(),
+            DetailPanel::Connections => self.connections.len(),
+            DetailPanel::MemoryMaps => self.memory_maps.len(),
+            DetailPanel::Threads => self.threads.len(),
+            DetailPanel::Limits => self.limits.len(),
+            DetailPanel::Journal => self.journal.len(),
+            DetailPanel::Output => self
+                .output
+                .iter()
+                .map(|stream| 1 + stream.tail.as_ref().map_or(1, Vec::len))
+                .sum(),
+        };
+        self.detail_scroll = self
+            .detail_scroll
+            .saturating_add_signed(delta)
+            .min(rows.saturating_sub(1));
+    }
+
+    // Reload the data behind the active Detailed panel for the selected process.
+    // Only does work while the Detailed tab is visible.
+    pub fn refresh_details(&mut self) {
+        if !self.on_tab("Detailed") || self.processes.is_empty() {
+            return;
+        }
+        if self.follow.as_ref().is_some_and(|f| f.exited) {
+            return;
+        }
+
+        let pid = self.processes[self.selected_index];
+        if self.detail_pid != Some(pid) {
+            self.detail_pid = Some(pid);
+            self.detail_scroll = 0;
+            self.threads.clear();
+            self.threads_sampled_at = None;
+            self.activity = None;
+            self.activity_rates.clear();
+            self.activity_sampled_at = None;
+            self.wait_channel = None;
+            self.executable = None;
+            self.security = None;
+            self.journal.clear();
+            self.journal_read_at = None;
+            self.journal_error = None;
+            self.output.clear();
+            self.output_read_at = None;
+            self.process_history.clear();
+            self.process_history_read_at = None;
+            self.scrub_time = None;
+        }
+
+        self.detail_error = None;
+        if self.host.is_some()
+            && !matches!(
+                self.detail_panel,
+                DetailPanel::Overview | DetailPanel::Arguments
+            )
+        {
+            self.detail_error = Some("not available for remote hosts".to_string());
+            return;
+        }
+        match self.detail_panel {
+            DetailPanel::Overview => {
+                self.refresh_process_history();
+                self.refresh_activity(pid);
+                (self.wait_channel, self.executable) = if self.host.is_none() {
+                    (inspect::wait_channel(pid), inspect::executable(pid))
+                } else {
+                    (None, None)
+                };
+            }
+            DetailPanel::Arguments => {}
+            DetailPanel::OpenFiles => match inspect::open_files(pid) {
+                Ok(files) => self.open_files = files,
+                Err(e) => {
+                    self.open_files.clear();
+                    self.detail_error = Some(e.to_string());
+                }
+            },
+            DetailPanel::Connections => match inspect::connections(pid) {
+                Ok(connections) => {
+                    let reverse_dns = self.config.reverse_dns();
+                    for connection in &connections {
+                        let udp = connection.protocol.starts_with("udp");
+                        self.resolver.request(&connection.local, udp, false);
+                        self.resolver.request(&connection.remote, udp, reverse_dns);
+                    }
+                    self.connections = connections;
+                }
+                Err(e) => {
+                    self.connections.clear();
+                    self.detail_error = Some(e.to_string());
+                }
+            },
+            DetailPanel::MemoryMaps => match inspect::memory_maps(pid) {
+                Ok(maps) => self.memory_maps = maps,
+                Err(e) => {
+                    self.memory_maps.clear();
+                    self.detail_error = Some(e.to_string());
+                }
+            },
+            DetailPanel::Threads => self.refresh_threads(pid),
+            DetailPanel::Security => match inspect::security(pid) {
+                Ok(security) => self.security = Some(security),
+                Err(e) => {
+                    self.security = None;
+                    self.detail_error = Some(e.to_string());
+                }
+            },
+            DetailPanel::Limits => match inspect::limits(pid) {
+                Ok(limits) => self.limits = limits,
+                Err(e) => {
+                    self.limits.clear();
+                    self.detail_error = Some(e.to_string());
+                }
+            },
+            DetailPanel::Journal => self.refresh_journal(pid),
+            DetailPanel::Output => self.refresh_output(pid),
+        }
+    }
+
+    // The Dashboard charts the last hour from the history database
+    pub fn refresh_system_history(&mut self) {
+        if !self.on_tab("Dashboard") || self.host.is_some() {
+            return;
+        }
+        let Some(history) = &self.history else {
+            return;
+        };
+        if self
+            .system_history_read_at
+            .is_some_and(|at| at.elapsed() < Duration::from_secs(5))
+        {
+            return;
+        }
+
+        match history.system_history(Duration::from_secs(3600)) {
+            Ok(samples) => self.system_history = samples,
+            Err(e) => {
+                self.system_history.clear();
+                self.set_error(format!("Failed to read history: {}", e));
+            }
+        }
+        self.system_history_read_at = Some(Instant::now());
+    }
+
+    // Times of the points charted on the current tab, oldest first. In-memory
+    // history has no timestamps; its points are one refresh interval apart.
+    pub fn chart_times(&self) -> Vec<i64> {
+        let stored = if self.on_tab("Dashboard") {
+            &self.system_history
+        } else if self.on_tab("Detailed") && self.detail_panel == DetailPanel::Overview {
+            &self.process_history
+        } else {
+            return Vec::new();
+        };
+        if stored.len() > 1 {
+            return stored.iter().map(|s| s.time).collect();
+        }
+
+        let points = if self.on_tab("Dashboard") {
+            self.system_resources.cpu_history.len()
+        } else {
+            self.selected_process().map_or(0, |p| p.cpu_history.len())
+        };
+        let now = history::now();
+        let interval = self.refresh_interval.as_secs_f64();
+        (0..points)
+            .map(|i| now - ((points - 1 - i) as f64 * interval).round() as i64)
+            .collect()
+    }
+
+    pub fn can_scrub(&self) -> bool {
+        self.chart_times().len() > 1
+    }
+
+    // Index of the charted point closest to the scrubbed-to time
+    pub fn scrub_index(&self, times: &[i64]) -> Option<usize> {
+        let time = self.scrub_time?;
+        times
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, &t)| (t - time).abs())
+            .map(|(index, _)| index)
+    }
+
+    // Step the charts `delta` points back (negative) or forward in time;
+    // stepping past the newest point returns to live data
+    pub fn scrub(&mut self, delta: isize) {
+        let times = self.chart_times();
+        let Some(last) = times.len().checked_sub(1) else {
+            return;
+        };
+        let index = self.scrub_index(&times).unwrap_or(last);
+        let index = index.saturating_add_signed(delta).min(last);
+        self.scrub_time = if index == last {
+            None
+        } else {
+            Some(times[index])
+        };
+    }
+
+    // Reloaded every few seconds; the query is cheap next to a refresh but
+    // covers hours of samples
+    fn refresh_process_history(&mut self) {
+        let Some(history) = &self.history else {
+            return;
+        };
+        if self
+            .process_history_read_at
+            .is_some_and(|at| at.elapsed() < Duration::from_secs(5))
+        {
+            return;
+        }
+
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        match history.process_history(process) {
+            Ok(samples) => self.process_history = samples,
+            Err(e) => {
+                self.process_history.clear();
+                self.set_error(format!("Failed to read history: {}", e));
+            }
+        }
+        self.process_history_read_at = Some(Instant::now());
+    }
+
+    // Add the latest refresh to the history database. Agents' processes
+    // aren't recorded, their PIDs would mix with this machine's.
+    pub fn record_history(&mut self) {
+        if self.host.is_some() {
+            return;
+        }
+        let Some(history) = &mut self.history else {
+            return;
+        };
+        if let Err(e) = history.record(&self.system_resources, &self.all_processes) {
+            self.set_error(format!("History disabled: {}", e));
+            self.history = None;
+        }
+    }
+
+    // Check the configured alert rules against the latest refresh
+    pub fn evaluate_alerts(&mut self) {
+        self.alerts.evaluate(
+            &self.config.alerts,
+            &self.system_resources,
+            &self.all_processes,
+        );
+        let mut script_errors = Vec::new();
+        let mut command_error = None;
+        for alert in self.alerts.active.iter().filter(|a| a.new) {
+            let rule = &self.config.alerts[alert.rule];
+            if self.notify {
+                alerts::notify(rule, alert);
+            }
+            let process = alert.pid.and_then(|pid| self.all_processes.get(&pid));
+            if let Some(url) = &rule.webhook {
+                alerts::post_webhook(url, rule, alert, process, self.host.as_deref());
+            }
+            if let Some(scripts) = &self.scripts {
+                if let Err(e) = scripts.on_alert(rule.describe(), alert) {
+                    script_errors.push(e);
+                }
+            }
+            // An agent's PIDs would point at the wrong processes here
+            if let (Some(command), None) = (&rule.exec, &self.host) {
+                if let Err(e) = alerts::run_command(command, rule, alert, process) {
+                    command_error = Some(format!("Alert command failed: {}", e));
+                }
+            }
+        }
+        if let Some(error) = command_error {
+            self.set_error(error);
+        }
+        for error in script_errors {
+            self.report_once(error);
+        }
+        self.apply_script_actions();
+    }
+
+    // journalctl is slow compared to reading /proc, so the tail is re-read
+    // every couple of seconds rather than on every key press. In between,
+    // the last read's error stays up.
+    fn refresh_journal(&mut self, pid: u32) {
+        let now = Instant::now();
+        if self
+            .journal_read_at
+            .is_some_and(|at| now.duration_since(at) < Duration::from_secs(2))
+        {
+            self.detail_error = self.journal_error.clone();
+            return;
+        }
+
+        let unit = self.selected_process().and_then(|p| p.unit.clone());
+        match systemd::journal(pid, unit.as_deref(), 200) {
+            Ok(lines) => {
+                self.journal = lines;
+                self.journal_error = None;
+            }
+            Err(e) => {
+                self.journal.clear();
+                self.journal_error = Some(e.to_string());
+            }
+        }
+        self.detail_error = self.journal_error.clone();
+        self.journal_read_at = Some(now);
+    }
+
+    // Finding a pipe's reader means a scan of every process's descriptors, so
+    // this is re-read at most once a second rather than on every key
+    fn refresh_output(&mut self, pid: u32) {
+        let now = Instant::now();
+        if self
+            .output_read_at
+            .is_some_and(|at| now.duration_since(at) < Duration::from_secs(1))
+        {
+            return;
+        }
+
+        // Failures are cheap, and retried so the error stays up
+        match inspect::output(pid) {
+            Ok(output) => {
+                self.output = output;
+                self.output_read_at = Some(now);
+            }
+            Err(e) => {
+                self.output.clear();
+                self.detail_error = Some(e.to_string());
+            }
+        }
+    }
+
+    // Re-read the cgroup hierarchy while the Cgroups tab is visible, deriving
+    // each cgroup's CPU usage from its CPU time since the last sample
+    pub fn refresh_cgroups(&mut self) {
+        if !self.on_tab("Cgroups") {
+            return;
+        }
+        let now = Instant::now();
+        if self
+            .cgroups_sampled_at
+            .is_some_and(|at| now.duration_since(at) < Duration::from_millis(500))
+        {
+            return;
+        }
+        if self.host.is_some() {
+            self.cgroups.clear();
+            self.cgroup_error = Some("not available for remote hosts".to_string());
+            return;
+        }
+
+        match cgroups::cgroups() {
+            Ok(mut cgroups) => {
+                if let Some(at) = self.cgroups_sampled_at {
+                    let elapsed_usec = now.duration_since(at).as_micros() as f64;
+                    let previous: HashMap<&str, u64> = self
+                        .cgroups
+                        .iter()
+                        .map(|c| (c.path.as_str(), c.cpu_usage_usec))
+                        .collect();
+                    for cgroup in &mut cgroups {
+                        if let Some(&usec) = previous.get(cgroup.path.as_str()) {
+                            cgroup.cpu_usage = (cgroup.cpu_usage_usec.saturating_sub(usec) as f64
+                                / elapsed_usec
+                                * 100.0) as f32;
+                        }
+                    }
+                }
+                self.cgroups = cgroups;
+                self.cgroup_error = None;
+                self.cgroup_selected = self
+                    .cgroup_selected
+                    .min(self.cgroups.len().saturating_sub(1));
+            }
+            Err(e) => {
+                self.cgroups.clear();
+                self.cgroup_error = Some(e.to_string());
+            }
+        }
+        self.cgroups_sampled_at = Some(now);
+    }
+
+    // Finding the owners means reading every process's descriptors, so the
+    // Ports tab is refreshed every couple of seconds rather than on every key
+    pub fn refresh_ports(&mut self) {
+        if !self.on_tab("Ports") {
+            return;
+        }
+        let now = Instant::now();
+        if self
+            .ports_sampled_at
+            .is_some_and(|at| now.duration_since(at) < Duration::from_secs(2))
+        {
+            return;
+        }
+        if self.host.is_some() {
+            self.ports.clear();
+            self.ports_error = Some("not available for remote hosts".to_string());
+            return;
+        }
+
+        match inspect::listening_ports() {
+            Ok(ports) => {
+                self.ports = ports;
+                self.ports_error = None;
+            }
+            Err(e) => {
+                self.ports.clear();
+                self.ports_error = Some(e.to_string());
+            }
+        }
+        self.ports_sampled_at = Some(now);
+    }
+
+    // Listening sockets as the Ports tab shows them: narrowed by the filter to
+    // port numbers or owners matching it, and in port order unless sorted by
+    // PID, name or user
+    pub fn visible_ports(&self) -> Vec<&ListeningPort> {
+        let filter = self.filter.to_lowercase();
+        let owner = |port: &ListeningPort| {
+            port.pids
+                .first()
+                .and_then(|pid| self.all_processes.get(pid))
+        };
+        let mut ports: Vec<&ListeningPort> = self
+            .ports
+            .iter()
+            .filter(|port| {
+                filter.is_empty()
+                    || port.port.to_string().contains(&filter)
+                    || port.pids.iter().any(|pid| {
+                        self.all_processes
+                            .get(pid)
+                            .is_some_and(|p| p.name.to_lowercase().contains(&filter))
+                    })
+            })
+            .collect();
+        ports.sort_by(|a, b| {
+            let order = match self.sort_key {
+                SortKey::Pid => a.pids.first().cmp(&b.pids.first()),
+                SortKey::Name => owner(a).map(|p| &p.name).cmp(&owner(b).map(|p| &p.name)),
+                SortKey::User => owner(a).map(|p| &p.user).cmp(&owner(b).map(|p| &p.user)),
+                _ => std::cmp::Ordering::Equal,
+            };
+            order.then_with(|| {
+                (a.port, a.protocol, &a.address).cmp(&(b.port, b.protocol, &b.address))
+            })
+        });
+        ports
+    }
+
+    // Reading every process's descriptors is too slow to do on each key
+    // typed into the filter, so the sockets are re-read every couple of seconds
+    fn refresh_sockets(&mut self) {
+        if self.host.is_some() {
+            self.sockets.clear();
+            self.filter_error =
+                Some("socket filters aren't available for remote hosts".to_string());
+            return;
+        }
+        let now = Instant::now();
+        if self
+            .sockets_sampled_at
+            .is_some_and(|at| now.duration_since(at) < Duration::from_secs(2))
+        {
+            return;
+        }
+        match inspect::sockets() {
+            Ok(sockets) => {
+                self.sockets = sockets;
+                self.sockets_sampled_at = Some(now);
+            }
+            Err(e) => {
+                self.sockets.clear();
+                self.filter_error = Some(e.to_string());
+            }
+        }
+    }
+
+    pub fn move_event_selection(&mut self, delta: isize) {
+        self.event_selected = self
+            .event_selected
+            .saturating_add_signed(delta)
+            .min(self.events.len().saturating_sub(1));
+    }
+
+    pub fn move_port_selection(&mut self, delta: isize) {
+        self.port_selected = self
+            .port_selected
+            .saturating_add_signed(delta)
+            .min(self.visible_ports().len().saturating_sub(1));
+    }
+
+    // Inspect the selected port's owner in the Detailed tab. The filter is
+    // cleared, as it's likely a port number that no process name matches.
+    pub fn open_port(&mut self) {
+        let Some(&pid) = self
+            .visible_ports()
+            .get(self.port_selected)
+            .and_then(|port| port.pids.first())
+        else {
+            return;
+        };
+        self.clear_filter();
+        self.select_pid(pid);
+        self.select_tab("Detailed");
+    }
+
+    pub fn move_cgroup_selection(&mut self, delta: isize) {
+        self.cgroup_selected = self
+            .cgroup_selected
+            .saturating_add_signed(delta)
+            .min(self.cgroups.len().saturating_sub(1));
+    }
+
+    // Show the selected cgroup's member processes in the All Processes tab
+    pub fn open_cgroup(&mut self) {
+        let Some(cgroup) = self.cgroups.get(self.cgroup_selected) else {
+            return;
+        };
+        self.cgroup_filter = Some(cgroup.path.clone());
+        self.select_tab("processes");
+        self.update_selection();
+    }
+
+    pub fn clear_cgroup_filter(&mut self) {
+        self.cgroup_filter = None;
+        self.update_selection();
+    }
+
+    // Per-thread CPU usage comes from the change in CPU time between samples,
+    // so keep samples at least half a second apart to avoid noisy readings
+    fn refresh_threads(&mut self, pid: u32) {
+        let now = Instant::now();
+        if self
+            .threads_sampled_at
+            .is_some_and(|at| now.duration_since(at) < Duration::from_millis(500))
+        {
+            return;
+        }
+
+        match inspect::threads(pid) {
+            Ok(mut threads) => {
+                if let Some(at) = self.threads_sampled_at {
+                    let elapsed = now.duration_since(at).as_secs_f64();
+                    for thread in &mut threads {
+                        if let Some(previous) = self.threads.iter().find(|t| t.tid == thread.tid) {
+                            thread.cpu_usage =
+                                ((thread.cpu_time - previous.cpu_time) / elapsed * 100.0) as f32;
+                        }
+                    }
+                }
+                self.threads = threads;
+                self.threads_sampled_at = Some(now);
+            }
+            Err(e) => {
+                self.threads.clear();
+                self.threads_sampled_at = None;
+                self.detail_error = Some(e.to_string());
+            }
+        }
+    }
+
+    // Sampled at most once a second, so the rates aren't dominated by the
+    // jitter of a short interval. Only this machine's processes can be read.
+    fn refresh_activity(&mut self, pid: u32) {
+        let now = Instant::now();
+        if self.host.is_some()
+            || self
+                .activity_sampled_at
+                .is_some_and(|at| now.duration_since(at) < Duration::from_secs(1))
+        {
+            return;
+        }
+
+        match inspect::activity(pid) {
+            Ok(totals) => {
+                if let (Some(previous), Some(at)) = (&self.activity, self.activity_sampled_at) {
+                    let rates = totals.rates_since(previous, now.duration_since(at));
+                    push_history(&mut self.activity_rates, rates);
+                }
+                self.activity = Some(totals);
+                self.activity_sampled_at = Some(now);
+            }
+            Err(_) => {
+                self.activity = None;
+                self.activity_rates.clear();
+                self.activity_sampled_at = None;
+            }
+        }
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.help = match self.help {
+            Some(_) => None,
+            None => Some(Help::default()),
+        };
+    }
+
+    // Keys while the help is open: arrows and paging scroll, `/` narrows it
+    // down to matching entries, and Esc clears that before closing
+    pub fn help_key(&mut self, key: KeyEvent, action: Option<keymap::Action>) {
+        let Some(help) = &mut self.help else {
+            return;
+        };
+        if help.searching {
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Esc => {
+                    help.searching = false;
+                    help.search.clear();
+                }
+                KeyCode::Enter => help.searching = false,
+                KeyCode::Backspace => {
+                    help.search.pop();
+                }
+                KeyCode::Char(c) if !ctrl => help.search.push(c),
+                _ => {}
+            }
+            help.scroll = 0;
+            return;
+        }
+
+        let page = help.page.max(1);
+        match key.code {
+            KeyCode::Up => help.scroll = help.scroll.saturating_sub(1),
+            KeyCode::Down => help.scroll = help.scroll.saturating_add(1),
+            KeyCode::PageUp => help.scroll = help.scroll.saturating_sub(page),
+            KeyCode::PageDown => help.scroll = help.scroll.saturating_add(page),
+            KeyCode::Home => help.scroll = 0,
+            // Drawing stops it at the last page
+            KeyCode::End => help.scroll = usize::MAX,
+            KeyCode::Char('/') => {
+                help.searching = true;
+                help.search.clear();
+                help.scroll = 0;
+            }
+            KeyCode::Esc if !help.search.is_empty() => {
+                help.search.clear();
+                help.scroll = 0;
+            }
+            _ if matches!(action, Some(keymap::Action::Help | keymap::Action::Quit)) => {
+                self.help = None
+            }
+            _ => {}
+        }
+    }
+
+    // Replace the full process list with a fresh refresh and rebuild the view.
+    // The first list starts nothing: everything in it would look new.
+    pub fn set_processes(&mut self, processes: Vec<ProcessInfo>) {
+        let mut previous = std::mem::replace(
+            &mut self.all_processes,
+            processes.into_iter().map(|p| (p.pid, p)).collect(),
+        );
+        let started = if previous.is_empty() {
+            Vec::new()
+        } else {
+            self.all_processes
+                .keys()
+                .filter(|pid| !previous.contains_key(pid))
+                .copied()
+                .collect()
+        };
+        previous.retain(|pid, _| !self.all_processes.contains_key(pid));
+        let exited: Vec<ProcessInfo> = previous.into_values().collect();
+        self.processes_changed(&started, &exited);
+    }
+
+    // Apply the changes since the last refresh and rebuild the view
+    pub fn apply_delta(&mut self, delta: ProcessDelta) {
+        let started: Vec<u32> = delta.added.iter().map(|p| p.pid).collect();
+        let exited = delta.apply(&mut self.all_processes);
+        self.processes_changed(&started, &exited);
+    }
+
+    fn processes_changed(&mut self, started: &[u32], exited: &[ProcessInfo]) {
+        self.sort_dirty = true;
+        let events = started
+            .iter()
+            .filter_map(|pid| self.all_processes.get(pid))
+            .map(|process| ProcessEvent::new(process, false))
+            .chain(
+                exited
+                    .iter()
+                    .map(|process| ProcessEvent::new(process, true)),
+            );
+        let logged = self.events.len();
+        self.events.extend(events);
+        // New events go in above the selection; it stays on the same event
+        // unless it's at the top, following the newest
+        if self.event_selected > 0 {
+            self.event_selected += self.events.len() - logged;
+        }
+        let excess = self.events.len().saturating_sub(EVENT_LOG_LEN);
+        self.events.drain(..excess);
+        self.event_selected = self.event_selected.min(self.events.len().saturating_sub(1));
+        if !self.config.flash_duration().is_zero() {
+            let now = Instant::now();
+            for &pid in started {
+                // A reused PID replaces the exited process still on show
+                self.exiting.remove(&pid);
+                self.started_at.insert(pid, now);
+            }
+            for process in exited {
+                self.started_at.remove(&process.pid);
+                self.exiting.insert(process.pid, (process.clone(), now));
+            }
+        }
+        self.run_scripts(started, exited);
+        self.refresh_plugin_cells();
+        self.refresh_computed();
+        self.prune_tags();
+        self.update_selection();
+    }
+
+    fn run_scripts(&mut self, started: &[u32], exited: &[ProcessInfo]) {
+        let Some(scripts) = &self.scripts else {
+            return;
+        };
+        match scripts.refresh(started, exited, &self.all_processes) {
+            Ok(results) => {
+                self.script_results = results;
+            }
+            Err(e) => {
+                // A broken filter shouldn't hide anything
+                self.script_results = ScriptResults::default();
+                self.report_once(e);
+            }
+        }
+        self.apply_script_actions();
+    }
+
+    fn report_once(&mut self, error: String) {
+        if self.reported_error.as_ref() != Some(&error) {
+            self.set_error(error.clone());
+            self.reported_error = Some(error);
+        }
+    }
+
+    fn refresh_computed(&mut self) {
+        let Some(computed) = &self.computed else {
+            return;
+        };
+        let mut error = None;
+        self.computed_values = self
+            .all_processes
+            .values()
+            .map(|process| {
+                let (values, failed) = computed.evaluate(process);
+                if error.is_none() {
+                    error = failed;
+                }
+                (process.pid, values)
+            })
+            .collect();
+        if let Some(error) = error {
+            self.report_once(format!("Computed column: {}", error));
+        }
+    }
+
+    // Ask the plugins with a column for each process's text. Their data comes
+    // from this machine, so agents' processes get none. Each plugin has one
+    // fuel budget per refresh for every process, and the rows on show go
+    // first, so a slow plugin leaves cells blank rather than stalling the UI.
+    fn refresh_plugin_cells(&mut self) {
+        self.plugin_cells = vec![HashMap::new(); self.plugins.len()];
+        if self.host.is_some() || self.plugins.iter().all(|p| p.column.is_none()) {
+            return;
+        }
+        let shown: HashSet<u32> = self.processes.iter().copied().collect();
+        let order: Vec<&ProcessInfo> = self
+            .processes
+            .iter()
+            .filter_map(|pid| self.all_processes.get(pid))
+            .chain(
+                self.all_processes
+                    .values()
+                    .filter(|p| !shown.contains(&p.pid)),
+            )
+            .collect();
+
+        let mut errors = Vec::new();
+        for (plugin, cells) in self.plugins.iter_mut().zip(&mut self.plugin_cells) {
+            if plugin.column.is_none() {
+                continue;
+            }
+            if let Err(e) = plugin.start_cells(plugins::CELLS_FUEL) {
+                errors.push(format!("plugin {}: {}", plugin.name, e));
+                continue;
+            }
+            for process in &order {
+                match plugin.cell(process) {
+                    Ok(text) if !text.is_empty() => {
+                        cells.insert(process.pid, text);
+                    }
+                    Ok(_) => {}
+                    Err(e) if plugins::out_of_fuel(&e) => break,
+                    Err(e) => {
+                        errors.push(format!("plugin {}: {}", plugin.name, e));
+                        break;
+                    }
+                }
+            }
+        }
+        for error in errors {
+            self.report_once(error);
+        }
+    }
+
+    // Re-collect the visible plugin tab's table, at most once a second
+    pub fn refresh_plugin_tab(&mut self) {
+        let tab = &self.tabs[self.current_tab];
+        let Some(plugin) = self
+            .plugins
+            .iter_mut()
+            .find(|p| p.tab.as_ref() == Some(tab))
+        else {
+            return;
+        };
+        let now = Instant::now();
+        if self
+            .plugins_collected_at
+            .is_some_and(|at| now.duration_since(at) < Duration::from_secs(1))
+            && self.plugin_tables.contains_key(tab)
+        {
+            return;
+        }
+
+        let table = if self.host.is_some() {
+            Err("not available for remote hosts".to_string())
+        } else {
+            plugin.collect().map_err(|e| e.to_string())
+        };
+        self.plugin_tables.insert(tab.clone(), table);
+        self.plugins_collected_at = Some(now);
+    }
+
+    // Carry out what the scripts asked for while they ran
+    fn apply_script_actions(&mut self) {
+        let Some(scripts) = &self.scripts else {
+            return;
+        };
+        for action in scripts.take_actions() {
+            match action {
+                Action::Kill(pid) => {
+                    if !self.remote_only_refusal() {
+                        if let Err(e) = control::kill(pid) {
+                            self.set_error(format!("Kill failed for PID {}: {}", pid, e));
+                        }
+                    }
+                }
+                Action::Renice(pid, nice) => {
+                    if !self.remote_only_refusal() {
+                        if let Err(e) = control::set_nice(pid, nice) {
+                            self.set_error(format!("Failed to renice {}: {}", pid, e));
+                        }
+                    }
+                }
+                Action::Status(text) => self.set_status(text),
+            }
+        }
+    }
+
+    // Rebuild the visible list from the full one after the processes or the
+    // filter change, keeping the selection on the same process where possible
+    pub fn update_selection(&mut self) {
+        // If we have a previous selection, try to maintain it
+        let previous_pid = if let Some(follow) = &self.follow {
+            Some(follow.pid)
+        } else if !self.processes.is_empty() {
+            Some(self.processes[self.selected_index])
+        } else {
+            self.previous_selected_pid
+        };
+
+        // A followed process that left the list has exited
+        if let Some(follow) = &mut self.follow {
+            follow.exited = !self.all_processes.contains_key(&follow.pid);
+        }
+
+        // An invalid regex leaves the list unfiltered and is reported in the filter line
+        self.filter_error = None;
+        self.filter_pattern = None;
+        if !self.filter.is_empty() {
+            match Filter::new(&self.filter, self.filter_regex) {
+                Ok(filter) => self.filter_pattern = Some(filter),
+                Err(e) => self.filter_error = Some(regex_error_summary(&e)),
+            }
+        }
+        let socket_filter = self
+            .filter_pattern
+            .as_ref()
+            .and_then(|filter| filter.field)
+            .is_some_and(|field| field.is_socket());
+        if socket_filter {
+            self.refresh_sockets();
+        }
+
+        self.sort_processes();
+        let followed_pid = self.follow.as_ref().map(|f| f.pid);
+        let processes: Vec<u32> = match &self.filter_pattern {
+            Some(filter) => self
+                .sort_order
+                .iter()
+                .copied()
+                // The followed process stays visible whatever the filter
+                .filter(|&pid| {
+                    Some(pid) == followed_pid
+                        || if socket_filter {
+                            self.sockets.get(&pid).is_some_and(|sockets| {
+                                sockets
+                                    .iter()
+                                    .any(|s| filter.matches_socket(&s.local, &s.remote))
+                            })
+                        } else {
+                            self.process(pid).is_some_and(|p| filter.matches(p))
+                        }
+                })
+                .collect(),
+            None => self
+                .sort_order
+                .iter()
+                .copied()
+                .filter(|&pid| self.process(pid).is_some())
+                .collect(),
+        };
+        self.processes = processes;
+        if let Some(path) = &self.cgroup_filter {
+            let members: HashSet<u32> = cgroups::member_pids(path).into_iter().collect();
+            self.processes
+                .retain(|&pid| members.contains(&pid) || Some(pid) == followed_pid);
+        }
+        if !self.script_results.hidden.is_empty() {
+            self.processes.retain(|&pid| {
+                !self.script_results.hidden.contains(&pid) || Some(pid) == followed_pid
+            });
+        }
+        match self.view_mode {
+            ViewMode::Flat => self.tree_rows.clear(),
+            ViewMode::Tree => self.arrange_tree(),
+            ViewMode::Grouped => self.arrange_groups(),
+        }
+
+        // Ensure selection is within bounds
+        if self.processes.is_empty() {
+            self.selected_index = 0;
+        } else if self.selected_index >= self.processes.len() {
+            self.selected_index = self.processes.len() - 1;
+        }
+
+        // Try to maintain previous selection if possible
+        if let Some(pid) = previous_pid {
+            if let Some(index) = self.processes.iter().position(|&p| p == pid) {
+                self.selected_index = index;
+            }
+        }
+    }
+
+    // What process CPU% is divided by for display: the core count when it
+    // is shown as a share of all cores
+    pub fn cpu_divisor(&self) -> f32 {
+        if self.normalize_cpu {
+            self.system_resources.cpu_count as f32
+        } else {
+            1.0
+        }
+    }
+
+    pub fn toggle_normalize_cpu(&mut self) {
+        self.normalize_cpu = !self.normalize_cpu;
+        self.set_status(if self.normalize_cpu {
+            "CPU% as a share of all cores"
+        } else {
+            "CPU% per core"
+        });
+    }
+
+    pub fn clear_filter(&mut self) {
+        if !self.filter.is_empty() {
+            self.filter.clear();
+            self.update_selection();
+        }
+    }
+
+    // Note that something shown may have changed
+    pub fn invalidate(&mut self) {
+        self.generation += 1;
+    }
+
+    pub fn should_refresh_ui(&self) -> bool {
+        let elapsed = self.last_ui_refresh.elapsed();
+        (self.generation != self.drawn_generation && elapsed >= self.ui_refresh_interval)
+            || elapsed >= IDLE_REDRAW_INTERVAL
+    }
+
+    pub fn refresh_ui(&mut self) {
+        self.last_ui_refresh = Instant::now();
+        self.drawn_generation = self.generation;
+    }
+
+    pub fn toggle_sort(&mut self) {
+        self.sort_ascending = !self.sort_ascending;
+        self.resort();
+    }
+
+    pub fn set_sort_key(&mut self, key: SortKey) {
+        if self.sort_key == key {
+            self.sort_ascending = !self.sort_ascending;
+        } else {
+            self.sort_key = key;
+            self.sort_ascending = false; // Default to descending for new sort key
+        }
+        self.resort();
+    }
+
+    // Sort by the column to the left (-1) or right (1) of the sorted one,
+    // among the sortable columns last drawn, wrapping around at the ends.
+    // Policy and RT Prio share a key, so they count as one column.
+    pub fn move_sort_column(&mut self, delta: isize) {
+        let mut keys: Vec<SortKey> = self.mouse.headers.iter().map(|&(_, key)| key).collect();
+        keys.dedup();
+        if keys.is_empty() {
+            return;
+        }
+        let next = match keys.iter().position(|&key| key == self.sort_key) {
+            Some(index) => (index as isize + delta).rem_euclid(keys.len() as isize) as usize,
+            None if delta > 0 => 0,
+            None => keys.len() - 1,
+        };
+        if keys[next] != self.sort_key {
+            self.set_sort_key(keys[next]);
+        }
+    }
+
+    // Work out the order of all processes if the data or the sort settings
+    // changed since last time. Filtering keeps the order, so typing in the
+    // filter doesn't sort again.
+    fn sort_processes(&mut self) {
+        if !self.sort_dirty {
+            return;
+        }
+        self.sort_dirty = false;
+        // Exited processes still flashing keep their place among the rest
+        let mut sorted: Vec<&ProcessInfo> = self
+            .all_processes
+            .values()
+            .chain(self.exiting.values().map(|(process, _)| process))
+            .collect();
+        if let Some(by) = self.sort_key.sort_by() {
+            sorted.sort_by(|a, b| {
+                let ordering = by.compare(a, b);
+                if self.sort_ascending {
+                    ordering
+                } else {
+                    ordering.reverse()
+                }
+            });
+        } else if let SortKey::Computed(index) = self.sort_key {
+            // Processes without a value sort last either way
+            let values = &self.computed_values;
+            let value = |pid: u32| values.get(&pid).and_then(|v| v.get(index)?.as_ref());
+            sorted.sort_by(|a, b| match (value(a.pid), value(b.pid)) {
+                (Some(a), Some(b)) if self.sort_ascending => a.compare(b),
+                (Some(a), Some(b)) => b.compare(a),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            });
+        } else if let Some(cells) = match self.sort_key {
+            SortKey::Script => Some(&self.script_results.columns),
+            SortKey::Plugin(index) => self.plugin_cells.get(index),
+            _ => None,
+        } {
+            // Text set by scripts or plugins: as numbers when both are, and
+            // like computed columns, processes without one last either way
+            sorted.sort_by(|a, b| match (cells.get(&a.pid), cells.get(&b.pid)) {
+                (Some(a), Some(b)) => {
+                    let ordering = match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+                        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal),
+                        _ => a.cmp(b),
+                    };
+                    if self.sort_ascending {
+                        ordering
+                    } else {
+                        ordering.reverse()
+                    }
+                }
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            });
+        }
+
+        // Pinned processes go first; the sort is stable so each group keeps its order
+        let config = &self.config;
+        sorted.sort_by_key(|p| !config.is_pinned(&p.name));
+        self.sort_order = sorted.iter().map(|p| p.pid).collect();
+    }
+
+    // Sort again after the sort settings change, keeping the selection on
+    // the same process
+    fn resort(&mut self) {
+        self.sort_dirty = true;
+        self.update_selection();
+    }
+
+    // Reorder the sorted view so children follow their parent (siblings keep
+    // the sort order) and leave out everything under collapsed parents
+    fn arrange_tree(&mut self) {
+        let shown: Vec<&ProcessInfo> = self.shown_processes().collect();
+        let listed: HashSet<u32> = self.processes.iter().copied().collect();
+        let mut children: HashMap<u32, Vec<usize>> = HashMap::new();
+        let mut roots = Vec::new();
+        for (index, process) in shown.iter().enumerate() {
+            match process.parent {
+                Some(parent) if parent != process.pid && listed.contains(&parent) => {
+                    children.entry(parent).or_default().push(index)
+                }
+                _ => roots.push(index),
+            }
+        }
+
+        let mut tree = TreeBuilder {
+            processes: &shown,
+            children,
+            collapsed: &self.collapsed,
+            visited: HashSet::new(),
+            order: Vec::new(),
+            rows: HashMap::new(),
+        };
+        for root in roots {
+            tree.visit(root, 0, false);
+        }
+        // Parent loops (possible with recycled PIDs) have no root; list them flat
+        for index in 0..shown.len() {
+            tree.visit(index, 0, false);
+        }
+
+        let (order, rows) = (tree.order, tree.rows);
+        self.processes = order.into_iter().map(|index| shown[index].pid).collect();
+        self.tree_rows = rows;
+    }
+
+    // Merge processes with the same name into one row at the position of the
+    // highest sorted one, listing the rest under it when the group is expanded
+    fn arrange_groups(&mut self) {
+        let shown: Vec<&ProcessInfo> = self.shown_processes().collect();
+        let mut groups: Vec<(&str, Vec<usize>)> = Vec::new();
+        let mut group_of: HashMap<&str, usize> = HashMap::new();
+        for (index, process) in shown.iter().enumerate() {
+            let group = *group_of.entry(&process.name).or_insert_with(|| {
+                groups.push((&process.name, Vec::new()));
+                groups.len() - 1
+            });
+            groups[group].1.push(index);
+        }
+
+        let mut order = Vec::with_capacity(shown.len());
+        let mut rows = HashMap::new();
+        for (name, members) in groups {
+            let expanded = self.expanded_groups.contains(name);
+            let cpu_total = members.iter().map(|&i| shown[i].cpu_usage).sum();
+            let memory_total = members.iter().map(|&i| shown[i].memory).sum();
+            for (position, &index) in members.iter().enumerate() {
+                let first = position == 0;
+                if first || expanded {
+                    order.push(index);
+                }
+                rows.insert(
+                    shown[index].pid,
+                    TreeRow {
+                        depth: if first { 0 } else { 1 },
+                        has_children: first && members.len() > 1,
+                        collapsed: first && !expanded && members.len() > 1,
+                        descendants: if first { members.len() - 1 } else { 0 },
+                        cpu_total,
+                        memory_total,
+                    },
+                );
+            }
+        }
+
+        self.processes = order.into_iter().map(|index| shown[index].pid).collect();
+        self.tree_rows = rows;
+    }
+
+    // Switch to the given tree or grouped view, or back to the flat list
+    pub fn toggle_view_mode(&mut self, mode: ViewMode) {
+        self.view_mode = if self.view_mode == mode {
+            ViewMode::Flat
+        } else {
+            mode
+        };
+        self.update_selection();
+    }
+
+    // Fold the selected process's children (or its group) into one row, or
+    // unfold them
+    pub fn toggle_collapse(&mut self) {
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        let pid = process.pid;
+        let name = process.name.clone();
+
+        match self.view_mode {
+            ViewMode::Flat => return,
+            ViewMode::Tree => {
+                if !self.tree_rows.get(&pid).is_some_and(|row| row.has_children) {
+                    return;
+                }
+                if !self.collapsed.remove(&pid) {
+                    self.collapsed.insert(pid);
+                }
+            }
+            ViewMode::Grouped => {
+                if !self.expanded_groups.remove(&name) {
+                    self.expanded_groups.insert(name.clone());
+                }
+            }
+        }
+        self.update_selection();
+
+        // Collapsing from one of a group's members lands on the group's row
+        if !self.processes.contains(&pid) {
+            let group = self
+                .processes
+                .iter()
+                .position(|&p| self.process(p).is_some_and(|p| p.name == name));
+            if let Some(index) = group {
+                self.selected_index = index;
+            }
+        }
+    }
+
+    // Lock the selection to the selected process, or release it
+    pub fn toggle_follow(&mut self) {
+        if self.follow.take().is_some() {
+            return;
+        }
+
+        if let Some(process) = self.selected_process() {
+            self.follow = Some(FollowedProcess {
+                pid: process.pid,
+                name: process.name.clone(),
+                exited: false,
+            });
+        }
+    }
+
+    pub fn is_pinned(&self, process: &ProcessInfo) -> bool {
+        self.config.is_pinned(&process.name)
+    }
+
+    // Pin the selected process by name, or unpin every pattern that matches it
+    pub fn toggle_pin(&mut self) {
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        let pid = process.pid;
+        let name = process.name.clone();
+
+        if self.config.is_pinned(&name) {
+            self.config
+                .pinned
+                .retain(|pattern| !config::matches_pattern(pattern, &name));
+        } else {
+            self.config.pinned.push(name);
+        }
+
+        if let Err(e) = self.config.save() {
+            self.set_error(format!("Failed to save pinned processes: {}", e));
+        }
+
+        self.resort();
+        if let Some(index) = self.processes.iter().position(|&p| p == pid) {
+            self.selected_index = index;
+        }
+    }
+
+    // Put the selected process's command line on the clipboard, quoted so it
+    // can be pasted back into a shell
+    pub fn copy_command_line(&mut self) {
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        if process.cmd.is_empty() {
+            self.set_status(format!("PID {} has no command line", process.pid));
+            return;
+        }
+
+        let pid = process.pid;
+        let command = clipboard::shell_join(&process.cmd);
+        match clipboard::copy(&command) {
+            Ok(()) => self.set_status(format!("Copied the command line of PID {}", pid)),
+            Err(e) => self.set_error(format!("Failed to copy the command line: {}", e)),
+        }
+    }
+
+    // Open the renice prompt, pre-filled with the selected process's nice value
+    pub fn start_renice(&mut self) {
+        let targets = self.action_targets();
+        if targets.is_empty() || self.remote_only_refusal() {
+            return;
+        }
+
+        // Tagged processes may all be filtered out, leaving no selected row
+        let nice = match self.selected_process() {
+            Some(process) => process.nice,
+            None => self
+                .all_processes
+                .get(&targets[0])
+                .map_or(0, |process| process.nice),
+        }
+        .to_string();
+        let message = format!(
+            "Nice value for {} (-20 to 19)",
+            self.describe_targets(&targets)
+        );
+        self.dialog = Some(Dialog::new(
+            "Renice",
+            message,
+            DialogKind::Input(nice),
+            DialogAction::Renice(targets),
+        ));
+    }
+
+    // Ask for a command to start
+    pub fn start_run(&mut self) {
+        if self.remote_only_refusal() {
+            return;
+        }
+        self.dialog = Some(Dialog::new(
+            "Run",
+            "Command to run; end it with & to keep it running after psr exits".to_string(),
+            DialogKind::Input(String::new()),
+            DialogAction::Run,
+        ));
+    }
+
+    // Start a command and follow it
+    fn run_process(&mut self, command: &str, detach: bool) {
+        let child = match control::spawn(command, detach) {
+            Ok(child) => child,
+            Err(e) => {
+                self.set_error(format!("Failed to run {}: {}", command, e));
+                return;
+            }
+        };
+        let program = command.split_whitespace().next().unwrap_or(command);
+        let name = program.rsplit('/').next().unwrap_or(program).to_string();
+        self.follow_child(child, name, detach);
+    }
+
+    // Ask before killing the selected process and starting it the same way again
+    pub fn start_restart(&mut self) {
+        if self.remote_only_refusal() {
+            return;
+        }
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        let pid = process.pid;
+        let launch = match control::launch_of(pid) {
+            Ok(launch) => launch,
+            Err(e) => {
+                self.set_error(format!("Can't restart PID {}: {}", pid, e));
+                return;
+            }
+        };
+        let message = format!(
+            "Stop {} and start it again?\n\n{}\nin {} as {}",
+            self.describe_targets(&[pid]),
+            launch.args.join(" "),
+            launch.cwd.display(),
+            process.user
+        );
+        self.dialog = Some(Dialog::new(
+            "Restart",
+            message,
+            DialogKind::Confirm,
+            DialogAction::Restart(pid, launch),
+        ));
+    }
+
+    // The old process may take a while to exit, so the restart waits for it
+    // on a thread of its own; reap_children follows the new one
+    fn restart_process(&mut self, pid: u32, launch: control::Launch) {
+        let name = self
+            .all_processes
+            .get(&pid)
+            .map_or_else(|| pid.to_string(), |p| p.name.clone());
+        let restart = std::thread::spawn(move || {
+            control::restart(pid, &launch, RESTART_GRACE).map_err(|e| {
+                io::Error::new(e.kind(), format!("Failed to restart PID {}: {}", pid, e))
+            })
+        });
+        self.restarts.push((name, restart));
+        self.set_status(format!("Restarting PID {}", pid));
+    }
+
+    // The selected process, if a tracer or debugger can attach to it from
+    // here. That runs from the main loop, which owns the terminal.
+    pub fn attach_target(&mut self) -> Option<u32> {
+        if self.remote_only_refusal() {
+            return None;
+        }
+        self.processes.get(self.selected_index).copied()
+    }
+
+    pub fn attach_done(&mut self, pid: u32, tool: &str, result: io::Result<ExitStatus>) {
+        match result {
+            Ok(status) if status.success() => {
+                self.set_status(format!("{} detached from PID {}", tool, pid))
+            }
+            Ok(status) => self.set_error(format!("{} on PID {} ended with {}", tool, pid, status)),
+            Err(e) => self.set_error(format!(
+                "Can't attach {} to PID {}: {}",
+                tool.to_lowercase(),
+                pid,
+                e
+            )),
+        }
+    }
+
+    // Ask how long to record the selected process with perf for, to keep the
+    // data or to draw a flamegraph of it
+    pub fn start_profile(&mut self, flamegraph: bool) {
+        if self.remote_only_refusal() {
+            return;
+        }
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        let pid = process.pid;
+        let (title, message) = if flamegraph {
+            (
+                "Flamegraph",
+                format!(
+                    "Seconds to sample {} for a flamegraph",
+                    self.describe_targets(&[pid])
+                ),
+            )
+        } else {
+            (
+                "Profile",
+                format!(
+                    "Seconds to record {} with perf {}",
+                    self.describe_targets(&[pid]),
+                    self.config.perf_args()
+                ),
+            )
+        };
+        self.dialog = Some(Dialog::new(
+            title,
+            message,
+            DialogKind::Input("10".to_string()),
+            DialogAction::Profile(pid, flamegraph),
+        ));
+    }
+
+    // Start perf in the background. Its data goes to the working directory,
+    // or to a temporary file when it's only wanted for a flamegraph, which
+    // goes there instead.
+    fn profile_process(&mut self, pid: u32, seconds: &str, flamegraph: bool) {
+        let seconds = match seconds.trim().parse::<u64>() {
+            Ok(seconds) if seconds > 0 => seconds,
+            _ => {
+                self.set_error(format!("Invalid number of seconds: {}", seconds));
+                return;
+            }
+        };
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let mut args = self.config.perf_args().to_string();
+        let (output, svg) = if flamegraph {
+            // Flamegraphs are made of call stacks
+            if !args
+                .split_whitespace()
+                .any(|arg| arg == "-g" || arg.starts_with("--call-graph"))
+            {
+                args.push_str(" -g");
+            }
+            let output = std::env::temp_dir().join(format!("psr-perf-{}-{}.data", pid, stamp));
+            let svg = PathBuf::from(format!("flamegraph-{}-{}.svg", pid, stamp));
+            (output, Some(svg))
+        } else {
+            (PathBuf::from(format!("perf-{}-{}.data", pid, stamp)), None)
+        };
+        match control::profile(pid, seconds, &args, &output) {
+            Ok(perf) => {
+                self.set_status(format!("Profiling PID {} for {}s", pid, seconds));
+                self.profiles.push(Profile {
+                    pid,
+                    output,
+                    flamegraph: svg,
+                    perf,
                 });
             }
-            SortKey::Status => {
-                self.processes.sort_by(|a, b| {
-                    if self.sort_ascending {
-                        a.status.cmp(&b.status)
-                    } else {
-                        b.status.cmp(&a.status)
-                    }
-                });
+            Err(e) => self.set_error(format!("Can't profile PID {}: {}", pid, e)),
+        }
+    }
+
+    fn profile_done(&mut self, profile: Profile) {
+        let Profile {
+            pid,
+            output,
+            flamegraph,
+            perf,
+        } = profile;
+        let result = match perf.wait_with_output() {
+            Ok(result) => result,
+            Err(e) => {
+                self.set_error(format!("Profiling PID {} failed: {}", pid, e));
+                return;
+            }
+        };
+        if !result.status.success() {
+            if flamegraph.is_some() {
+                let _ = std::fs::remove_file(&output);
+            }
+            // perf explains itself on stderr, under a bare "Error:" line
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            let reason = stderr
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty() && *line != "Error:")
+                .map_or_else(|| result.status.to_string(), str::to_string);
+            self.set_error(format!("Profiling PID {} failed: {}", pid, reason));
+            return;
+        }
+
+        let Some(svg) = flamegraph else {
+            let path = std::fs::canonicalize(&output).unwrap_or(output);
+            self.set_status(format!(
+                "Profile of PID {} written to {}",
+                pid,
+                path.display()
+            ));
+            return;
+        };
+        let title = match self.all_processes.get(&pid) {
+            Some(process) => format!("{} (PID {})", process.name, pid),
+            None => format!("PID {}", pid),
+        };
+        self.tasks.push(std::thread::spawn(move || {
+            let result = flamegraph::render(&output, &svg, &title);
+            let _ = std::fs::remove_file(&output);
+            match result {
+                Ok(()) => Ok(format!(
+                    "Flamegraph of PID {} written to {}",
+                    pid,
+                    std::fs::canonicalize(&svg).unwrap_or(svg).display()
+                )),
+                Err(e) => Err(format!("Flamegraph of PID {} failed: {}", pid, e)),
+            }
+        }));
+        self.set_status(format!("Drawing the flamegraph of PID {}", pid));
+    }
+
+    // Ask how to dump the selected process's core: with gcore into the
+    // configured directory, or by aborting it and leaving it to the kernel
+    pub fn start_core_dump(&mut self) {
+        if self.remote_only_refusal() {
+            return;
+        }
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        let pid = process.pid;
+        let mut options = vec![format!(
+            "gcore into {}, leaving it running",
+            self.config.core_dir().display()
+        )];
+        let abort = match control::core_pattern() {
+            Some(pattern) => format!("SIGABRT, killing it; the kernel writes {}", pattern),
+            None => "SIGABRT, killing it; the kernel writes the core".to_string(),
+        };
+        options.push(abort);
+        self.dialog = Some(Dialog::new(
+            "Core Dump",
+            format!("Dump the core of {}", self.describe_targets(&[pid])),
+            DialogKind::List {
+                options,
+                selected: 0,
+            },
+            DialogAction::CoreDump(pid),
+        ));
+    }
+
+    // gcore can take a while for a big process, so it runs in the background
+    fn dump_core(&mut self, pid: u32, abort: bool) {
+        if abort {
+            match control::abort(pid) {
+                Ok(()) => self.set_status(format!("Sent SIGABRT to PID {}", pid)),
+                Err(e) => self.set_error(format!("Failed to abort PID {}: {}", pid, e)),
+            }
+            self.request_refresh();
+            return;
+        }
+        let dir = self.config.core_dir();
+        self.tasks.push(std::thread::spawn(move || {
+            match control::dump_core(pid, &dir) {
+                Ok(path) => Ok(format!(
+                    "Core of PID {} written to {}",
+                    pid,
+                    std::fs::canonicalize(&path).unwrap_or(path).display()
+                )),
+                Err(e) => Err(format!("Core dump of PID {} failed: {}", pid, e)),
+            }
+        }));
+        self.set_status(format!("Dumping the core of PID {}", pid));
+    }
+
+    // Keep track of a process psr started and follow it once it shows up in
+    // the next refresh
+    fn follow_child(&mut self, mut child: Child, name: String, detach: bool) {
+        let pid = child.id();
+        if detach {
+            // Still psr's child, so it's waited for to keep it from lingering
+            // as a zombie after it exits
+            std::thread::spawn(move || child.wait());
+        } else {
+            self.children.push(child);
+        }
+
+        self.follow = Some(FollowedProcess {
+            pid,
+            name,
+            exited: false,
+        });
+        if !self.on_tab("All Processes") && !self.on_tab("Detailed") {
+            self.select_tab("All Processes");
+        }
+        self.set_status(format!("Started PID {}", pid));
+        self.request_refresh();
+    }
+
+    // Forget commands started from psr that have exited
+    pub fn reap_children(&mut self) {
+        self.children
+            .retain_mut(|child| matches!(child.try_wait(), Ok(None)));
+        while let Some(index) = self
+            .profiles
+            .iter_mut()
+            .position(|profile| !matches!(profile.perf.try_wait(), Ok(None)))
+        {
+            let profile = self.profiles.remove(index);
+            self.profile_done(profile);
+        }
+        while let Some(index) = self
+            .restarts
+            .iter()
+            .position(|(_, restart)| restart.is_finished())
+        {
+            let (name, restart) = self.restarts.remove(index);
+            match restart.join() {
+                // Restarted daemons carry on after psr exits
+                Ok(Ok(child)) => self.follow_child(child, name, true),
+                Ok(Err(e)) => self.set_error(e.to_string()),
+                Err(_) => {}
+            }
+        }
+        while let Some(index) = self.tasks.iter().position(|task| task.is_finished()) {
+            match self.tasks.remove(index).join() {
+                Ok(Ok(message)) => self.set_status(message),
+                Ok(Err(message)) => self.set_error(message),
+                // A panic on any thread already exited psr
+                Err(_) => {}
+            }
+        }
+    }
+
+    // Profiles in progress are interrupted rather than killed, so perf still
+    // writes out what it recorded
+    pub fn stop_children(&mut self) {
+        for child in &mut self.children {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        self.children.clear();
+        let interrupt = control::SIGNALS.iter().find(|(name, _)| *name == "INT");
+        for profile in &mut self.profiles {
+            match interrupt {
+                Some(&(_, signal)) => {
+                    let _ = control::send_signal(profile.perf.id(), signal);
+                }
+                None => {
+                    let _ = profile.perf.kill();
+                }
+            }
+            let _ = profile.perf.wait();
+            // There's no time left to draw a flamegraph from it
+            if profile.flamegraph.is_some() {
+                let _ = std::fs::remove_file(&profile.output);
+            }
+        }
+        self.profiles.clear();
+    }
+
+    // The processes table's columns in order: those the config lists, with
+    // computed and plugin columns found by name, or the defaults followed by
+    // every computed and plugin column
+    pub fn columns(&self) -> Vec<Column> {
+        if self.config.columns.is_empty() {
+            return Column::DEFAULT
+                .iter()
+                .copied()
+                .chain(self.named_columns())
+                .collect();
+        }
+        self.config
+            .columns
+            .iter()
+            .filter_map(|entry| match entry {
+                ColumnEntry::Builtin(column) => Some(*column),
+                ColumnEntry::Named(name) => self
+                    .named_columns()
+                    .find(|&column| self.column_name(column) == Some(name.as_str())),
+            })
+            .collect()
+    }
+
+    // Computed columns, then those of the plugins that have one
+    fn named_columns(&self) -> impl Iterator<Item = Column> + '_ {
+        (0..self.config.computed.len()).map(Column::Computed).chain(
+            (0..self.plugins.len())
+                .filter(|&index| self.plugins[index].column.is_some())
+                .map(Column::Plugin),
+        )
+    }
+
+    // What a computed or plugin column is called, both in its header and in
+    // the config; None for the built-in ones
+    pub fn column_name(&self, column: Column) -> Option<&str> {
+        match column {
+            Column::Computed(index) => Some(&self.config.computed[index].name),
+            Column::Plugin(index) => self.plugins[index].column.as_deref(),
+            _ => None,
+        }
+    }
+
+    // Open the column picker with the shown columns first, in table order,
+    // followed by the hidden ones
+    pub fn start_column_picker(&mut self) {
+        let shown = self.columns();
+        let hidden: Vec<Column> = Column::ALL
+            .iter()
+            .copied()
+            .chain(self.named_columns())
+            .filter(|c| !shown.contains(c))
+            .collect();
+        self.column_picker = shown.into_iter().map(|c| (c, true)).collect();
+        self.column_picker
+            .extend(hidden.into_iter().map(|c| (c, false)));
+        self.column_cursor = 0;
+        self.input_mode = InputMode::Columns;
+    }
+
+    pub fn move_column_cursor(&mut self, delta: isize) {
+        let last = self.column_picker.len().saturating_sub(1);
+        self.column_cursor = self.column_cursor.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn toggle_column(&mut self) {
+        if let Some((_, shown)) = self.column_picker.get_mut(self.column_cursor) {
+            *shown = !*shown;
+        }
+    }
+
+    // Move the column under the cursor, taking the cursor along with it
+    pub fn move_column(&mut self, delta: isize) {
+        let target = self.column_cursor.saturating_add_signed(delta);
+        if target < self.column_picker.len() {
+            self.column_picker.swap(self.column_cursor, target);
+            self.column_cursor = target;
+        }
+    }
+
+    fn save_columns(&mut self) {
+        let mut columns: Vec<ColumnEntry> = std::mem::take(&mut self.column_picker)
+            .into_iter()
+            .filter(|(_, shown)| *shown)
+            .map(|(column, _)| match self.column_name(column) {
+                Some(name) => ColumnEntry::Named(name.to_string()),
+                None => ColumnEntry::Builtin(column),
+            })
+            .collect();
+        if columns.is_empty() {
+            self.set_error("At least one column must be shown");
+            return;
+        }
+
+        // Names that match nothing here, like the column of a plugin that
+        // isn't installed at the moment, are kept for when it is
+        let unmatched: Vec<ColumnEntry> = self
+            .config
+            .columns
+            .iter()
+            .filter(|entry| match entry {
+                ColumnEntry::Named(name) => !self
+                    .named_columns()
+                    .any(|column| self.column_name(column) == Some(name.as_str())),
+                ColumnEntry::Builtin(_) => false,
+            })
+            .cloned()
+            .collect();
+        columns.extend(unmatched);
+
+        self.config.columns = columns;
+        if let Err(e) = self.config.save() {
+            self.set_error(format!("Failed to save columns: {}", e));
+        }
+    }
+
+    pub fn start_search(&mut self) {
+        self.input = self.search.take().unwrap_or_default();
+        self.search_origin = self.selected_index;
+        self.input_mode = InputMode::Search;
+        self.update_search();
+    }
+
+    // Jump to the first match at or after where the search started, as the
+    // query is typed
+    pub fn update_search(&mut self) {
+        if self.input_mode != InputMode::Search {
+            return;
+        }
+        let index = if self.input.is_empty() {
+            Some(self.search_origin)
+        } else {
+            self.find_match(&self.input, self.search_origin, true)
+        };
+        if let Some(index) = index {
+            self.select_index(index);
+        }
+    }
+
+    // Move to the next (or previous) process matching the last search,
+    // wrapping around the ends of the list
+    pub fn search_next(&mut self, forward: bool) {
+        let Some(query) = self.search.clone() else {
+            return;
+        };
+        if self.processes.is_empty() {
+            return;
+        }
+
+        let len = self.processes.len();
+        let start = if forward {
+            (self.selected_index + 1) % len
+        } else {
+            (self.selected_index + len - 1) % len
+        };
+        match self.find_match(&query, start, forward) {
+            Some(index) => self.select_index(index),
+            None => self.set_status(format!("No process matches '{}'", query)),
+        }
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search = None;
+    }
+
+    // Case-insensitive match against the PID, name and command line
+    fn find_match(&self, query: &str, start: usize, forward: bool) -> Option<usize> {
+        let len = self.processes.len();
+        let query = query.to_lowercase();
+        (0..len)
+            .map(|step| {
+                if forward {
+                    (start + step) % len
+                } else {
+                    (start + len - step % len) % len
+                }
+            })
+            .find(|&index| {
+                let Some(p) = self.process(self.processes[index]) else {
+                    return false;
+                };
+                p.pid.to_string().contains(&query)
+                    || p.name.to_lowercase().contains(&query)
+                    || p.cmd.join(" ").to_lowercase().contains(&query)
+            })
+    }
+
+    pub fn cancel_input(&mut self) {
+        // An abandoned search puts the selection back where it was
+        if self.input_mode == InputMode::Search {
+            self.input_mode = InputMode::Normal;
+            self.select_index(self.search_origin);
+        }
+        self.input.clear();
+        self.column_picker.clear();
+        self.service_unit = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn submit_input(&mut self) {
+        let input = std::mem::take(&mut self.input);
+        let mode = self.input_mode;
+        self.input_mode = InputMode::Normal;
+
+        match mode {
+            InputMode::Normal => {}
+            InputMode::Columns => self.save_columns(),
+            InputMode::Service => self.service_unit = None,
+            InputMode::Search => {
+                if !input.is_empty() {
+                    self.search = Some(input);
+                }
+            }
+            InputMode::Command => self.run_command(&input),
+        }
+    }
+
+    pub fn start_command(&mut self) {
+        self.input.clear();
+        self.input_mode = InputMode::Command;
+    }
+
+    // Carry out a line typed after `:` (see command.rs)
+    pub fn run_command(&mut self, line: &str) {
+        let command = match command::parse(line) {
+            Ok(command) => command,
+            Err(e) => {
+                self.set_error(e);
+                return;
             }
-            SortKey::User => {
-                self.processes.sort_by(|a, b| {
-                    if self.sort_ascending {
-                        a.user.cmp(&b.user)
-                    } else {
-                        b.user.cmp(&a.user)
-                    }
-                });
+        };
+
+        match command {
+            Command::Kill { signal, pids } => {
+                let targets = if pids.is_empty() {
+                    self.action_targets()
+                } else {
+                    pids
+                };
+                if !targets.is_empty() && !self.remote_only_refusal() {
+                    self.confirm_kill(targets, signal);
+                }
             }
-            SortKey::StartTime => {
-                self.processes.sort_by(|a, b| {
-                    if self.sort_ascending {
-                        a.start_time.cmp(&b.start_time)
-                    } else {
-                        b.start_time.cmp(&a.start_time)
-                    }
-                });
+            Command::Renice { nice, pids } => {
+                let targets = if pids.is_empty() {
+                    self.action_targets()
+                } else {
+                    pids
+                };
+                if !targets.is_empty() && !self.remote_only_refusal() {
+                    self.renice_processes(&targets, &nice.to_string());
+                }
             }
+            Command::Run { command, detach } => {
+                if !self.remote_only_refusal() {
+                    self.run_process(&command, detach);
+                }
+            }
+            Command::Sort { key, ascending } => {
+                self.sort_key = key;
+                self.sort_ascending = ascending.unwrap_or(false);
+                self.resort();
+            }
+            Command::Filter(text) => {
+                self.filter = text;
+                self.update_selection();
+            }
+            Command::Tab(name) => {
+                if !self.select_tab(&name) {
+                    self.set_error(format!("Unknown tab: {}", name));
+                }
+            }
+            Command::Refresh => {
+                self.request_refresh();
+                self.set_status("Refresh requested");
+            }
+            Command::Quit => self.quit = true,
         }
     }
-    pub fn kill_selected_process(&mut self) {
+
+    // Ask what to do with the systemd unit of the selected process
+    pub fn start_service_action(&mut self) {
+        if self.remote_only_refusal() {
+            return;
+        }
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        match systemd::unit(process.pid) {
+            Some(unit) => {
+                self.service_unit = Some(unit);
+                self.input_mode = InputMode::Service;
+            }
+            None => self.set_status(format!(
+                "PID {} doesn't belong to a systemd unit",
+                process.pid
+            )),
+        }
+    }
+
+    // Restart or stop the unit picked by start_service_action
+    pub fn service_action(&mut self, action: &str) {
+        self.input_mode = InputMode::Normal;
+        let Some(unit) = self.service_unit.take() else {
+            return;
+        };
+
+        match systemd::systemctl(action, &unit) {
+            Ok(()) => self.set_status(format!("Queued {} of {}", action, unit.name)),
+            Err(e) => self.set_error(format!("Failed to {} {}: {}", action, unit.name, e)),
+        }
+        self.request_refresh();
+    }
+
+    fn renice_processes(&mut self, targets: &[u32], input: &str) {
+        let nice = match input.trim().parse::<i32>() {
+            Ok(nice) if (-20..=19).contains(&nice) => nice,
+            _ => {
+                self.set_error(format!("Invalid nice value: {}", input));
+                return;
+            }
+        };
+
+        let mut failures = Vec::new();
+        for &pid in targets {
+            if let Err(e) = control::set_nice(pid, nice) {
+                failures.push(format!("{}: {}", pid, e));
+            }
+        }
+
+        if failures.is_empty() {
+            self.set_status(format!(
+                "Set nice to {} for {} process(es)",
+                nice,
+                targets.len()
+            ));
+        } else {
+            self.set_error(format!("Failed to renice {}", failures.join(", ")));
+        }
+
+        self.request_refresh();
+    }
+
+    // Tag or untag the selected process and move down, like htop
+    pub fn toggle_tag(&mut self) {
         if self.processes.is_empty() {
             return;
         }
 
-        let pid = self.processes[self.selected_index].pid;
+        let pid = self.processes[self.selected_index];
+        if !self.tagged.remove(&pid) {
+            self.tagged.insert(pid);
+        }
+        self.next();
+    }
+
+    pub fn clear_tags(&mut self) {
+        self.tagged.clear();
+    }
+
+    // Forget tags of processes that have exited
+    fn prune_tags(&mut self) {
+        let alive = &self.all_processes;
+        self.tagged.retain(|pid| alive.contains_key(pid));
+        self.collapsed.retain(|pid| alive.contains_key(pid));
+    }
+
+    // Commands that act on processes only reach this machine's, so they refuse
+    // to run while an agent's processes are shown
+    fn remote_only_refusal(&mut self) -> bool {
+        match &self.host {
+            Some(host) => {
+                self.set_error(format!("Not available for remote host {}", host));
+                true
+            }
+            None => false,
+        }
+    }
+
+    // The current host's latest state, kept while another host is shown.
+    // switch_host clears it all anyway, so it's moved out rather than copied.
+    pub fn take_snapshot(&mut self) -> Snapshot {
+        Snapshot {
+            processes: std::mem::take(&mut self.all_processes),
+            cpu_usage: self.system_resources.cpu_usage,
+            used_memory: self.system_resources.used_memory,
+            total_memory: self.system_resources.total_memory,
+            used_swap: self.system_resources.used_swap,
+            total_swap: self.system_resources.total_swap,
+            cpu_count: self.system_resources.cpu_count,
+            cores: std::mem::take(&mut self.cores),
+            load: self.system_load,
+            pressure: self.pressure_history.back().copied(),
+            numa_nodes: std::mem::take(&mut self.numa_nodes),
+            networks: std::mem::take(&mut self.network_stats.interfaces),
+            disks: std::mem::take(&mut self.disk_stats.disks),
+            gpus: std::mem::take(&mut self.gpus),
+            status: (!self.loading_status.is_empty()).then(|| self.loading_status.clone()),
+            collector: self.collector,
+        }
+    }
+
+    // Show another host's data, dropping everything tied to the previous
+    // host's processes. Its latest updates are replayed afterwards.
+    pub fn switch_host(&mut self, host: Option<String>) {
+        self.host = host;
+        self.system_resources = SystemResources::new();
+        self.network_stats = NetworkStats::new();
+        self.disk_stats = DiskStats::new();
+        self.collector = None;
+        self.system_load = None;
+        self.cores.clear();
+        self.gpus.clear();
+        self.numa_nodes.clear();
+        self.pressure_history.clear();
+        self.tagged.clear();
+        self.collapsed.clear();
+        self.started_at.clear();
+        self.follow = None;
+        self.cgroup_filter = None;
+        self.alerts.clear();
+        self.detail_pid = None;
+        self.cgroups_sampled_at = None;
+        self.plugin_tables.clear();
+        self.selected_index = 0;
+        self.set_processes(Vec::new());
+        // Leaving a host isn't its processes exiting
+        self.exiting.clear();
+        self.events.clear();
+        self.event_selected = 0;
+        self.resort();
+    }
+
+    // The processes a command acts on: all tagged ones, or else the selection
+    pub fn action_targets(&self) -> Vec<u32> {
+        if !self.tagged.is_empty() {
+            let mut pids: Vec<u32> = self.tagged.iter().copied().collect();
+            pids.sort_unstable();
+            pids
+        } else if let Some(process) = self.selected_process() {
+            vec![process.pid]
+        } else {
+            Vec::new()
+        }
+    }
+    // "PID 1234 (name)" for one process, a count for several
+    fn describe_targets(&self, targets: &[u32]) -> String {
+        match targets {
+            [pid] => match self.all_processes.get(pid) {
+                Some(process) => format!("PID {} ({})", pid, process.name),
+                None => format!("PID {}", pid),
+            },
+            _ => format!("{} processes", targets.len()),
+        }
+    }
+
+    // Ask before killing the selected or tagged processes
+    pub fn kill_selected_process(&mut self) {
+        let targets = self.action_targets();
+        if targets.is_empty() || self.remote_only_refusal() {
+            return;
+        }
+        self.confirm_kill(targets, None);
+    }
+
+    // Ask before killing the targets, or sending them the signal
+    fn confirm_kill(&mut self, targets: Vec<u32>, signal: Option<(&'static str, i32)>) {
+        let message = match signal {
+            Some((name, _)) => format!("Send SIG{} to {}?", name, self.describe_targets(&targets)),
+            None => format!("Kill {}?", self.describe_targets(&targets)),
+        };
+        self.dialog = Some(Dialog::new(
+            "Kill",
+            message,
+            DialogKind::Confirm,
+            DialogAction::Kill(targets, signal),
+        ));
+    }
+
+    // Offer the signals that can be sent to the selected or tagged processes
+    pub fn start_signal(&mut self) {
+        let targets = self.action_targets();
+        if targets.is_empty() || self.remote_only_refusal() {
+            return;
+        }
+        if control::SIGNALS.is_empty() {
+            self.set_error("Signals are not supported on this platform");
+            return;
+        }
+
+        let options = control::SIGNALS
+            .iter()
+            .map(|(name, number)| format!("{:>2} SIG{}", number, name))
+            .collect();
+        let message = format!("Send a signal to {}", self.describe_targets(&targets));
+        self.dialog = Some(Dialog::new(
+            "Signal",
+            message,
+            DialogKind::List {
+                options,
+                selected: 0,
+            },
+            DialogAction::Signal(targets),
+        ));
+    }
+
+    // Zombies are already dead, so the selected or tagged ones can only go
+    // once their parents reap them. Offer to nudge the parents with SIGCHLD
+    // or kill them, which hands the zombies to init.
+    pub fn start_reap(&mut self) {
+        let targets = self.action_targets();
+        if targets.is_empty() || self.remote_only_refusal() {
+            return;
+        }
+        let mut parents: Vec<u32> = targets
+            .iter()
+            .filter_map(|pid| self.all_processes.get(pid))
+            .filter(|process| process.status == ProcessStatus::Zombie)
+            .filter_map(|process| process.parent)
+            .collect();
+        parents.sort_unstable();
+        parents.dedup();
+        if parents.is_empty() {
+            self.set_error("No zombie selected; only a zombie's parent can reap it");
+            return;
+        }
+        if control::SIGNALS.is_empty() {
+            self.set_error("Signals are not supported on this platform");
+            return;
+        }
+
+        let mut options = vec!["Send SIGCHLD to the parent".to_string()];
+        // Killing init would take the whole system down
+        if !parents.contains(&1) {
+            options.push("Kill the parent".to_string());
+        }
+        let message = format!(
+            "Killing a zombie does nothing; its parent {} has to reap it",
+            self.describe_targets(&parents)
+        );
+        self.dialog = Some(Dialog::new(
+            "Reap Zombie",
+            message,
+            DialogKind::List {
+                options,
+                selected: 0,
+            },
+            DialogAction::Reap(parents),
+        ));
+    }
+
+    // Send a signal (or kill, for None) to each target, reporting the outcome.
+    // Several failures are listed in a dialog since they won't fit the status line.
+    fn signal_targets(&mut self, targets: &[u32], signal: Option<(&str, i32)>) {
+        let mut failures = Vec::new();
+        for &pid in targets {
+            let result = match signal {
+                Some((_, number)) => control::send_signal(pid, number),
+                None => control::kill(pid),
+            };
+            if let Err(e) = result {
+                failures.push(format!("PID {}: {}", pid, e));
+            }
+        }
 
-        // Use the system command directly
-        if cfg!(unix) {
-            let _ = std::process::Command::new("kill")
-                .arg("-9")
-                .arg(pid.to_string())
-                .status();
-        } else if cfg!(windows) {
-            let _ = std::process::Command::new("taskkill")
-                .args(&["/F", "/PID", &pid.to_string()])
-                .status();
+        let done = match signal {
+            Some((name, _)) => format!("Sent SIG{} to", name),
+            None => "Killed".to_string(),
+        };
+        match (targets, failures.as_slice()) {
+            ([pid], []) => self.set_status(format!("{} PID {}", done, pid)),
+            (_, []) => self.set_status(format!("{} {} processes", done, targets.len())),
+            (_, [failure]) => self.set_error(format!("Failed for {}", failure)),
+            (_, _) => {
+                self.dialog = Some(Dialog::new(
+                    "Failed",
+                    format!(
+                        "{} {} of {} processes. These failed:\n{}",
+                        done,
+                        targets.len() - failures.len(),
+                        targets.len(),
+                        failures.join("\n")
+                    ),
+                    DialogKind::Message,
+                    DialogAction::None,
+                ))
+            }
         }
 
-        // Request a refresh after killing
-        if let Some(tx) = &self.refresh_sender {
-            let _ = tx.try_send(());
+        // Request a refresh after signalling
+        self.request_refresh();
+    }
+
+    // Keys go to the open dialog, which closes once answered
+    pub fn dialog_key(&mut self, key: KeyCode) {
+        let Some(dialog) = &mut self.dialog else {
+            return;
+        };
+        let answer = match (&mut dialog.kind, key) {
+            (_, KeyCode::Esc) => None,
+            (DialogKind::Message, _) => None,
+            (DialogKind::Confirm, KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y')) => {
+                Some(String::new())
+            }
+            (DialogKind::Confirm, KeyCode::Char('n') | KeyCode::Char('N')) => None,
+            (DialogKind::Input(text), KeyCode::Enter) => Some(std::mem::take(text)),
+            (DialogKind::Input(text), KeyCode::Backspace) => {
+                text.pop();
+                return;
+            }
+            (DialogKind::Input(text), KeyCode::Char(c)) => {
+                text.push(c);
+                return;
+            }
+            (DialogKind::List { options, selected }, KeyCode::Enter) => {
+                options.get(*selected).cloned()
+            }
+            (DialogKind::List { options, selected }, KeyCode::Up) => {
+                *selected = selected
+                    .checked_sub(1)
+                    .unwrap_or(options.len().saturating_sub(1));
+                return;
+            }
+            (DialogKind::List { options, selected }, KeyCode::Down) => {
+                *selected = (*selected + 1) % options.len().max(1);
+                return;
+            }
+            _ => return,
+        };
+
+        let Some(dialog) = self.dialog.take() else {
+            return;
+        };
+        let Some(answer) = answer else {
+            return;
+        };
+        match dialog.action {
+            DialogAction::None => {}
+            DialogAction::Kill(targets, signal) => self.signal_targets(&targets, signal),
+            DialogAction::Signal(targets) => {
+                let selected = match dialog.kind {
+                    DialogKind::List { selected, .. } => selected,
+                    _ => 0,
+                };
+                if let Some(&signal) = control::SIGNALS.get(selected) {
+                    self.signal_targets(&targets, Some(signal));
+                }
+            }
+            DialogAction::Renice(targets) => self.renice_processes(&targets, &answer),
+            DialogAction::Restart(pid, launch) => self.restart_process(pid, launch),
+            DialogAction::CoreDump(pid) => {
+                let selected = match dialog.kind {
+                    DialogKind::List { selected, .. } => selected,
+                    _ => 0,
+                };
+                self.dump_core(pid, selected == 1);
+            }
+            DialogAction::Profile(pid, flamegraph) => {
+                self.profile_process(pid, &answer, flamegraph)
+            }
+            DialogAction::Run => {
+                let command = answer.trim();
+                match command.strip_suffix('&') {
+                    Some(command) => self.run_process(command.trim_end(), true),
+                    None if !command.is_empty() => self.run_process(command, false),
+                    None => {}
+                }
+            }
+            DialogAction::Reap(parents) => {
+                let selected = match dialog.kind {
+                    DialogKind::List { selected, .. } => selected,
+                    _ => 0,
+                };
+                if selected == 0 {
+                    let chld = control::SIGNALS.iter().find(|(name, _)| *name == "CHLD");
+                    if let Some(&signal) = chld {
+                        self.signal_targets(&parents, Some(signal));
+                    }
+                } else {
+                    self.signal_targets(&parents, None);
+                }
+            }
         }
     }
 
+    // Switch between substring and regex filtering
+    pub fn toggle_filter_regex(&mut self) {
+        self.filter_regex = !self.filter_regex;
+        self.update_selection();
+    }
+
     pub fn add_to_filter(&mut self, c: char) {
         self.filter.push(c);
         self.update_selection(); // Apply filter immediately
@@ -337,25 +3860,396 @@ impl App {
         self.update_selection(); // Apply filter immediately
     }
 
+    pub fn user_summaries(&self) -> Vec<GroupSummary> {
+        self.summarize(|p| Some(&p.user))
+    }
+
+    pub fn container_summaries(&self) -> Vec<GroupSummary> {
+        self.summarize(|p| p.container.as_deref())
+    }
+
+    // Totals over the listed processes for each value of `key`, ordered by the
+    // current sort where it applies and by CPU otherwise. Processes without a
+    // key are left out.
+    fn summarize(&self, key: impl Fn(&ProcessInfo) -> Option<&str>) -> Vec<GroupSummary> {
+        let mut totals: HashMap<&str, GroupSummary> = HashMap::new();
+        for process in self.shown_processes() {
+            let Some(name) = key(process) else {
+                continue;
+            };
+            let summary = totals.entry(name).or_insert_with(|| GroupSummary {
+                name: name.to_string(),
+                processes: 0,
+                cpu_usage: 0.0,
+                memory: 0,
+            });
+            summary.processes += 1;
+            summary.cpu_usage += process.cpu_usage;
+            summary.memory += process.memory;
+        }
+
+        let mut summaries: Vec<GroupSummary> = totals.into_values().collect();
+        summaries.sort_by(|a, b| {
+            let ordering = match self.sort_key {
+                SortKey::User | SortKey::Name => a.name.cmp(&b.name),
+                SortKey::Count => a.processes.cmp(&b.processes),
+                SortKey::Memory => a.memory.cmp(&b.memory),
+                _ => a
+                    .cpu_usage
+                    .partial_cmp(&b.cpu_usage)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            };
+            if self.sort_ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+        summaries
+    }
+
     // Get the top CPU and memory processes for dashboard
     pub fn top_processes(&self, count: usize) -> (Vec<&ProcessInfo>, Vec<&ProcessInfo>) {
-        let mut cpu_sorted = self.processes.iter().collect::<Vec<_>>();
-        let mut mem_sorted = self.processes.iter().collect::<Vec<_>>();
-
-        cpu_sorted.sort_by(|a, b| {
+        let top_cpu = top_by(self.shown_processes(), count, |a, b| {
             b.cpu_usage
                 .partial_cmp(&a.cpu_usage)
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
-        mem_sorted.sort_by(|a, b| {
-            b.memory
-                .partial_cmp(&a.memory)
-                .unwrap_or(std::cmp::Ordering::Equal)
+        let top_memory = top_by(self.shown_processes(), count, |a, b| {
+            b.memory.cmp(&a.memory)
         });
+        (top_cpu, top_memory)
+    }
+}
+
+// The first `count` processes in `compare` order. The dashboard asks on every
+// frame, so only the leaders are sorted; ties go to the lower PID to keep the
+// bars from swapping places.
+fn top_by<'a>(
+    processes: impl Iterator<Item = &'a ProcessInfo>,
+    count: usize,
+    compare: impl Fn(&ProcessInfo, &ProcessInfo) -> std::cmp::Ordering,
+) -> Vec<&'a ProcessInfo> {
+    let compare = |a: &&ProcessInfo, b: &&ProcessInfo| compare(a, b).then(a.pid.cmp(&b.pid));
+    let mut top: Vec<&ProcessInfo> = processes.collect();
+    if count < top.len() {
+        top.select_nth_unstable_by(count, compare);
+        top.truncate(count);
+    }
+    top.sort_by(compare);
+    top
+}
+
+struct TreeBuilder<'a> {
+    processes: &'a [&'a ProcessInfo],
+    children: HashMap<u32, Vec<usize>>,
+    collapsed: &'a HashSet<u32>,
+    visited: HashSet<usize>,
+    order: Vec<usize>,
+    rows: HashMap<u32, TreeRow>,
+}
+
+impl TreeBuilder<'_> {
+    // List the subtree under `index` depth-first and return its total CPU,
+    // memory and process count
+    fn visit(&mut self, index: usize, depth: usize, hidden: bool) -> (f32, u64, usize) {
+        if !self.visited.insert(index) {
+            return (0.0, 0, 0);
+        }
+
+        let process = self.processes[index];
+        if !hidden {
+            self.order.push(index);
+        }
+
+        let collapsed = self.collapsed.contains(&process.pid);
+        let children = self.children.get(&process.pid).cloned().unwrap_or_default();
+        let mut totals = (process.cpu_usage, process.memory, 1);
+        for &child in &children {
+            let (cpu, memory, count) = self.visit(child, depth + 1, hidden || collapsed);
+            totals.0 += cpu;
+            totals.1 += memory;
+            totals.2 += count;
+        }
 
-        (
-            cpu_sorted.into_iter().take(count).collect(),
-            mem_sorted.into_iter().take(count).collect(),
+        self.rows.insert(
+            process.pid,
+            TreeRow {
+                depth,
+                has_children: !children.is_empty(),
+                collapsed,
+                descendants: totals.2 - 1,
+                cpu_total: totals.0,
+                memory_total: totals.1,
+            },
+        );
+        totals
+    }
+}
+
+// The regex crate's errors span several lines with a caret diagram; keep the
+// final "error: ..." line for the one-line filter bar
+fn regex_error_summary(error: &regex::Error) -> String {
+    let message = error.to_string();
+    message
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or(&message)
+        .trim()
+        .to_string()
+}
+
+fn current_user() -> String {
+    if cfg!(unix) {
+        std::env::var("USER").unwrap_or_else(|_| "unknown".to_string())
+    } else {
+        std::env::var("USERNAME").unwrap_or_else(|_| "unknown".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use psr_core::{MockSource, ProcessChange, ProcessSource, ProcessStatus};
+
+    fn process(pid: u32, name: &str, cpu: f32) -> ProcessInfo {
+        ProcessInfo::new(
+            pid,
+            name.to_string(),
+            cpu,
+            u64::from(pid) * 1024,
+            ProcessStatus::Running,
+            "root".to_string(),
+            Duration::from_secs(60),
+            vec![format!("/usr/bin/{}", name)],
+            Some(1),
+            Some(1),
         )
     }
+
+    // An app fed everything the source has ready, as the main loop would
+    fn app_with(source: &mut MockSource) -> App {
+        let mut app = App::new();
+        drain(&mut app, source);
+        app
+    }
+
+    fn drain(app: &mut App, source: &mut MockSource) {
+        while let Some(update) = source.try_next() {
+            app.apply_update(update);
+        }
+    }
+
+    fn selected_pid(app: &App) -> Option<u32> {
+        app.selected_process().map(|p| p.pid)
+    }
+
+    #[test]
+    fn lists_processes_by_cpu() {
+        let mut source = MockSource::new(vec![
+            process(10, "idle", 0.5),
+            process(20, "busy", 80.0),
+            process(30, "some", 10.0),
+        ])
+        .with_system(45.0, 1 << 30, 4 << 30);
+        let app = app_with(&mut source);
+
+        assert_eq!(app.processes, vec![20, 30, 10]);
+        assert_eq!(app.system_resources.cpu_usage, 45.0);
+        assert_eq!(app.system_resources.total_memory, 4 << 30);
+    }
+
+    #[test]
+    fn new_list_replaces_the_old_one() {
+        let mut source = MockSource::new(vec![process(10, "a", 1.0), process(20, "b", 2.0)]);
+        let mut app = app_with(&mut source);
+        app.config.flash_ms = Some(0);
+
+        source.set_processes(vec![process(20, "b", 2.0), process(30, "c", 3.0)]);
+        drain(&mut app, &mut source);
+
+        assert_eq!(app.processes, vec![30, 20]);
+        assert!(!app.all_processes.contains_key(&10));
+        let events: Vec<(u32, bool)> = app.events.iter().map(|e| (e.pid, e.exited)).collect();
+        assert!(events.contains(&(30, false)));
+        assert!(events.contains(&(10, true)));
+    }
+
+    #[test]
+    fn delta_adds_updates_and_removes() {
+        let mut source = MockSource::new(vec![
+            process(10, "a", 1.0),
+            process(20, "b", 2.0),
+            process(30, "c", 3.0),
+        ]);
+        let mut app = app_with(&mut source);
+        app.config.flash_ms = Some(0);
+
+        let mut busier = process(10, "a", 50.0);
+        busier.memory = 4096;
+        source.push(ProcessUpdate::ProcessDelta(ProcessDelta {
+            added: vec![process(40, "d", 4.0)],
+            updated: vec![ProcessChange::new(&busier, false)],
+            removed: vec![20],
+        }));
+        drain(&mut app, &mut source);
+
+        assert_eq!(app.processes, vec![10, 40, 30]);
+        let updated = &app.all_processes[&10];
+        assert_eq!(updated.cpu_usage, 50.0);
+        assert_eq!(updated.memory, 4096);
+        assert_eq!(updated.cpu_history.back(), Some(&50.0));
+        assert!(!app.all_processes.contains_key(&20));
+    }
+
+    #[test]
+    fn exited_process_stays_on_show_while_flashing() {
+        let mut source = MockSource::new(vec![process(10, "a", 1.0), process(20, "b", 2.0)]);
+        let mut app = app_with(&mut source);
+        app.config.flash_ms = Some(60_000);
+
+        source.push(ProcessUpdate::ProcessDelta(ProcessDelta {
+            removed: vec![20],
+            ..Default::default()
+        }));
+        drain(&mut app, &mut source);
+
+        assert!(!app.all_processes.contains_key(&20));
+        assert_eq!(app.processes, vec![20, 10]);
+        assert_eq!(app.process(20).map(|p| p.name.as_str()), Some("b"));
+    }
+
+    #[test]
+    fn filter_hides_others_and_keeps_the_selection() {
+        let mut source = MockSource::new(vec![
+            process(10, "nginx", 5.0),
+            process(20, "postgres", 30.0),
+            process(30, "nginx", 20.0),
+        ]);
+        let mut app = app_with(&mut source);
+        app.select_pid(10);
+
+        for c in "ngi".chars() {
+            app.add_to_filter(c);
+        }
+        assert_eq!(app.processes, vec![30, 10]);
+        assert_eq!(selected_pid(&app), Some(10));
+
+        app.clear_filter();
+        assert_eq!(app.processes, vec![20, 30, 10]);
+        assert_eq!(selected_pid(&app), Some(10));
+    }
+
+    #[test]
+    fn invalid_regex_leaves_the_list_unfiltered() {
+        let mut source = MockSource::new(vec![process(10, "a", 1.0), process(20, "b", 2.0)]);
+        let mut app = app_with(&mut source);
+        app.filter_regex = true;
+
+        app.add_to_filter('(');
+
+        assert!(app.filter_error.is_some());
+        assert_eq!(app.processes, vec![20, 10]);
+    }
+
+    #[test]
+    fn selection_stays_on_a_process_that_moves() {
+        let mut source = MockSource::new(vec![
+            process(10, "a", 1.0),
+            process(20, "b", 2.0),
+            process(30, "c", 3.0),
+        ]);
+        let mut app = app_with(&mut source);
+        app.select_pid(10);
+        assert_eq!(app.selected_index, 2);
+
+        source.set_processes(vec![
+            process(10, "a", 90.0),
+            process(20, "b", 2.0),
+            process(30, "c", 3.0),
+        ]);
+        drain(&mut app, &mut source);
+
+        assert_eq!(app.processes, vec![10, 30, 20]);
+        assert_eq!(app.selected_index, 0);
+        assert_eq!(selected_pid(&app), Some(10));
+    }
+
+    #[test]
+    fn selection_moves_on_when_its_process_exits() {
+        let mut source = MockSource::new(vec![process(10, "a", 1.0), process(20, "b", 2.0)]);
+        let mut app = app_with(&mut source);
+        app.config.flash_ms = Some(0);
+        app.select_pid(10);
+
+        source.set_processes(vec![process(20, "b", 2.0)]);
+        drain(&mut app, &mut source);
+
+        assert_eq!(selected_pid(&app), Some(20));
+    }
+
+    #[test]
+    fn tags_act_on_tagged_processes_until_they_exit() {
+        let mut source = MockSource::new(vec![
+            process(10, "a", 1.0),
+            process(20, "b", 2.0),
+            process(30, "c", 3.0),
+        ]);
+        let mut app = app_with(&mut source);
+        app.config.flash_ms = Some(0);
+
+        // Tagging moves down, so this tags the first two rows
+        app.toggle_tag();
+        app.toggle_tag();
+        assert_eq!(app.action_targets(), vec![20, 30]);
+        assert_eq!(selected_pid(&app), Some(10));
+
+        app.select_pid(20);
+        app.toggle_tag();
+        assert_eq!(app.action_targets(), vec![30]);
+
+        source.set_processes(vec![process(10, "a", 1.0), process(20, "b", 2.0)]);
+        drain(&mut app, &mut source);
+        assert!(app.tagged.is_empty());
+        assert_eq!(app.action_targets(), vec![selected_pid(&app).unwrap()]);
+    }
+
+    #[test]
+    fn renice_with_every_tagged_process_filtered_out() {
+        let mut source = MockSource::new(vec![process(10, "a", 1.0), process(20, "b", 2.0)]);
+        let mut app = app_with(&mut source);
+        app.all_processes.get_mut(&20).unwrap().nice = 5;
+        app.toggle_tag();
+
+        for c in "nothing".chars() {
+            app.add_to_filter(c);
+        }
+        assert!(app.processes.is_empty());
+
+        app.start_renice();
+        match app.dialog.as_ref().map(|d| &d.kind) {
+            Some(DialogKind::Input(nice)) => assert_eq!(nice, "5"),
+            _ => panic!("no renice prompt"),
+        }
+    }
+
+    #[test]
+    fn switching_back_to_a_host_restores_its_state() {
+        let mut source = MockSource::new(vec![process(10, "a", 1.0), process(20, "b", 2.0)])
+            .with_system(30.0, 1 << 30, 4 << 30);
+        let mut app = app_with(&mut source);
+
+        let snapshot = app.take_snapshot();
+        app.switch_host(Some("other".to_string()));
+        assert!(app.processes.is_empty());
+        assert!(app.events.is_empty());
+
+        app.switch_host(None);
+        for update in snapshot.into_updates() {
+            app.apply_update(update);
+        }
+        assert_eq!(app.processes, vec![20, 10]);
+        assert_eq!(app.system_resources.total_memory, 4 << 30);
+    }
 }
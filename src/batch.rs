@@ -0,0 +1,179 @@
+use psr_core::{ChannelSource, DemoSource, ProcessMonitor, ProcessUpdate};
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crate::api::ApiServer;
+use crate::app::App;
+use crate::cli::Args;
+use crate::export;
+use crate::prometheus::Exporter;
+use crate::ui::{format_duration, format_uptime};
+use crate::DEMO_SEED;
+
+// Run without the TUI, printing snapshots of the process list to stdout.
+// `--batch` prints on every refresh (up to `--iterations`), while `--json` on
+// its own prints a single JSON snapshot and exits.
+pub async fn run(
+    mut app: App,
+    args: &Args,
+    api: Option<&ApiServer>,
+    exporter: Option<&Exporter>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let iterations = if args.batch { args.iterations } else { Some(1) };
+
+    let refresh_interval = Duration::from_millis(args.refresh_ms);
+    let mut source = if args.demo {
+        Source::Demo(DemoSource::new(refresh_interval, DEMO_SEED))
+    } else {
+        Source::Live(ProcessMonitor::spawn(
+            refresh_interval,
+            args.log_csv.as_deref(),
+            args.pss,
+            args.kernel_stats,
+        )?)
+    };
+
+    // A one-off JSON snapshot skips the initial list, whose CPU figures are
+    // measured over a much shorter window than a regular refresh
+    let mut skip_initial = !args.batch;
+    let mut printed = 0;
+    loop {
+        let Some(update) = source.recv().await else {
+            return Err("the process collector stopped".into());
+        };
+        match update {
+            ProcessUpdate::ProcessList(processes) => app.set_processes(processes),
+            ProcessUpdate::ProcessDelta(delta) => app.apply_delta(delta),
+            ProcessUpdate::SystemInfo(cpu, used, total, cores) => {
+                app.system_resources.update(cpu, used, total, cores);
+                continue;
+            }
+            ProcessUpdate::SwapInfo(used, total) => {
+                app.system_resources.update_swap(used, total);
+                continue;
+            }
+            ProcessUpdate::NetworkInfo(interfaces) => {
+                app.network_stats.update(interfaces);
+                continue;
+            }
+            ProcessUpdate::DiskInfo(disks) => {
+                app.disk_stats.update(disks);
+                continue;
+            }
+            ProcessUpdate::SystemLoad(load) => {
+                app.system_load = Some(load);
+                continue;
+            }
+            ProcessUpdate::LoadingStatus(_)
+            | ProcessUpdate::CollectorStats(_)
+            | ProcessUpdate::CoreInfo(_)
+            | ProcessUpdate::GpuInfo(_)
+            | ProcessUpdate::NumaInfo(_)
+            | ProcessUpdate::Pressure(_) => continue,
+        }
+        app.record_history();
+        if let Some(api) = api {
+            api.update(&app);
+        }
+        if let Some(exporter) = exporter {
+            exporter.update(&app);
+        }
+
+        if skip_initial {
+            skip_initial = false;
+            continue;
+        }
+
+        let result = if args.json {
+            print_json(&app)
+        } else {
+            print_snapshot(&app)
+        };
+
+        // Stop quietly if the reader went away (e.g. piped into `head`)
+        if result.is_err() {
+            break;
+        }
+
+        printed += 1;
+        if iterations.is_some_and(|limit| printed >= limit) {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+// Where batch mode's updates come from. Unlike the TUI, which polls between
+// key presses, batch mode has nothing else to do and waits on the source.
+enum Source {
+    Live(ChannelSource),
+    Demo(DemoSource),
+}
+
+impl Source {
+    async fn recv(&mut self) -> Option<ProcessUpdate> {
+        match self {
+            Source::Live(source) => source.recv().await,
+            Source::Demo(source) => source.recv().await,
+        }
+    }
+}
+
+fn print_json(app: &App) -> io::Result<()> {
+    let json = export::to_json(app)?;
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    writeln!(out, "{}", json)?;
+    out.flush()
+}
+
+fn print_snapshot(app: &App) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let resources = &app.system_resources;
+    let load = app.system_load.map_or(String::new(), |load| {
+        format!(
+            "  Load: {:.2} {:.2} {:.2}  Up: {}",
+            load.one,
+            load.five,
+            load.fifteen,
+            format_uptime(load.uptime)
+        )
+    });
+
+    writeln!(
+        out,
+        "psr - {}  CPU: {:.1}%  Mem: {} ({:.1}%)  Tasks: {}{}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        resources.cpu_usage,
+        app.config
+            .units
+            .fraction(resources.used_memory, resources.total_memory),
+        resources.memory_percentage(),
+        app.processes.len(),
+        load
+    )?;
+    writeln!(
+        out,
+        "{:>8} {:<25} {:>7} {:>11} {:<9} {:<12} {:>12}",
+        "PID", "NAME", "CPU%", "MEM", "STATUS", "USER", "TIME"
+    )?;
+
+    for p in app.shown_processes() {
+        writeln!(
+            out,
+            "{:>8} {:<25} {:>7.1} {:>11} {:<9} {:<12} {:>12}",
+            p.pid,
+            p.name.chars().take(25).collect::<String>(),
+            p.cpu_usage / app.cpu_divisor(),
+            app.config.units.bytes(p.memory),
+            p.status.to_string(),
+            p.user,
+            format_duration(p.start_time)
+        )?;
+    }
+
+    writeln!(out)?;
+    out.flush()
+}
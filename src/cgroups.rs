@@ -0,0 +1,116 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Linux control groups (v2), read from the unified hierarchy
+#[derive(Clone)]
+pub struct CgroupInfo {
+    // Path relative to the hierarchy root, "/" for the root itself
+    pub path: String,
+    pub depth: usize,
+    // Members of the cgroup and its descendants, as memory and CPU count them
+    pub pids: Vec<u32>,
+    pub memory_current: Option<u64>,
+    // None when unlimited ("max") or the memory controller isn't enabled
+    pub memory_max: Option<u64>,
+    // Cumulative CPU time, and the usage derived from it between samples
+    pub cpu_usage_usec: u64,
+    pub cpu_usage: f32,
+}
+
+// The unified hierarchy is at /sys/fs/cgroup on cgroup v2 systems and under
+// /sys/fs/cgroup/unified on hybrid v1/v2 ones
+fn root() -> Option<PathBuf> {
+    ["/sys/fs/cgroup", "/sys/fs/cgroup/unified"]
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.join("cgroup.controllers").exists())
+}
+
+#[cfg(target_os = "linux")]
+pub fn cgroups() -> io::Result<Vec<CgroupInfo>> {
+    let root = root()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no cgroup v2 hierarchy mounted"))?;
+    let mut cgroups = Vec::new();
+    walk(&root, &root, 0, &mut cgroups)?;
+    Ok(cgroups)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn cgroups() -> io::Result<Vec<CgroupInfo>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "cgroups are only available on Linux",
+    ))
+}
+
+// Depth-first, children in name order, so the list reads as a tree
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn walk(root: &Path, dir: &Path, depth: usize, cgroups: &mut Vec<CgroupInfo>) -> io::Result<()> {
+    let relative = dir.strip_prefix(root).unwrap_or(dir);
+    let read = |file: &str| std::fs::read_to_string(dir.join(file)).ok();
+
+    let index = cgroups.len();
+    cgroups.push(CgroupInfo {
+        path: format!("/{}", relative.display()),
+        depth,
+        pids: procs(dir),
+        memory_current: read("memory.current").and_then(|v| v.trim().parse().ok()),
+        memory_max: read("memory.max").and_then(|v| v.trim().parse().ok()),
+        cpu_usage_usec: read("cpu.stat")
+            .and_then(|stat| {
+                stat.lines()
+                    .find_map(|line| line.strip_prefix("usage_usec "))
+                    .and_then(|v| v.trim().parse().ok())
+            })
+            .unwrap_or(0),
+        cpu_usage: 0.0,
+    });
+
+    let mut children: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
+        .collect();
+    children.sort();
+    for child in children {
+        // Cgroups can vanish while we walk them
+        let _ = walk(root, &child, depth + 1, cgroups);
+    }
+    // The children's lists already take in their own descendants
+    let descendants: Vec<u32> = cgroups[index + 1..]
+        .iter()
+        .filter(|cgroup| cgroup.depth == depth + 1)
+        .flat_map(|cgroup| cgroup.pids.iter().copied())
+        .collect();
+    cgroups[index].pids.extend(descendants);
+    Ok(())
+}
+
+// The processes directly in the cgroup at `dir`
+fn procs(dir: &Path) -> Vec<u32> {
+    std::fs::read_to_string(dir.join("cgroup.procs"))
+        .map(|procs| procs.lines().filter_map(|l| l.parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+// Current members of the cgroup at `path` (relative to the hierarchy root)
+// and of the cgroups below it
+pub fn member_pids(path: &str) -> Vec<u32> {
+    let Some(root) = root() else {
+        return Vec::new();
+    };
+    let mut pids = Vec::new();
+    let mut dirs = vec![root.join(path.trim_start_matches('/'))];
+    while let Some(dir) = dirs.pop() {
+        pids.extend(procs(&dir));
+        if let Ok(entries) = std::fs::read_dir(&dir) {
+            dirs.extend(
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+                    .map(|entry| entry.path()),
+            );
+        }
+    }
+    pids
+}
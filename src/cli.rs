@@ -0,0 +1,113 @@
+use clap::Parser;
+use regex::Regex;
+use std::path::PathBuf;
+
+use crate::app::SortKey;
+
+// Command-line options used to choose the initial state of the app
+#[derive(Parser, Debug)]
+#[command(name = "psr", version, about = "PSR - Process Status Reporter")]
+pub struct Args {
+    /// Column to sort the process list by
+    #[arg(long, value_enum, default_value_t = SortKey::Cpu)]
+    pub sort: SortKey,
+
+    /// Sort in ascending instead of descending order
+    #[arg(long)]
+    pub ascending: bool,
+
+    /// Initial filter applied to process names, PIDs and users
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// Treat filters as case-insensitive regular expressions
+    #[arg(long)]
+    pub regex: bool,
+
+    /// Interval between data refreshes in milliseconds
+    #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u64).range(100..))]
+    pub refresh_ms: u64,
+
+    /// Tab to open on startup (dashboard, processes, user, system, users, containers, cgroups, detailed, network, disks, gpu, alerts, events, ports, or a plugin tab)
+    #[arg(long, default_value = "dashboard")]
+    pub tab: String,
+
+    /// Print process snapshots to stdout on every refresh instead of starting the TUI
+    #[arg(short, long)]
+    pub batch: bool,
+
+    /// Print a JSON snapshot and exit (one JSON document per refresh with --batch)
+    #[arg(long)]
+    pub json: bool,
+
+    /// Number of snapshots to print in batch mode before exiting
+    #[arg(short = 'n', long, requires = "batch")]
+    pub iterations: Option<u64>,
+
+    /// Append one CSV row per process on every refresh to this file
+    #[arg(long, value_name = "PATH")]
+    pub log_csv: Option<PathBuf>,
+
+    /// Also report proportional (PSS) and unique (USS) memory, which is more
+    /// accurate for processes sharing libraries or forked workers but costs
+    /// more to collect (Linux only)
+    #[arg(long, conflicts_with_all = ["demo", "connect"])]
+    pub pss: bool,
+
+    /// Also report system calls per second, time spent waiting for block I/O
+    /// and TCP retransmits per process (Linux only). Built with the ebpf
+    /// feature and run as root on Linux 6.5 or later, eBPF programs count
+    /// every system call and time each block I/O request. Otherwise these are
+    /// the counters the kernel keeps anyway: only read and write calls are
+    /// counted, and block I/O wait is delay accounting, which stays empty
+    /// unless the kernel.task_delayacct sysctl is 1
+    #[arg(long, conflicts_with_all = ["demo", "connect"])]
+    pub kernel_stats: bool,
+
+    /// Keep samples in this SQLite database so charts can show more than the last minute
+    #[arg(long, value_name = "PATH")]
+    pub history: Option<PathBuf>,
+
+    /// Hours of samples to keep in the history database
+    #[arg(long, value_name = "HOURS", default_value_t = 24, requires = "history")]
+    pub history_hours: u64,
+
+    /// Show a desktop notification when an alert rule from the config fires
+    #[arg(long)]
+    pub notify: bool,
+
+    /// Overlay frame, event loop and collection timings on the TUI
+    #[arg(long, conflicts_with_all = ["batch", "json", "agent"])]
+    pub debug: bool,
+
+    /// Show simulated processes and load instead of this machine's; the same on
+    /// every run, for screenshots and trying out the UI
+    #[arg(long, conflicts_with_all = ["log_csv", "connect"])]
+    pub demo: bool,
+
+    /// Run without the TUI, streaming updates to psr instances that connect to ADDR
+    #[arg(
+        long,
+        value_name = "ADDR",
+        conflicts_with_all = ["batch", "json", "connect", "api", "prometheus", "log_csv", "history", "notify", "demo"]
+    )]
+    pub agent: Option<String>,
+
+    /// Show a psr agent's processes instead of this machine's; repeat to switch
+    /// between several hosts with Ctrl+w
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["batch", "json", "log_csv"])]
+    pub connect: Vec<String>,
+
+    /// Serve the current system and process data as JSON at http://ADDR/system,
+    /// /processes and /process/<pid>
+    #[arg(long, value_name = "ADDR")]
+    pub api: Option<String>,
+
+    /// Serve system and process metrics for Prometheus at http://ADDR/metrics
+    #[arg(long, value_name = "ADDR")]
+    pub prometheus: Option<String>,
+
+    /// Only export processes whose name matches this regular expression
+    #[arg(long, value_name = "PATTERN", requires = "prometheus", value_parser = Regex::new)]
+    pub prometheus_filter: Option<Regex>,
+}
@@ -0,0 +1,231 @@
+use clap::ValueEnum;
+use psr_core::control;
+
+use crate::app::SortKey;
+
+// Commands typed after `:`, for driving psr from scripts (tmux send-keys and
+// the like) as well as by hand:
+//
+//   :kill [-SIGNAL] [PID...]   kill, or send SIGNAL, to the PIDs or else the
+//                              selected or tagged processes
+//   :renice NICE [PID...]      the same for changing the nice value
+//   :run [-d] COMMAND          run COMMAND through the shell and follow it;
+//                              -d keeps it running after psr exits
+//   :sort KEY [asc|desc]       KEY as for --sort, e.g. `:sort mem`
+//   :filter [TEXT]             e.g. `:filter user=root`; no text clears it
+//   :tab NAME                  as for --tab
+//   :refresh
+//   :quit, :q
+pub enum Command {
+    Kill {
+        signal: Option<(&'static str, i32)>,
+        pids: Vec<u32>,
+    },
+    Renice {
+        nice: i32,
+        pids: Vec<u32>,
+    },
+    Run {
+        command: String,
+        detach: bool,
+    },
+    Sort {
+        key: SortKey,
+        ascending: Option<bool>,
+    },
+    Filter(String),
+    Tab(String),
+    Refresh,
+    Quit,
+}
+
+pub fn parse(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    let (name, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let rest = rest.trim();
+    let args: Vec<&str> = rest.split_whitespace().collect();
+
+    match name {
+        "kill" => {
+            let (signal, pids) = match args.split_first() {
+                Some((first, pids)) if first.starts_with('-') => (Some(signal(first)?), pids),
+                _ => (None, args.as_slice()),
+            };
+            Ok(Command::Kill {
+                signal,
+                pids: parse_pids(pids)?,
+            })
+        }
+        "renice" => {
+            let Some((nice, pids)) = args.split_first() else {
+                return Err("Usage: renice NICE [PID...]".to_string());
+            };
+            let nice = nice
+                .parse()
+                .map_err(|_| format!("Invalid nice value: {}", nice))?;
+            Ok(Command::Renice {
+                nice,
+                pids: parse_pids(pids)?,
+            })
+        }
+        "run" => {
+            let (detach, command) = match rest.strip_prefix("-d") {
+                Some(command) if command.is_empty() || command.starts_with(char::is_whitespace) => {
+                    (true, command.trim())
+                }
+                _ => (false, rest),
+            };
+            if command.is_empty() {
+                return Err("Usage: run [-d] COMMAND".to_string());
+            }
+            Ok(Command::Run {
+                command: command.to_string(),
+                detach,
+            })
+        }
+        "sort" => {
+            let (key, ascending) = match args.as_slice() {
+                [key] => (key, None),
+                [key, "asc"] => (key, Some(true)),
+                [key, "desc"] => (key, Some(false)),
+                _ => return Err("Usage: sort KEY [asc|desc]".to_string()),
+            };
+            let key = SortKey::from_str(key, true).map_err(|_| {
+                let keys: Vec<String> = SortKey::value_variants()
+                    .iter()
+                    .filter_map(|k| k.to_possible_value())
+                    .map(|v| v.get_name().to_string())
+                    .collect();
+                format!("Unknown sort key {} (one of {})", key, keys.join(", "))
+            })?;
+            Ok(Command::Sort { key, ascending })
+        }
+        "filter" => Ok(Command::Filter(rest.to_string())),
+        "tab" if !rest.is_empty() => Ok(Command::Tab(rest.to_string())),
+        "tab" => Err("Usage: tab NAME".to_string()),
+        "refresh" => Ok(Command::Refresh),
+        "q" | "quit" => Ok(Command::Quit),
+        "" => Err("No command".to_string()),
+        _ => Err(format!(
+            "Unknown command {} (kill, renice, run, sort, filter, tab, refresh, quit)",
+            name
+        )),
+    }
+}
+
+// A signal given as -TERM, -SIGTERM or -15
+fn signal(arg: &str) -> Result<(&'static str, i32), String> {
+    let name = arg.trim_start_matches('-').to_uppercase();
+    let name = name.strip_prefix("SIG").unwrap_or(&name);
+    control::SIGNALS
+        .iter()
+        .find(|(signal, number)| *signal == name || number.to_string() == name)
+        .copied()
+        .ok_or_else(|| format!("Unknown signal {}", arg))
+}
+
+fn parse_pids(args: &[&str]) -> Result<Vec<u32>, String> {
+    args.iter()
+        .map(|arg| match arg.parse::<i32>() {
+            // 0 and negative PIDs would signal whole process groups
+            Ok(pid) if pid >= 1 => Ok(pid as u32),
+            _ => Err(format!("Invalid PID: {}", arg)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kill_takes_an_optional_signal_and_pids() {
+        let Ok(Command::Kill { signal, pids }) = parse("kill") else {
+            panic!("kill without arguments");
+        };
+        assert!(signal.is_none() && pids.is_empty());
+
+        for spelling in ["-KILL", "-sigkill", "-9"] {
+            let Ok(Command::Kill { signal, pids }) = parse(&format!("kill {} 12 34", spelling))
+            else {
+                panic!("kill {}", spelling);
+            };
+            assert_eq!(signal, Some(("KILL", libc::SIGKILL)));
+            assert_eq!(pids, vec![12, 34]);
+        }
+
+        assert!(parse("kill -NOPE 12").is_err());
+        assert!(parse("kill 0").is_err());
+        assert!(parse("kill -- -12").is_err());
+        assert!(parse("kill 12 x").is_err());
+    }
+
+    #[test]
+    fn renice_needs_a_nice_value() {
+        let Ok(Command::Renice { nice, pids }) = parse("  renice -5 42 ") else {
+            panic!("renice");
+        };
+        assert_eq!((nice, pids), (-5, vec![42]));
+        assert!(parse("renice").is_err());
+        assert!(parse("renice high 42").is_err());
+    }
+
+    #[test]
+    fn run_keeps_the_command_line_whole() {
+        let Ok(Command::Run { command, detach }) = parse("run -d sleep 60 && echo  done") else {
+            panic!("run -d");
+        };
+        assert_eq!(command, "sleep 60 && echo  done");
+        assert!(detach);
+        let Ok(Command::Run { command, detach }) = parse("run make -j8") else {
+            panic!("run");
+        };
+        assert_eq!(command, "make -j8");
+        assert!(!detach);
+        assert!(parse("run").is_err());
+        assert!(parse("run -d ").is_err());
+        assert!(matches!(
+            parse("run -dump"),
+            Ok(Command::Run { detach: false, .. })
+        ));
+    }
+
+    #[test]
+    fn sort_takes_keys_and_aliases() {
+        assert!(matches!(
+            parse("sort mem desc"),
+            Ok(Command::Sort {
+                key: SortKey::Memory,
+                ascending: Some(false)
+            })
+        ));
+        assert!(matches!(
+            parse("sort PID"),
+            Ok(Command::Sort {
+                key: SortKey::Pid,
+                ascending: None
+            })
+        ));
+        assert!(parse("sort cpu sideways").is_err());
+        assert!(parse("sort").is_err());
+        let Err(error) = parse("sort size") else {
+            panic!("sort size");
+        };
+        assert!(error.contains("memory"));
+    }
+
+    #[test]
+    fn the_rest_take_their_text_as_is() {
+        assert!(
+            matches!(parse("filter user=root"), Ok(Command::Filter(text)) if text == "user=root")
+        );
+        assert!(matches!(parse("filter"), Ok(Command::Filter(text)) if text.is_empty()));
+        assert!(matches!(parse("tab Network"), Ok(Command::Tab(name)) if name == "Network"));
+        assert!(parse("tab").is_err());
+        assert!(matches!(parse("refresh"), Ok(Command::Refresh)));
+        assert!(matches!(parse("q"), Ok(Command::Quit)));
+        assert!(matches!(parse("quit"), Ok(Command::Quit)));
+        assert!(parse("").is_err());
+        assert!(parse("reboot").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use psr_core::ProcessInfo;
+use rhai::{Array, Dynamic, Engine, Scope, AST};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+// A column defined in the config as an expression over a process's fields:
+//
+//   [[computed]]
+//   name = "MB/thread"
+//   expr = "memory / threads"
+//
+// Expressions are Rhai, with cpu (%), memory (MB), threads, nice, pid,
+// uptime (seconds), disk_read, disk_write, net_rx and net_tx (bytes per
+// second) as numbers, name, user and status as text, and cpu_history and
+// memory_history as arrays that avg, min, max and sum reduce, as in
+// `cpu_history.avg()`. Computed columns follow the default ones, or go where
+// `columns` names them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ComputedColumn {
+    pub name: String,
+    pub expr: String,
+}
+
+// A computed cell; numbers sort numerically and before text
+#[derive(Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Text(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Number(n) if !n.is_finite() => write!(f, "-"),
+            Value::Number(n) if n.fract() == 0.0 => write!(f, "{}", n),
+            Value::Number(n) => write!(f, "{:.1}", n),
+            Value::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl Value {
+    pub fn compare(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+            (Value::Number(_), Value::Text(_)) => Ordering::Less,
+            (Value::Text(_), Value::Number(_)) => Ordering::Greater,
+            (Value::Text(a), Value::Text(b)) => a.cmp(b),
+        }
+    }
+}
+
+pub struct Evaluator {
+    engine: Engine,
+    columns: Vec<Option<AST>>,
+}
+
+impl Evaluator {
+    // Compile the configured expressions; ones that don't compile are
+    // reported and leave their column empty
+    pub fn new(columns: &[ComputedColumn]) -> (Self, Vec<String>) {
+        let mut engine = Engine::new();
+        engine.set_max_operations(100_000);
+        engine.register_fn("avg", |values: Array| {
+            let numbers = numbers(&values);
+            if numbers.is_empty() {
+                0.0
+            } else {
+                numbers.iter().sum::<f64>() / numbers.len() as f64
+            }
+        });
+        engine.register_fn("sum", |values: Array| numbers(&values).iter().sum::<f64>());
+        engine.register_fn("min", |values: Array| {
+            numbers(&values).into_iter().reduce(f64::min).unwrap_or(0.0)
+        });
+        engine.register_fn("max", |values: Array| {
+            numbers(&values).into_iter().reduce(f64::max).unwrap_or(0.0)
+        });
+
+        let mut errors = Vec::new();
+        let columns = columns
+            .iter()
+            .map(|column| match engine.compile_expression(&column.expr) {
+                Ok(ast) => Some(ast),
+                Err(e) => {
+                    errors.push(format!("column {}: {}", column.name, e));
+                    None
+                }
+            })
+            .collect();
+        (Self { engine, columns }, errors)
+    }
+
+    // Every column's value for a process. The first failing expression's
+    // error comes back alongside; its cell is left empty.
+    pub fn evaluate(&self, process: &ProcessInfo) -> (Vec<Option<Value>>, Option<String>) {
+        let mut scope = Scope::new();
+        let mb = |bytes: u64| bytes as f64 / 1024.0 / 1024.0;
+        scope.push("pid", process.pid as f64);
+        scope.push("name", process.name.clone());
+        scope.push("user", process.user.clone());
+        scope.push("status", process.status.to_string());
+        scope.push("cpu", process.cpu_usage as f64);
+        scope.push("memory", mb(process.memory));
+        scope.push("threads", process.threads.unwrap_or(0) as f64);
+        scope.push("nice", process.nice as f64);
+        scope.push("uptime", process.start_time.as_secs_f64());
+        scope.push("disk_read", process.disk_read_rate as f64);
+        scope.push("disk_write", process.disk_write_rate as f64);
+        scope.push("net_rx", process.net_rx_rate.unwrap_or(0) as f64);
+        scope.push("net_tx", process.net_tx_rate.unwrap_or(0) as f64);
+        let cpu_history: Array = process
+            .cpu_history
+            .iter()
+            .map(|&v| Dynamic::from_float(v as f64))
+            .collect();
+        scope.push("cpu_history", cpu_history);
+        let memory_history: Array = process
+            .memory_history
+            .iter()
+            .map(|&v| Dynamic::from_float(mb(v)))
+            .collect();
+        scope.push("memory_history", memory_history);
+
+        let mut error = None;
+        let values = self
+            .columns
+            .iter()
+            .map(|ast| {
+                let ast = ast.as_ref()?;
+                match self
+                    .engine
+                    .eval_ast_with_scope::<Dynamic>(&mut scope.clone(), ast)
+                {
+                    Ok(value) => Some(to_value(value)),
+                    Err(e) => {
+                        error.get_or_insert_with(|| e.to_string());
+                        None
+                    }
+                }
+            })
+            .collect();
+        (values, error)
+    }
+}
+
+fn numbers(values: &Array) -> Vec<f64> {
+    values
+        .iter()
+        .filter_map(|v| {
+            v.as_float()
+                .ok()
+                .or_else(|| v.as_int().ok().map(|i| i as f64))
+        })
+        .collect()
+}
+
+fn to_value(value: Dynamic) -> Value {
+    if let Ok(n) = value.as_float() {
+        Value::Number(n)
+    } else if let Ok(n) = value.as_int() {
+        Value::Number(n as f64)
+    } else {
+        Value::Text(value.to_string())
+    }
+}
@@ -0,0 +1,139 @@
+use crate::alerts::AlertRule;
+use crate::app::Column;
+use crate::computed::ComputedColumn;
+use crate::units::Units;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// Settings persisted in ~/.config/psr/config.toml (or $XDG_CONFIG_HOME/psr)
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Config {
+    // Name patterns of processes kept at the top of every table; `*` matches
+    // any run of characters
+    pub pinned: Vec<String>,
+    // Processes table columns in display order, computed and plugin columns
+    // by their names; empty means the defaults followed by every computed and
+    // plugin column
+    pub columns: Vec<ColumnEntry>,
+    // Thresholds checked on every refresh; see alerts.rs
+    pub alerts: Vec<AlertRule>,
+    // Expression columns shown after `columns`; see computed.rs
+    pub computed: Vec<ComputedColumn>,
+    // Keys for actions by name, replacing the defaults; see keymap.rs
+    pub keys: HashMap<String, String>,
+    // Show process CPU% as a share of all cores (up to 100%) rather than per
+    // core, where a process keeping four cores busy shows 400%
+    pub normalize_cpu: bool,
+    // Binary or SI units and decimal places for byte counts; see units.rs
+    pub units: Units,
+    // How long new processes' rows flash green, and exited ones stay in red
+    // before they're removed, in milliseconds; 0 turns it off
+    pub flash_ms: Option<u64>,
+    // Shell command attaching a debugger to the process in $PSR_PID; gdb, or
+    // lldb on macOS, unless set
+    pub debugger: Option<String>,
+    // Arguments for `perf record` besides the PID, output and duration; "-g"
+    // (call graphs) unless set
+    pub perf_args: Option<String>,
+    // Directory gcore writes core dumps to; the working directory unless set
+    pub core_dir: Option<PathBuf>,
+    // Look up host names for the Connections panel's remote addresses, which
+    // sends them to the DNS server; on unless set to false
+    pub reverse_dns: Option<bool>,
+}
+
+// An entry of `columns`: a built-in column by its kebab-case name, or a
+// computed or plugin column by the name it's given there
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ColumnEntry {
+    Builtin(Column),
+    Named(String),
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        let base = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(base.join("psr").join("config.toml"))
+    }
+
+    // A missing config file just means the defaults
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents)
+                .map_err(|e| format!("invalid config {}: {}", path.display(), e).into()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::path().ok_or("no home directory to store the config in")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn flash_duration(&self) -> Duration {
+        Duration::from_millis(self.flash_ms.unwrap_or(1000))
+    }
+
+    pub fn debugger(&self) -> &str {
+        self.debugger
+            .as_deref()
+            .unwrap_or(psr_core::control::DEFAULT_DEBUGGER)
+    }
+
+    pub fn perf_args(&self) -> &str {
+        self.perf_args.as_deref().unwrap_or("-g")
+    }
+
+    pub fn core_dir(&self) -> PathBuf {
+        self.core_dir.clone().unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    pub fn reverse_dns(&self) -> bool {
+        self.reverse_dns.unwrap_or(true)
+    }
+
+    pub fn is_pinned(&self, name: &str) -> bool {
+        self.pinned
+            .iter()
+            .any(|pattern| matches_pattern(pattern, name))
+    }
+}
+
+// Glob-style match where `*` stands for any (possibly empty) run of characters
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard at all, so the whole name must match
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
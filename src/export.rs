@@ -0,0 +1,56 @@
+use psr_core::ProcessInfo;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use crate::app::{App, SystemResources};
+
+// Point-in-time view of everything psr knows, as written to JSON
+#[derive(Serialize)]
+pub struct Snapshot<'a> {
+    pub timestamp: String,
+    pub system: &'a SystemResources,
+    pub processes: Vec<Cow<'a, ProcessInfo>>,
+}
+
+impl<'a> Snapshot<'a> {
+    pub fn from_app(app: &'a App) -> Self {
+        // CPU% as the table shows it; only a scaled process needs a copy
+        let divisor = app.cpu_divisor();
+        let processes = app
+            .shown_processes()
+            .map(|process| {
+                if divisor == 1.0 {
+                    return Cow::Borrowed(process);
+                }
+                let mut process = process.clone();
+                process.cpu_usage /= divisor;
+                process
+                    .cpu_history
+                    .iter_mut()
+                    .for_each(|cpu| *cpu /= divisor);
+                Cow::Owned(process)
+            })
+            .collect();
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            system: &app.system_resources,
+            processes,
+        }
+    }
+}
+
+pub fn to_json(app: &App) -> serde_json::Result<String> {
+    serde_json::to_string(&Snapshot::from_app(app))
+}
+
+// Write a pretty-printed snapshot to a timestamped file in the working directory
+pub fn write_snapshot(app: &App) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = PathBuf::from(format!(
+        "psr-snapshot-{}.json",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let json = serde_json::to_string_pretty(&Snapshot::from_app(app))?;
+    std::fs::write(&path, json)?;
+    Ok(path)
+}
@@ -0,0 +1,38 @@
+use inferno::collapse::{perf::Folder, Collapse};
+use inferno::flamegraph::{self, Options};
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+// Turn a `perf record -g` data file into an SVG flamegraph: `perf script`
+// prints the sampled stacks, inferno folds identical ones together and draws
+// them. perf's symbol resolution makes this take a few seconds for a busy
+// process, so it's meant to be run off the UI thread.
+pub fn render(data: &Path, svg: &Path, title: &str) -> io::Result<()> {
+    let script = Command::new("perf")
+        .arg("script")
+        .arg("-i")
+        .arg(data)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()?;
+    if !script.status.success() {
+        return Err(io::Error::other(format!(
+            "perf script exited with {}",
+            script.status
+        )));
+    }
+
+    let mut folded = Vec::new();
+    Folder::default().collapse(&script.stdout[..], &mut folded)?;
+    if folded.is_empty() {
+        return Err(io::Error::other("no samples were recorded"));
+    }
+
+    let mut options = Options::default();
+    options.title = title.to_string();
+    let writer = BufWriter::new(File::create(svg)?);
+    flamegraph::from_reader(&mut options, &folded[..], writer)
+        .map_err(|e| io::Error::other(e.to_string()))
+}
@@ -0,0 +1,155 @@
+use psr_core::ProcessInfo;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::app::SystemResources;
+
+// Optional on-disk history of system and per-process samples, so charts can
+// reach back further than the in-memory minute. Samples older than the
+// retention period are pruned as new ones come in.
+pub struct HistoryStore {
+    conn: Connection,
+    retention: Duration,
+    pruned_at: Option<Instant>,
+}
+
+// One point of a history chart, `time` in seconds since the Unix epoch
+#[derive(Clone, Copy)]
+pub struct HistorySample {
+    pub time: i64,
+    pub cpu_usage: f32,
+    pub memory: u64,
+}
+
+// Charts get at most this many points, averaged over equal time buckets
+const MAX_POINTS: i64 = 300;
+
+impl HistoryStore {
+    pub fn open(path: &Path, retention: Duration) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE IF NOT EXISTS system_samples (
+                 time INTEGER NOT NULL,
+                 cpu_usage REAL NOT NULL,
+                 used_memory INTEGER NOT NULL,
+                 total_memory INTEGER NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS system_samples_time ON system_samples (time);
+             CREATE TABLE IF NOT EXISTS process_samples (
+                 time INTEGER NOT NULL,
+                 pid INTEGER NOT NULL,
+                 name TEXT NOT NULL,
+                 cpu_usage REAL NOT NULL,
+                 memory INTEGER NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS process_samples_pid ON process_samples (pid, time);
+             CREATE INDEX IF NOT EXISTS process_samples_time ON process_samples (time);",
+        )?;
+        Ok(Self {
+            conn,
+            retention,
+            pruned_at: None,
+        })
+    }
+
+    pub fn record(
+        &mut self,
+        system: &SystemResources,
+        processes: &HashMap<u32, ProcessInfo>,
+    ) -> rusqlite::Result<()> {
+        let time = now();
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO system_samples VALUES (?1, ?2, ?3, ?4)",
+            params![
+                time,
+                system.cpu_usage,
+                system.used_memory as i64,
+                system.total_memory as i64
+            ],
+        )?;
+        {
+            let mut insert =
+                tx.prepare_cached("INSERT INTO process_samples VALUES (?1, ?2, ?3, ?4, ?5)")?;
+            for process in processes.values() {
+                insert.execute(params![
+                    time,
+                    process.pid,
+                    process.name,
+                    process.cpu_usage,
+                    process.memory as i64
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        // Pruning scans the time index, so it runs every few minutes
+        if self
+            .pruned_at
+            .is_none_or(|at| at.elapsed() > Duration::from_secs(300))
+        {
+            let cutoff = time - self.retention.as_secs() as i64;
+            self.conn
+                .execute("DELETE FROM system_samples WHERE time < ?1", [cutoff])?;
+            self.conn
+                .execute("DELETE FROM process_samples WHERE time < ?1", [cutoff])?;
+            self.pruned_at = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    // Samples of a process since it started. The name guards against the PID
+    // having belonged to another process earlier in the history.
+    pub fn process_history(&self, process: &ProcessInfo) -> rusqlite::Result<Vec<HistorySample>> {
+        let end = now();
+        let start = end - process.start_time.as_secs() as i64;
+        let bucket = ((end - start) / MAX_POINTS).max(1);
+        let mut query = self.conn.prepare_cached(
+            "SELECT time / ?1 * ?1 AS bucket, AVG(cpu_usage), AVG(memory)
+             FROM process_samples
+             WHERE pid = ?2 AND name = ?3 AND time >= ?4
+             GROUP BY bucket ORDER BY bucket",
+        )?;
+        let samples = query
+            .query_map(params![bucket, process.pid, process.name, start], |row| {
+                Ok(HistorySample {
+                    time: row.get(0)?,
+                    cpu_usage: row.get::<_, f64>(1)? as f32,
+                    memory: row.get::<_, f64>(2)? as u64,
+                })
+            })?
+            .collect();
+        samples
+    }
+
+    // System-wide samples over the last `span`; memory is the used bytes
+    pub fn system_history(&self, span: Duration) -> rusqlite::Result<Vec<HistorySample>> {
+        let start = now() - span.as_secs() as i64;
+        let bucket = (span.as_secs() as i64 / MAX_POINTS).max(1);
+        let mut query = self.conn.prepare_cached(
+            "SELECT time / ?1 * ?1 AS bucket, AVG(cpu_usage), AVG(used_memory)
+             FROM system_samples
+             WHERE time >= ?2
+             GROUP BY bucket ORDER BY bucket",
+        )?;
+        let samples = query
+            .query_map(params![bucket, start], |row| {
+                Ok(HistorySample {
+                    time: row.get(0)?,
+                    cpu_usage: row.get::<_, f64>(1)? as f32,
+                    memory: row.get::<_, f64>(2)? as u64,
+                })
+            })?
+            .collect();
+        samples
+    }
+}
+
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
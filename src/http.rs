@@ -0,0 +1,93 @@
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{ALLOW, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, StatusCode};
+use hyper_util::rt::{TokioIo, TokioTimer};
+use std::convert::Infallible;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+// psr's read-only endpoints over hyper: one GET per connection, answered and
+// closed. Anything else is refused with 405.
+
+// A client gets this long to send its request headers
+const HEADER_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct Response {
+    pub status: StatusCode,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn ok(content_type: &'static str, body: String) -> Self {
+        Self {
+            status: StatusCode::OK,
+            content_type,
+            body,
+        }
+    }
+
+    pub fn not_found(body: String) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            content_type: "text/plain",
+            body,
+        }
+    }
+}
+
+// Bind `addr` and answer every GET with `handler`, which gets the request
+// path
+pub async fn serve<F>(addr: &str, handler: F) -> io::Result<()>
+where
+    F: Fn(&str) -> Response + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind(addr).await?;
+    let handler = Arc::new(handler);
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let handler = handler.clone();
+            let service = service_fn(move |request| {
+                let response = respond(&request, handler.as_ref());
+                async move { Ok::<_, Infallible>(response) }
+            });
+            tokio::spawn(
+                http1::Builder::new()
+                    .timer(TokioTimer::new())
+                    .header_read_timeout(HEADER_TIMEOUT)
+                    .keep_alive(false)
+                    .serve_connection(TokioIo::new(stream), service),
+            );
+        }
+    });
+    Ok(())
+}
+
+fn respond<F>(request: &Request<Incoming>, handler: &F) -> hyper::Response<Full<Bytes>>
+where
+    F: Fn(&str) -> Response,
+{
+    let response = match *request.method() {
+        Method::GET | Method::HEAD => handler(request.uri().path()),
+        _ => Response {
+            status: StatusCode::METHOD_NOT_ALLOWED,
+            content_type: "text/plain",
+            body: "only GET is supported\n".to_string(),
+        },
+    };
+
+    let mut builder = hyper::Response::builder()
+        .status(response.status)
+        .header(CONTENT_TYPE, response.content_type);
+    if response.status == StatusCode::METHOD_NOT_ALLOWED {
+        builder = builder.header(ALLOW, "GET, HEAD");
+    }
+    builder
+        .body(Full::new(Bytes::from(response.body)))
+        .unwrap_or_default()
+}
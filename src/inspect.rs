@@ -0,0 +1,1101 @@
+use std::io;
+use std::time::Duration;
+
+// On-demand inspection of a single process, used by the Detailed view panels
+
+// An open file descriptor and what it points at
+pub struct OpenFile {
+    pub fd: String,
+    pub kind: &'static str,
+    pub target: String,
+}
+
+#[cfg(target_os = "linux")]
+pub fn open_files(pid: u32) -> io::Result<Vec<OpenFile>> {
+    let mut files: Vec<OpenFile> = std::fs::read_dir(format!("/proc/{}/fd", pid))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            // Descriptors can close while we iterate, so skip unreadable links
+            let target = std::fs::read_link(entry.path()).ok()?;
+            let target = target.to_string_lossy().to_string();
+            Some(OpenFile {
+                fd: entry.file_name().to_string_lossy().to_string(),
+                kind: classify_fd_target(&target),
+                target,
+            })
+        })
+        .collect();
+
+    files.sort_by_key(|f| f.fd.parse::<u64>().unwrap_or(u64::MAX));
+    Ok(files)
+}
+
+#[cfg(target_os = "linux")]
+fn classify_fd_target(target: &str) -> &'static str {
+    if target.starts_with("socket:") {
+        "socket"
+    } else if target.starts_with("pipe:") {
+        "pipe"
+    } else if target.starts_with("anon_inode:") {
+        "anon"
+    } else if target.starts_with("/dev/") {
+        "device"
+    } else {
+        "file"
+    }
+}
+
+// Elsewhere fall back to lsof's machine-readable field output
+#[cfg(not(target_os = "linux"))]
+pub fn open_files(pid: u32) -> io::Result<Vec<OpenFile>> {
+    let output = std::process::Command::new("lsof")
+        .args(["-n", "-P", "-p", &pid.to_string(), "-F", "ftn"])
+        .output()?;
+
+    let mut files = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let (field, value) = line.split_at(line.len().min(1));
+        match field {
+            "f" => files.push(OpenFile {
+                fd: value.to_string(),
+                kind: "file",
+                target: String::new(),
+            }),
+            "t" => {
+                if let Some(file) = files.last_mut() {
+                    file.kind = match value {
+                        "DIR" => "dir",
+                        "IPv4" | "IPv6" | "unix" => "socket",
+                        "PIPE" | "FIFO" => "pipe",
+                        "CHR" | "BLK" => "device",
+                        _ => "file",
+                    };
+                }
+            }
+            "n" => {
+                if let Some(file) = files.last_mut() {
+                    file.target = value.to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(files)
+}
+
+// A TCP or UDP socket owned by a process
+#[derive(Clone)]
+pub struct Connection {
+    pub protocol: &'static str,
+    pub local: String,
+    pub remote: String,
+    pub state: String,
+}
+
+// Match the socket inodes among the process's descriptors against the kernel's
+// socket tables. The tables are read from the process's own network namespace.
+#[cfg(target_os = "linux")]
+pub fn connections(pid: u32) -> io::Result<Vec<Connection>> {
+    let inodes: std::collections::HashSet<String> = std::fs::read_dir(format!("/proc/{}/fd", pid))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let target = std::fs::read_link(entry.path()).ok()?;
+            let target = target.to_string_lossy();
+            let inode = target.strip_prefix("socket:[")?.strip_suffix(']')?;
+            Some(inode.to_string())
+        })
+        .collect();
+
+    let mut connections = Vec::new();
+    for (table, protocol) in [
+        ("tcp", "tcp"),
+        ("tcp6", "tcp6"),
+        ("udp", "udp"),
+        ("udp6", "udp6"),
+    ] {
+        // A table is missing when the protocol is disabled, which is fine
+        let Ok(contents) = std::fs::read_to_string(format!("/proc/{}/net/{}", pid, table)) else {
+            continue;
+        };
+
+        for line in contents.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 || !inodes.contains(fields[9]) {
+                continue;
+            }
+
+            connections.push(Connection {
+                protocol,
+                local: parse_socket_address(fields[1]).unwrap_or_default(),
+                remote: parse_socket_address(fields[2]).unwrap_or_default(),
+                state: socket_state(protocol, fields[3]).to_string(),
+            });
+        }
+    }
+
+    Ok(connections)
+}
+
+#[cfg(target_os = "linux")]
+fn parse_socket_address(field: &str) -> Option<String> {
+    socket_address(field).map(|address| address.to_string())
+}
+
+// Addresses look like "0100007F:1F90": the IP in host byte order, 32 bits at a
+// time, followed by the port in hex
+#[cfg(target_os = "linux")]
+fn socket_address(field: &str) -> Option<std::net::SocketAddr> {
+    let (ip, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+
+    let mut bytes = Vec::new();
+    for chunk in ip.as_bytes().chunks(8) {
+        let word = u32::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+
+    let address = match bytes.len() {
+        4 => std::net::IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?),
+        16 => std::net::IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?),
+        _ => return None,
+    };
+    Some(std::net::SocketAddr::new(address, port))
+}
+
+#[cfg(target_os = "linux")]
+fn socket_state(protocol: &str, code: &str) -> &'static str {
+    match code {
+        "01" => "ESTABLISHED",
+        "02" => "SYN_SENT",
+        "03" => "SYN_RECV",
+        "04" => "FIN_WAIT1",
+        "05" => "FIN_WAIT2",
+        "06" => "TIME_WAIT",
+        // Unconnected UDP sockets sit in the CLOSE state
+        "07" if protocol.starts_with("udp") => "UNCONN",
+        "07" => "CLOSE",
+        "08" => "CLOSE_WAIT",
+        "09" => "LAST_ACK",
+        "0A" => "LISTEN",
+        "0B" => "CLOSING",
+        _ => "UNKNOWN",
+    }
+}
+
+// A socket waiting for connections (TCP) or datagrams (UDP), and the
+// processes holding it. Forked servers' workers share their listening
+// socket, and other users' processes can't be seen without root, so there may
+// be several owners or none.
+pub struct ListeningPort {
+    pub protocol: &'static str,
+    pub address: String,
+    pub port: u16,
+    pub pids: Vec<u32>,
+}
+
+// Listening sockets in psr's own network namespace from the kernel's socket
+// tables, with owners found by matching socket inodes against every
+// process's descriptors
+#[cfg(target_os = "linux")]
+pub fn listening_ports() -> io::Result<Vec<ListeningPort>> {
+    let mut ports = Vec::new();
+    let mut inodes = std::collections::HashMap::new();
+    for (table, protocol) in [
+        ("tcp", "tcp"),
+        ("tcp6", "tcp6"),
+        ("udp", "udp"),
+        ("udp6", "udp6"),
+    ] {
+        let Ok(contents) = std::fs::read_to_string(format!("/proc/net/{}", table)) else {
+            continue;
+        };
+        for line in contents.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                continue;
+            }
+            let (Some(local), Some(remote)) =
+                (socket_address(fields[1]), socket_address(fields[2]))
+            else {
+                continue;
+            };
+            // UDP sockets bound to a port but not connected anywhere
+            let listening = match protocol {
+                "tcp" | "tcp6" => fields[3] == "0A",
+                _ => fields[3] == "07" && remote.port() == 0 && local.port() != 0,
+            };
+            if !listening {
+                continue;
+            }
+            inodes.insert(fields[9].to_string(), ports.len());
+            ports.push(ListeningPort {
+                protocol,
+                address: local.ip().to_string(),
+                port: local.port(),
+                pids: Vec::new(),
+            });
+        }
+    }
+
+    each_socket(|pid, inode| {
+        if let Some(&index) = inodes.get(inode) {
+            let pids = &mut ports[index].pids;
+            if !pids.contains(&pid) {
+                pids.push(pid);
+            }
+        }
+    })?;
+    Ok(ports)
+}
+
+// Every TCP and UDP socket in psr's own network namespace, by the processes
+// holding it, for filtering the process list by port or address
+#[cfg(target_os = "linux")]
+pub fn sockets() -> io::Result<std::collections::HashMap<u32, Vec<Connection>>> {
+    let mut by_inode = std::collections::HashMap::new();
+    for (table, protocol) in [
+        ("tcp", "tcp"),
+        ("tcp6", "tcp6"),
+        ("udp", "udp"),
+        ("udp6", "udp6"),
+    ] {
+        let Ok(contents) = std::fs::read_to_string(format!("/proc/net/{}", table)) else {
+            continue;
+        };
+        for line in contents.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                continue;
+            }
+            by_inode.insert(
+                fields[9].to_string(),
+                Connection {
+                    protocol,
+                    local: parse_socket_address(fields[1]).unwrap_or_default(),
+                    remote: parse_socket_address(fields[2]).unwrap_or_default(),
+                    state: socket_state(protocol, fields[3]).to_string(),
+                },
+            );
+        }
+    }
+
+    let mut sockets: std::collections::HashMap<u32, Vec<Connection>> =
+        std::collections::HashMap::new();
+    each_socket(|pid, inode| {
+        if let Some(connection) = by_inode.get(inode) {
+            sockets.entry(pid).or_default().push(connection.clone());
+        }
+    })?;
+    Ok(sockets)
+}
+
+// Call `found` with the PID and inode of each socket descriptor of every
+// process we're allowed to look at
+#[cfg(target_os = "linux")]
+fn each_socket(mut found: impl FnMut(u32, &str)) -> io::Result<()> {
+    for entry in std::fs::read_dir("/proc")?.filter_map(|entry| entry.ok()) {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.filter_map(|fd| fd.ok()) {
+            let Ok(target) = std::fs::read_link(fd.path()) else {
+                continue;
+            };
+            let target = target.to_string_lossy();
+            if let Some(inode) = target
+                .strip_prefix("socket:[")
+                .and_then(|t| t.strip_suffix(']'))
+            {
+                found(pid, inode);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Elsewhere lsof lists them, TCP and UDP separately since its state filter
+// only applies to TCP
+#[cfg(not(target_os = "linux"))]
+pub fn listening_ports() -> io::Result<Vec<ListeningPort>> {
+    let mut ports: Vec<ListeningPort> = Vec::new();
+    for (selection, protocol) in [
+        (&["-iTCP", "-sTCP:LISTEN"][..], "tcp"),
+        (&["-iUDP"][..], "udp"),
+    ] {
+        let output = std::process::Command::new("lsof")
+            .args(["-n", "-P", "-F", "pn"])
+            .args(selection)
+            .output()?;
+        let mut pid = 0;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let (field, value) = line.split_at(line.len().min(1));
+            match field {
+                "p" => pid = value.parse().unwrap_or(0),
+                // Connected UDP sockets name their peer too
+                "n" if !value.contains("->") => {
+                    let Some((address, port)) = value.rsplit_once(':') else {
+                        continue;
+                    };
+                    let Ok(port) = port.parse() else {
+                        continue;
+                    };
+                    let address = address.trim_start_matches('[').trim_end_matches(']');
+                    match ports
+                        .iter_mut()
+                        .find(|p| p.protocol == protocol && p.port == port && p.address == address)
+                    {
+                        Some(existing) if !existing.pids.contains(&pid) => existing.pids.push(pid),
+                        Some(_) => {}
+                        None => ports.push(ListeningPort {
+                            protocol,
+                            address: address.to_string(),
+                            port,
+                            pids: vec![pid],
+                        }),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(ports)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn connections(pid: u32) -> io::Result<Vec<Connection>> {
+    let mut sockets = lsof_sockets(&["-a", "-i", "-p", &pid.to_string()])?;
+    Ok(sockets.remove(&pid).unwrap_or_default())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn sockets() -> io::Result<std::collections::HashMap<u32, Vec<Connection>>> {
+    lsof_sockets(&["-i"])
+}
+
+// lsof's field output names the process, then each of its sockets' protocol,
+// addresses and TCP state
+#[cfg(not(target_os = "linux"))]
+fn lsof_sockets(selection: &[&str]) -> io::Result<std::collections::HashMap<u32, Vec<Connection>>> {
+    let output = std::process::Command::new("lsof")
+        .args(["-n", "-P"])
+        .args(selection)
+        .args(["-F", "pPnT"])
+        .output()?;
+
+    let mut sockets: std::collections::HashMap<u32, Vec<Connection>> =
+        std::collections::HashMap::new();
+    let mut pid = 0;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let (field, value) = line.split_at(line.len().min(1));
+        match field {
+            "p" => pid = value.parse().unwrap_or(0),
+            "P" => sockets.entry(pid).or_default().push(Connection {
+                protocol: if value == "UDP" { "udp" } else { "tcp" },
+                local: String::new(),
+                remote: String::new(),
+                state: String::new(),
+            }),
+            "n" => {
+                if let Some(connection) = sockets.get_mut(&pid).and_then(|c| c.last_mut()) {
+                    let (local, remote) = value.split_once("->").unwrap_or((value, ""));
+                    connection.local = local.to_string();
+                    connection.remote = remote.to_string();
+                }
+            }
+            "T" => {
+                if let (Some(connection), Some(state)) = (
+                    sockets.get_mut(&pid).and_then(|c| c.last_mut()),
+                    value.strip_prefix("ST="),
+                ) {
+                    connection.state = state.to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(sockets)
+}
+
+// Mapped memory backed by the same file (or the same kind of anonymous memory)
+pub struct MemoryMapping {
+    pub name: String,
+    pub kind: &'static str,
+    pub size: u64,
+    pub regions: usize,
+}
+
+// Group the mapped regions by what backs them, largest first
+fn group_mappings(regions: impl Iterator<Item = (String, u64)>) -> Vec<MemoryMapping> {
+    let mut mappings: Vec<MemoryMapping> = Vec::new();
+    for (name, size) in regions {
+        match mappings.iter_mut().find(|m| m.name == name) {
+            Some(mapping) => {
+                mapping.size += size;
+                mapping.regions += 1;
+            }
+            None => mappings.push(MemoryMapping {
+                kind: classify_mapping(&name),
+                name,
+                size,
+                regions: 1,
+            }),
+        }
+    }
+
+    mappings.sort_by_key(|m| std::cmp::Reverse(m.size));
+    mappings
+}
+
+fn classify_mapping(name: &str) -> &'static str {
+    if name.is_empty() {
+        "anon"
+    } else if name.contains("heap") || name.contains("MALLOC") {
+        "heap"
+    } else if name.contains("stack") || name.contains("Stack") {
+        "stack"
+    } else if name.contains(".so") || name.ends_with(".dylib") {
+        "library"
+    } else if name.starts_with('[') {
+        "kernel"
+    } else {
+        "file"
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn memory_maps(pid: u32) -> io::Result<Vec<MemoryMapping>> {
+    let contents = std::fs::read_to_string(format!("/proc/{}/maps", pid))?;
+
+    // Lines look like "start-end perms offset dev inode [pathname]"
+    let regions = contents.lines().filter_map(|line| {
+        let mut fields = line.splitn(6, char::is_whitespace);
+        let (start, end) = fields.next()?.split_once('-')?;
+        let size = u64::from_str_radix(end, 16).ok()? - u64::from_str_radix(start, 16).ok()?;
+        let name = fields.nth(4).unwrap_or("").trim().to_string();
+        Some((name, size))
+    });
+
+    Ok(group_mappings(regions))
+}
+
+// vmmap prints one region per line with its address range and, for file
+// mappings, the path after the share mode
+#[cfg(not(target_os = "linux"))]
+pub fn memory_maps(pid: u32) -> io::Result<Vec<MemoryMapping>> {
+    let output = std::process::Command::new("vmmap")
+        .args(["-interleaved", &pid.to_string()])
+        .output()?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let regions = stdout.lines().filter_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let range = fields.iter().position(|f| f.contains('-'))?;
+        let (start, end) = fields[range].split_once('-')?;
+        let size = u64::from_str_radix(end, 16).ok()? - u64::from_str_radix(start, 16).ok()?;
+
+        let name = match fields.iter().position(|f| f.starts_with("SM=")) {
+            Some(share_mode) => fields[share_mode + 1..].join(" "),
+            None => fields[0].to_string(),
+        };
+        Some((name, size))
+    });
+
+    Ok(group_mappings(regions))
+}
+
+// A single thread of a process. `cpu_time` is cumulative, in seconds; the usage
+// percentage is derived by the caller from two samples.
+pub struct ThreadInfo {
+    pub tid: u32,
+    pub name: String,
+    pub state: char,
+    pub cpu_time: f64,
+    pub cpu_usage: f32,
+}
+
+#[cfg(target_os = "linux")]
+pub fn threads(pid: u32) -> io::Result<Vec<ThreadInfo>> {
+    let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as f64;
+
+    let mut threads: Vec<ThreadInfo> = std::fs::read_dir(format!("/proc/{}/task", pid))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let tid = entry.file_name().to_string_lossy().parse().ok()?;
+            let stat = std::fs::read_to_string(entry.path().join("stat")).ok()?;
+
+            // The name is wrapped in parentheses and may itself contain spaces
+            let open = stat.find('(')?;
+            let close = stat.rfind(')')?;
+            let fields: Vec<&str> = stat[close + 1..].split_whitespace().collect();
+            let utime: u64 = fields.get(11)?.parse().ok()?;
+            let stime: u64 = fields.get(12)?.parse().ok()?;
+
+            Some(ThreadInfo {
+                tid,
+                name: stat[open + 1..close].to_string(),
+                state: fields.first()?.chars().next()?,
+                cpu_time: (utime + stime) as f64 / ticks_per_second,
+                cpu_usage: 0.0,
+            })
+        })
+        .collect();
+
+    threads.sort_by_key(|t| t.tid);
+    Ok(threads)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn threads(_pid: u32) -> io::Result<Vec<ThreadInfo>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "thread listing is only available on Linux",
+    ))
+}
+
+// Cumulative context switches and page faults of a process, or per-second
+// rates of them when taken between two samples
+#[derive(Clone, Copy, Default)]
+pub struct Activity {
+    pub voluntary_switches: u64,
+    pub involuntary_switches: u64,
+    pub minor_faults: u64,
+    pub major_faults: u64,
+}
+
+impl Activity {
+    // Voluntary and involuntary switches, then minor and major faults
+    pub fn counts(&self) -> [u64; 4] {
+        [
+            self.voluntary_switches,
+            self.involuntary_switches,
+            self.minor_faults,
+            self.major_faults,
+        ]
+    }
+
+    pub fn rates_since(&self, earlier: &Activity, elapsed: Duration) -> Activity {
+        let seconds = elapsed.as_secs_f64().max(0.001);
+        let rate = |now: u64, then: u64| (now.saturating_sub(then) as f64 / seconds).round() as u64;
+        Activity {
+            voluntary_switches: rate(self.voluntary_switches, earlier.voluntary_switches),
+            involuntary_switches: rate(self.involuntary_switches, earlier.involuntary_switches),
+            minor_faults: rate(self.minor_faults, earlier.minor_faults),
+            major_faults: rate(self.major_faults, earlier.major_faults),
+        }
+    }
+}
+
+// Context switches come from /proc/<pid>/status and faults from the minflt and
+// majflt fields of /proc/<pid>/stat
+#[cfg(target_os = "linux")]
+pub fn activity(pid: u32) -> io::Result<Activity> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "unexpected /proc format");
+
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid))?;
+    let switches = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|count| count.trim().parse::<u64>().ok())
+            .ok_or_else(invalid)
+    };
+
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid))?;
+    let close = stat.rfind(')').ok_or_else(invalid)?;
+    let fields: Vec<&str> = stat[close + 1..].split_whitespace().collect();
+    let field = |index: usize| {
+        fields
+            .get(index)
+            .and_then(|count| count.parse::<u64>().ok())
+            .ok_or_else(invalid)
+    };
+
+    Ok(Activity {
+        voluntary_switches: switches("voluntary_ctxt_switches:")?,
+        involuntary_switches: switches("nonvoluntary_ctxt_switches:")?,
+        minor_faults: field(7)?,
+        major_faults: field(9)?,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn activity(_pid: u32) -> io::Result<Activity> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "context switch and page fault counts are only available on Linux",
+    ))
+}
+
+// The kernel function a sleeping process is blocked in, e.g. "ep_poll". None
+// for a running process, or when the kernel hides it from us.
+#[cfg(target_os = "linux")]
+pub fn wait_channel(pid: u32) -> Option<String> {
+    let wchan = std::fs::read_to_string(format!("/proc/{}/wchan", pid)).ok()?;
+    let wchan = wchan.trim();
+    (!wchan.is_empty() && wchan != "0").then(|| wchan.to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn wait_channel(_pid: u32) -> Option<String> {
+    None
+}
+
+// How confined a process is: its effective capabilities, seccomp mode and the
+// label SELinux or AppArmor gave it
+pub struct Security {
+    pub capabilities: Vec<String>,
+    // Whether it holds every capability the kernel has
+    pub all_capabilities: bool,
+    pub seccomp: Seccomp,
+    pub no_new_privs: bool,
+    // The security module's name and the process's label under it
+    pub label: Option<(&'static str, String)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Seccomp {
+    Disabled,
+    Strict,
+    Filter,
+    Unknown,
+}
+
+// Capability names by bit number, as in linux/capability.h
+#[cfg(target_os = "linux")]
+const CAPABILITIES: &[&str] = &[
+    "chown",
+    "dac_override",
+    "dac_read_search",
+    "fowner",
+    "fsetid",
+    "kill",
+    "setgid",
+    "setuid",
+    "setpcap",
+    "linux_immutable",
+    "net_bind_service",
+    "net_broadcast",
+    "net_admin",
+    "net_raw",
+    "ipc_lock",
+    "ipc_owner",
+    "sys_module",
+    "sys_rawio",
+    "sys_chroot",
+    "sys_ptrace",
+    "sys_pacct",
+    "sys_admin",
+    "sys_boot",
+    "sys_nice",
+    "sys_resource",
+    "sys_time",
+    "sys_tty_config",
+    "mknod",
+    "lease",
+    "audit_write",
+    "audit_control",
+    "setfcap",
+    "mac_override",
+    "mac_admin",
+    "syslog",
+    "wake_alarm",
+    "block_suspend",
+    "audit_read",
+    "perfmon",
+    "bpf",
+    "checkpoint_restore",
+];
+
+// Capabilities and seccomp come from /proc/<pid>/status
+#[cfg(target_os = "linux")]
+pub fn security(pid: u32) -> io::Result<Security> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "unexpected /proc format");
+
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid))?;
+    let field = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .map(str::trim)
+            .ok_or_else(invalid)
+    };
+    let mask = |name: &str| u64::from_str_radix(field(name)?, 16).map_err(|_| invalid());
+
+    let effective = mask("CapEff:")?;
+    let capabilities = (0..64)
+        .filter(|bit| effective & (1 << bit) != 0)
+        .map(|bit| match CAPABILITIES.get(bit) {
+            Some(name) => name.to_string(),
+            None => format!("cap_{}", bit),
+        })
+        .collect();
+    // init's bounding set is everything the kernel supports
+    let known = std::fs::read_to_string("/proc/1/status")
+        .ok()
+        .and_then(|init| {
+            let mask = init.lines().find_map(|line| line.strip_prefix("CapBnd:"))?;
+            u64::from_str_radix(mask.trim(), 16).ok()
+        });
+    let seccomp = match field("Seccomp:") {
+        Ok("0") => Seccomp::Disabled,
+        Ok("1") => Seccomp::Strict,
+        Ok("2") => Seccomp::Filter,
+        _ => Seccomp::Unknown,
+    };
+
+    Ok(Security {
+        capabilities,
+        all_capabilities: effective != 0 && known.is_some_and(|known| effective & known == known),
+        seccomp,
+        no_new_privs: field("NoNewPrivs:").is_ok_and(|value| value == "1"),
+        label: lsm_label(pid),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn security(_pid: u32) -> io::Result<Security> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "security contexts are only available on Linux",
+    ))
+}
+
+// Newer kernels give AppArmor a file of its own; otherwise the shared one
+// belongs to whichever module is active
+#[cfg(target_os = "linux")]
+fn lsm_label(pid: u32) -> Option<(&'static str, String)> {
+    let read = |path: String| {
+        let label = std::fs::read_to_string(path).ok()?;
+        let label = label.trim_end_matches(['\0', '\n']);
+        (!label.is_empty()).then(|| label.to_string())
+    };
+    if let Some(label) = read(format!("/proc/{}/attr/apparmor/current", pid)) {
+        return Some(("AppArmor", label));
+    }
+    let label = read(format!("/proc/{}/attr/current", pid))?;
+    let module = if std::path::Path::new("/sys/fs/selinux/enforce").exists() {
+        "SELinux"
+    } else if std::path::Path::new("/sys/kernel/security/apparmor").exists() {
+        "AppArmor"
+    } else {
+        "LSM"
+    };
+    Some((module, label))
+}
+
+// One of a process's resource limits; None stands for unlimited
+pub struct ResourceLimit {
+    // The name ulimit and setrlimit know it by, e.g. "nofile"
+    pub name: &'static str,
+    pub description: String,
+    pub soft: Option<u64>,
+    pub hard: Option<u64>,
+    pub units: String,
+}
+
+#[cfg(target_os = "linux")]
+const LIMIT_NAMES: &[(&str, &str)] = &[
+    ("Max cpu time", "cpu"),
+    ("Max file size", "fsize"),
+    ("Max data size", "data"),
+    ("Max stack size", "stack"),
+    ("Max core file size", "core"),
+    ("Max resident set", "rss"),
+    ("Max processes", "nproc"),
+    ("Max open files", "nofile"),
+    ("Max locked memory", "memlock"),
+    ("Max address space", "as"),
+    ("Max file locks", "locks"),
+    ("Max pending signals", "sigpending"),
+    ("Max msgqueue size", "msgqueue"),
+    ("Max nice priority", "nice"),
+    ("Max realtime priority", "rtprio"),
+    ("Max realtime timeout", "rttime"),
+];
+
+// Lines of /proc/<pid>/limits have the description padded to 26 columns,
+// then the soft and hard limits and, for most, the units
+#[cfg(target_os = "linux")]
+pub fn limits(pid: u32) -> io::Result<Vec<ResourceLimit>> {
+    let contents = std::fs::read_to_string(format!("/proc/{}/limits", pid))?;
+    let value = |field: &str| field.parse().ok();
+    Ok(contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let (description, rest) = line.split_at_checked(26)?;
+            let description = description.trim();
+            let mut fields = rest.split_whitespace();
+            let (soft, hard) = (fields.next()?, fields.next()?);
+            Some(ResourceLimit {
+                name: LIMIT_NAMES
+                    .iter()
+                    .find(|(known, _)| *known == description)
+                    .map_or("", |(_, name)| name),
+                description: description.to_string(),
+                soft: value(soft),
+                hard: value(hard),
+                units: fields.next().unwrap_or("").to_string(),
+            })
+        })
+        .collect())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn limits(_pid: u32) -> io::Result<Vec<ResourceLimit>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "resource limits are only available on Linux",
+    ))
+}
+
+// Where a process's stdout or stderr goes, and the end of what it wrote
+// there when it can be read back
+pub struct OutputStream {
+    // "stdout", "stderr", or both when they go to the same place
+    pub streams: &'static str,
+    pub target: String,
+    // The last lines, oldest first, for regular files. Anything else can't be
+    // read back, or only by taking the output away from whoever reads it;
+    // the reason says where it goes instead.
+    pub tail: Result<Vec<String>, String>,
+}
+
+// How much of the end of an output file is read, and the most lines kept
+#[cfg(target_os = "linux")]
+const TAIL_BYTES: u64 = 64 * 1024;
+#[cfg(target_os = "linux")]
+const TAIL_LINES: usize = 200;
+
+#[cfg(target_os = "linux")]
+pub fn output(pid: u32) -> io::Result<Vec<OutputStream>> {
+    let fd = |n: u32| format!("/proc/{}/fd/{}", pid, n);
+    let stdout = std::fs::read_link(fd(1)).map(|t| t.to_string_lossy().to_string());
+    let stderr = std::fs::read_link(fd(2)).map(|t| t.to_string_lossy().to_string());
+    let (stdout, stderr) = match (stdout, stderr) {
+        // Both closed, or more likely hidden from us
+        (Err(e), Err(_)) => return Err(e),
+        (stdout, stderr) => (stdout.ok(), stderr.ok()),
+    };
+
+    let mut streams = Vec::new();
+    if stdout.is_some() && stdout == stderr {
+        streams.push(("stdout and stderr", 1, stdout));
+    } else {
+        streams.push(("stdout", 1, stdout));
+        streams.push(("stderr", 2, stderr));
+    }
+    Ok(streams
+        .into_iter()
+        .filter_map(|(name, n, target)| {
+            let target = target?;
+            Some(OutputStream {
+                streams: name,
+                tail: tail_output(pid, &fd(n), &target),
+                target,
+            })
+        })
+        .collect())
+}
+
+// Read the end of a regular file through the descriptor's /proc link, which
+// works for deleted files and ones in other mount namespaces too. Pipes,
+// sockets and terminals are only described: whatever's read from them
+// wouldn't reach their reader.
+#[cfg(target_os = "linux")]
+fn tail_output(pid: u32, fd: &str, target: &str) -> Result<Vec<String>, String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    if target == "/dev/null" {
+        return Err("discarded".to_string());
+    }
+    if target.starts_with("/dev/pts/") || target.starts_with("/dev/tty") {
+        return Err("a terminal".to_string());
+    }
+    if target.starts_with("pipe:") {
+        return Err(match pipe_reader(pid, target) {
+            Some((reader, name)) => format!("a pipe read by PID {} ({})", reader, name),
+            None => "a pipe".to_string(),
+        });
+    }
+    if target.starts_with("socket:") {
+        return Err("a socket".to_string());
+    }
+    let metadata = std::fs::metadata(fd).map_err(|e| e.to_string())?;
+    if !metadata.is_file() {
+        return Err("not a regular file".to_string());
+    }
+
+    let mut file = std::fs::File::open(fd).map_err(|e| e.to_string())?;
+    let start = metadata.len().saturating_sub(TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| e.to_string())?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+
+    let text = String::from_utf8_lossy(&bytes);
+    let mut lines: Vec<&str> = text.lines().collect();
+    if start > 0 && !lines.is_empty() {
+        // Most likely cut mid-line
+        lines.remove(0);
+    }
+    let skip = lines.len().saturating_sub(TAIL_LINES);
+    Ok(lines[skip..]
+        .iter()
+        .map(|line| strip_control(line))
+        .collect())
+}
+
+// Drop color codes and other escape sequences, and any other control
+// characters, which would garble the UI
+#[cfg(target_os = "linux")]
+fn strip_control(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI sequences run up to a final byte from @ to ~
+            if chars.clone().next() == Some('[') {
+                chars.find(|c| ('@'..='~').contains(c) && *c != '[');
+            }
+        } else if !c.is_control() {
+            text.push(c);
+        }
+    }
+    text
+}
+
+// The process holding the read end of a pipe, going by the access mode in
+// each descriptor's fdinfo
+#[cfg(target_os = "linux")]
+fn pipe_reader(writer: u32, pipe: &str) -> Option<(u32, String)> {
+    std::fs::read_dir("/proc")
+        .ok()?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|&pid| pid != writer)
+        .find_map(|pid| {
+            let fds = std::fs::read_dir(format!("/proc/{}/fd", pid)).ok()?;
+            let reads = fds.filter_map(|entry| entry.ok()).any(|entry| {
+                std::fs::read_link(entry.path()).is_ok_and(|target| target.as_os_str() == pipe)
+                    && std::fs::read_to_string(format!(
+                        "/proc/{}/fdinfo/{}",
+                        pid,
+                        entry.file_name().to_string_lossy()
+                    ))
+                    .ok()
+                    .and_then(|info| {
+                        let flags = info.lines().find_map(|l| l.strip_prefix("flags:"))?;
+                        u32::from_str_radix(flags.trim(), 8).ok()
+                    })
+                    .is_some_and(|flags| flags & libc::O_ACCMODE as u32 == libc::O_RDONLY as u32)
+            });
+            if !reads {
+                return None;
+            }
+            let name = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+            Some((pid, name.trim().to_string()))
+        })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn output(_pid: u32) -> io::Result<Vec<OutputStream>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "output targets are only available on Linux",
+    ))
+}
+
+// The file a process was started from, and whether that file is still what's
+// on disk at its path
+pub struct Executable {
+    pub path: String,
+    pub state: BinaryState,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BinaryState {
+    Intact,
+    // Removed, with nothing at the path since
+    Deleted,
+    // Removed and another file put at the path, as upgrades do
+    Replaced,
+}
+
+// None when the kernel hides the link from us, as for other users' processes
+#[cfg(target_os = "linux")]
+pub fn executable(pid: u32) -> Option<Executable> {
+    let link = std::fs::read_link(format!("/proc/{}/exe", pid)).ok()?;
+    let link = link.to_string_lossy();
+    let Some(path) = link.strip_suffix(" (deleted)") else {
+        return Some(Executable {
+            path: link.to_string(),
+            state: BinaryState::Intact,
+        });
+    };
+    // The path is as the process sees it, so look through its root in case
+    // it's in a container
+    let state = if std::fs::metadata(format!("/proc/{}/root{}", pid, path)).is_ok() {
+        BinaryState::Replaced
+    } else {
+        BinaryState::Deleted
+    };
+    Some(Executable {
+        path: path.to_string(),
+        state,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn executable(_pid: u32) -> Option<Executable> {
+    None
+}
+
+// What a wait channel means in broad terms, going by the kernel's naming of
+// the functions processes commonly block in
+pub fn classify_wait_channel(wchan: &str) -> &'static str {
+    const KINDS: &[(&[&str], &str)] = &[
+        (&["futex"], "lock (futex)"),
+        (&["ep_poll", "poll", "select"], "poll/epoll"),
+        (
+            &["tcp", "udp", "inet", "sk_", "sock", "unix_stream"],
+            "network",
+        ),
+        (&["pipe"], "pipe"),
+        (&["tty", "n_tty"], "terminal"),
+        (
+            &[
+                "io_schedule",
+                "blk",
+                "bio",
+                "folio",
+                "page",
+                "ext4",
+                "xfs",
+                "btrfs",
+                "nfs",
+                "jbd2",
+            ],
+            "disk I/O",
+        ),
+        (&["do_wait", "wait4"], "child process"),
+        (&["nanosleep", "hrtimer", "schedule_timeout"], "timer"),
+        (&["sigsuspend", "do_sigtimedwait", "signal"], "signal"),
+        (
+            &["worker_thread", "kthread", "rescuer_thread", "smpboot"],
+            "kernel work",
+        ),
+    ];
+    KINDS
+        .iter()
+        .find(|(patterns, _)| patterns.iter().any(|p| wchan.contains(p)))
+        .map_or("other", |(_, kind)| kind)
+}
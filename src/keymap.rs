@@ -0,0 +1,619 @@
+use crate::app::SortKey;
+use clap::ValueEnum;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+
+// The keys of the main view and what they do. The help screen is generated
+// from this, and the config's `keys` table rebinds actions by name:
+//
+//   [keys]
+//   kill = "ctrl+k"
+//   quit = "ctrl+q esc"
+//
+// A key is a character or one of up, down, left, right, pgup, pgdn, home,
+// end, tab, backtab, enter, esc, backspace, space and f1-f12, optionally
+// prefixed with `ctrl+`; several keys are separated by spaces. A key bound
+// in the config is taken away from whatever action had it by default, and
+// plain characters that aren't bound to anything type into the filter.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Key {
+    pub code: KeyCode,
+    pub ctrl: bool,
+}
+
+impl Key {
+    fn new(code: KeyCode) -> Self {
+        Self { code, ctrl: false }
+    }
+
+    fn char(c: char) -> Self {
+        Self::new(KeyCode::Char(c))
+    }
+
+    fn ctrl(c: char) -> Self {
+        Self {
+            code: KeyCode::Char(c),
+            ctrl: true,
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (ctrl, name) = match text.get(..5) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("ctrl+") => (true, &text[5..]),
+            _ => (false, text),
+        };
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_lowercase().as_str() {
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pgup" => KeyCode::PageUp,
+                "pgdn" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "space" => KeyCode::Char(' '),
+                f => match f.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key {}", text)),
+                },
+            },
+        };
+        Ok(Self { code, ctrl })
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        Self {
+            code: event.code,
+            ctrl: event.modifiers.contains(KeyModifiers::CONTROL),
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Action {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    Left,
+    Right,
+    NextTab,
+    PreviousTab,
+    NextHost,
+    Tag,
+    Untag,
+    Pin,
+    Follow,
+    ToggleSort,
+    Sort(SortKey),
+    SortLeft,
+    SortRight,
+    Refresh,
+    Kill,
+    Signal,
+    Renice,
+    Reap,
+    Run,
+    Restart,
+    Trace,
+    Debug,
+    Profile,
+    Flamegraph,
+    CoreDump,
+    Export,
+    DetailPanel,
+    Columns,
+    Copy,
+    Tree,
+    Group,
+    NormalizeCpu,
+    Open,
+    Service,
+    Help,
+    Quit,
+    Backspace,
+    FilterRegex,
+    Search,
+    SearchNext,
+    SearchPrevious,
+    Command,
+}
+
+// Help sections in display order
+const SECTIONS: [&str; 4] = [
+    "NAVIGATION",
+    "TAGGING AND SORTING",
+    "PROCESS ACTIONS",
+    "FILTERING",
+];
+
+// Help entries for things that aren't rebindable keys, by section
+const NOTES: &[(usize, &str, &str)] = &[
+    (
+        0,
+        "Mouse",
+        "Click tabs, rows or headers; scroll with the wheel",
+    ),
+    (3, "Any char", "Type characters to filter processes by name"),
+    (
+        3,
+        "",
+        "Filter on one field with name=, user=, pid=, cmd=, port=, remote=...",
+    ),
+];
+
+impl Action {
+    // As written in the config's `keys` table
+    pub fn name(&self) -> String {
+        let name = match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::PageUp => "page-up",
+            Action::PageDown => "page-down",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::NextTab => "next-tab",
+            Action::PreviousTab => "previous-tab",
+            Action::NextHost => "next-host",
+            Action::Tag => "tag",
+            Action::Untag => "untag",
+            Action::Pin => "pin",
+            Action::Follow => "follow",
+            Action::ToggleSort => "toggle-sort",
+            Action::Sort(key) => {
+                let key = key.to_possible_value().map(|v| v.get_name().to_string());
+                return format!("sort-{}", key.unwrap_or_default());
+            }
+            Action::SortLeft => "sort-left",
+            Action::SortRight => "sort-right",
+            Action::Refresh => "refresh",
+            Action::Kill => "kill",
+            Action::Signal => "signal",
+            Action::Renice => "renice",
+            Action::Reap => "reap",
+            Action::Run => "run",
+            Action::Restart => "restart",
+            Action::Trace => "trace",
+            Action::Debug => "debug",
+            Action::Profile => "profile",
+            Action::Flamegraph => "flamegraph",
+            Action::CoreDump => "core-dump",
+            Action::Export => "export",
+            Action::DetailPanel => "detail-panel",
+            Action::Columns => "columns",
+            Action::Copy => "copy",
+            Action::Tree => "tree",
+            Action::NormalizeCpu => "normalize-cpu",
+            Action::Group => "group",
+            Action::Open => "open",
+            Action::Service => "service",
+            Action::Help => "help",
+            Action::Quit => "quit",
+            Action::Backspace => "backspace",
+            Action::FilterRegex => "filter-regex",
+            Action::Search => "search",
+            Action::SearchNext => "search-next",
+            Action::SearchPrevious => "search-previous",
+            Action::Command => "command",
+        };
+        name.to_string()
+    }
+
+    pub fn description(&self) -> String {
+        let description = match self {
+            Action::Up => "Move the selection up",
+            Action::Down => "Move the selection down",
+            Action::PageUp => "Move a page up; scrolls the panel in the Detailed tab",
+            Action::PageDown => "Move a page down; scrolls the panel in the Detailed tab",
+            Action::First => "Jump to the first process",
+            Action::Last => "Jump to the last process",
+            Action::Left => "Previous tab; Dashboard/Detailed: scrub charts back",
+            Action::Right => "Next tab; Dashboard/Detailed: scrub charts forward",
+            Action::NextTab => "Switch to the next tab",
+            Action::PreviousTab => "Switch to the previous tab",
+            Action::NextHost => "Switch to the next --connect host",
+            Action::Tag => "Tag the selected process for batch kill/renice",
+            Action::Untag => "Untag all processes",
+            Action::Pin => "Pin or unpin processes with the selected name",
+            Action::Follow => "Follow the selected process across refreshes",
+            Action::ToggleSort => "Toggle between ascending and descending sort",
+            Action::Sort(key) => return format!("Sort processes by {}", key.as_str()),
+            Action::SortLeft => "Sort by the next shown column to the left",
+            Action::SortRight => "Sort by the next shown column to the right",
+            Action::Refresh => "Force refresh all process information",
+            Action::Kill => "Kill the selected or tagged processes, after asking",
+            Action::Signal => "Send a signal to the selected or tagged processes",
+            Action::Renice => "Change the nice value of selected or tagged processes",
+            Action::Reap => "Zombies: signal or kill the parent that isn't reaping them",
+            Action::Run => "Run a command and follow the new process",
+            Action::Restart => "Stop the selected process and start it again, after asking",
+            Action::Trace => "Attach strace (dtruss on macOS) to the selected process",
+            Action::Debug => "Attach a debugger (config `debugger`) to the selected process",
+            Action::Profile => "Record the selected process with perf for some seconds",
+            Action::Flamegraph => "Sample the selected process and draw an SVG flamegraph",
+            Action::CoreDump => "Dump the selected process's core with gcore or SIGABRT",
+            Action::Export => "Export a JSON snapshot to the working directory",
+            Action::DetailPanel => "Switch panel in the Detailed tab",
+            Action::Columns => "Choose and reorder the processes table columns",
+            Action::Copy => "Copy the selected process's command line",
+            Action::Tree => "Toggle the process tree view",
+            Action::NormalizeCpu => "Show CPU% per core or normalized to all cores",
+            Action::Group => "Toggle grouping processes by name",
+            Action::Open => "Collapse or expand; Cgroups/Ports tab: show the processes",
+            Action::Service => "Detailed tab: restart/stop the systemd unit",
+            Action::Help => "Show or close this help",
+            Action::Quit => "Clear the search, scrubbing or filter, else exit",
+            Action::Backspace => "Delete the last character from the filter",
+            Action::FilterRegex => "Toggle regex matching for the filter",
+            Action::Search => "Search without filtering",
+            Action::SearchNext => "Jump to the next search match",
+            Action::SearchPrevious => "Jump to the previous search match",
+            Action::Command => "Command line: kill, renice, run, sort, filter, tab, quit",
+        };
+        description.to_string()
+    }
+
+    fn section(&self) -> usize {
+        match self {
+            Action::Up
+            | Action::Down
+            | Action::PageUp
+            | Action::PageDown
+            | Action::First
+            | Action::Last
+            | Action::Left
+            | Action::Right
+            | Action::NextTab
+            | Action::PreviousTab
+            | Action::NextHost => 0,
+            Action::Tag
+            | Action::Untag
+            | Action::Pin
+            | Action::Follow
+            | Action::ToggleSort
+            | Action::Sort(_)
+            | Action::SortLeft
+            | Action::SortRight => 1,
+            Action::Backspace
+            | Action::FilterRegex
+            | Action::Search
+            | Action::SearchNext
+            | Action::SearchPrevious
+            | Action::Command => 3,
+            _ => 2,
+        }
+    }
+
+    // Actions whose default keys are printable, like `/`. On such a key they
+    // only act while the filter is empty; once it has text the key is typed
+    // into it instead, as it may be part of the pattern.
+    pub fn yields_to_filter(self) -> bool {
+        matches!(
+            self,
+            Action::Tag
+                | Action::Search
+                | Action::SearchNext
+                | Action::SearchPrevious
+                | Action::Command
+                | Action::SortLeft
+                | Action::SortRight
+        )
+    }
+}
+
+pub struct Binding {
+    pub action: Action,
+    pub keys: Vec<Key>,
+}
+
+pub struct Keymap {
+    pub bindings: Vec<Binding>,
+}
+
+// A line of the help screen
+pub enum HelpLine {
+    Heading(&'static str),
+    Entry(String, String),
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let sort = [
+            SortKey::Pid,
+            SortKey::Name,
+            SortKey::Cpu,
+            SortKey::Memory,
+            SortKey::Status,
+            SortKey::User,
+            SortKey::StartTime,
+            SortKey::DiskRead,
+            SortKey::DiskWrite,
+        ];
+        let mut bindings = vec![
+            (Action::Up, vec![Key::new(KeyCode::Up)]),
+            (Action::Down, vec![Key::new(KeyCode::Down)]),
+            (Action::PageUp, vec![Key::new(KeyCode::PageUp)]),
+            (Action::PageDown, vec![Key::new(KeyCode::PageDown)]),
+            (Action::First, vec![Key::new(KeyCode::Home)]),
+            (Action::Last, vec![Key::new(KeyCode::End)]),
+            (Action::Left, vec![Key::new(KeyCode::Left)]),
+            (Action::Right, vec![Key::new(KeyCode::Right)]),
+            (Action::NextTab, vec![Key::new(KeyCode::Tab)]),
+            (Action::PreviousTab, vec![Key::new(KeyCode::BackTab)]),
+            (Action::NextHost, vec![Key::ctrl('w')]),
+            (Action::Tag, vec![Key::char(' ')]),
+            (Action::Untag, vec![Key::ctrl('u')]),
+            (Action::Pin, vec![Key::ctrl('p')]),
+            (Action::Follow, vec![Key::ctrl('f')]),
+            (Action::ToggleSort, vec![Key::ctrl('s')]),
+        ];
+        bindings.extend(
+            sort.iter()
+                .zip('1'..='9')
+                .map(|(&key, c)| (Action::Sort(key), vec![Key::ctrl(c)])),
+        );
+        bindings.extend([
+            (Action::SortLeft, vec![Key::char('<')]),
+            (Action::SortRight, vec![Key::char('>')]),
+            (Action::Refresh, vec![Key::ctrl('r')]),
+            (Action::Kill, vec![Key::ctrl('k')]),
+            (Action::Signal, vec![Key::new(KeyCode::F(9))]),
+            (Action::Renice, vec![Key::ctrl('n')]),
+            (Action::Reap, vec![Key::ctrl('z')]),
+            (Action::Run, vec![Key::ctrl('b')]),
+            (Action::Restart, vec![Key::ctrl('v')]),
+            (Action::Trace, vec![Key::new(KeyCode::F(8))]),
+            (Action::Debug, vec![Key::new(KeyCode::F(7))]),
+            (Action::Profile, vec![Key::new(KeyCode::F(6))]),
+            (Action::Flamegraph, vec![Key::new(KeyCode::F(5))]),
+            (Action::CoreDump, vec![Key::new(KeyCode::F(4))]),
+            (Action::Export, vec![Key::ctrl('e')]),
+            (Action::DetailPanel, vec![Key::ctrl('d')]),
+            (Action::Columns, vec![Key::ctrl('o')]),
+            (Action::Copy, vec![Key::ctrl('y')]),
+            (Action::Tree, vec![Key::ctrl('t')]),
+            (Action::Group, vec![Key::ctrl('g')]),
+            (Action::NormalizeCpu, vec![Key::ctrl('l')]),
+            (Action::Open, vec![Key::new(KeyCode::Enter)]),
+            (Action::Service, vec![Key::ctrl('a')]),
+            (Action::Help, vec![Key::ctrl('h')]),
+            (
+                Action::Quit,
+                vec![Key::ctrl('q'), Key::new(KeyCode::Esc), Key::ctrl('c')],
+            ),
+            (Action::Backspace, vec![Key::new(KeyCode::Backspace)]),
+            (Action::FilterRegex, vec![Key::ctrl('x')]),
+            (Action::Search, vec![Key::char('/')]),
+            (Action::SearchNext, vec![Key::char('n')]),
+            (Action::SearchPrevious, vec![Key::char('N')]),
+            (Action::Command, vec![Key::char(':')]),
+        ]);
+
+        Self {
+            bindings: bindings
+                .into_iter()
+                .map(|(action, keys)| Binding { action, keys })
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    // The default bindings with the config's changes applied. Unknown
+    // actions and keys are reported and leave the defaults alone.
+    pub fn new(overrides: &HashMap<String, String>) -> (Self, Vec<String>) {
+        let mut keymap = Self::default();
+        let mut errors = Vec::new();
+
+        let mut overrides: Vec<_> = overrides.iter().collect();
+        overrides.sort();
+        for (name, keys) in overrides {
+            let Some(index) = keymap
+                .bindings
+                .iter()
+                .position(|b| b.action.name() == *name)
+            else {
+                errors.push(format!("unknown action {}", name));
+                continue;
+            };
+            let keys: Result<Vec<Key>, String> = keys.split_whitespace().map(Key::parse).collect();
+            match keys {
+                Ok(keys) => {
+                    for binding in &mut keymap.bindings {
+                        binding.keys.retain(|key| !keys.contains(key));
+                    }
+                    keymap.bindings[index].keys = keys;
+                }
+                Err(e) => errors.push(format!("{}: {}", name, e)),
+            }
+        }
+        (keymap, errors)
+    }
+
+    pub fn action(&self, event: KeyEvent) -> Option<Action> {
+        let key = Key::from(event);
+        self.bindings
+            .iter()
+            .find(|binding| binding.keys.contains(&key))
+            .map(|binding| binding.action)
+    }
+
+    // How an action's keys read in the help, e.g. "Ctrl+q/Esc"
+    pub fn keys(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .find(|binding| binding.action == action)
+            .map(|binding| {
+                let keys: Vec<String> = binding.keys.iter().map(|k| k.to_string()).collect();
+                keys.join("/")
+            })
+            .unwrap_or_default()
+    }
+
+    // The first key bound to an action, for hints
+    pub fn key(&self, action: Action) -> Option<Key> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.action == action)
+            .and_then(|binding| binding.keys.first().copied())
+    }
+
+    // The help screen, limited to entries whose keys or text contain `query`
+    pub fn help(&self, query: &str) -> Vec<HelpLine> {
+        let query = query.to_lowercase();
+        let mut lines = Vec::new();
+        for (section, heading) in SECTIONS.iter().enumerate() {
+            let bindings = self
+                .bindings
+                .iter()
+                .filter(|b| b.action.section() == section && !b.keys.is_empty())
+                .map(|b| (self.keys(b.action), b.action.description()));
+            let notes = NOTES
+                .iter()
+                .filter(|(s, _, _)| *s == section)
+                .map(|(_, keys, text)| (keys.to_string(), text.to_string()));
+            let entries: Vec<HelpLine> = bindings
+                .chain(notes)
+                .filter(|(keys, text)| {
+                    query.is_empty()
+                        || keys.to_lowercase().contains(&query)
+                        || text.to_lowercase().contains(&query)
+                })
+                .map(|(keys, text)| HelpLine::Entry(keys, text))
+                .collect();
+            if !entries.is_empty() {
+                lines.push(HelpLine::Heading(heading));
+                lines.extend(entries);
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn keys_parse_by_character_or_name() {
+        assert_eq!(Key::parse("k"), Ok(Key::char('k')));
+        assert_eq!(Key::parse("ctrl+k"), Ok(Key::ctrl('k')));
+        assert_eq!(Key::parse("CTRL+k"), Ok(Key::ctrl('k')));
+        assert_eq!(Key::parse("ctrl++"), Ok(Key::ctrl('+')));
+        assert_eq!(Key::parse("PgDn"), Ok(Key::new(KeyCode::PageDown)));
+        assert_eq!(Key::parse("space"), Ok(Key::char(' ')));
+        assert_eq!(Key::parse("f12"), Ok(Key::new(KeyCode::F(12))));
+        // A single character is taken as itself, even if it's a name's initial
+        assert_eq!(Key::parse("f"), Ok(Key::char('f')));
+
+        for unknown in ["f0", "f13", "ctrl+", "", "pageup", "ctrl+esc+x"] {
+            assert!(Key::parse(unknown).is_err(), "{:?}", unknown);
+        }
+    }
+
+    #[test]
+    fn keys_read_back_as_written_in_the_help() {
+        assert_eq!(Key::ctrl('q').to_string(), "Ctrl+q");
+        assert_eq!(Key::char(' ').to_string(), "Space");
+        assert_eq!(Key::new(KeyCode::BackTab).to_string(), "Shift+Tab");
+        assert_eq!(Key::new(KeyCode::F(9)).to_string(), "F9");
+        assert_eq!(Keymap::default().keys(Action::Quit), "Ctrl+q/Esc/Ctrl+c");
+    }
+
+    #[test]
+    fn config_keys_move_from_their_default_action() {
+        let overrides = HashMap::from([
+            ("kill".to_string(), "ctrl+q  k".to_string()),
+            ("sort-memory".to_string(), "m".to_string()),
+        ]);
+        let (keymap, errors) = Keymap::new(&overrides);
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let ctrl_q = event(KeyCode::Char('q'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.action(ctrl_q), Some(Action::Kill));
+        assert_eq!(keymap.keys(Action::Quit), "Esc/Ctrl+c");
+        assert_eq!(
+            keymap.action(event(KeyCode::Char('m'), KeyModifiers::NONE)),
+            Some(Action::Sort(SortKey::Memory))
+        );
+        // Ctrl+k is no longer bound, and plain characters stay unbound
+        assert_eq!(
+            keymap.action(event(KeyCode::Char('k'), KeyModifiers::CONTROL)),
+            None
+        );
+        assert_eq!(
+            keymap.action(event(KeyCode::Char('x'), KeyModifiers::NONE)),
+            None
+        );
+    }
+
+    #[test]
+    fn bad_overrides_are_reported_and_leave_the_defaults() {
+        let overrides = HashMap::from([
+            ("explode".to_string(), "x".to_string()),
+            ("quit".to_string(), "esc hyper+q".to_string()),
+        ]);
+        let (keymap, errors) = Keymap::new(&overrides);
+        assert_eq!(
+            errors,
+            vec![
+                "unknown action explode".to_string(),
+                "quit: unknown key hyper+q".to_string()
+            ]
+        );
+        assert_eq!(keymap.keys(Action::Quit), "Ctrl+q/Esc/Ctrl+c");
+    }
+
+    #[test]
+    fn default_bindings_are_unambiguous() {
+        let keymap = Keymap::default();
+        let keys: Vec<Key> = keymap
+            .bindings
+            .iter()
+            .flat_map(|b| b.keys.iter().copied())
+            .collect();
+        for (i, key) in keys.iter().enumerate() {
+            assert!(!keys[i + 1..].contains(key), "{} is bound twice", key);
+        }
+    }
+}
@@ -1,132 +1,538 @@
+mod agent;
+mod alerts;
+mod api;
 mod app;
-mod processes;
+mod batch;
+mod cgroups;
+mod cli;
+mod clipboard;
+mod command;
+mod computed;
+mod config;
+mod export;
+mod flamegraph;
+mod history;
+mod http;
+mod inspect;
+mod keymap;
+mod plugins;
+mod prometheus;
+mod resolve;
+mod scripting;
 mod ui;
+mod units;
 
-use app::{App, SortKey};
+use agent::Host;
+use app::{App, DebugStats, InputMode, ViewMode};
+use clap::{CommandFactory, Parser};
+use cli::Args;
+use computed::Evaluator;
+use config::Config;
 use crossterm::{
+    cursor,
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use processes::{ProcessMonitor, ProcessUpdate};
+use keymap::{Action, Keymap};
+use psr_core::{control, ChannelSource, DemoSource, ProcessMonitor, ProcessUpdate};
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
-use std::time::Duration;
-use tokio::sync::mpsc;
+use scripting::Scripts;
+use std::io::{self, Write};
+use std::panic;
+use std::process::ExitStatus;
+use std::time::{Duration, Instant};
+
+// Fixed so every --demo run shows the same simulation
+const DEMO_SEED: u64 = 42;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Terminal initialization
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let args = Args::parse();
+
+    // Create app with empty initial state, adjusted by the command-line options
+    let mut app = App::new();
+    app.config = Config::load()?;
+    app.sort_key = args.sort;
+    app.sort_ascending = args.ascending;
+    app.filter_regex = args.regex;
+    app.refresh_interval = Duration::from_millis(args.refresh_ms);
+    app.notify = args.notify;
+    app.normalize_cpu = app.config.normalize_cpu;
+    if args.debug {
+        app.debug = Some(DebugStats::default());
+    }
+    let (scripts, errors) = Scripts::load();
+    if !scripts.is_empty() {
+        app.scripts = Some(scripts);
+    }
+    if !errors.is_empty() {
+        app.set_error(format!("Script errors: {}", errors.join("; ")));
+    }
+    if !app.config.computed.is_empty() {
+        let (computed, errors) = Evaluator::new(&app.config.computed);
+        app.computed = Some(computed);
+        if !errors.is_empty() {
+            app.set_error(format!("Computed column errors: {}", errors.join("; ")));
+        }
+    }
+    let (keymap, errors) = Keymap::new(&app.config.keys);
+    app.keymap = keymap;
+    if !errors.is_empty() {
+        app.set_error(format!("Key binding errors: {}", errors.join("; ")));
+    }
+    let (plugins, errors) = plugins::load();
+    app.tabs
+        .extend(plugins.iter().filter_map(|p| p.tab.clone()));
+    app.plugins = plugins;
+    if !errors.is_empty() {
+        app.set_error(format!("Plugin errors: {}", errors.join("; ")));
+    }
+    if let Some(filter) = &args.filter {
+        app.filter = filter.clone();
+    }
+    if args.regex {
+        if let Err(e) = psr_core::Filter::new(&app.filter, true) {
+            Args::command()
+                .error(clap::error::ErrorKind::InvalidValue, e.to_string())
+                .exit();
+        }
+    }
+    if !app.select_tab(&args.tab) {
+        Args::command()
+            .error(
+                clap::error::ErrorKind::InvalidValue,
+                format!("unknown tab '{}'", args.tab),
+            )
+            .exit();
+    }
 
-    // Create communication channels
-    let (tx, mut rx) = mpsc::channel(100);
+    if let Some(path) = &args.history {
+        let retention = Duration::from_secs(args.history_hours * 3600);
+        let store = history::HistoryStore::open(path, retention)
+            .map_err(|e| format!("cannot open history database {}: {}", path.display(), e))?;
+        app.history = Some(store);
+    }
 
-    // Create process monitor and start it in the background
-    let (process_monitor, refresh_sender) = ProcessMonitor::new(tx.clone());
-    tokio::spawn(async move {
-        process_monitor.start_monitoring().await;
-    });
+    // Bind the servers up front so a busy address fails before the TUI starts
+    let api = match &args.api {
+        Some(addr) => Some(
+            api::ApiServer::start(addr)
+                .await
+                .map_err(|e| format!("cannot serve the API on {}: {}", addr, e))?,
+        ),
+        None => None,
+    };
+    let exporter = match &args.prometheus {
+        Some(addr) => Some(
+            prometheus::Exporter::start(addr, args.prometheus_filter.clone())
+                .await
+                .map_err(|e| format!("cannot serve metrics on {}: {}", addr, e))?,
+        ),
+        None => None,
+    };
+
+    if args.batch || args.json {
+        return batch::run(app, &args, api.as_ref(), exporter.as_ref()).await;
+    }
+
+    if let Some(addr) = &args.agent {
+        return agent::run(addr, &args).await;
+    }
+
+    // Hosts to show: this machine, or the agents given with --connect
+    let mut hosts = Vec::new();
+    if args.demo {
+        let source = DemoSource::new(Duration::from_millis(args.refresh_ms), DEMO_SEED);
+        hosts.push(Host::new(Some("demo".to_string()), Box::new(source)));
+        app.switch_host(hosts[0].name.clone());
+    } else if args.connect.is_empty() {
+        // Start the process monitor before touching the terminal so setup errors print normally
+        let source = ProcessMonitor::spawn(
+            Duration::from_millis(args.refresh_ms),
+            args.log_csv.as_deref(),
+            args.pss,
+            args.kernel_stats,
+        )?;
+        hosts.push(Host::new(None, Box::new(source)));
+    } else {
+        for addr in &args.connect {
+            let source = ChannelSource::new(agent::connect(addr.clone()), None);
+            hosts.push(Host::new(Some(addr.clone()), Box::new(source)));
+        }
+        app.switch_host(hosts[0].name.clone());
+    }
+    let mut active_host = 0;
+
+    // Terminal initialization; the guard puts it back however the loop ends
+    let _guard = TerminalGuard::new()?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = Terminal::new(backend)?;
 
-    // Create app with empty initial state
-    let mut app = App::new();
-    app.refresh_sender = Some(refresh_sender);
     // Display "Loading..." message
-    terminal.draw(|f| ui::draw_loading_screen(f))?;
+    terminal.draw(ui::draw_loading_screen)?;
 
     // Main loop
     loop {
-        // Process any updates from the background task
-        while let Ok(update) = rx.try_recv() {
-            match update {
-                ProcessUpdate::ProcessList(processes) => {
-                    app.processes = processes;
-                    app.update_selection();
-                    app.sort_processes();
-                }
-                ProcessUpdate::SystemInfo(cpu, used, total) => {
-                    app.system_resources.update(cpu, used, total);
-                }
-                ProcessUpdate::LoadingStatus(status) => {
-                    app.loading_status = status;
+        let pass = Instant::now();
+
+        // Process any updates from the background tasks, keeping the latest
+        // of every host for when it's switched to
+        let mut backlog = 0;
+        for (index, host) in hosts.iter_mut().enumerate() {
+            while let Some(update) = host.source.try_next() {
+                backlog += 1;
+                if index == active_host {
+                    apply_update(&mut app, update, api.as_ref(), exporter.as_ref());
+                } else {
+                    host.remember(update);
                 }
             }
         }
 
         // Draw UI if needed
+        app.expire_status();
+        app.expire_flashes();
+        app.reap_children();
         if app.should_refresh_ui() {
+            let drawing = Instant::now();
             terminal.draw(|f| ui::draw_ui(f, &mut app))?;
+            if let Some(debug) = &mut app.debug {
+                debug.frame_drawn(drawing.elapsed());
+            }
             app.refresh_ui();
         }
+        if let Some(debug) = &mut app.debug {
+            debug.pass_done(pass.elapsed(), backlog);
+        }
 
         // Poll for events with a short timeout to keep things responsive
         if event::poll(Duration::from_millis(16))? {
-            if let Event::Key(key) = event::read()? {
+            let event = event::read()?;
+            // Any input, including a resize, may change what's on screen
+            app.invalidate();
+            if let Event::Mouse(mouse) = event {
+                app.handle_mouse(mouse);
+                app.refresh_details();
+            }
+            if let Event::Key(key) = event {
                 // Check if Ctrl is being pressed
                 let ctrl_pressed = key.modifiers.contains(KeyModifiers::CONTROL);
 
-                match (key.code, ctrl_pressed) {
-                    // Ctrl+key combinations for commands
-                    (KeyCode::Char('q'), true) | (KeyCode::Esc, _) | (KeyCode::Char('c'), true) => {
-                        if !app.filter.is_empty() {
+                // Any key dismisses the message from the last command
+                app.status_message = None;
+
+                // An open dialog or prompt takes all input until it is answered or cancelled
+                if app.dialog.is_some() {
+                    app.dialog_key(key.code);
+                    app.refresh_details();
+                    continue;
+                }
+                if app.input_mode == InputMode::Columns {
+                    let shift_pressed = key.modifiers.contains(KeyModifiers::SHIFT);
+                    match (key.code, shift_pressed) {
+                        (KeyCode::Esc, _) => app.cancel_input(),
+                        (KeyCode::Enter, _) => app.submit_input(),
+                        (KeyCode::Char(' '), _) => app.toggle_column(),
+                        (KeyCode::Up, true) | (KeyCode::Char('-'), _) => app.move_column(-1),
+                        (KeyCode::Down, true) | (KeyCode::Char('+'), _) => app.move_column(1),
+                        (KeyCode::Up, false) => app.move_column_cursor(-1),
+                        (KeyCode::Down, false) => app.move_column_cursor(1),
+                        _ => {}
+                    }
+                    continue;
+                }
+                if app.input_mode == InputMode::Service {
+                    match key.code {
+                        KeyCode::Char('r') => app.service_action("restart"),
+                        KeyCode::Char('s') => app.service_action("stop"),
+                        KeyCode::Esc => app.cancel_input(),
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                if app.input_mode != InputMode::Normal {
+                    match key.code {
+                        KeyCode::Esc => app.cancel_input(),
+                        KeyCode::Enter => app.submit_input(),
+                        KeyCode::Backspace => {
+                            app.input.pop();
+                        }
+                        KeyCode::Char(c) => app.input.push(c),
+                        _ => {}
+                    }
+                    app.update_search();
+                    continue;
+                }
+
+                // Keys of the help screen are fixed so it can't be locked out by
+                // the config
+                let action = app.keymap.action(key);
+                if app.help.is_some() {
+                    app.help_key(key, action);
+                    continue;
+                }
+
+                // A printable key typed after some filter text is more of it
+                let action = match (action, key.code) {
+                    (Some(action), KeyCode::Char(_))
+                        if action.yields_to_filter() && !ctrl_pressed && !app.filter.is_empty() =>
+                    {
+                        None
+                    }
+                    _ => action,
+                };
+
+                match action {
+                    Some(Action::Quit) => {
+                        if app.search.is_some() {
+                            app.clear_search();
+                        } else if app.scrub_time.is_some() {
+                            app.scrub_time = None; // Back to live charts
+                        } else if app.cgroup_filter.is_some() {
+                            app.clear_cgroup_filter();
+                        } else if !app.filter.is_empty() {
                             app.clear_filter();
-                        } else if app.show_help {
-                            app.toggle_help(); // Close help tab first
                         } else {
-                            break; // Only exit if filter is empty and help is not shown
+                            break; // Only exit if there is nothing left to clear
+                        }
+                    }
+                    Some(Action::Refresh) => {
+                        app.request_refresh();
+                        app.set_status("Refresh requested");
+                    }
+                    Some(Action::Kill) => app.kill_selected_process(),
+                    Some(Action::Signal) => app.start_signal(),
+                    Some(Action::Help) => app.toggle_help(),
+                    Some(Action::Export) => match export::write_snapshot(&app) {
+                        Ok(path) => app.set_status(format!("Saved {}", path.display())),
+                        Err(e) => app.set_error(format!("Export failed: {}", e)),
+                    },
+                    Some(Action::Renice) => app.start_renice(),
+                    Some(Action::Reap) => app.start_reap(),
+                    Some(Action::Run) => app.start_run(),
+                    Some(Action::Restart) => app.start_restart(),
+                    Some(Action::Trace) => {
+                        if let Some(pid) = app.attach_target() {
+                            let result = suspended(&mut terminal, || {
+                                println!("Tracing PID {} - Ctrl+C to stop", pid);
+                                control::trace(pid)
+                            })?;
+                            app.attach_done(pid, "Tracer", result);
+                        }
+                    }
+                    Some(Action::Debug) => {
+                        if let Some(pid) = app.attach_target() {
+                            let debugger = app.config.debugger().to_string();
+                            let result = suspended(&mut terminal, || {
+                                println!("Debugging PID {} with `{}`", pid, debugger);
+                                control::debug(pid, &debugger)
+                            })?;
+                            app.attach_done(pid, "Debugger", result);
                         }
                     }
-                    (KeyCode::Char('r'), true) => {
-                        // Request an immediate refresh
-                        if let Some(tx) = &app.refresh_sender {
-                            let _ = tx.try_send(());
+                    Some(Action::Profile) => app.start_profile(false),
+                    Some(Action::Flamegraph) => app.start_profile(true),
+                    Some(Action::CoreDump) => app.start_core_dump(),
+                    Some(Action::Untag) => app.clear_tags(),
+                    Some(Action::NextHost) if hosts.len() > 1 => {
+                        hosts[active_host].keep(app.take_snapshot());
+                        active_host = (active_host + 1) % hosts.len();
+                        let host = &mut hosts[active_host];
+                        app.switch_host(host.name.clone());
+                        for update in host.replay() {
+                            apply_update(&mut app, update, api.as_ref(), exporter.as_ref());
                         }
                     }
-                    (KeyCode::Char('k'), true) => app.kill_selected_process(),
-                    (KeyCode::Char('h'), true) => app.toggle_help(),
+                    Some(Action::Pin) => app.toggle_pin(),
+                    Some(Action::Follow) => app.toggle_follow(),
+                    Some(Action::FilterRegex) => app.toggle_filter_regex(),
+                    Some(Action::DetailPanel) => app.next_detail_panel(),
+                    Some(Action::Columns) => app.start_column_picker(),
+                    Some(Action::Copy) => app.copy_command_line(),
+                    Some(Action::Tree) => app.toggle_view_mode(ViewMode::Tree),
+                    Some(Action::NormalizeCpu) => app.toggle_normalize_cpu(),
+                    Some(Action::Group) => app.toggle_view_mode(ViewMode::Grouped),
+                    Some(Action::Service) if app.on_tab("Detailed") => app.start_service_action(),
+                    Some(Action::Open) if app.on_tab("Cgroups") => app.open_cgroup(),
+                    Some(Action::Open) if app.on_tab("Ports") => app.open_port(),
+                    Some(Action::Open) => app.toggle_collapse(),
 
                     // Navigation and UI controls
-                    (KeyCode::Up, _) => app.previous(),
-                    (KeyCode::Down, _) => app.next(),
-                    (KeyCode::Left, _) => app.previous_tab(),
-                    (KeyCode::Right, _) => app.next_tab(),
-                    (KeyCode::Tab, _) => app.next_tab(),
-                    (KeyCode::BackTab, _) => app.previous_tab(), // Shift+Tab
+                    Some(Action::Up) if app.on_tab("Ports") => app.move_port_selection(-1),
+                    Some(Action::Down) if app.on_tab("Ports") => app.move_port_selection(1),
+                    Some(Action::PageUp) if app.on_tab("Ports") => {
+                        app.move_port_selection(-(app.page_size as isize))
+                    }
+                    Some(Action::PageDown) if app.on_tab("Ports") => {
+                        app.move_port_selection(app.page_size as isize)
+                    }
+                    Some(Action::Up) if app.on_tab("Events") => app.move_event_selection(-1),
+                    Some(Action::Down) if app.on_tab("Events") => app.move_event_selection(1),
+                    Some(Action::PageUp) if app.on_tab("Events") => {
+                        app.move_event_selection(-(app.page_size as isize))
+                    }
+                    Some(Action::PageDown) if app.on_tab("Events") => {
+                        app.move_event_selection(app.page_size as isize)
+                    }
+                    Some(Action::Up) if app.on_tab("Cgroups") => app.move_cgroup_selection(-1),
+                    Some(Action::Down) if app.on_tab("Cgroups") => app.move_cgroup_selection(1),
+                    Some(Action::PageUp) if app.on_tab("Cgroups") => {
+                        app.move_cgroup_selection(-(app.page_size as isize))
+                    }
+                    Some(Action::PageDown) if app.on_tab("Cgroups") => {
+                        app.move_cgroup_selection(app.page_size as isize)
+                    }
+                    Some(Action::Up) => app.previous(),
+                    Some(Action::Down) => app.next(),
+                    Some(Action::PageUp) if app.on_tab("Detailed") => app.scroll_detail(-10),
+                    Some(Action::PageDown) if app.on_tab("Detailed") => app.scroll_detail(10),
+                    Some(Action::PageUp) => app.page_up(),
+                    Some(Action::PageDown) => app.page_down(),
+                    Some(Action::First) => app.select_first(),
+                    Some(Action::Last) => app.select_last(),
+                    // The Dashboard and Detailed charts scrub through history,
+                    // further with Shift; Tab still switches tabs there
+                    Some(Action::Left | Action::Right) if app.can_scrub() => {
+                        let step = if key.modifiers.contains(KeyModifiers::SHIFT) {
+                            10
+                        } else {
+                            1
+                        };
+                        app.scrub(if action == Some(Action::Left) {
+                            -step
+                        } else {
+                            step
+                        });
+                    }
+                    Some(Action::Left | Action::PreviousTab) => app.previous_tab(),
+                    Some(Action::Right | Action::NextTab) => app.next_tab(),
+
+                    // Search jumps between matches without filtering rows
+                    Some(Action::Search) => app.start_search(),
+                    Some(Action::Command) => app.start_command(),
+                    Some(Action::SearchNext) if app.search.is_some() => app.search_next(true),
+                    Some(Action::SearchPrevious) if app.search.is_some() => app.search_next(false),
+
+                    // Tagging for batch actions
+                    Some(Action::Tag) => app.toggle_tag(),
 
                     // Sorting controls
-                    (KeyCode::Char(' '), _) => app.toggle_sort(),
-                    (KeyCode::Char('1'), true) => app.set_sort_key(SortKey::Pid),
-                    (KeyCode::Char('2'), true) => app.set_sort_key(SortKey::Name),
-                    (KeyCode::Char('3'), true) => app.set_sort_key(SortKey::Cpu),
-                    (KeyCode::Char('4'), true) => app.set_sort_key(SortKey::Memory),
-                    (KeyCode::Char('5'), true) => app.set_sort_key(SortKey::Status),
-                    (KeyCode::Char('6'), true) => app.set_sort_key(SortKey::User),
-                    (KeyCode::Char('7'), true) => app.set_sort_key(SortKey::StartTime),
+                    Some(Action::ToggleSort) => app.toggle_sort(),
+                    Some(Action::Sort(key)) => app.set_sort_key(key),
+                    Some(Action::SortLeft) => app.move_sort_column(-1),
+                    Some(Action::SortRight) => app.move_sort_column(1),
 
                     // Filter controls
-                    (KeyCode::Backspace, _) => app.backspace_filter(),
+                    Some(Action::Backspace) => app.backspace_filter(),
 
                     // Regular character typing for filter (when Ctrl is not pressed)
-                    (KeyCode::Char(c), false) => app.add_to_filter(c),
-
-                    _ => {}
+                    _ => {
+                        if let (KeyCode::Char(c), false) = (key.code, ctrl_pressed) {
+                            app.add_to_filter(c);
+                        }
+                    }
                 }
+
+                // Selection or tab may have changed, so reload the detail panel
+                app.refresh_details();
+                app.refresh_cgroups();
+                app.refresh_ports();
+                app.refresh_plugin_tab();
+                app.refresh_system_history();
             }
         }
+
+        if app.quit {
+            break;
+        }
+        if std::mem::take(&mut app.refresh_requested) {
+            hosts[active_host].source.request_refresh();
+        }
     }
 
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
+    app.stop_children();
+    Ok(())
+}
+
+// Raw mode and the alternate screen for as long as it lives. A panic hook
+// restores the terminal before the message is printed, so it shows up on the
+// normal screen instead of being wiped along with the UI. The UI can't carry
+// on in a restored terminal, so a panic on a background thread exits too.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> io::Result<Self> {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            default_hook(info);
+            if std::thread::current().name() != Some("main") {
+                std::process::exit(101);
+            }
+        }));
+
+        let guard = Self;
+        setup_terminal()?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+// Hand the terminal to a command run in the foreground until it exits, then
+// take it back once a key is pressed, leaving the command's last lines up to
+// read until then
+fn suspended(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    run: impl FnOnce() -> io::Result<ExitStatus>,
+) -> io::Result<io::Result<ExitStatus>> {
+    restore_terminal();
+    let result = run();
+    if result.is_ok() {
+        print!("\nPress any key to return to psr");
+        io::stdout().flush()?;
+        enable_raw_mode()?;
+        while !matches!(event::read()?, Event::Key(_)) {}
+    }
+    setup_terminal()?;
+    terminal.clear()?;
+    Ok(result)
+}
+
+fn setup_terminal() -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)
+}
+
+// Safe to call more than once, and when the terminal was never set up
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
         LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+        DisableMouseCapture,
+        cursor::Show
+    );
+}
 
-    Ok(())
+fn apply_update(
+    app: &mut App,
+    update: ProcessUpdate,
+    api: Option<&api::ApiServer>,
+    exporter: Option<&prometheus::Exporter>,
+) {
+    if app.apply_update(update) {
+        if let Some(api) = api {
+            api.update(app);
+        }
+        if let Some(exporter) = exporter {
+            exporter.update(app);
+        }
+    }
 }
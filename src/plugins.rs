@@ -0,0 +1,292 @@
+use psr_core::ProcessInfo;
+use serde::Deserialize;
+use serde_json::json;
+use std::io::Read;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use wasmtime::{Caller, Engine, Instance, Linker, Memory, Module, Store, Trap, TypedFunc};
+
+use crate::config::Config;
+
+// WebAssembly plugins, loaded from the `plugins` directory next to the config
+// file (*.wasm, or *.wat for hand-written ones). Strings cross the boundary
+// as UTF-8 in the plugin's memory, passed around as an i64 holding the
+// pointer in the high 32 bits and the length in the low 32.
+//
+// A plugin exports `memory` and:
+//
+//   psr_alloc(len: i32) -> i32   room for the host to write input into
+//   psr_reset()                  free everything psr_alloc and the plugin's
+//                                results took since the last reset
+//   psr_info() -> i64            JSON: {"name", "tab"?, "column"?}
+//   psr_collect() -> i64         JSON for its tab: {"headers": [..], "rows": [[..]]}
+//   psr_column(ptr, len) -> i64  cell text for the process JSON at ptr
+//
+// psr_collect is only needed with a tab and psr_column with a column. The
+// psr_column calls of one refresh share a single instruction budget, so a
+// slow plugin leaves the cells it didn't get to blank. psr
+// calls psr_reset once it has read the result of each call, so a bump
+// allocator that starts over there never runs out of memory. To gather data,
+// plugins can import env.psr_read_file(ptr, len) -> i64, which returns the
+// contents (up to 4 MiB) of the regular file under /proc or /sys whose path
+// is at ptr, or 0 when it can't be read.
+pub struct Plugin {
+    pub name: String,
+    pub tab: Option<String>,
+    pub column: Option<String>,
+    store: Store<()>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    reset: TypedFunc<(), ()>,
+    collect: Option<TypedFunc<(), i64>>,
+    cell: Option<TypedFunc<(i32, i32), i64>>,
+}
+
+#[derive(Deserialize)]
+struct Info {
+    name: String,
+    tab: Option<String>,
+    column: Option<String>,
+}
+
+// What a plugin's tab shows
+#[derive(Deserialize, Default)]
+pub struct PluginTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+// Instructions a plugin may run per call, so a stuck plugin can't hang psr;
+// about a few tens of milliseconds' work
+const FUEL: u64 = 20_000_000;
+
+// Instructions a plugin's psr_column calls share on each refresh, however
+// many processes there are
+pub const CELLS_FUEL: u64 = 20_000_000;
+
+pub fn dir() -> Option<PathBuf> {
+    Some(Config::path()?.parent()?.join("plugins"))
+}
+
+// Every plugin in the plugins directory, in name order, along with errors
+// for the ones that failed to load
+pub fn load() -> (Vec<Plugin>, Vec<String>) {
+    let mut plugins = Vec::new();
+    let mut errors = Vec::new();
+    let Some(entries) = dir().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return (plugins, errors);
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "wasm" || ext == "wat")
+        })
+        .collect();
+    if paths.is_empty() {
+        return (plugins, errors);
+    }
+    paths.sort();
+
+    let mut config = wasmtime::Config::new();
+    config.consume_fuel(true);
+    let engine = match Engine::new(&config) {
+        Ok(engine) => engine,
+        Err(e) => {
+            errors.push(format!("plugins unavailable: {}", e));
+            return (plugins, errors);
+        }
+    };
+    for path in paths {
+        let file = path
+            .file_name()
+            .map_or(String::new(), |n| n.to_string_lossy().into_owned());
+        match Plugin::load(&engine, &path) {
+            Ok(plugin) => plugins.push(plugin),
+            Err(e) => errors.push(format!("{}: {}", file, e)),
+        }
+    }
+    (plugins, errors)
+}
+
+impl Plugin {
+    fn load(engine: &Engine, path: &std::path::Path) -> wasmtime::Result<Self> {
+        let module = Module::from_file(engine, path)?;
+        let mut linker = Linker::new(engine);
+        linker.func_wrap("env", "psr_read_file", read_file)?;
+        let mut store = Store::new(engine, ());
+        store.set_fuel(FUEL)?;
+        let instance = linker.instantiate(&mut store, &module)?;
+
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| wasmtime::Error::msg("no exported memory"))?;
+        let alloc = instance.get_typed_func(&mut store, "psr_alloc")?;
+        let reset = instance.get_typed_func(&mut store, "psr_reset")?;
+        let info = instance.get_typed_func::<(), i64>(&mut store, "psr_info")?;
+        let collect = optional_func(&instance, &mut store, "psr_collect");
+        let cell = optional_func(&instance, &mut store, "psr_column");
+
+        let packed = info.call(&mut store, ())?;
+        let info = read_string(&memory, &store, packed)?;
+        reset.call(&mut store, ())?;
+        let info: Info = serde_json::from_str(&info)?;
+        if info.tab.is_some() && collect.is_none() {
+            return Err(wasmtime::Error::msg("has a tab but no psr_collect"));
+        }
+        if info.column.is_some() && cell.is_none() {
+            return Err(wasmtime::Error::msg("has a column but no psr_column"));
+        }
+
+        Ok(Self {
+            name: info.name,
+            tab: info.tab,
+            column: info.column,
+            store,
+            memory,
+            alloc,
+            reset,
+            collect,
+            cell,
+        })
+    }
+
+    pub fn collect(&mut self) -> wasmtime::Result<PluginTable> {
+        let Some(collect) = &self.collect else {
+            return Ok(PluginTable::default());
+        };
+        self.store.set_fuel(FUEL)?;
+        let packed = collect.call(&mut self.store, ())?;
+        let table = read_string(&self.memory, &self.store, packed)?;
+        self.reset.call(&mut self.store, ())?;
+        Ok(serde_json::from_str(&table)?)
+    }
+
+    // Start a refresh's psr_column calls, which share `fuel` between them
+    pub fn start_cells(&mut self, fuel: u64) -> wasmtime::Result<()> {
+        self.store.set_fuel(fuel)?;
+        // A call that ran out of fuel last time never got to reset
+        self.reset.call(&mut self.store, ())
+    }
+
+    // Call after start_cells; fails with out_of_fuel once the fuel is spent
+    pub fn cell(&mut self, process: &ProcessInfo) -> wasmtime::Result<String> {
+        let Some(cell) = &self.cell else {
+            return Ok(String::new());
+        };
+        let input = json!({
+            "pid": process.pid,
+            "name": process.name,
+            "user": process.user,
+            "cpu": process.cpu_usage,
+            "memory": process.memory,
+            "cmd": process.cmd,
+        })
+        .to_string();
+
+        let ptr = self.alloc.call(&mut self.store, input.len() as i32)?;
+        self.memory
+            .write(&mut self.store, ptr as usize, input.as_bytes())?;
+        let packed = cell.call(&mut self.store, (ptr, input.len() as i32))?;
+        let text = read_string(&self.memory, &self.store, packed)?;
+        self.reset.call(&mut self.store, ())?;
+        Ok(text)
+    }
+}
+
+// Whether a call stopped because the plugin used up its fuel
+pub fn out_of_fuel(error: &wasmtime::Error) -> bool {
+    error.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel)
+}
+
+fn optional_func<Params, Results>(
+    instance: &Instance,
+    store: &mut Store<()>,
+    name: &str,
+) -> Option<TypedFunc<Params, Results>>
+where
+    Params: wasmtime::WasmParams,
+    Results: wasmtime::WasmResults,
+{
+    instance.get_typed_func(store, name).ok()
+}
+
+fn read_string(memory: &Memory, store: &Store<()>, packed: i64) -> wasmtime::Result<String> {
+    let ptr = (packed as u64 >> 32) as usize;
+    let len = (packed as u64 & 0xffff_ffff) as usize;
+    let bytes = memory
+        .data(store)
+        .get(ptr..ptr + len)
+        .ok_or_else(|| wasmtime::Error::msg("string out of bounds"))?;
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+// Where psr_read_file may read from: the kernel's views of processes and
+// the system, which is what plugins collect from. The path is resolved
+// first, so a symlink like /proc/<pid>/root can't lead back out.
+fn readable(path: &str) -> Option<PathBuf> {
+    let path = std::fs::canonicalize(path).ok()?;
+    (path.starts_with("/proc") || path.starts_with("/sys")).then_some(path)
+}
+
+// Files under /proc that block, or hand out memory rather than statistics
+const UNREADABLE: [&str; 4] = ["kmsg", "kcore", "mem", "pagemap"];
+
+// The most psr_read_file copies of a file; plugins run on the UI thread
+const READ_LIMIT: u64 = 4 << 20;
+
+// A regular file's first READ_LIMIT bytes, opened non-blocking so nothing
+// waits on the kernel for more
+fn read_limited(path: &Path) -> Option<Vec<u8>> {
+    let name = path.file_name()?.to_str()?;
+    if UNREADABLE.contains(&name) {
+        return None;
+    }
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)
+        .ok()?;
+    if !file.metadata().ok()?.is_file() {
+        return None;
+    }
+    let mut contents = Vec::new();
+    file.take(READ_LIMIT).read_to_end(&mut contents).ok()?;
+    Some(contents)
+}
+
+// env.psr_read_file: copy a file into the plugin's memory
+fn read_file(mut caller: Caller<'_, ()>, ptr: i32, len: i32) -> i64 {
+    let (Some(memory), Some(alloc)) = (
+        caller.get_export("memory").and_then(|e| e.into_memory()),
+        caller.get_export("psr_alloc").and_then(|e| e.into_func()),
+    ) else {
+        return 0;
+    };
+    let Some(path) = memory
+        .data(&caller)
+        .get(ptr as usize..(ptr as usize).saturating_add(len as usize))
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+    else {
+        return 0;
+    };
+    let Some(contents) = readable(&path).and_then(|path| read_limited(&path)) else {
+        return 0;
+    };
+    let Ok(size) = i32::try_from(contents.len()) else {
+        return 0;
+    };
+    let Ok(alloc) = alloc.typed::<i32, i32>(&caller) else {
+        return 0;
+    };
+    let Ok(target) = alloc.call(&mut caller, size) else {
+        return 0;
+    };
+    if memory
+        .write(&mut caller, target as usize, &contents)
+        .is_err()
+    {
+        return 0;
+    }
+    ((target as u32 as i64) << 32) | size as i64
+}
@@ -0,0 +1,172 @@
+use psr_core::ProcessInfo;
+use regex::Regex;
+use std::fmt::Write;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use crate::app::App;
+use crate::http::{self, Response};
+
+// Serves the latest system and per-process figures at /metrics in the
+// Prometheus text format. The page is rendered once per refresh and handed
+// out as-is to every scrape.
+pub struct Exporter {
+    page: Arc<Mutex<String>>,
+    // Only processes whose name matches are exported
+    filter: Option<Regex>,
+}
+
+impl Exporter {
+    pub async fn start(addr: &str, filter: Option<Regex>) -> io::Result<Self> {
+        let page = Arc::new(Mutex::new(String::new()));
+        let shared = page.clone();
+        http::serve(addr, move |path| {
+            if path == "/metrics" {
+                let body = shared.lock().map(|p| p.clone()).unwrap_or_default();
+                Response::ok("text/plain; version=0.0.4", body)
+            } else {
+                Response::not_found("psr exports metrics at /metrics\n".to_string())
+            }
+        })
+        .await?;
+
+        Ok(Self { page, filter })
+    }
+
+    pub fn update(&self, app: &App) {
+        let processes: Vec<&ProcessInfo> = app
+            .all_processes
+            .values()
+            .filter(|p| self.filter.as_ref().is_none_or(|f| f.is_match(&p.name)))
+            .collect();
+        let page = render(app, &processes);
+        if let Ok(mut shared) = self.page.lock() {
+            *shared = page;
+        }
+    }
+}
+
+// Reads one per-process metric; None leaves the process out of that metric
+type ProcessValue = fn(&ProcessInfo) -> Option<f64>;
+
+fn render(app: &App, processes: &[&ProcessInfo]) -> String {
+    let mut out = String::new();
+    let resources = &app.system_resources;
+
+    gauge(&mut out, "psr_cpu_usage_percent", "Total CPU usage");
+    let _ = writeln!(out, "psr_cpu_usage_percent {}", resources.cpu_usage);
+    gauge(&mut out, "psr_memory_used_bytes", "Memory in use");
+    let _ = writeln!(out, "psr_memory_used_bytes {}", resources.used_memory);
+    gauge(&mut out, "psr_memory_total_bytes", "Installed memory");
+    let _ = writeln!(out, "psr_memory_total_bytes {}", resources.total_memory);
+    gauge(&mut out, "psr_swap_used_bytes", "Swap in use");
+    let _ = writeln!(out, "psr_swap_used_bytes {}", resources.used_swap);
+    gauge(&mut out, "psr_swap_total_bytes", "Configured swap");
+    let _ = writeln!(out, "psr_swap_total_bytes {}", resources.total_swap);
+    gauge(&mut out, "psr_processes", "Number of processes");
+    let _ = writeln!(out, "psr_processes {}", app.all_processes.len());
+
+    gauge(
+        &mut out,
+        "psr_network_receive_bytes_per_second",
+        "Bytes received per second by interface",
+    );
+    for interface in &app.network_stats.interfaces {
+        let _ = writeln!(
+            out,
+            "psr_network_receive_bytes_per_second{{interface=\"{}\"}} {}",
+            escape(&interface.name),
+            interface.rx_rate
+        );
+    }
+    gauge(
+        &mut out,
+        "psr_network_transmit_bytes_per_second",
+        "Bytes sent per second by interface",
+    );
+    for interface in &app.network_stats.interfaces {
+        let _ = writeln!(
+            out,
+            "psr_network_transmit_bytes_per_second{{interface=\"{}\"}} {}",
+            escape(&interface.name),
+            interface.tx_rate
+        );
+    }
+
+    gauge(&mut out, "psr_disk_used_bytes", "Used space by mount point");
+    for disk in &app.disk_stats.disks {
+        let _ = writeln!(
+            out,
+            "psr_disk_used_bytes{{mount_point=\"{}\"}} {}",
+            escape(&disk.mount_point),
+            disk.used_space()
+        );
+    }
+    gauge(
+        &mut out,
+        "psr_disk_total_bytes",
+        "Total space by mount point",
+    );
+    for disk in &app.disk_stats.disks {
+        let _ = writeln!(
+            out,
+            "psr_disk_total_bytes{{mount_point=\"{}\"}} {}",
+            escape(&disk.mount_point),
+            disk.total_space
+        );
+    }
+
+    let per_process: [(&str, &str, ProcessValue); 6] = [
+        ("psr_process_cpu_usage_percent", "CPU usage", |p| {
+            Some(p.cpu_usage as f64)
+        }),
+        ("psr_process_memory_bytes", "Resident memory", |p| {
+            Some(p.memory as f64)
+        }),
+        (
+            "psr_process_disk_read_bytes_per_second",
+            "Bytes read from disk per second",
+            |p| Some(p.disk_read_rate as f64),
+        ),
+        (
+            "psr_process_disk_write_bytes_per_second",
+            "Bytes written to disk per second",
+            |p| Some(p.disk_write_rate as f64),
+        ),
+        ("psr_process_threads", "Number of threads", |p| {
+            p.threads.map(|t| t as f64)
+        }),
+        ("psr_process_nice", "Nice value", |p| Some(p.nice as f64)),
+    ];
+    for (name, help, value) in per_process {
+        gauge(&mut out, name, help);
+        for process in processes {
+            if let Some(value) = value(process) {
+                let _ = writeln!(
+                    out,
+                    "{}{{pid=\"{}\",name=\"{}\",user=\"{}\"}} {}",
+                    name,
+                    process.pid,
+                    escape(&process.name),
+                    escape(&process.user),
+                    value
+                );
+            }
+        }
+    }
+
+    out
+}
+
+fn gauge(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+}
+
+// Label values are quoted, so backslashes, quotes and newlines need escaping
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+
+// Lookups a slow or unreachable DNS server can keep busy at once; the rest
+// queue behind them
+const WORKERS: usize = 4;
+// Names kept before the cache starts over, so a long session watching a busy
+// server doesn't grow without bound
+const CACHE_SIZE: usize = 4096;
+
+// Host and service names for the addresses in the Connections panel. Reverse
+// DNS lookups run on worker threads and land in a shared cache, so an address
+// shows as its IP until its name arrives; service names come from the local
+// services database (/etc/services) and are looked up straight away.
+pub struct Resolver {
+    // None while the lookup is running, and after it when there's no name
+    hosts: Arc<Mutex<HashMap<IpAddr, Option<String>>>>,
+    services: HashMap<(u16, bool), Option<String>>,
+    lookups: Option<Sender<IpAddr>>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self {
+            hosts: Arc::default(),
+            services: HashMap::new(),
+            lookups: None,
+        }
+    }
+
+    // Start finding names for an address, written as `address:port`, unless
+    // they're known or being looked up already. `reverse_dns` false skips the
+    // host name, which would send the address to the DNS server.
+    pub fn request(&mut self, address: &str, udp: bool, reverse_dns: bool) {
+        let Some(socket) = parse_address(address) else {
+            return;
+        };
+        if socket.port() != 0 {
+            self.services
+                .entry((socket.port(), udp))
+                .or_insert_with(|| service_name(socket, udp));
+        }
+
+        let ip = socket.ip();
+        if !reverse_dns || ip.is_unspecified() {
+            return;
+        }
+        {
+            let mut hosts = self.hosts.lock().unwrap();
+            if hosts.contains_key(&ip) {
+                return;
+            }
+            if hosts.len() >= CACHE_SIZE {
+                hosts.clear();
+            }
+            hosts.insert(ip, None);
+        }
+        let lookups = self
+            .lookups
+            .get_or_insert_with(|| spawn_workers(&self.hosts));
+        let _ = lookups.send(ip);
+    }
+
+    // The address with whatever names are known for it: `host:service`, or
+    // the parts that aren't known as they were
+    pub fn describe(&self, address: &str, udp: bool) -> String {
+        let Some(socket) = parse_address(address) else {
+            return address.to_string();
+        };
+        let host = self
+            .hosts
+            .lock()
+            .unwrap()
+            .get(&socket.ip())
+            .cloned()
+            .flatten();
+        let service = self.services.get(&(socket.port(), udp)).cloned().flatten();
+        if host.is_none() && service.is_none() {
+            return address.to_string();
+        }
+
+        let host = host.unwrap_or_else(|| match socket.ip() {
+            IpAddr::V6(ip) => format!("[{}]", ip),
+            ip => ip.to_string(),
+        });
+        let service = service.unwrap_or_else(|| socket.port().to_string());
+        format!("{}:{}", host, service)
+    }
+}
+
+fn spawn_workers(hosts: &Arc<Mutex<HashMap<IpAddr, Option<String>>>>) -> Sender<IpAddr> {
+    let (sender, receiver) = mpsc::channel::<IpAddr>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..WORKERS {
+        let receiver = Arc::clone(&receiver);
+        let hosts = Arc::clone(hosts);
+        std::thread::spawn(move || loop {
+            // The lock is only held while waiting for the next address
+            let Ok(ip) = receiver.lock().unwrap().recv() else {
+                return;
+            };
+            if let Ok(name) = dns_lookup::lookup_addr(&ip) {
+                hosts.lock().unwrap().insert(ip, Some(name));
+            }
+        });
+    }
+    sender
+}
+
+// The port's name in the services database, if it has one
+fn service_name(socket: SocketAddr, udp: bool) -> Option<String> {
+    let flags = libc::NI_NUMERICHOST | if udp { libc::NI_DGRAM } else { 0 };
+    let (_, service) = dns_lookup::getnameinfo(&socket, flags).ok()?;
+    (service != socket.port().to_string()).then_some(service)
+}
+
+// Addresses as the connection lists write them: "10.0.0.5:443" or
+// "[::1]:8080". lsof's "*:*" for an unbound end doesn't parse.
+fn parse_address(address: &str) -> Option<SocketAddr> {
+    if let Ok(socket) = address.parse() {
+        return Some(socket);
+    }
+    let (ip, port) = address.rsplit_once(':')?;
+    Some(SocketAddr::new(ip.parse().ok()?, port.parse().ok()?))
+}
@@ -0,0 +1,267 @@
+use psr_core::ProcessInfo;
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::alerts::Alert;
+use crate::config::Config;
+
+// User scripts in Rhai, loaded from the `scripts` directory next to the
+// config file. A script defines whichever of these functions it needs:
+//
+//   fn filter(p)     false hides the process from the tables
+//   fn column(p)     text for the Script column
+//   fn on_start(p)   a process appeared since the last refresh
+//   fn on_exit(p)    a process went away
+//   fn on_refresh(ps) every refresh, with the whole process list
+//   fn on_alert(a)   an alert rule fired (rule, subject, value, pid)
+//
+// Processes are maps with pid, name, user, cpu, memory, status, nice,
+// threads, parent, cmd, container and unit. Scripts act through kill(pid),
+// renice(pid, nice) and status(text), which are queued and carried out by
+// the app once the script returns.
+pub struct Scripts {
+    engine: Engine,
+    scripts: Vec<Script>,
+    actions: Rc<RefCell<Vec<Action>>>,
+}
+
+struct Script {
+    name: String,
+    ast: AST,
+    functions: HashSet<String>,
+}
+
+// What the tables take from the scripts after a refresh
+#[derive(Default)]
+pub struct ScriptResults {
+    pub hidden: HashSet<u32>,
+    pub columns: HashMap<u32, String>,
+}
+
+pub enum Action {
+    Kill(u32),
+    Renice(u32, i32),
+    Status(String),
+}
+
+// Keeps a runaway loop in a script from freezing the TUI
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+impl Scripts {
+    pub fn dir() -> Option<PathBuf> {
+        Some(Config::path()?.parent()?.join("scripts"))
+    }
+
+    // Every *.rhai file in the scripts directory, in name order. Scripts that
+    // fail to compile are left out and reported.
+    pub fn load() -> (Self, Vec<String>) {
+        let actions: Rc<RefCell<Vec<Action>>> = Rc::default();
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let queue = actions.clone();
+        engine.register_fn("kill", move |pid: i64| -> Result<(), Box<EvalAltResult>> {
+            queue.borrow_mut().push(Action::Kill(script_pid(pid)?));
+            Ok(())
+        });
+        let queue = actions.clone();
+        engine.register_fn(
+            "renice",
+            move |pid: i64, nice: i64| -> Result<(), Box<EvalAltResult>> {
+                let nice =
+                    i32::try_from(nice).map_err(|_| format!("invalid nice value {}", nice))?;
+                queue
+                    .borrow_mut()
+                    .push(Action::Renice(script_pid(pid)?, nice));
+                Ok(())
+            },
+        );
+        let queue = actions.clone();
+        engine.register_fn("status", move |text: &str| {
+            queue.borrow_mut().push(Action::Status(text.to_string()));
+        });
+
+        let mut scripts = Self {
+            engine,
+            scripts: Vec::new(),
+            actions,
+        };
+        let mut errors = Vec::new();
+        let Some(dir) = Self::dir() else {
+            return (scripts, errors);
+        };
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return (scripts, errors);
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let name = path
+                .file_stem()
+                .map_or(String::new(), |s| s.to_string_lossy().into_owned());
+            // Top-level statements run once, at load
+            let compiled = scripts.engine.compile_file(path).and_then(|ast| {
+                scripts.engine.run_ast(&ast)?;
+                Ok(ast)
+            });
+            match compiled {
+                Ok(ast) => {
+                    let functions = ast.iter_functions().map(|f| f.name.to_string()).collect();
+                    scripts.scripts.push(Script {
+                        name,
+                        ast,
+                        functions,
+                    });
+                }
+                Err(e) => errors.push(format!("{}: {}", name, e)),
+            }
+        }
+        (scripts, errors)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    // Run the scripts over a refresh: start and exit events for the PIDs that
+    // appeared and the processes that went away, on_refresh, then the filter
+    // and column of every process
+    pub fn refresh(
+        &self,
+        started: &[u32],
+        exited: &[ProcessInfo],
+        current: &HashMap<u32, ProcessInfo>,
+    ) -> Result<ScriptResults, String> {
+        for process in started.iter().filter_map(|pid| current.get(pid)) {
+            self.call("on_start", process_map(process))?;
+        }
+        for process in exited {
+            self.call("on_exit", process_map(process))?;
+        }
+        if self.defines("on_refresh") {
+            let list: Array = current.values().map(process_map).collect();
+            self.call("on_refresh", list.into())?;
+        }
+
+        let mut results = ScriptResults::default();
+        let filters = self.defines("filter");
+        let columns = self.defines("column");
+        if filters || columns {
+            for process in current.values() {
+                let map = process_map(process);
+                if filters && !self.filter(&map)? {
+                    results.hidden.insert(process.pid);
+                }
+                if columns {
+                    results.columns.insert(process.pid, self.column(&map)?);
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    fn defines(&self, function: &str) -> bool {
+        self.scripts.iter().any(|s| s.functions.contains(function))
+    }
+
+    // Call `function` in every script that defines it, collecting the results
+    fn call(&self, function: &str, arg: Dynamic) -> Result<Vec<Dynamic>, String> {
+        let mut results = Vec::new();
+        for script in &self.scripts {
+            if !script.functions.contains(function) {
+                continue;
+            }
+            let options = CallFnOptions::new().eval_ast(false);
+            let result = self
+                .engine
+                .call_fn_with_options::<Dynamic>(
+                    options,
+                    &mut Scope::new(),
+                    &script.ast,
+                    function,
+                    (arg.clone(),),
+                )
+                .map_err(|e| format!("script {}: {}: {}", script.name, function, e))?;
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    // A process stays listed unless some script's filter rejects it
+    fn filter(&self, process: &Dynamic) -> Result<bool, String> {
+        Ok(self
+            .call("filter", process.clone())?
+            .iter()
+            .all(|keep| keep.as_bool().unwrap_or(true)))
+    }
+
+    // Script column text, joined across scripts that define one
+    fn column(&self, process: &Dynamic) -> Result<String, String> {
+        let values: Vec<String> = self
+            .call("column", process.clone())?
+            .into_iter()
+            .filter(|value| !value.is_unit())
+            .map(|value| value.to_string())
+            .collect();
+        Ok(values.join(" "))
+    }
+
+    pub fn on_alert(&self, rule: String, alert: &Alert) -> Result<(), String> {
+        let mut map = Map::new();
+        map.insert("rule".into(), rule.into());
+        map.insert("subject".into(), alert.subject.clone().into());
+        map.insert("value".into(), alert.value.into());
+        map.insert(
+            "pid".into(),
+            alert.pid.map_or(Dynamic::UNIT, |pid| (pid as i64).into()),
+        );
+        self.call("on_alert", map.into()).map(drop)
+    }
+
+    pub fn take_actions(&self) -> Vec<Action> {
+        std::mem::take(&mut *self.actions.borrow_mut())
+    }
+}
+
+// What scripts see of a process
+fn process_map(process: &ProcessInfo) -> Dynamic {
+    let optional = |value: Option<&String>| value.map_or(Dynamic::UNIT, |v| v.clone().into());
+    let mut map = Map::new();
+    map.insert("pid".into(), (process.pid as i64).into());
+    map.insert("name".into(), process.name.clone().into());
+    map.insert("user".into(), process.user.clone().into());
+    map.insert("cpu".into(), (process.cpu_usage as f64).into());
+    map.insert("memory".into(), (process.memory as i64).into());
+    map.insert("status".into(), process.status.to_string().into());
+    map.insert("nice".into(), (process.nice as i64).into());
+    map.insert(
+        "threads".into(),
+        process.threads.map_or(Dynamic::UNIT, |n| (n as i64).into()),
+    );
+    map.insert(
+        "parent".into(),
+        process
+            .parent
+            .map_or(Dynamic::UNIT, |pid| (pid as i64).into()),
+    );
+    let cmd: Array = process.cmd.iter().map(|arg| arg.clone().into()).collect();
+    map.insert("cmd".into(), cmd.into());
+    map.insert("container".into(), optional(process.container.as_ref()));
+    map.insert("unit".into(), optional(process.unit.as_ref()));
+    map.into()
+}
+
+// A PID passed to kill() or renice(). Anything below 1 or beyond pid_t would
+// reach kill(2) as a process group, or every process, so it's a script error.
+fn script_pid(pid: i64) -> Result<u32, Box<EvalAltResult>> {
+    match u32::try_from(pid) {
+        Ok(pid) if pid >= 1 && pid <= i32::MAX as u32 => Ok(pid),
+        _ => Err(format!("invalid PID {}", pid).into()),
+    }
+}
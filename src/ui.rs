@@ -1,17 +1,27 @@
+use chrono::{Local, TimeZone};
+use psr_core::{ProcessInfo, ProcessStatus, SystemLoad};
 use ratatui::backend::Backend;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::symbols::Marker;
 use ratatui::text::{Span, Spans};
 use ratatui::widgets::{
-    Axis, BarChart, Block, Borders, Cell, Chart, Dataset, Paragraph, Row, Sparkline, Table, Tabs,
-    Wrap,
+    Axis, BarChart, Block, BorderType, Borders, Cell, Chart, Clear, Dataset, GraphType, Paragraph,
+    Row, Sparkline, Table, TableState, Tabs, Wrap,
 };
 use ratatui::Frame;
+use std::collections::{HashMap, VecDeque};
 use std::time::Duration;
 
-use crate::app::{App, SortKey};
-use crate::processes::ProcessInfo;
+use crate::alerts::Metric;
+use crate::app::{
+    App, Column, DetailPanel, Dialog, DialogKind, GroupSummary, InputMode, MouseRegions,
+    RateHistory, SortKey, ViewMode,
+};
+use crate::config::Config;
+use crate::history::{self, HistorySample};
+use crate::inspect::{self, Activity, BinaryState, Seccomp};
+use crate::keymap::{Action, HelpLine};
 
 // Collection of color constants
 struct Colors;
@@ -48,28 +58,48 @@ pub fn draw_ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         )
         .split(size);
 
+    // A line of task counts sits above the content of every tab
+    let content_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(chunks[1]);
+    let content = content_chunks[1];
+
+    // Table rows that fit in the content area, less borders and header
+    app.page_size = content.height.saturating_sub(3) as usize;
+    app.update_viewport();
+
     // Draw tabs with improved styling
     let tab_titles: Vec<Spans> = app
         .tabs
         .iter()
         .map(|t| {
+            let style = if *t == "Alerts" && !app.alerts.active.is_empty() {
+                Style::default().fg(Colors::ERROR)
+            } else {
+                Style::default().fg(Colors::TEXT)
+            };
             Spans::from(vec![
                 Span::styled(" ", Style::default().fg(Colors::TEXT)),
-                Span::styled(*t, Style::default().fg(Colors::TEXT)),
+                Span::styled(tab_title(app, t), style),
                 Span::styled(" ", Style::default().fg(Colors::TEXT)),
             ])
         })
         .collect();
 
+    // Agents are named in the title, along with any connection trouble
+    let title = match &app.host {
+        Some(host) if app.loading_status.is_empty() => format!(" Process Monitor - {} ", host),
+        Some(host) => format!(" Process Monitor - {} ({}) ", host, app.loading_status),
+        None => " Process Monitor ".to_string(),
+    };
+    let title_width = title.chars().count() as u16;
     let tabs = Tabs::new(tab_titles)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Colors::BORDER))
-                .title(Span::styled(
-                    " Process Monitor ",
-                    Style::default().fg(Colors::HEADER),
-                )),
+                .title(Span::styled(title, Style::default().fg(Colors::HEADER))),
         )
         .select(app.current_tab)
         .style(Style::default().fg(Colors::TAB_INACTIVE))
@@ -82,25 +112,158 @@ pub fn draw_ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
     f.render_widget(tabs, chunks[0]);
 
-    // Draw main content based on current tab
+    // psr's own footprint sits at the right end of the top border, if it fits
+    if let Some(usage) = monitor_usage(app) {
+        let width = usage.chars().count() as u16;
+        let free = chunks[0].width.saturating_sub(title_width + 4);
+        if width <= free {
+            let area = Rect::new(
+                chunks[0].x + chunks[0].width - 1 - width,
+                chunks[0].y,
+                width,
+                1,
+            );
+            let text = Paragraph::new(Span::styled(usage, Style::default().fg(Colors::TEXT)));
+            f.render_widget(text, area);
+        }
+    }
+
+    f.render_widget(Paragraph::new(task_summary(app)), content_chunks[0]);
+
+    // Draw main content based on current tab; process tables report where
+    // their headers and rows landed for mouse handling
+    let mut mouse = MouseRegions::default();
     match app.current_tab {
-        0 => draw_dashboard_tab(f, app, chunks[1]),
-        1 => draw_processes_tab(f, app, chunks[1]),
-        2 => draw_user_processes_tab(f, app, chunks[1]),
-        3 => draw_system_processes_tab(f, app, chunks[1]),
-        4 => draw_detailed_view(f, app, chunks[1]),
-        _ => {}
+        0 => draw_dashboard_tab(f, app, content),
+        1 => mouse = draw_processes_tab(f, app, content),
+        2 => mouse = draw_user_processes_tab(f, app, content),
+        3 => mouse = draw_system_processes_tab(f, app, content),
+        4 => mouse = draw_summary_tab(f, app, content, "User", &app.user_summaries()),
+        5 => mouse = draw_summary_tab(f, app, content, "Container", &app.container_summaries()),
+        6 => draw_cgroups_tab(f, app, content),
+        7 => draw_detailed_view(f, app, content),
+        8 => draw_network_tab(f, app, content),
+        9 => draw_disks_tab(f, app, content),
+        10 => draw_gpu_tab(f, app, content),
+        11 => draw_alerts_tab(f, app, content),
+        12 => draw_events_tab(f, app, content),
+        13 => draw_ports_tab(f, app, content),
+        _ => draw_plugin_tab(f, app, content),
+    }
+
+    // Tab titles sit one column apart inside the border, separated by a divider
+    let mut x = chunks[0].x + 1;
+    for (index, title) in app.tabs.iter().enumerate() {
+        x += 1;
+        let width = tab_title(app, title).chars().count() as u16 + 2;
+        mouse
+            .tabs
+            .push((Rect::new(x, chunks[0].y + 1, width, 1), index));
+        x += width + 2;
     }
+    mouse.scroll_area = content;
+    app.mouse = mouse;
 
-    // Draw filter bar
-    let filter_text = if app.filter.is_empty() {
+    // Draw filter bar, which doubles as the prompt and status line
+    let filter_text = if app.input_mode == InputMode::Columns {
+        Span::styled(
+            " Space: show/hide | Shift+↑/↓ or -/+: move | Enter: save | Esc: cancel ",
+            Style::default()
+                .fg(Colors::HIGHLIGHT)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if let Some(unit) = app
+        .service_unit
+        .as_ref()
+        .filter(|_| app.input_mode == InputMode::Service)
+    {
+        Span::styled(
+            format!(
+                " {}{}: r to restart, s to stop, Esc to cancel ",
+                unit.name,
+                if unit.user { " (user unit)" } else { "" }
+            ),
+            Style::default()
+                .fg(Colors::HIGHLIGHT)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if app.input_mode == InputMode::Command {
+        Span::styled(
+            format!(" :{}_ ", app.input),
+            Style::default()
+                .fg(Colors::HIGHLIGHT)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if app.input_mode == InputMode::Search {
+        Span::styled(
+            format!(" Search: {}_ ", app.input),
+            Style::default()
+                .fg(Colors::HIGHLIGHT)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if let Some(toast) = &app.status_message {
+        let color = if toast.error {
+            Colors::ERROR
+        } else {
+            Colors::HEADER
+        };
+        Span::styled(format!(" {} ", toast.text), Style::default().fg(color))
+    } else if let Some(search) = &app.search {
+        Span::styled(
+            format!(
+                " Search: {} - n/N for next/previous match, Esc to clear ",
+                search
+            ),
+            Style::default().fg(Colors::HEADER),
+        )
+    } else if let Some(follow) = &app.follow {
+        Span::styled(
+            format!(
+                " Following {} (PID {}){} - Ctrl+f to release ",
+                follow.name,
+                follow.pid,
+                if follow.exited { ", exited" } else { "" }
+            ),
+            Style::default().fg(Colors::HEADER),
+        )
+    } else if let Some(path) = &app.cgroup_filter {
+        let filter = if app.filter.is_empty() {
+            String::new()
+        } else {
+            format!(" | Filter: {}", app.filter)
+        };
+        Span::styled(
+            format!(" Cgroup: {}{} - Esc to show all processes ", path, filter),
+            Style::default().fg(Colors::HEADER),
+        )
+    } else if app.filter.is_empty() {
         Span::styled(
-            " Type to filter processes... ",
+            if app.filter_regex {
+                " Type a regex to filter processes... "
+            } else {
+                " Type to filter processes... "
+            },
             Style::default().fg(Color::DarkGray),
         )
+    } else if let Some(error) = &app.filter_error {
+        Span::styled(
+            format!(
+                " {}: {}  ({}) ",
+                if app.filter_regex { "Regex" } else { "Filter" },
+                app.filter,
+                error
+            ),
+            Style::default()
+                .fg(Colors::ERROR)
+                .add_modifier(Modifier::BOLD),
+        )
     } else {
         Span::styled(
-            format!(" Filter: {} ", app.filter),
+            format!(
+                " {}: {} ",
+                if app.filter_regex { "Regex" } else { "Filter" },
+                app.filter
+            ),
             Style::default()
                 .fg(Color::White)
                 .add_modifier(Modifier::BOLD),
@@ -110,19 +273,45 @@ pub fn draw_ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     let filter_bar = Paragraph::new(filter_text).style(Style::default().bg(Color::Black));
     f.render_widget(filter_bar, chunks[2]);
 
+    if app.input_mode == InputMode::Columns {
+        draw_column_picker(f, app, chunks[1]);
+    }
+
+    if let Some(dialog) = &app.dialog {
+        draw_dialog(f, dialog, chunks[1]);
+    }
+
+    if app.debug.is_some() {
+        draw_debug_overlay(f, app, chunks[1]);
+    }
+
     // Draw help
-    if app.show_help {
+    if app.help.is_some() {
         draw_help_popup(f, app, size);
     } else {
+        // Hints for whatever keys the actions are bound to
+        let hint = |action: Action, label: &str| match app.keymap.key(action) {
+            Some(key) => format!("{}: {} | ", key, label),
+            None => String::new(),
+        };
+        let tabs = if app.can_scrub() {
+            "Scrub history"
+        } else {
+            "Change tab"
+        };
         let help_text = Spans::from(vec![
-            Span::raw(" q: Quit | "),
-            Span::raw("r: Refresh | "),
-            Span::raw("k: Kill | "),
+            Span::raw(" "),
+            Span::raw(hint(Action::Quit, "Quit")),
+            Span::raw(hint(Action::Refresh, "Refresh")),
+            Span::raw(hint(Action::Kill, "Kill")),
             Span::raw("↑/↓: Navigate | "),
-            Span::raw("←/→: Change tab | "),
-            Span::raw("Space: Toggle sort | "),
-            Span::raw("h: Help | "),
-            Span::raw("Esc: Clear filter"),
+            Span::raw(format!("←/→: {} | ", tabs)),
+            Span::raw(hint(Action::Tag, "Tag")),
+            Span::raw(
+                hint(Action::Help, "Help")
+                    .trim_end_matches(" | ")
+                    .to_string(),
+            ),
         ]);
         let help = Paragraph::new(help_text).style(Style::default().fg(Color::DarkGray));
 
@@ -131,7 +320,32 @@ pub fn draw_ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 }
 
 fn draw_dashboard_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
-    // Create 2x2 grid layout for dashboard
+    // Load and uptime across the top, next to pressure stalls where the
+    // system reports them, then a 2x2 grid of charts and tables
+    let area = match (app.system_load, app.pressure_history.is_empty()) {
+        (Some(load), true) => {
+            let header = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(0)])
+                .split(area);
+            draw_system_load(f, app, &load, header[0]);
+            header[1]
+        }
+        (Some(load), false) => {
+            let header = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(5), Constraint::Min(0)])
+                .split(area);
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .split(header[0]);
+            draw_system_load(f, app, &load, columns[0]);
+            draw_pressure(f, app, columns[1]);
+            header[1]
+        }
+        (None, _) => area,
+    };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
@@ -147,11 +361,34 @@ fn draw_dashboard_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(chunks[1]);
 
-    // Draw CPU usage chart
-    draw_cpu_chart(f, app, top_row[0]);
+    // Draw CPU usage chart, with the per-core panel beside it once the
+    // cores have been reported
+    if app.cores.is_empty() {
+        draw_cpu_chart(f, app, top_row[0]);
+    } else {
+        let cpu_area = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(30)])
+            .split(top_row[0]);
+        draw_cpu_chart(f, app, cpu_area[0]);
+        draw_cores(f, app, cpu_area[1]);
+    }
 
-    // Draw memory usage chart
-    draw_memory_chart(f, app, top_row[1]);
+    // Draw memory usage chart, above each NUMA node's share where there
+    // are several
+    if app.numa_nodes.is_empty() {
+        draw_memory_chart(f, app, top_row[1]);
+    } else {
+        let memory_area = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(app.numa_nodes.len() as u16 + 2),
+            ])
+            .split(top_row[1]);
+        draw_memory_chart(f, app, memory_area[0]);
+        draw_numa_nodes(f, app, memory_area[1]);
+    }
 
     // Draw top CPU processes
     draw_top_cpu_processes(f, app, bottom_row[0]);
@@ -160,29 +397,179 @@ fn draw_dashboard_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
     draw_top_memory_processes(f, app, bottom_row[1]);
 }
 
+// Load averages are colored against the number of cores, above which tasks
+// are queueing for a CPU
+fn draw_system_load<B: Backend>(f: &mut Frame<B>, app: &App, load: &SystemLoad, area: Rect) {
+    let cores = app.system_resources.cpu_count as f64;
+    let load_style = |value: f64| {
+        if value > cores {
+            Style::default().fg(Colors::ERROR)
+        } else if value > cores * 0.7 {
+            Style::default().fg(Colors::WARNING)
+        } else {
+            Style::default().fg(Colors::TEXT)
+        }
+    };
+    let booted = Local
+        .timestamp_opt(load.boot_time as i64, 0)
+        .single()
+        .map_or(String::new(), |at| at.format("%b %-d %H:%M").to_string());
+
+    let load_line = vec![
+        Span::styled(" Load average: ", Style::default().fg(Colors::HEADER)),
+        Span::styled(format!("{:.2}", load.one), load_style(load.one)),
+        Span::styled(", ", Style::default().fg(Colors::TEXT)),
+        Span::styled(format!("{:.2}", load.five), load_style(load.five)),
+        Span::styled(", ", Style::default().fg(Colors::TEXT)),
+        Span::styled(format!("{:.2}", load.fifteen), load_style(load.fifteen)),
+        Span::styled(
+            match app.system_resources.cpu_count {
+                1 => " (1 core)".to_string(),
+                cores => format!(" ({} cores)", cores),
+            },
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+    let uptime_line = vec![
+        Span::styled(" Uptime: ", Style::default().fg(Colors::HEADER)),
+        Span::styled(
+            format_uptime(load.uptime),
+            Style::default().fg(Colors::TEXT),
+        ),
+    ];
+    let booted_line = vec![
+        Span::styled(" Booted: ", Style::default().fg(Colors::HEADER)),
+        Span::styled(booted, Style::default().fg(Colors::TEXT)),
+    ];
+
+    // One line each when there's room for them, otherwise all on one
+    let lines = if area.height >= 5 {
+        vec![
+            Spans::from(load_line),
+            Spans::from(uptime_line),
+            Spans::from(booted_line),
+        ]
+    } else {
+        let separator = || Span::styled("  ", Style::default());
+        vec![Spans::from(
+            load_line
+                .into_iter()
+                .chain([separator()])
+                .chain(uptime_line)
+                .chain([separator()])
+                .chain(booted_line)
+                .collect::<Vec<_>>(),
+        )]
+    };
+    let widget = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Colors::BORDER)),
+    );
+    f.render_widget(widget, area);
+}
+
+// One row per resource with its some/full stall percentages and a sparkline
+// of `some` over the last minute
+fn draw_pressure<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let block = Block::default()
+        .title(Span::styled(
+            " Pressure (10s avg, some/full) ",
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let Some(latest) = app.pressure_history.back() else {
+        return;
+    };
+    let resources = [
+        ("CPU", Colors::CPU),
+        ("Memory", Colors::MEMORY),
+        ("I/O", Colors::WARNING),
+    ];
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1); 3])
+        .split(inner);
+
+    for (index, ((label, color), row)) in resources.into_iter().zip(rows.iter()).enumerate() {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(24), Constraint::Min(0)])
+            .split(*row);
+        let current = latest.stalls()[index];
+        let style = if current.some >= 10.0 {
+            Style::default().fg(Colors::ERROR)
+        } else if current.some >= 1.0 {
+            Style::default().fg(Colors::WARNING)
+        } else {
+            Style::default().fg(Colors::TEXT)
+        };
+        let text = Paragraph::new(Spans::from(vec![
+            Span::styled(
+                format!(" {:<7}", label),
+                Style::default().fg(Colors::HEADER),
+            ),
+            Span::styled(
+                format!("{:>6.2}% / {:>5.2}% ", current.some, current.full),
+                style,
+            ),
+        ]));
+        f.render_widget(text, columns[0]);
+
+        // Hundredths of a percent, scaled to at least 10% so that a quiet
+        // system's noise doesn't fill the row
+        let data: Vec<u64> = app
+            .pressure_history
+            .iter()
+            .map(|p| (p.stalls()[index].some * 100.0) as u64)
+            .collect();
+        let peak = data.iter().copied().max().unwrap_or(0).max(1000);
+        let sparkline = Sparkline::default()
+            .data(&data)
+            .max(peak)
+            .style(Style::default().fg(color));
+        f.render_widget(sparkline, columns[1]);
+    }
+}
+
 fn draw_cpu_chart<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
     // CPU data: convert history to (x, y) data pairs
-    let data: Vec<(f64, f64)> = app
-        .system_resources
-        .cpu_history
-        .iter()
-        .enumerate()
-        .map(|(i, &cpu)| (i as f64, cpu as f64))
-        .collect();
+    let series = history_series(
+        &app.system_history,
+        app.system_resources.cpu_history.iter().map(|&c| c as f64),
+        |s| s.cpu_usage as f64,
+    );
+    let scrub = scrub_point(app, &series);
+    let title = match &scrub {
+        Some(point) => format!(" CPU Usage{}: {:.1}% ", point.label, point.value),
+        None => format!(
+            " CPU Usage: {:.1}%{} ",
+            app.system_resources.cpu_usage, series.span
+        ),
+    };
 
     // Create dataset
-    let datasets = vec![Dataset::default()
+    let mut datasets = vec![Dataset::default()
         .name("CPU %")
         .marker(Marker::Braille)
         .style(Style::default().fg(Colors::CPU))
-        .data(&data)];
+        .data(&series.data)];
+    if let Some(point) = &scrub {
+        datasets.push(scrub_marker(&point.line));
+    }
 
     // Create chart
     let chart = Chart::new(datasets)
         .block(
             Block::default()
                 .title(Span::styled(
-                    format!(" CPU Usage: {:.1}% ", app.system_resources.cpu_usage),
+                    title,
                     Style::default()
                         .fg(Colors::HEADER)
                         .add_modifier(Modifier::BOLD),
@@ -193,8 +580,8 @@ fn draw_cpu_chart<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
         .x_axis(
             Axis::default()
                 .style(Style::default().fg(Colors::TEXT))
-                .bounds([0.0, 60.0])
-                .labels(vec![]),
+                .bounds(series.bounds)
+                .labels(series.labels.clone()),
         )
         .y_axis(
             Axis::default()
@@ -210,37 +597,218 @@ fn draw_cpu_chart<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
     f.render_widget(chart, area);
 }
 
-fn draw_memory_chart<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
-    // Memory data: convert history to (x, y) data pairs
-    let data: Vec<(f64, f64)> = app
-        .system_resources
-        .memory_history
+// One row per core with a usage bar and its current clock. A busy core
+// running well below the fastest one is marked, as that's throttling or a
+// power-saving governor rather than a lack of work.
+fn draw_cores<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let clocked: Vec<u64> = app
+        .cores
+        .iter()
+        .map(|core| core.frequency)
+        .filter(|&mhz| mhz > 0)
+        .collect();
+    let fastest = clocked.iter().copied().max().unwrap_or(0);
+    let title = if clocked.is_empty() {
+        " Cores ".to_string()
+    } else {
+        let average = clocked.iter().sum::<u64>() as f64 / clocked.len() as f64;
+        format!(" Cores · avg {:.2} GHz ", average / 1000.0)
+    };
+    let block = Block::default()
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = inner.height as usize;
+    if rows == 0 {
+        return;
+    }
+    // Leave the last row to say how many didn't fit
+    let shown = if app.cores.len() > rows {
+        rows - 1
+    } else {
+        app.cores.len()
+    };
+    let label_width = (app.cores.len() - 1).to_string().len();
+    const BAR: usize = 10;
+
+    let mut lines: Vec<Spans> = app
+        .cores
         .iter()
+        .take(shown)
         .enumerate()
-        .map(|(i, &mem)| (i as f64, mem as f64))
+        .map(|(index, core)| {
+            let usage = core.usage.clamp(0.0, 100.0);
+            let filled = ((usage / 100.0) * BAR as f32).round() as usize;
+            let frequency = if core.frequency == 0 {
+                format!("{:>9}", "-")
+            } else {
+                format!(" {:.2} GHz", core.frequency as f64 / 1000.0)
+            };
+            let throttled = usage >= 50.0 && (core.frequency as f64) < fastest as f64 * 0.6;
+            let frequency_style = if throttled {
+                Style::default().fg(Colors::WARNING)
+            } else {
+                Style::default().fg(Colors::TEXT)
+            };
+            Spans::from(vec![
+                Span::styled(
+                    format!("{:>width$} ", index, width = label_width),
+                    Style::default().fg(Colors::HEADER),
+                ),
+                Span::styled("█".repeat(filled), Style::default().fg(Colors::CPU)),
+                Span::styled(
+                    "░".repeat(BAR - filled),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{:>5.0}%", usage),
+                    Style::default().fg(Colors::TEXT),
+                ),
+                Span::styled(frequency, frequency_style),
+            ])
+        })
+        .collect();
+    if shown < app.cores.len() {
+        lines.push(Spans::from(Span::styled(
+            format!("+{} more", app.cores.len() - shown),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+// A usage bar per NUMA node, to see whether memory is piling up on one
+// socket while the others have room
+fn draw_numa_nodes<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let block = Block::default()
+        .title(Span::styled(
+            " NUMA Nodes ",
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let lines: Vec<Spans> = app
+        .numa_nodes
+        .iter()
+        .map(|node| {
+            let percent = if node.total > 0 {
+                node.used as f64 / node.total as f64 * 100.0
+            } else {
+                0.0
+            };
+            let label = format!(" Node {} ", node.id);
+            let usage = format!(
+                " {} {:>5.1}%",
+                app.config.units.fraction(node.used, node.total),
+                percent
+            );
+            let bar = (inner.width as usize).saturating_sub(label.len() + usage.chars().count());
+            let filled = ((percent / 100.0) * bar as f64).round() as usize;
+            let style = if percent > 90.0 {
+                Style::default().fg(Colors::ERROR)
+            } else if percent > 75.0 {
+                Style::default().fg(Colors::WARNING)
+            } else {
+                Style::default().fg(Colors::MEMORY)
+            };
+            Spans::from(vec![
+                Span::styled(label, Style::default().fg(Colors::HEADER)),
+                Span::styled("█".repeat(filled.min(bar)), style),
+                Span::styled(
+                    "░".repeat(bar.saturating_sub(filled)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(usage, Style::default().fg(Colors::TEXT)),
+            ])
+        })
         .collect();
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+fn draw_memory_chart<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    // Memory data: convert history to (x, y) data pairs
+    let total_memory = app.system_resources.total_memory.max(1) as f64;
+    let series = history_series(
+        &app.system_history,
+        app.system_resources
+            .memory_history
+            .iter()
+            .map(|&m| m as f64),
+        |s| s.memory as f64 / total_memory * 100.0,
+    );
+    let scrub = scrub_point(app, &series);
 
     // Create dataset
-    let datasets = vec![Dataset::default()
+    let mut datasets = vec![Dataset::default()
         .name("Memory %")
         .marker(Marker::Braille)
         .style(Style::default().fg(Colors::MEMORY))
-        .data(&data)];
+        .data(&series.data)];
+    // Swap is charted alongside, but only over the last minute in memory
+    // since the history database doesn't keep it
+    let resources = &app.system_resources;
+    let swap_data: Vec<(f64, f64)> = resources
+        .swap_history
+        .iter()
+        .enumerate()
+        .map(|(i, &s)| (i as f64, s as f64))
+        .collect();
+    if resources.total_swap > 0 && series.span.is_empty() {
+        datasets.push(
+            Dataset::default()
+                .name("Swap %")
+                .marker(Marker::Braille)
+                .style(Style::default().fg(Colors::WARNING))
+                .data(&swap_data),
+        );
+    }
+    if let Some(point) = &scrub {
+        datasets.push(scrub_marker(&point.line));
+    }
 
     // Memory usage information
-    let memory_percent = app.system_resources.memory_percentage();
-    let used_gb = app.system_resources.used_memory as f64 / 1024.0 / 1024.0 / 1024.0;
-    let total_gb = app.system_resources.total_memory as f64 / 1024.0 / 1024.0 / 1024.0;
+    let memory_percent = resources.memory_percentage();
+    let memory_used = app
+        .config
+        .units
+        .fraction(resources.used_memory, resources.total_memory);
+    let swap_used = if resources.total_swap > 0 {
+        format!(
+            " · Swap: {:.1}% ({})",
+            resources.swap_percentage(),
+            app.config
+                .units
+                .fraction(resources.used_swap, resources.total_swap)
+        )
+    } else {
+        String::new()
+    };
 
     // Create chart
     let chart = Chart::new(datasets)
         .block(
             Block::default()
                 .title(Span::styled(
-                    format!(
-                        " Memory: {:.1}% ({:.1}/{:.1} GB) ",
-                        memory_percent, used_gb, total_gb
-                    ),
+                    match &scrub {
+                        Some(point) => format!(" Memory{}: {:.1}% ", point.label, point.value),
+                        None => format!(
+                            " Memory: {:.1}% ({}){}{} ",
+                            memory_percent, memory_used, swap_used, series.span
+                        ),
+                    },
                     Style::default()
                         .fg(Colors::HEADER)
                         .add_modifier(Modifier::BOLD),
@@ -251,8 +819,8 @@ fn draw_memory_chart<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
         .x_axis(
             Axis::default()
                 .style(Style::default().fg(Colors::TEXT))
-                .bounds([0.0, 60.0])
-                .labels(vec![]),
+                .bounds(series.bounds)
+                .labels(series.labels.clone()),
         )
         .y_axis(
             Axis::default()
@@ -268,13 +836,92 @@ fn draw_memory_chart<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
     f.render_widget(chart, area);
 }
 
+// Points of a history chart. Stored samples sit at their age in seconds so
+// the x axis reads back to now; in-memory ones are spaced by index.
+struct HistorySeries {
+    data: Vec<(f64, f64)>,
+    bounds: [f64; 2],
+    labels: Vec<Span<'static>>,
+    // How far back stored history goes, for the chart title
+    span: String,
+}
+
+fn history_series(
+    stored: &[HistorySample],
+    live: impl Iterator<Item = f64>,
+    value: impl Fn(&HistorySample) -> f64,
+) -> HistorySeries {
+    if stored.len() < 2 {
+        return HistorySeries {
+            data: live.enumerate().map(|(i, v)| (i as f64, v)).collect(),
+            bounds: [0.0, 60.0],
+            labels: vec![],
+            span: String::new(),
+        };
+    }
+
+    let now = history::now();
+    let start = stored[0].time - now;
+    let ago = format_duration(Duration::from_secs(start.unsigned_abs()));
+    HistorySeries {
+        data: stored
+            .iter()
+            .map(|s| ((s.time - now) as f64, value(s)))
+            .collect(),
+        bounds: [start as f64, 0.0],
+        labels: vec![
+            Span::styled(format!("-{}", ago), Style::default().fg(Colors::TEXT)),
+            Span::styled("now", Style::default().fg(Colors::TEXT)),
+        ],
+        span: format!(" (last {})", ago),
+    }
+}
+
+// The point charts are scrubbed back to, with a vertical line marking it
+struct ScrubPoint {
+    index: usize,
+    value: f64,
+    line: [(f64, f64); 2],
+    // " @ 14:03:22 (5m 0s ago)"
+    label: String,
+}
+
+fn scrub_point(app: &App, series: &HistorySeries) -> Option<ScrubPoint> {
+    let times = app.chart_times();
+    let index = app.scrub_index(&times)?;
+    let &(x, value) = series.data.get(index)?;
+    let time = times[index];
+    let clock = Local
+        .timestamp_opt(time, 0)
+        .single()
+        .map_or(String::new(), |t| t.format("%H:%M:%S").to_string());
+    let ago = Duration::from_secs((history::now() - time).max(0) as u64);
+    Some(ScrubPoint {
+        index,
+        value,
+        line: [(x, 0.0), (x, 100.0)],
+        label: format!(" @ {} ({} ago)", clock, format_duration(ago)),
+    })
+}
+
+fn scrub_marker(line: &[(f64, f64)]) -> Dataset<'_> {
+    Dataset::default()
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(Colors::HIGHLIGHT))
+        .data(line)
+}
+
 fn draw_top_cpu_processes<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
     let (top_cpu, _) = app.top_processes(5);
 
     // Get the CPU usage percentages and process names
     let data: Vec<(&str, u64)> = top_cpu
         .iter()
-        .map(|p| (p.name.as_str(), p.cpu_usage.round() as u64))
+        .map(|p| {
+            let cpu = p.cpu_usage / app.cpu_divisor();
+            (p.name.as_str(), cpu.round() as u64)
+        })
         .collect();
 
     // Create bar chart data
@@ -309,12 +956,11 @@ fn draw_top_memory_processes<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect
 
     // Create rows for each top memory process
     let rows = top_mem.iter().map(|p| {
-        let memory_mb = p.memory / 1024 / 1024;
         let memory_percent = (p.memory as f64 / app.system_resources.total_memory as f64) * 100.0;
 
         Row::new(vec![
             Cell::from(format!("{:.1}", memory_percent)).style(Style::default().fg(Colors::TEXT)),
-            Cell::from(format!("{}MB", memory_mb)).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(app.config.units.bytes(p.memory)).style(Style::default().fg(Colors::TEXT)),
             Cell::from(p.name.clone()).style(Style::default().fg(Colors::TEXT)),
         ])
     });
@@ -349,58 +995,83 @@ fn draw_top_memory_processes<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect
     f.render_widget(table, area);
 }
 
-fn draw_processes_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
-    // Create table header with sort indicators
-    let header_cells = vec![
-        create_header_cell("PID", SortKey::Pid, app.sort_key, app.sort_ascending),
-        create_header_cell("Name", SortKey::Name, app.sort_key, app.sort_ascending),
-        create_header_cell("CPU%", SortKey::Cpu, app.sort_key, app.sort_ascending),
-        create_header_cell("Memory", SortKey::Memory, app.sort_key, app.sort_ascending),
-        create_header_cell("Status", SortKey::Status, app.sort_key, app.sort_ascending),
-        create_header_cell("User", SortKey::User, app.sort_key, app.sort_ascending),
-        create_header_cell(
-            "Started",
-            SortKey::StartTime,
-            app.sort_key,
-            app.sort_ascending,
-        ),
-    ];
-
-    let header = Row::new(header_cells).style(Style::default().add_modifier(Modifier::BOLD));
+fn draw_processes_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) -> MouseRegions {
+    // Energy columns only appear where the platform reports them (macOS), and
+    // the container and unit columns when something runs in a container or
+    // under systemd, and the script and plugin columns when a script or
+    // plugin computes one, GPU memory when there's a GPU to report it, and
+    // the NUMA node when there's more than one, and the kernel statistics
+    // when they're collected
+    let show_energy = app.shown_processes().any(|p| p.power.is_some());
+    let show_syscalls = app.shown_processes().any(|p| p.syscall_rate.is_some());
+    let show_blkio = app.shown_processes().any(|p| p.blkio_delay_rate.is_some());
+    let show_latency = app.shown_processes().any(|p| p.blkio_latency.is_some());
+    let show_retransmits = app
+        .shown_processes()
+        .any(|p| p.tcp_retransmit_rate.is_some());
+    let show_container = app.shown_processes().any(|p| p.container.is_some());
+    let show_unit = app.shown_processes().any(|p| p.unit.is_some());
+    let mut columns: Vec<Column> = app
+        .columns()
+        .into_iter()
+        .filter(|c| show_energy || !matches!(c, Column::Power | Column::Wakeups))
+        .filter(|c| show_syscalls || *c != Column::Syscalls)
+        .filter(|c| show_blkio || *c != Column::BlkioDelay)
+        .filter(|c| show_latency || *c != Column::BlkioLatency)
+        .filter(|c| show_retransmits || *c != Column::Retransmits)
+        .filter(|c| show_container || *c != Column::Container)
+        .filter(|c| !app.gpus.is_empty() || *c != Column::GpuMemory)
+        .filter(|c| !app.numa_nodes.is_empty() || *c != Column::Node)
+        .filter(|c| show_unit || *c != Column::Unit)
+        .filter(|c| !app.script_results.columns.is_empty() || *c != Column::Script)
+        .filter(|c| match c {
+            Column::Plugin(index) => app.plugin_cells.get(*index).is_some_and(|c| !c.is_empty()),
+            _ => true,
+        })
+        .collect();
 
-    // Create rows with process information
-    let rows = app.processes.iter().map(|p| {
-        // Color code CPU usage
-        let cpu_style = if p.cpu_usage > 50.0 {
-            Style::default().fg(Colors::ERROR)
-        } else if p.cpu_usage > 20.0 {
-            Style::default().fg(Colors::WARNING)
-        } else {
-            Style::default().fg(Colors::TEXT)
-        };
+    // Columns that don't fit are dropped from the right rather than squeezed
+    let all_widths: Vec<Constraint> = columns.iter().map(|&c| column_width(c)).collect();
+    let shown = fitting_columns(area, &all_widths);
+    let hidden = columns.len() - shown;
+    columns.truncate(shown);
+    let widths = &all_widths[..shown];
+    let keys: Vec<Option<SortKey>> = columns.iter().map(|c| c.sort_key()).collect();
+    let laid_out = layout_columns(area, widths);
 
-        // Color code memory usage
-        let memory_mb = p.memory / 1024 / 1024;
-        let memory_style = if memory_mb > 1024 {
-            Style::default().fg(Colors::ERROR)
-        } else if memory_mb > 512 {
-            Style::default().fg(Colors::WARNING)
-        } else {
-            Style::default().fg(Colors::TEXT)
-        };
+    // Create table header with sort indicators
+    let header_cells: Vec<Cell> = columns
+        .iter()
+        .map(|column| {
+            let title = column_title(app, *column);
+            match column.sort_key() {
+                Some(key) => create_header_cell(&title, key, app.sort_key, app.sort_ascending),
+                None => Cell::from(title).style(
+                    Style::default()
+                        .fg(Colors::HEADER)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            }
+        })
+        .collect();
+    let header = Row::new(header_cells).style(Style::default().add_modifier(Modifier::BOLD));
 
-        // Format process uptime
-        let uptime = format_duration(p.start_time);
+    // Only the rows in the viewport are built and rendered
+    let visible = app
+        .processes
+        .iter()
+        .skip(app.table_offset)
+        .take(app.page_size)
+        .filter_map(|&pid| app.process(pid));
 
-        Row::new(vec![
-            Cell::from(p.pid.to_string()).style(Style::default().fg(Colors::TEXT)),
-            Cell::from(p.name.clone()).style(Style::default().fg(Colors::TEXT)),
-            Cell::from(format!("{:.1}%", p.cpu_usage)).style(cpu_style),
-            Cell::from(format!("{}MB", memory_mb)).style(memory_style),
-            Cell::from(p.status.to_string()).style(Style::default().fg(Colors::TEXT)),
-            Cell::from(p.user.clone()).style(Style::default().fg(Colors::TEXT)),
-            Cell::from(uptime).style(Style::default().fg(Colors::TEXT)),
-        ])
+    // Create rows with process information
+    let rows = visible.clone().map(|p| {
+        let cells: Vec<Cell> = columns
+            .iter()
+            .zip(&laid_out)
+            .map(|(&column, &width)| process_cell(app, p, column, width))
+            .collect();
+        Row::new(cells).style(row_style(app, p))
     });
 
     // Create table with header and rows
@@ -409,7 +1080,19 @@ fn draw_processes_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
         .block(
             Block::default()
                 .title(Span::styled(
-                    format!(" Processes ({}) ", app.processes.len()),
+                    format!(
+                        " Processes{} ({}){} ",
+                        match app.view_mode {
+                            ViewMode::Flat => "",
+                            ViewMode::Tree => " (tree)",
+                            ViewMode::Grouped => " (grouped)",
+                        },
+                        position_label(
+                            (!app.processes.is_empty()).then_some(app.selected_index),
+                            app.processes.len()
+                        ),
+                        hidden_label(hidden)
+                    ),
                     Style::default()
                         .fg(Colors::HEADER)
                         .add_modifier(Modifier::BOLD),
@@ -423,101 +1106,846 @@ fn draw_processes_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol("➤ ")
-        .widths(&[
-            Constraint::Length(8),
-            Constraint::Percentage(25),
-            Constraint::Length(8),
-            Constraint::Length(10),
-            Constraint::Length(10),
-            Constraint::Length(12),
-            Constraint::Percentage(15),
-        ]);
+        .widths(widths);
 
     // Create table state
     let mut state = ratatui::widgets::TableState::default();
 
-    // Set selected item
+    // Set selected item, relative to the first visible row
     if !app.processes.is_empty() {
-        state.select(Some(app.selected_index));
+        state.select(Some(app.selected_index - app.table_offset));
     }
 
     // Render table
     f.render_stateful_widget(table, area, &mut state);
-}
-
-fn create_header_cell(text: &str, key: SortKey, current_sort: SortKey, ascending: bool) -> Cell {
-    let is_selected = key == current_sort;
-    let display_text = if is_selected {
-        format!("{} {}", text, if ascending { "↑" } else { "↓" })
-    } else {
-        text.to_string()
-    };
 
-    Cell::from(display_text).style(
-        Style::default()
-            .fg(if is_selected {
-                Colors::HIGHLIGHT
-            } else {
-                Colors::HEADER
-            })
-            .add_modifier(Modifier::BOLD),
-    )
+    let pids: Vec<u32> = visible.map(|p| p.pid).collect();
+    table_mouse_regions(area, widths, &keys, &pids, &state)
 }
 
-fn draw_user_processes_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
-    // Filter processes owned by the current user
-    let current_user = if cfg!(unix) {
-        std::env::var("USER").unwrap_or_else(|_| "unknown".to_string())
-    } else {
-        std::env::var("USERNAME").unwrap_or_else(|_| "unknown".to_string())
-    };
+// Checklist of the processes table columns, drawn over the content area while
+// the picker is open
+fn draw_column_picker<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let width = 30.min(area.width);
+    let height = (app.column_picker.len() as u16 + 2).min(area.height);
+    let popup_area = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
 
-    let user_processes: Vec<_> = app
-        .processes
+    let lines: Vec<Spans> = app
+        .column_picker
         .iter()
-        .filter(|p| p.user == current_user)
-        .collect();
-
+        .enumerate()
+        .map(|(index, (column, shown))| {
+            let style = if index == app.column_cursor {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .fg(Colors::HIGHLIGHT)
+                    .add_modifier(Modifier::BOLD)
+            } else if *shown {
+                Style::default().fg(Colors::TEXT)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            let text = format!(
+                " [{}] {}",
+                if *shown { 'x' } else { ' ' },
+                column_title(app, *column)
+            );
+            Spans::from(Span::styled(
+                format!("{:<1$}", text, width.saturating_sub(2) as usize),
+                style,
+            ))
+        })
+        .collect();
+
+    // Keep the cursor in view when the list is taller than the popup
+    let scroll = (app.column_cursor as u16 + 1).saturating_sub(height.saturating_sub(2));
+
+    let picker = Paragraph::new(lines).scroll((scroll, 0)).block(
+        Block::default()
+            .title(Span::styled(
+                " Columns ",
+                Style::default()
+                    .fg(Colors::HEADER)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Colors::HIGHLIGHT)),
+    );
+    f.render_widget(Clear, popup_area);
+    f.render_widget(picker, popup_area);
+}
+
+// A dialog centred over the main area, sized to its content
+// The --debug timings, in the top right corner of the content. The frame
+// time is the previous frame's, as this one is still being drawn; the
+// maximums are over the last second.
+fn draw_debug_overlay<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let Some(debug) = &app.debug else {
+        return;
+    };
+    let millis = |duration: Duration| format!("{:.1}ms", duration.as_secs_f64() * 1000.0);
+    let collect = app
+        .collector
+        .map_or("-".to_string(), |stats| millis(stats.collect_time));
+    let rows = [
+        ("FPS", debug.fps().to_string()),
+        ("Frame", millis(debug.frame_time)),
+        ("Loop max", millis(debug.loop_time())),
+        ("Backlog max", debug.backlog().to_string()),
+        ("Collect", collect),
+    ];
+
+    let width = 24;
+    let height = rows.len() as u16 + 2;
+    if area.width < width + 2 || area.height < height + 1 {
+        return;
+    }
+    let popup = Rect::new(area.x + area.width - width - 1, area.y + 1, width, height);
+    let lines: Vec<Spans> = rows
+        .into_iter()
+        .map(|(label, value)| {
+            Spans::from(vec![
+                Span::styled(
+                    format!(" {:<12}", label),
+                    Style::default().fg(Colors::HEADER),
+                ),
+                Span::styled(value, Style::default().fg(Colors::TEXT)),
+            ])
+        })
+        .collect();
+    let overlay = Paragraph::new(lines).block(
+        Block::default()
+            .title(Span::styled(" Debug ", Style::default().fg(Colors::HEADER)))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Colors::BORDER)),
+    );
+    f.render_widget(Clear, popup);
+    f.render_widget(overlay, popup);
+}
+
+fn draw_dialog<B: Backend>(f: &mut Frame<B>, dialog: &Dialog, area: Rect) {
+    let mut lines: Vec<Spans> = dialog
+        .message
+        .lines()
+        .map(|line| {
+            Spans::from(Span::styled(
+                format!(" {}", line),
+                Style::default().fg(Colors::TEXT),
+            ))
+        })
+        .collect();
+    lines.push(Spans::from(""));
+
+    let key_style = Style::default().fg(Color::DarkGray);
+    let mut selected_line = 0;
+    match &dialog.kind {
+        DialogKind::Message => lines.push(Spans::from(Span::styled(
+            " Press any key to close",
+            key_style,
+        ))),
+        DialogKind::Confirm => lines.push(Spans::from(Span::styled(
+            " Enter/y: Yes | Esc/n: No",
+            key_style,
+        ))),
+        DialogKind::Input(text) => {
+            lines.push(Spans::from(Span::styled(
+                format!(" > {}_", text),
+                Style::default()
+                    .fg(Colors::HIGHLIGHT)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            lines.push(Spans::from(""));
+            lines.push(Spans::from(Span::styled(
+                " Enter: Apply | Esc: Cancel",
+                key_style,
+            )));
+        }
+        DialogKind::List { options, selected } => {
+            for (index, option) in options.iter().enumerate() {
+                let style = if index == *selected {
+                    selected_line = lines.len();
+                    Style::default()
+                        .bg(Color::DarkGray)
+                        .fg(Colors::HIGHLIGHT)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Colors::TEXT)
+                };
+                lines.push(Spans::from(Span::styled(format!(" {} ", option), style)));
+            }
+            lines.push(Spans::from(""));
+            lines.push(Spans::from(Span::styled(
+                " ↑/↓: Choose | Enter: Send | Esc: Cancel",
+                key_style,
+            )));
+        }
+    }
+
+    let content_width = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16;
+    let width = (content_width.max(dialog.title.len() as u16 + 2) + 4).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup_area = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    // Keep the selected option in view when the list is taller than the popup
+    let scroll = (selected_line as u16 + 1).saturating_sub(height.saturating_sub(2));
+
+    let popup = Paragraph::new(lines).scroll((scroll, 0)).block(
+        Block::default()
+            .title(Span::styled(
+                format!(" {} ", dialog.title),
+                Style::default()
+                    .fg(Colors::HEADER)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Colors::HIGHLIGHT)),
+    );
+    f.render_widget(Clear, popup_area);
+    f.render_widget(popup, popup_area);
+}
+
+fn column_width(column: Column) -> Constraint {
+    match column {
+        Column::Pid => Constraint::Length(8),
+        Column::Name => Constraint::Percentage(20),
+        Column::Cpu => Constraint::Length(8),
+        Column::Memory
+        | Column::Virtual
+        | Column::Shared
+        | Column::Swap
+        | Column::Pss
+        | Column::Uss
+        | Column::GpuMemory => Constraint::Length(11),
+        Column::Status => Constraint::Length(10),
+        Column::Nice | Column::Node => Constraint::Length(6),
+        Column::Policy => Constraint::Length(9),
+        Column::RtPriority => Constraint::Length(8),
+        Column::User | Column::Started => Constraint::Length(12),
+        Column::StartedAt => Constraint::Length(13),
+        Column::DiskRead | Column::DiskWrite | Column::NetDown | Column::NetUp => {
+            Constraint::Length(13)
+        }
+        Column::Power => Constraint::Length(9),
+        Column::BlkioDelay => Constraint::Length(10),
+        Column::BlkioLatency => Constraint::Length(13),
+        Column::Retransmits => Constraint::Length(11),
+        Column::Wakeups => Constraint::Length(12),
+        Column::Syscalls => Constraint::Length(13),
+        Column::Parent => Constraint::Length(8),
+        Column::Threads => Constraint::Length(10),
+        Column::Fds => Constraint::Length(14),
+        Column::Command => Constraint::Percentage(30),
+        Column::Container => Constraint::Length(16),
+        Column::Unit => Constraint::Length(24),
+        Column::Script | Column::Plugin(_) => Constraint::Length(20),
+        Column::Computed(_) => Constraint::Length(12),
+    }
+}
+
+// One cell of the processes table; text columns are cut to `width` with an
+// ellipsis so truncation is visible
+fn process_cell(app: &App, p: &ProcessInfo, column: Column, width: u16) -> Cell<'static> {
+    let text_style = Style::default().fg(Colors::TEXT);
+    // A collapsed tree row stands for its whole subtree
+    let (cpu_usage, memory) = match app.tree_rows.get(&p.pid) {
+        Some(row) if row.collapsed => (row.cpu_total, row.memory_total),
+        _ => (p.cpu_usage, p.memory),
+    };
+    let cpu_usage = cpu_usage / app.cpu_divisor();
+    match column {
+        Column::Pid => pid_cell(app, p),
+        Column::Name => name_cell(app, p, width),
+        Column::Cpu => {
+            // Color code CPU usage
+            let cpu_style = if cpu_usage > 50.0 {
+                Style::default().fg(Colors::ERROR)
+            } else if cpu_usage > 20.0 {
+                Style::default().fg(Colors::WARNING)
+            } else {
+                text_style
+            };
+            Cell::from(format!("{:.1}%", cpu_usage)).style(cpu_style)
+        }
+        Column::Memory => {
+            // Color code memory usage
+            let memory_mb = memory / 1024 / 1024;
+            let memory_style = if memory_mb > 1024 {
+                Style::default().fg(Colors::ERROR)
+            } else if memory_mb > 512 {
+                Style::default().fg(Colors::WARNING)
+            } else {
+                text_style
+            };
+            Cell::from(app.config.units.bytes(memory)).style(memory_style)
+        }
+        Column::Virtual => Cell::from(app.config.units.bytes(p.virtual_memory)).style(text_style),
+        Column::Shared => Cell::from(
+            p.shared_memory
+                .map_or("-".to_string(), |b| app.config.units.bytes(b)),
+        )
+        .style(text_style),
+        Column::Swap => Cell::from(
+            p.swap
+                .map_or("-".to_string(), |b| app.config.units.bytes(b)),
+        )
+        .style(text_style),
+        Column::Pss => Cell::from(p.pss.map_or("-".to_string(), |b| app.config.units.bytes(b)))
+            .style(text_style),
+        Column::Uss => Cell::from(p.uss.map_or("-".to_string(), |b| app.config.units.bytes(b)))
+            .style(text_style),
+        Column::Status => {
+            // Zombies stand out since they need their parent dealt with
+            let status_style = if p.status == ProcessStatus::Zombie {
+                Style::default()
+                    .fg(Colors::ERROR)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                text_style
+            };
+            Cell::from(p.status.to_string()).style(status_style)
+        }
+        Column::Nice => Cell::from(p.nice.to_string()).style(text_style),
+        Column::Policy => Cell::from(
+            p.sched_policy
+                .map_or("-".to_string(), |policy| policy.to_string()),
+        )
+        .style(scheduling_style(p, text_style)),
+        Column::RtPriority => Cell::from(
+            p.rt_priority
+                .map_or("-".to_string(), |priority| priority.to_string()),
+        )
+        .style(scheduling_style(p, text_style)),
+        Column::User => match_cell(app, ellipsize(&p.user, width), text_style),
+        Column::Started => Cell::from(format_duration(p.start_time)).style(text_style),
+        Column::StartedAt => Cell::from(format_started_at(p.started_at)).style(text_style),
+        Column::DiskRead => Cell::from(app.config.units.rate(p.disk_read_rate)).style(text_style),
+        Column::DiskWrite => Cell::from(app.config.units.rate(p.disk_write_rate)).style(text_style),
+        Column::NetDown => Cell::from(
+            p.net_rx_rate
+                .map_or("-".to_string(), |r| app.config.units.rate(r)),
+        )
+        .style(text_style),
+        Column::NetUp => Cell::from(
+            p.net_tx_rate
+                .map_or("-".to_string(), |r| app.config.units.rate(r)),
+        )
+        .style(text_style),
+        Column::GpuMemory => Cell::from(
+            p.gpu_memory
+                .map_or("-".to_string(), |b| app.config.units.bytes(b)),
+        )
+        .style(Style::default().fg(Colors::MEMORY)),
+        Column::Node => Cell::from(p.numa_node.map_or("-".to_string(), |node| node.to_string()))
+            .style(text_style),
+        Column::Power => {
+            Cell::from(p.power.map_or("-".to_string(), format_power)).style(text_style)
+        }
+        Column::Wakeups => Cell::from(
+            p.wakeups_rate
+                .map_or("-".to_string(), |w| format!("{:.0}", w)),
+        )
+        .style(text_style),
+        Column::Syscalls => {
+            Cell::from(p.syscall_rate.map_or("-".to_string(), |r| r.to_string())).style(text_style)
+        }
+        Column::BlkioDelay => Cell::from(
+            p.blkio_delay_rate
+                .map_or("-".to_string(), |ms| format!("{} ms/s", ms)),
+        )
+        .style(text_style),
+        Column::BlkioLatency => Cell::from(
+            p.blkio_latency
+                .map_or("-".to_string(), |micros| format!("{} µs", micros)),
+        )
+        .style(text_style),
+        Column::Retransmits => Cell::from(
+            p.tcp_retransmit_rate
+                .map_or("-".to_string(), |r| r.to_string()),
+        )
+        .style(text_style),
+        Column::Parent => {
+            Cell::from(p.parent.map_or("-".to_string(), |pid| pid.to_string())).style(text_style)
+        }
+        Column::Threads => {
+            Cell::from(p.threads.map_or("-".to_string(), |n| n.to_string())).style(text_style)
+        }
+        Column::Fds => {
+            // Turns yellow, then red, as the process runs out of descriptors
+            let (text, used) = match (p.fds, p.fd_limit) {
+                (Some(fds), Some(limit)) if limit > 0 => {
+                    (format!("{}/{}", fds, limit), fds as f64 / limit as f64)
+                }
+                (Some(fds), _) => (fds.to_string(), 0.0),
+                (None, _) => ("-".to_string(), 0.0),
+            };
+            let style = if used >= 0.9 {
+                Style::default().fg(Colors::ERROR)
+            } else if used >= 0.7 {
+                Style::default().fg(Colors::WARNING)
+            } else {
+                text_style
+            };
+            Cell::from(text).style(style)
+        }
+        Column::Command => {
+            // Kernel threads have no command line, so fall back to the name
+            let command = if p.cmd.is_empty() {
+                format!("[{}]", p.name)
+            } else {
+                p.cmd.join(" ")
+            };
+            Cell::from(ellipsize(&command, width)).style(text_style)
+        }
+        Column::Container => match &p.container {
+            Some(container) => match_cell(app, ellipsize(container, width), text_style),
+            None => Cell::from("-").style(text_style),
+        },
+        Column::Unit => match &p.unit {
+            Some(unit) => match_cell(app, ellipsize(unit, width), text_style),
+            None => Cell::from("-").style(text_style),
+        },
+        Column::Script => match app.script_results.columns.get(&p.pid) {
+            Some(value) if !value.is_empty() => {
+                Cell::from(ellipsize(value, width)).style(text_style)
+            }
+            _ => Cell::from("-").style(text_style),
+        },
+        Column::Plugin(index) => match app.plugin_cells[index].get(&p.pid) {
+            Some(value) => Cell::from(ellipsize(value, width)).style(text_style),
+            None => Cell::from("-").style(text_style),
+        },
+        Column::Computed(index) => {
+            match app
+                .computed_values
+                .get(&p.pid)
+                .and_then(|v| v.get(index)?.as_ref())
+            {
+                Some(value) => Cell::from(ellipsize(&value.to_string(), width)).style(text_style),
+                None => Cell::from("-").style(text_style),
+            }
+        }
+    }
+}
+
+// Column widths as the table lays them out inside `area`: the highlight
+// symbol first, then one column of spacing between columns
+fn layout_columns(area: Rect, widths: &[Constraint]) -> Vec<u16> {
+    let mut constraints = vec![Constraint::Length(2)];
+    for width in widths {
+        constraints.push(*width);
+        constraints.push(Constraint::Length(1));
+    }
+    // Trailing filler so the last column isn't stretched to the edge
+    constraints.push(Constraint::Min(0));
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(Rect::new(0, 0, area.width.saturating_sub(2), 1))[1..]
+        .iter()
+        .step_by(2)
+        .map(|c| c.width)
+        .collect()
+}
+
+// How many leading columns fit without any fixed-width column being cut
+// short; the first column is always kept
+fn fitting_columns(area: Rect, widths: &[Constraint]) -> usize {
+    let mut count = widths.len();
+    while count > 1 {
+        let laid_out = layout_columns(area, &widths[..count]);
+        let fits =
+            widths[..count]
+                .iter()
+                .zip(&laid_out)
+                .all(|(constraint, width)| match constraint {
+                    Constraint::Length(length) => width >= length,
+                    _ => true,
+                });
+        if fits {
+            break;
+        }
+        count -= 1;
+    }
+    count
+}
+
+// Tells the reader the table has more columns than the terminal shows
+fn hidden_label(hidden: usize) -> String {
+    match hidden {
+        0 => String::new(),
+        1 => " · 1 column hidden".to_string(),
+        n => format!(" · {} columns hidden", n),
+    }
+}
+
+// Shorten text to `width` characters, marking the cut with an ellipsis
+fn ellipsize(text: &str, width: u16) -> String {
+    let width = width as usize;
+    if text.chars().count() <= width {
+        text.to_string()
+    } else if width == 0 {
+        String::new()
+    } else {
+        let mut shortened: String = text.chars().take(width - 1).collect();
+        shortened.push('…');
+        shortened
+    }
+}
+
+// "153/420" when a row is selected, otherwise just the number of rows
+fn position_label(selected: Option<usize>, len: usize) -> String {
+    match selected {
+        Some(index) => format!("{}/{}", index + 1, len),
+        None => len.to_string(),
+    }
+}
+
+// Where the sortable headers and visible rows of a rendered process table
+// landed, mirroring the table's own column layout (highlight symbol first, then
+// one column of spacing between columns)
+fn table_mouse_regions(
+    area: Rect,
+    widths: &[Constraint],
+    keys: &[Option<SortKey>],
+    pids: &[u32],
+    state: &TableState,
+) -> MouseRegions {
+    let inner = Rect::new(
+        area.x + 1,
+        area.y + 1,
+        area.width.saturating_sub(2),
+        area.height.saturating_sub(2),
+    );
+
+    // The highlight symbol column only exists while a row is selected
+    let highlight_width = if state.selected().is_some() { 2 } else { 0 };
+    let mut constraints = vec![Constraint::Length(highlight_width)];
+    for width in widths {
+        constraints.push(*width);
+        constraints.push(Constraint::Length(1));
+    }
+    // Trailing filler so the last column isn't stretched to the edge
+    constraints.push(Constraint::Min(0));
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(Rect::new(inner.x, inner.y, inner.width, 1));
+
+    let headers = columns[1..]
+        .iter()
+        .step_by(2)
+        .zip(keys)
+        .filter_map(|(rect, key)| key.map(|key| (*rect, key)))
+        .collect();
+
+    let rows = pids
+        .iter()
+        .skip(state.offset())
+        .take(inner.height.saturating_sub(1) as usize)
+        .enumerate()
+        .map(|(i, pid)| {
+            (
+                Rect::new(inner.x, inner.y + 1 + i as u16, inner.width, 1),
+                *pid,
+            )
+        })
+        .collect();
+
+    MouseRegions {
+        headers,
+        rows,
+        ..MouseRegions::default()
+    }
+}
+
+// Tagged rows get a `*` and pinned rows a `^` next to the PID, and both are
+// highlighted in the PID and name columns
+fn pid_cell(app: &App, process: &ProcessInfo) -> Cell<'static> {
+    let marker = if app.tagged.contains(&process.pid) {
+        "*"
+    } else if app.is_pinned(process) {
+        "^"
+    } else {
+        ""
+    };
+    let style = mark_style(app, process);
+    let mut spans = match_spans(app, process.pid.to_string(), style);
+    spans.0.insert(0, Span::styled(marker, style));
+    Cell::from(spans)
+}
+
+// In the tree view names are indented under their parent, and parents are
+// marked ▾ when expanded or ▸ when collapsed, followed by how many processes
+// the row folds in. Grouped rows count the whole group instead.
+fn name_cell(app: &App, p: &ProcessInfo, width: u16) -> Cell<'static> {
+    let style = mark_style(app, p);
+    let Some(row) = app.tree_rows.get(&p.pid) else {
+        return match_cell(app, ellipsize(&p.name, width), style);
+    };
+
+    let marker = if !row.has_children {
+        "  "
+    } else if row.collapsed {
+        "▸ "
+    } else {
+        "▾ "
+    };
+    let prefix = format!("{}{}", "  ".repeat(row.depth), marker);
+    let suffix = if row.collapsed && app.view_mode == ViewMode::Grouped {
+        format!(" ({})", row.descendants + 1)
+    } else if row.collapsed {
+        format!(" (+{})", row.descendants)
+    } else {
+        String::new()
+    };
+    let room = (width as usize).saturating_sub(prefix.chars().count() + suffix.len());
+
+    let mut spans = match_spans(app, ellipsize(&p.name, room as u16), style);
+    spans.0.insert(
+        0,
+        Span::styled(prefix, Style::default().fg(Color::DarkGray)),
+    );
+    spans
+        .0
+        .push(Span::styled(suffix, Style::default().fg(Color::DarkGray)));
+    Cell::from(spans)
+}
+
+// A cell with the part of the text that matched the filter picked out, so
+// it's clear why the row is listed
+fn match_cell(app: &App, text: String, style: Style) -> Cell<'static> {
+    Cell::from(match_spans(app, text, style))
+}
+
+fn match_spans(app: &App, text: String, style: Style) -> Spans<'static> {
+    match app
+        .filter_pattern
+        .as_ref()
+        // Socket filters match nothing in the table's own columns
+        .filter(|filter| !filter.field.is_some_and(|field| field.is_socket()))
+        .and_then(|filter| filter.pattern.find(&text))
+    {
+        Some(found) if !found.as_str().is_empty() => Spans::from(vec![
+            Span::styled(text[..found.start()].to_string(), style),
+            Span::styled(
+                found.as_str().to_string(),
+                style
+                    .fg(Color::Black)
+                    .bg(Colors::HIGHLIGHT)
+                    // The selected row's highlight replaces the background
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            ),
+            Span::styled(text[found.end()..].to_string(), style),
+        ]),
+        _ => Spans::from(Span::styled(text, style)),
+    }
+}
+
+// " Tasks: 312 total, 2 running, 309 sleeping, 0 stopped, 1 zombie", over
+// every process regardless of tab or filter, with zombies in red
+fn task_summary(app: &App) -> Spans<'static> {
+    let (mut running, mut sleeping, mut stopped, mut zombie) = (0, 0, 0, 0);
+    for p in app.all_processes.values() {
+        match p.status {
+            ProcessStatus::Running => running += 1,
+            ProcessStatus::Sleeping => sleeping += 1,
+            ProcessStatus::Stopped => stopped += 1,
+            ProcessStatus::Zombie => zombie += 1,
+            ProcessStatus::Unknown => {}
+        }
+    }
+
+    let text = Style::default().fg(Colors::TEXT);
+    let zombie_style = if zombie > 0 {
+        Style::default()
+            .fg(Colors::ERROR)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        text
+    };
+    Spans::from(vec![
+        Span::styled(" Tasks: ", Style::default().fg(Colors::HEADER)),
+        Span::styled(
+            format!(
+                "{} total, {} running, {} sleeping, {} stopped, ",
+                app.all_processes.len(),
+                running,
+                sleeping,
+                stopped
+            ),
+            text,
+        ),
+        Span::styled(format!("{} zombie", zombie), zombie_style),
+    ])
+}
+
+// The CPU and memory of the psr collecting the processes (this one, or the
+// agent) and how long its last refresh took
+fn monitor_usage(app: &App) -> Option<String> {
+    let stats = app.collector?;
+    let mut parts = Vec::new();
+    if let Some(process) = app.all_processes.get(&stats.pid) {
+        parts.push(format!("{:.1}% CPU", process.cpu_usage));
+        parts.push(app.config.units.bytes(process.memory));
+    }
+    parts.push(format!("{}ms/refresh", stats.collect_time.as_millis()));
+    if stats.ebpf {
+        parts.push("eBPF".to_string());
+    }
+    Some(format!(" psr: {} ", parts.join(", ")))
+}
+
+// The Alerts tab counts what's currently firing
+fn tab_title(app: &App, title: &str) -> String {
+    match app.alerts.active.len() {
+        n if title == "Alerts" && n > 0 => format!("{} ({})", title, n),
+        _ => title.to_string(),
+    }
+}
+
+// Rows of processes with a firing alert get a red background
+fn row_style(app: &App, process: &ProcessInfo) -> Style {
+    if app.alerts.is_alerting(process.pid) {
+        Style::default().bg(Color::Rgb(90, 20, 20))
+    } else if app.exiting.contains_key(&process.pid) {
+        // Exited, and about to leave the list
+        Style::default()
+            .bg(Color::Rgb(110, 30, 30))
+            .add_modifier(Modifier::DIM)
+    } else if app.started_at.contains_key(&process.pid) {
+        Style::default().bg(Color::Rgb(20, 80, 30))
+    } else if process.status == ProcessStatus::Zombie {
+        Style::default().add_modifier(Modifier::DIM)
+    } else {
+        Style::default()
+    }
+}
+
+fn mark_style(app: &App, process: &ProcessInfo) -> Style {
+    if app.tagged.contains(&process.pid) {
+        Style::default()
+            .fg(Colors::HIGHLIGHT)
+            .add_modifier(Modifier::BOLD)
+    } else if app.is_pinned(process) {
+        Style::default().fg(Colors::HEADER)
+    } else {
+        Style::default().fg(Colors::TEXT)
+    }
+}
+
+fn create_header_cell(
+    text: &str,
+    key: SortKey,
+    current_sort: SortKey,
+    ascending: bool,
+) -> Cell<'static> {
+    let is_selected = key == current_sort;
+    let display_text = if is_selected {
+        format!("{} {}", text, if ascending { "↑" } else { "↓" })
+    } else {
+        text.to_string()
+    };
+
+    Cell::from(display_text).style(
+        Style::default()
+            .fg(if is_selected {
+                Colors::HIGHLIGHT
+            } else {
+                Colors::HEADER
+            })
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+fn draw_user_processes_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) -> MouseRegions {
+    // Filter processes owned by the current user
+    let user_processes = app.tab_processes();
+
     // Create table header with sort indicators
-    let header_cells = vec![
+    let mut header_cells = vec![
         create_header_cell("PID", SortKey::Pid, app.sort_key, app.sort_ascending),
         create_header_cell("Name", SortKey::Name, app.sort_key, app.sort_ascending),
         create_header_cell("CPU%", SortKey::Cpu, app.sort_key, app.sort_ascending),
         create_header_cell("Memory", SortKey::Memory, app.sort_key, app.sort_ascending),
         create_header_cell("Status", SortKey::Status, app.sort_key, app.sort_ascending),
+        create_header_cell("Nice", SortKey::Nice, app.sort_key, app.sort_ascending),
     ];
 
+    let mut widths = vec![
+        Constraint::Length(8),
+        Constraint::Percentage(40),
+        Constraint::Length(8),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(6),
+    ];
+    let mut keys = vec![
+        Some(SortKey::Pid),
+        Some(SortKey::Name),
+        Some(SortKey::Cpu),
+        Some(SortKey::Memory),
+        Some(SortKey::Status),
+        Some(SortKey::Nice),
+    ];
+
+    // Columns that don't fit are dropped from the right rather than squeezed
+    let shown = fitting_columns(area, &widths);
+    let hidden = widths.len() - shown;
+    widths.truncate(shown);
+    keys.truncate(shown);
+    header_cells.truncate(shown);
+    let name_width = layout_columns(area, &widths).get(1).copied().unwrap_or(0);
+
     let header = Row::new(header_cells).style(Style::default().add_modifier(Modifier::BOLD));
 
     // Create rows with process information
-    let rows = user_processes.iter().map(|p| {
-        // Color code CPU usage
-        let cpu_style = if p.cpu_usage > 50.0 {
-            Style::default().fg(Colors::ERROR)
-        } else if p.cpu_usage > 20.0 {
-            Style::default().fg(Colors::WARNING)
-        } else {
-            Style::default().fg(Colors::TEXT)
-        };
+    let selected = app.tab_selection(&user_processes);
+    let rows = user_processes
+        .iter()
+        .skip(app.table_offset)
+        .take(app.page_size)
+        .map(|p| {
+            // Color code CPU usage
+            let cpu_usage = p.cpu_usage / app.cpu_divisor();
+            let cpu_style = if cpu_usage > 50.0 {
+                Style::default().fg(Colors::ERROR)
+            } else if cpu_usage > 20.0 {
+                Style::default().fg(Colors::WARNING)
+            } else {
+                Style::default().fg(Colors::TEXT)
+            };
 
-        // Color code memory usage
-        let memory_mb = p.memory / 1024 / 1024;
-        let memory_style = if memory_mb > 1024 {
-            Style::default().fg(Colors::ERROR)
-        } else if memory_mb > 512 {
-            Style::default().fg(Colors::WARNING)
-        } else {
-            Style::default().fg(Colors::TEXT)
-        };
+            // Color code memory usage
+            let memory_mb = p.memory / 1024 / 1024;
+            let memory_style = if memory_mb > 1024 {
+                Style::default().fg(Colors::ERROR)
+            } else if memory_mb > 512 {
+                Style::default().fg(Colors::WARNING)
+            } else {
+                Style::default().fg(Colors::TEXT)
+            };
 
-        Row::new(vec![
-            Cell::from(p.pid.to_string()).style(Style::default().fg(Colors::TEXT)),
-            Cell::from(p.name.clone()).style(Style::default().fg(Colors::TEXT)),
-            Cell::from(format!("{:.1}%", p.cpu_usage)).style(cpu_style),
-            Cell::from(format!("{}MB", memory_mb)).style(memory_style),
-            Cell::from(p.status.to_string()).style(Style::default().fg(Colors::TEXT)),
-        ])
-    });
+            let mut cells = vec![
+                pid_cell(app, p),
+                match_cell(app, ellipsize(&p.name, name_width), mark_style(app, p)),
+                Cell::from(format!("{:.1}%", cpu_usage)).style(cpu_style),
+                Cell::from(app.config.units.bytes(p.memory)).style(memory_style),
+                Cell::from(p.status.to_string()).style(Style::default().fg(Colors::TEXT)),
+                Cell::from(p.nice.to_string()).style(Style::default().fg(Colors::TEXT)),
+            ];
+            cells.truncate(shown);
+            Row::new(cells).style(row_style(app, p))
+        });
 
     // Create table with header and rows
     let table = Table::new(rows)
@@ -525,7 +1953,11 @@ fn draw_user_processes_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect)
         .block(
             Block::default()
                 .title(Span::styled(
-                    format!(" User Processes ({}) ", user_processes.len()),
+                    format!(
+                        " User Processes ({}){} ",
+                        position_label(selected, user_processes.len()),
+                        hidden_label(hidden)
+                    ),
                     Style::default()
                         .fg(Colors::HEADER)
                         .add_modifier(Modifier::BOLD),
@@ -539,80 +1971,105 @@ fn draw_user_processes_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect)
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol("➤ ")
-        .widths(&[
-            Constraint::Length(8),
-            Constraint::Percentage(40),
-            Constraint::Length(8),
-            Constraint::Length(12),
-            Constraint::Length(12),
-        ]);
+        .widths(&widths);
 
     // Create table state
     let mut state = ratatui::widgets::TableState::default();
 
-    // Set selected item
-    if !app.processes.is_empty() {
-        state.select(Some(app.selected_index));
-    }
+    // Highlight the selected process if it is part of this subset
+    state.select(selected.map(|i| i - app.table_offset));
 
     // Render table
     f.render_stateful_widget(table, area, &mut state);
-}
-
-fn draw_system_processes_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
-    // Filter system processes (those not owned by the current user)
-    let current_user = if cfg!(unix) {
-        std::env::var("USER").unwrap_or_else(|_| "unknown".to_string())
-    } else {
-        std::env::var("USERNAME").unwrap_or_else(|_| "unknown".to_string())
-    };
 
-    let system_processes: Vec<_> = app
-        .processes
+    let pids: Vec<u32> = user_processes
         .iter()
-        .filter(|p| p.user != current_user && p.user != "unknown")
+        .skip(app.table_offset)
+        .take(app.page_size)
+        .map(|p| p.pid)
         .collect();
+    table_mouse_regions(area, &widths, &keys, &pids, &state)
+}
+
+fn draw_system_processes_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) -> MouseRegions {
+    // Filter system processes (those not owned by the current user)
+    let system_processes = app.tab_processes();
 
     // Create table header with sort indicators
-    let header_cells = vec![
+    let mut header_cells = vec![
         create_header_cell("PID", SortKey::Pid, app.sort_key, app.sort_ascending),
         create_header_cell("Name", SortKey::Name, app.sort_key, app.sort_ascending),
         create_header_cell("User", SortKey::User, app.sort_key, app.sort_ascending),
         create_header_cell("CPU%", SortKey::Cpu, app.sort_key, app.sort_ascending),
         create_header_cell("Memory", SortKey::Memory, app.sort_key, app.sort_ascending),
+        create_header_cell("Nice", SortKey::Nice, app.sort_key, app.sort_ascending),
+    ];
+
+    let mut widths = vec![
+        Constraint::Length(8),
+        Constraint::Percentage(30),
+        Constraint::Percentage(20),
+        Constraint::Length(8),
+        Constraint::Length(12),
+        Constraint::Length(6),
+    ];
+    let mut keys = vec![
+        Some(SortKey::Pid),
+        Some(SortKey::Name),
+        Some(SortKey::User),
+        Some(SortKey::Cpu),
+        Some(SortKey::Memory),
+        Some(SortKey::Nice),
     ];
 
+    // Columns that don't fit are dropped from the right rather than squeezed
+    let shown = fitting_columns(area, &widths);
+    let hidden = widths.len() - shown;
+    widths.truncate(shown);
+    keys.truncate(shown);
+    header_cells.truncate(shown);
+    let name_width = layout_columns(area, &widths).get(1).copied().unwrap_or(0);
+
     let header = Row::new(header_cells).style(Style::default().add_modifier(Modifier::BOLD));
 
     // Create rows with process information
-    let rows = system_processes.iter().map(|p| {
-        // Color code CPU usage
-        let cpu_style = if p.cpu_usage > 50.0 {
-            Style::default().fg(Colors::ERROR)
-        } else if p.cpu_usage > 20.0 {
-            Style::default().fg(Colors::WARNING)
-        } else {
-            Style::default().fg(Colors::TEXT)
-        };
+    let selected = app.tab_selection(&system_processes);
+    let rows = system_processes
+        .iter()
+        .skip(app.table_offset)
+        .take(app.page_size)
+        .map(|p| {
+            // Color code CPU usage
+            let cpu_usage = p.cpu_usage / app.cpu_divisor();
+            let cpu_style = if cpu_usage > 50.0 {
+                Style::default().fg(Colors::ERROR)
+            } else if cpu_usage > 20.0 {
+                Style::default().fg(Colors::WARNING)
+            } else {
+                Style::default().fg(Colors::TEXT)
+            };
 
-        // Color code memory usage
-        let memory_mb = p.memory / 1024 / 1024;
-        let memory_style = if memory_mb > 1024 {
-            Style::default().fg(Colors::ERROR)
-        } else if memory_mb > 512 {
-            Style::default().fg(Colors::WARNING)
-        } else {
-            Style::default().fg(Colors::TEXT)
-        };
+            // Color code memory usage
+            let memory_mb = p.memory / 1024 / 1024;
+            let memory_style = if memory_mb > 1024 {
+                Style::default().fg(Colors::ERROR)
+            } else if memory_mb > 512 {
+                Style::default().fg(Colors::WARNING)
+            } else {
+                Style::default().fg(Colors::TEXT)
+            };
 
-        Row::new(vec![
-            Cell::from(p.pid.to_string()).style(Style::default().fg(Colors::TEXT)),
-            Cell::from(p.name.clone()).style(Style::default().fg(Colors::TEXT)),
-            Cell::from(p.user.clone()).style(Style::default().fg(Colors::TEXT)),
-            Cell::from(format!("{:.1}%", p.cpu_usage)).style(cpu_style),
-            Cell::from(format!("{}MB", memory_mb)).style(memory_style),
-        ])
-    });
+            let mut cells = vec![
+                pid_cell(app, p),
+                match_cell(app, ellipsize(&p.name, name_width), mark_style(app, p)),
+                Cell::from(p.user.clone()).style(Style::default().fg(Colors::TEXT)),
+                Cell::from(format!("{:.1}%", cpu_usage)).style(cpu_style),
+                Cell::from(app.config.units.bytes(p.memory)).style(memory_style),
+                Cell::from(p.nice.to_string()).style(Style::default().fg(Colors::TEXT)),
+            ];
+            cells.truncate(shown);
+            Row::new(cells).style(row_style(app, p))
+        });
 
     // Create table with header and rows
     let table = Table::new(rows)
@@ -620,7 +2077,11 @@ fn draw_system_processes_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect
         .block(
             Block::default()
                 .title(Span::styled(
-                    format!(" System Processes ({}) ", system_processes.len()),
+                    format!(
+                        " System Processes ({}){} ",
+                        position_label(selected, system_processes.len()),
+                        hidden_label(hidden)
+                    ),
                     Style::default()
                         .fg(Colors::HEADER)
                         .add_modifier(Modifier::BOLD),
@@ -634,33 +2095,435 @@ fn draw_system_processes_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol("➤ ")
-        .widths(&[
-            Constraint::Length(8),
-            Constraint::Percentage(30),
-            Constraint::Percentage(20),
-            Constraint::Length(8),
-            Constraint::Length(12),
-        ]);
+        .widths(&widths);
 
     // Create table state
     let mut state = ratatui::widgets::TableState::default();
 
-    // Set selected item
-    if !app.processes.is_empty() {
-        state.select(Some(app.selected_index));
-    }
+    // Highlight the selected process if it is part of this subset
+    state.select(selected.map(|i| i - app.table_offset));
 
     // Render table
     f.render_stateful_widget(table, area, &mut state);
+
+    let pids: Vec<u32> = system_processes
+        .iter()
+        .skip(app.table_offset)
+        .take(app.page_size)
+        .map(|p| p.pid)
+        .collect();
+    table_mouse_regions(area, &widths, &keys, &pids, &state)
 }
 
-pub fn draw_loading_screen<B: Backend>(f: &mut Frame<B>) {
-    let size = f.size();
+// One row per user or container with the totals of its listed processes
+fn draw_summary_tab<B: Backend>(
+    f: &mut Frame<B>,
+    app: &App,
+    area: Rect,
+    kind: &str,
+    summaries: &[GroupSummary],
+) -> MouseRegions {
+    let name_key = if kind == "User" {
+        SortKey::User
+    } else {
+        SortKey::Name
+    };
 
-    // Create a centered area for the loading message
-    let loading_area = ratatui::layout::Rect {
-        x: size.width / 4,
-        y: size.height / 2 - 2,
+    let header = Row::new(vec![
+        create_header_cell(kind, name_key, app.sort_key, app.sort_ascending),
+        create_header_cell(
+            "Processes",
+            SortKey::Count,
+            app.sort_key,
+            app.sort_ascending,
+        ),
+        create_header_cell("CPU%", SortKey::Cpu, app.sort_key, app.sort_ascending),
+        create_header_cell("Memory", SortKey::Memory, app.sort_key, app.sort_ascending),
+        Cell::from("Memory%").style(
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let total_memory = app.system_resources.total_memory.max(1);
+    let rows = summaries.iter().map(|summary| {
+        let memory_percent = summary.memory as f64 / total_memory as f64 * 100.0;
+        Row::new(vec![
+            Cell::from(summary.name.clone()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(summary.processes.to_string()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(format!("{:.1}%", summary.cpu_usage / app.cpu_divisor()))
+                .style(Style::default().fg(Colors::CPU)),
+            Cell::from(app.config.units.bytes(summary.memory))
+                .style(Style::default().fg(Colors::MEMORY)),
+            Cell::from(format!("{:.1}%", memory_percent))
+                .style(Style::default().fg(Colors::MEMORY)),
+        ])
+    });
+
+    let widths = [
+        Constraint::Percentage(30),
+        Constraint::Length(11),
+        Constraint::Length(10),
+        Constraint::Length(12),
+        Constraint::Length(9),
+    ];
+    let keys = [
+        Some(name_key),
+        Some(SortKey::Count),
+        Some(SortKey::Cpu),
+        Some(SortKey::Memory),
+        None,
+    ];
+
+    let table = Table::new(rows)
+        .header(header)
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    format!(" {}s ({}) ", kind, summaries.len()),
+                    Style::default()
+                        .fg(Colors::HEADER)
+                        .add_modifier(Modifier::BOLD),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Colors::BORDER)),
+        )
+        .widths(&widths);
+
+    f.render_widget(table, area);
+
+    // Rows aren't processes, so only the headers respond to clicks
+    table_mouse_regions(area, &widths, &keys, &[], &TableState::default())
+}
+
+// The cgroup hierarchy as an indented list, with each cgroup's memory use
+// against its limit
+fn draw_alerts_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let rules = &app.config.alerts;
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " Alerts ({} firing, {} rules) ",
+                app.alerts.active.len(),
+                rules.len()
+            ),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    if app.alerts.active.is_empty() {
+        let message = if rules.is_empty() {
+            let path =
+                Config::path().map_or("the config file".to_string(), |p| p.display().to_string());
+            format!("No alert rules. Add [[alerts]] entries to {}", path)
+        } else {
+            let mut lines = vec!["No alerts firing. Rules:".to_string()];
+            lines.extend(rules.iter().map(|rule| format!("  {}", rule.describe())));
+            lines.join("\n")
+        };
+        let message = Paragraph::new(message)
+            .style(Style::default().fg(Colors::TEXT))
+            .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let header = Row::new(["Rule", "Subject", "Value", "For"].iter().map(|title| {
+        Cell::from(*title).style(
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        )
+    }));
+
+    let rows = app.alerts.active.iter().map(|alert| {
+        let rule = &rules[alert.rule];
+        let value = match rule.metric {
+            Metric::ProcessMemory => app
+                .config
+                .units
+                .bytes((alert.value * 1024.0 * 1024.0) as u64),
+            _ => format!("{:.1}%", alert.value),
+        };
+        Row::new(vec![
+            Cell::from(rule.describe()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(alert.subject.clone()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(value).style(Style::default().fg(Colors::ERROR)),
+            Cell::from(format_duration(alert.since.elapsed()))
+                .style(Style::default().fg(Colors::TEXT)),
+        ])
+    });
+
+    let widths = [
+        Constraint::Min(30),
+        Constraint::Min(20),
+        Constraint::Length(10),
+        Constraint::Length(12),
+    ];
+    let table = Table::new(rows).header(header).block(block).widths(&widths);
+    f.render_widget(table, area);
+}
+
+// Process starts and exits, newest first
+fn draw_events_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let exits = app.events.iter().filter(|event| event.exited).count();
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " Events ({} started, {} exited) ",
+                app.events.len() - exits,
+                exits
+            ),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    if app.events.is_empty() {
+        let message = Paragraph::new("No processes have started or exited yet")
+            .style(Style::default().fg(Colors::TEXT))
+            .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let titles = [
+        "Time", "Event", "PID", "Name", "User", "CPU%", "Memory", "Lifetime",
+    ];
+    let header = Row::new(titles.iter().map(|title| {
+        Cell::from(*title).style(
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        )
+    }));
+
+    // Keep the selection in view; the border and header take three rows
+    let visible = area.height.saturating_sub(3).max(1) as usize;
+    let offset = app.event_selected.saturating_sub(visible - 1);
+    let rows = app
+        .events
+        .iter()
+        .rev()
+        .enumerate()
+        .skip(offset)
+        .take(visible)
+        .map(|(index, event)| {
+            let time = Local
+                .timestamp_opt(event.at, 0)
+                .single()
+                .map_or(String::new(), |t| t.format("%H:%M:%S").to_string());
+            let (kind, kind_style) = if event.exited {
+                ("Exited", Style::default().fg(Colors::ERROR))
+            } else {
+                ("Started", Style::default().fg(Colors::CPU))
+            };
+            let text = Style::default().fg(Colors::TEXT);
+            Row::new(vec![
+                Cell::from(time).style(text),
+                Cell::from(kind).style(kind_style),
+                Cell::from(event.pid.to_string()).style(text),
+                Cell::from(event.name.clone()).style(text),
+                Cell::from(event.user.clone()).style(text),
+                Cell::from(format!("{:.1}%", event.cpu_usage)).style(text),
+                Cell::from(app.config.units.bytes(event.memory)).style(text),
+                Cell::from(event.lifetime.map_or(String::new(), format_duration)).style(text),
+            ])
+            .style(if index == app.event_selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            })
+        });
+
+    let widths = [
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Min(20),
+        Constraint::Length(12),
+        Constraint::Length(8),
+        Constraint::Length(12),
+        Constraint::Length(12),
+    ];
+    let table = Table::new(rows).header(header).block(block).widths(&widths);
+    f.render_widget(table, area);
+}
+
+// Plugin and computed columns are named at runtime rather than in Column,
+// and the eBPF collector counts every system call rather than reads and writes
+fn column_title(app: &App, column: Column) -> String {
+    if column == Column::Syscalls && app.collector.is_some_and(|stats| stats.ebpf) {
+        return "Syscalls/s".to_string();
+    }
+    app.column_name(column)
+        .unwrap_or(column.title())
+        .to_string()
+}
+
+// A tab contributed by a plugin, showing the table it last collected
+fn draw_plugin_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let tab = &app.tabs[app.current_tab];
+    let Some(plugin) = app.plugins.iter().find(|p| p.tab.as_ref() == Some(tab)) else {
+        return;
+    };
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" {} ({}) ", tab, plugin.name),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    let table = match app.plugin_tables.get(tab) {
+        Some(Ok(table)) => table,
+        Some(Err(error)) => {
+            let message = Paragraph::new(format!("Plugin failed: {}", error))
+                .style(Style::default().fg(Colors::ERROR))
+                .block(block);
+            f.render_widget(message, area);
+            return;
+        }
+        None => {
+            let message = Paragraph::new("Collecting...")
+                .style(Style::default().fg(Colors::TEXT))
+                .block(block);
+            f.render_widget(message, area);
+            return;
+        }
+    };
+
+    let header = Row::new(table.headers.iter().map(|title| {
+        Cell::from(title.clone()).style(
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        )
+    }));
+    let rows = table.rows.iter().map(|row| {
+        Row::new(
+            row.iter()
+                .map(|value| Cell::from(value.clone()).style(Style::default().fg(Colors::TEXT))),
+        )
+    });
+    // Columns share the width evenly; plugins don't say how wide they are
+    let columns = table.headers.len().max(1) as u32;
+    let widths: Vec<Constraint> = (0..columns)
+        .map(|_| Constraint::Ratio(1, columns))
+        .collect();
+    let table = Table::new(rows).header(header).block(block).widths(&widths);
+    f.render_widget(table, area);
+}
+
+fn draw_cgroups_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " Cgroups ({}) - Enter to show member processes ",
+                app.cgroups.len()
+            ),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    if let Some(error) = &app.cgroup_error {
+        let message = Paragraph::new(format!("Cgroups unavailable: {}", error))
+            .style(Style::default().fg(Colors::ERROR))
+            .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let header = Row::new(
+        ["Cgroup", "Procs", "CPU%", "Memory", "Limit", "Use%"]
+            .iter()
+            .map(|title| {
+                Cell::from(*title).style(
+                    Style::default()
+                        .fg(Colors::HEADER)
+                        .add_modifier(Modifier::BOLD),
+                )
+            }),
+    );
+
+    // Keep the selection in view; the border and header take three rows
+    let visible = area.height.saturating_sub(3).max(1) as usize;
+    let offset = app.cgroup_selected.saturating_sub(visible - 1);
+    let bytes = |bytes: u64| app.config.units.bytes(bytes);
+    let rows = app
+        .cgroups
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(visible)
+        .map(|(index, cgroup)| {
+            let name = cgroup.path.rsplit('/').next().unwrap_or("");
+            let name = if name.is_empty() { "/" } else { name };
+            let usage = match (cgroup.memory_current, cgroup.memory_max) {
+                (Some(current), Some(max)) if max > 0 => {
+                    format!("{:.1}%", current as f64 / max as f64 * 100.0)
+                }
+                _ => "-".to_string(),
+            };
+            let limit = match (cgroup.memory_max, cgroup.memory_current) {
+                (Some(max), _) => bytes(max),
+                (None, Some(_)) => "max".to_string(),
+                (None, None) => "-".to_string(),
+            };
+            let style = if index == app.cgroup_selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                Cell::from(format!("{}{}", "  ".repeat(cgroup.depth), name))
+                    .style(Style::default().fg(Colors::TEXT)),
+                Cell::from(cgroup.pids.len().to_string()).style(Style::default().fg(Colors::TEXT)),
+                Cell::from(format!("{:.1}%", cgroup.cpu_usage / app.cpu_divisor()))
+                    .style(Style::default().fg(Colors::CPU)),
+                Cell::from(cgroup.memory_current.map_or("-".to_string(), bytes))
+                    .style(Style::default().fg(Colors::MEMORY)),
+                Cell::from(limit).style(Style::default().fg(Colors::MEMORY)),
+                Cell::from(usage).style(Style::default().fg(Colors::MEMORY)),
+            ])
+            .style(style)
+        });
+
+    let widths = [
+        Constraint::Min(20),
+        Constraint::Length(7),
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(7),
+    ];
+    let table = Table::new(rows).header(header).block(block).widths(&widths);
+    f.render_widget(table, area);
+}
+
+pub fn draw_loading_screen<B: Backend>(f: &mut Frame<B>) {
+    let size = f.size();
+
+    // Create a centered area for the loading message
+    let loading_area = ratatui::layout::Rect {
+        x: size.width / 4,
+        y: size.height / 2 - 2,
         width: size.width / 2,
         height: 4,
     };
@@ -693,11 +2556,80 @@ pub fn draw_loading_screen<B: Backend>(f: &mut Frame<B>) {
 }
 
 fn draw_detailed_view<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    if let Some(follow) = app.follow.as_ref().filter(|f| f.exited) {
+        let notice = Paragraph::new(vec![
+            Spans::from(Span::styled(
+                format!("{} (PID {}) has exited.", follow.name, follow.pid),
+                Style::default()
+                    .fg(Colors::WARNING)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Spans::from(Span::styled(
+                "Press Ctrl+f to stop following it.",
+                Style::default().fg(Colors::TEXT),
+            )),
+        ])
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    " Following ",
+                    Style::default()
+                        .fg(Colors::HEADER)
+                        .add_modifier(Modifier::BOLD),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Colors::BORDER)),
+        );
+        f.render_widget(notice, area);
+        return;
+    }
+
     if app.processes.is_empty() {
         return;
     }
 
-    let selected_process = &app.processes[app.selected_index];
+    // Sub-tab bar for the panels of the selected process
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+
+    let titles = DetailPanel::ALL
+        .iter()
+        .map(|p| Spans::from(Span::raw(p.title())))
+        .collect();
+    let selected = DetailPanel::ALL
+        .iter()
+        .position(|p| *p == app.detail_panel)
+        .unwrap_or(0);
+    let panel_tabs = Tabs::new(titles)
+        .select(selected)
+        .style(Style::default().fg(Colors::TAB_INACTIVE))
+        .highlight_style(
+            Style::default()
+                .fg(Colors::TAB_ACTIVE)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_widget(panel_tabs, chunks[0]);
+
+    match app.detail_panel {
+        DetailPanel::Overview => draw_detail_overview(f, app, chunks[1]),
+        DetailPanel::Arguments => draw_arguments(f, app, chunks[1]),
+        DetailPanel::OpenFiles => draw_open_files(f, app, chunks[1]),
+        DetailPanel::Connections => draw_connections(f, app, chunks[1]),
+        DetailPanel::MemoryMaps => draw_memory_maps(f, app, chunks[1]),
+        DetailPanel::Threads => draw_threads(f, app, chunks[1]),
+        DetailPanel::Security => draw_security(f, app, chunks[1]),
+        DetailPanel::Limits => draw_limits(f, app, chunks[1]),
+        DetailPanel::Journal => draw_journal(f, app, chunks[1]),
+        DetailPanel::Output => draw_output(f, app, chunks[1]),
+    }
+}
+
+fn draw_detail_overview<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let Some(selected_process) = app.selected_process() else {
+        return;
+    };
 
     // Split into two sections - info and charts
     let chunks = Layout::default()
@@ -706,10 +2638,14 @@ fn draw_detailed_view<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
         .split(area);
 
     // Format detailed process information
-    let run_time = format_duration(selected_process.start_time);
+    let run_time = format!(
+        "{} (since {})",
+        format_duration(selected_process.start_time),
+        format_started_at(selected_process.started_at)
+    );
 
     // Left panel - detailed information
-    let info_text = vec![
+    let mut info_text = vec![
         Spans::from(vec![
             Span::styled("PID: ", Style::default().fg(Colors::HEADER)),
             Span::styled(
@@ -724,31 +2660,94 @@ fn draw_detailed_view<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
         Spans::from(vec![
             Span::styled("Command: ", Style::default().fg(Colors::HEADER)),
             Span::styled(
-                selected_process.cmd.join(" "),
+                // Long argument lists get their own panel
+                match selected_process.cmd.len() {
+                    0 | 1 => selected_process.cmd.join(" "),
+                    n => format!(
+                        "{} (+{} arguments, see Arguments)",
+                        selected_process.cmd[0],
+                        n - 1
+                    ),
+                },
                 Style::default().fg(Colors::TEXT),
             ),
         ]),
         Spans::from(vec![
             Span::styled("CPU Usage: ", Style::default().fg(Colors::HEADER)),
             Span::styled(
-                format!("{:.2}%", selected_process.cpu_usage),
+                format!("{:.2}%", selected_process.cpu_usage / app.cpu_divisor()),
                 Style::default().fg(Colors::CPU),
             ),
         ]),
         Spans::from(vec![
             Span::styled("Memory: ", Style::default().fg(Colors::HEADER)),
             Span::styled(
-                format!("{} MB", selected_process.memory / 1024 / 1024),
+                app.config.units.bytes(selected_process.memory),
                 Style::default().fg(Colors::MEMORY),
             ),
+            Span::styled(" resident", Style::default().fg(Colors::TEXT)),
+        ]),
+        Spans::from(vec![
+            Span::styled("Virtual Memory: ", Style::default().fg(Colors::HEADER)),
+            Span::styled(
+                app.config.units.bytes(selected_process.virtual_memory),
+                Style::default().fg(Colors::TEXT),
+            ),
+        ]),
+        // Shared memory and swap are only reported on Linux
+        Spans::from(vec![
+            Span::styled("Shared Memory: ", Style::default().fg(Colors::HEADER)),
+            Span::styled(
+                selected_process
+                    .shared_memory
+                    .map_or("-".to_string(), |b| app.config.units.bytes(b)),
+                Style::default().fg(Colors::TEXT),
+            ),
+        ]),
+        Spans::from(vec![
+            Span::styled("Swap: ", Style::default().fg(Colors::HEADER)),
+            Span::styled(
+                selected_process
+                    .swap
+                    .map_or("-".to_string(), |b| app.config.units.bytes(b)),
+                Style::default().fg(Colors::TEXT),
+            ),
         ]),
         Spans::from(vec![
             Span::styled("Status: ", Style::default().fg(Colors::HEADER)),
             Span::styled(
-                selected_process.status.to_string(),
+                match &app.wait_channel {
+                    Some(wchan) if selected_process.status != ProcessStatus::Running => format!(
+                        "{} (waiting on {}: {})",
+                        selected_process.status,
+                        inspect::classify_wait_channel(wchan),
+                        wchan
+                    ),
+                    _ => selected_process.status.to_string(),
+                },
+                Style::default().fg(Colors::TEXT),
+            ),
+        ]),
+        Spans::from(vec![
+            Span::styled("Nice: ", Style::default().fg(Colors::HEADER)),
+            Span::styled(
+                selected_process.nice.to_string(),
                 Style::default().fg(Colors::TEXT),
             ),
         ]),
+        Spans::from(vec![
+            Span::styled("Scheduling: ", Style::default().fg(Colors::HEADER)),
+            Span::styled(
+                match (selected_process.sched_policy, selected_process.rt_priority) {
+                    (Some(policy), Some(priority)) if policy.is_realtime() => {
+                        format!("{} (priority {})", policy, priority)
+                    }
+                    (Some(policy), _) => policy.to_string(),
+                    (None, _) => "Unknown".to_string(),
+                },
+                scheduling_style(selected_process, Style::default().fg(Colors::TEXT)),
+            ),
+        ]),
         Spans::from(vec![
             Span::styled("User: ", Style::default().fg(Colors::HEADER)),
             Span::styled(&selected_process.user, Style::default().fg(Colors::TEXT)),
@@ -776,6 +2775,94 @@ fn draw_detailed_view<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
             ),
         ]),
     ];
+    if let Some(executable) = &app.executable {
+        let mut spans = vec![
+            Span::styled("Executable: ", Style::default().fg(Colors::HEADER)),
+            Span::styled(executable.path.clone(), Style::default().fg(Colors::TEXT)),
+        ];
+        match executable.state {
+            BinaryState::Intact => {}
+            BinaryState::Deleted => spans.push(Span::styled(
+                " (deleted since it started)",
+                Style::default()
+                    .fg(Colors::ERROR)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            BinaryState::Replaced => spans.push(Span::styled(
+                " (replaced on disk since it started)",
+                Style::default().fg(Colors::WARNING),
+            )),
+        }
+        info_text.push(Spans::from(spans));
+    }
+    // A zombie lingers until its parent reaps it, so point at the parent
+    if selected_process.status == ProcessStatus::Zombie {
+        let parent = selected_process
+            .parent
+            .map(|pid| match app.all_processes.get(&pid) {
+                Some(parent) => format!("PID {} ({})", pid, parent.name),
+                None => format!("PID {}", pid),
+            });
+        let hint = match app.keymap.key(Action::Reap) {
+            Some(key) => format!(" - {}: reap", key),
+            None => String::new(),
+        };
+        info_text.push(Spans::from(vec![
+            Span::styled("Not Reaped By: ", Style::default().fg(Colors::ERROR)),
+            Span::styled(
+                match parent {
+                    Some(parent) => format!("{}{}", parent, hint),
+                    None => "no parent".to_string(),
+                },
+                Style::default().fg(Colors::ERROR),
+            ),
+        ]));
+    }
+    if let (Some(pss), Some(uss)) = (selected_process.pss, selected_process.uss) {
+        info_text.push(Spans::from(vec![
+            Span::styled("Proportional (PSS): ", Style::default().fg(Colors::HEADER)),
+            Span::styled(
+                app.config.units.bytes(pss),
+                Style::default().fg(Colors::TEXT),
+            ),
+        ]));
+        info_text.push(Spans::from(vec![
+            Span::styled("Unique (USS): ", Style::default().fg(Colors::HEADER)),
+            Span::styled(
+                app.config.units.bytes(uss),
+                Style::default().fg(Colors::TEXT),
+            ),
+        ]));
+    }
+    if let Some(node) = selected_process.numa_node {
+        info_text.push(Spans::from(vec![
+            Span::styled("NUMA Node: ", Style::default().fg(Colors::HEADER)),
+            Span::styled(node.to_string(), Style::default().fg(Colors::TEXT)),
+        ]));
+    }
+    if let Some(unit) = &selected_process.unit {
+        info_text.push(Spans::from(vec![
+            Span::styled("Unit: ", Style::default().fg(Colors::HEADER)),
+            Span::styled(unit, Style::default().fg(Colors::TEXT)),
+            Span::styled(
+                "  (Ctrl+a to restart/stop)",
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
+    if let (Some(power), Some(wakeups)) = (selected_process.power, selected_process.wakeups_rate) {
+        info_text.push(Spans::from(vec![
+            Span::styled("Power: ", Style::default().fg(Colors::HEADER)),
+            Span::styled(format_power(power), Style::default().fg(Colors::TEXT)),
+        ]));
+        info_text.push(Spans::from(vec![
+            Span::styled("Wakeups: ", Style::default().fg(Colors::HEADER)),
+            Span::styled(
+                format!("{:.0}/s", wakeups),
+                Style::default().fg(Colors::TEXT),
+            ),
+        ]));
+    }
 
     let info_panel = Paragraph::new(info_text)
         .block(
@@ -791,34 +2878,108 @@ fn draw_detailed_view<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
         )
         .wrap(Wrap { trim: true });
 
-    f.render_widget(info_panel, chunks[0]);
+    // Context switches and page faults go under the details when readable
+    let info_area = match &app.activity {
+        Some(totals) => {
+            let left = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(6)])
+                .split(chunks[0]);
+            draw_activity(f, app, totals, left[1]);
+            left[0]
+        }
+        None => chunks[0],
+    };
+    f.render_widget(info_panel, info_area);
 
     // Right panel - charts section
     let chart_area = chunks[1];
+    let syscalls_title = if app.collector.is_some_and(|stats| stats.ebpf) {
+        "System Calls"
+    } else {
+        "Read/Write Calls"
+    };
+    let kernel: Vec<(&str, &VecDeque<u64>, u64, &str, Color)> = [
+        (
+            syscalls_title,
+            &selected_process.syscall_history,
+            selected_process.syscall_rate,
+            "/s",
+            Colors::CPU,
+        ),
+        (
+            "Block I/O Wait",
+            &selected_process.blkio_delay_history,
+            selected_process.blkio_delay_rate,
+            " ms/s",
+            Colors::MEMORY,
+        ),
+        (
+            "Block I/O Latency",
+            &selected_process.blkio_latency_history,
+            selected_process.blkio_latency,
+            " µs",
+            Colors::WARNING,
+        ),
+        (
+            "TCP Retransmits",
+            &selected_process.tcp_retransmit_history,
+            selected_process.tcp_retransmit_rate,
+            "/s",
+            Colors::ERROR,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(title, history, rate, unit, color)| Some((title, history, rate?, unit, color)))
+    .collect();
     let chart_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .constraints(if kernel.is_empty() {
+            vec![
+                Constraint::Percentage(45),
+                Constraint::Percentage(30),
+                Constraint::Percentage(25),
+            ]
+        } else {
+            vec![
+                Constraint::Percentage(40),
+                Constraint::Percentage(24),
+                Constraint::Percentage(18),
+                Constraint::Percentage(18),
+            ]
+        })
         .split(chart_area);
 
-    // CPU history chart
-    let cpu_data: Vec<(f64, f64)> = selected_process
-        .cpu_history
-        .iter()
-        .enumerate()
-        .map(|(i, &cpu)| (i as f64, cpu as f64))
-        .collect();
+    // CPU history chart, reaching back to the process start when the history
+    // database has samples, or over the last minute in memory otherwise
+    let stored = &app.process_history;
+    let series = history_series(
+        stored,
+        selected_process
+            .cpu_history
+            .iter()
+            .map(|&c| (c / app.cpu_divisor()) as f64),
+        |s| (s.cpu_usage / app.cpu_divisor()) as f64,
+    );
+    let scrub = scrub_point(app, &series);
 
-    let cpu_dataset = vec![Dataset::default()
+    let mut cpu_dataset = vec![Dataset::default()
         .name("CPU %")
         .marker(Marker::Braille)
         .style(Style::default().fg(Colors::CPU))
-        .data(&cpu_data)];
+        .data(&series.data)];
+    if let Some(point) = &scrub {
+        cpu_dataset.push(scrub_marker(&point.line));
+    }
 
     let cpu_chart = Chart::new(cpu_dataset)
         .block(
             Block::default()
                 .title(Span::styled(
-                    " CPU Usage ",
+                    match &scrub {
+                        Some(point) => format!(" CPU Usage{}: {:.1}% ", point.label, point.value),
+                        None => format!(" CPU Usage{} ", series.span),
+                    },
                     Style::default()
                         .fg(Colors::HEADER)
                         .add_modifier(Modifier::BOLD),
@@ -829,8 +2990,8 @@ fn draw_detailed_view<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
         .x_axis(
             Axis::default()
                 .style(Style::default().fg(Colors::TEXT))
-                .bounds([0.0, 60.0])
-                .labels(vec![]),
+                .bounds(series.bounds)
+                .labels(series.labels.clone()),
         )
         .y_axis(
             Axis::default()
@@ -846,25 +3007,52 @@ fn draw_detailed_view<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
     f.render_widget(cpu_chart, chart_chunks[0]);
 
     // Memory gauge and history
-    let memory_mb = selected_process.memory / 1024 / 1024;
     let memory_percent =
         (selected_process.memory as f64 / app.system_resources.total_memory as f64) * 100.0;
 
-    // Create sparkline for memory history
-    let memory_data: Vec<u64> = selected_process
-        .memory_history
-        .iter()
-        .map(|&mem| mem / (1024 * 1024)) // Convert to MB for display
-        .collect();
+    // Create sparkline for memory history. Stored history is squeezed into
+    // the sparkline's width, keeping each stretch's peak.
+    let memory_data: Vec<u64> = if stored.len() > 1 {
+        let width = chart_chunks[1].width.saturating_sub(2).max(1) as usize;
+        stored
+            .chunks(stored.len().div_ceil(width))
+            .map(|chunk| chunk.iter().map(|s| s.memory).max().unwrap_or(0) / (1024 * 1024))
+            .collect()
+    } else {
+        selected_process
+            .memory_history
+            .iter()
+            .map(|&mem| mem / (1024 * 1024)) // Convert to MB for display
+            .collect()
+    };
 
     let memory_sparkline = Sparkline::default()
         .block(
             Block::default()
                 .title(Span::styled(
-                    format!(
-                        " Memory: {}MB ({:.1}% of total) ",
-                        memory_mb, memory_percent
-                    ),
+                    match &scrub {
+                        Some(point) => {
+                            let memory = match stored.get(point.index).filter(|_| stored.len() > 1)
+                            {
+                                Some(sample) => sample.memory,
+                                None => selected_process
+                                    .memory_history
+                                    .get(point.index)
+                                    .copied()
+                                    .unwrap_or(0),
+                            };
+                            format!(
+                                " Memory{}: {} ",
+                                point.label,
+                                app.config.units.bytes(memory)
+                            )
+                        }
+                        None => format!(
+                            " Memory: {} ({:.1}% of total) ",
+                            app.config.units.bytes(selected_process.memory),
+                            memory_percent
+                        ),
+                    },
                     Style::default()
                         .fg(Colors::HEADER)
                         .add_modifier(Modifier::BOLD),
@@ -876,437 +3064,1298 @@ fn draw_detailed_view<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
         .style(Style::default().fg(Colors::MEMORY));
 
     f.render_widget(memory_sparkline, chart_chunks[1]);
-}
 
-fn draw_help_popup<B: Backend>(f: &mut Frame<B>, _app: &App, area: Rect) {
-    // Calculate a centered position for a reasonably sized panel
-    let popup_width = 72;
-    let popup_height = 30;
-    let popup_x = (area.width.saturating_sub(popup_width)) / 2;
-    let popup_y = (area.height.saturating_sub(popup_height)) / 2;
+    // Disk read and write rates, from the in-memory history only since the
+    // history database doesn't keep them. Each is scaled to its own peak,
+    // like the Disks tab's.
+    let scrubbed = |history: &VecDeque<u64>, rate: u64| match &scrub {
+        Some(point) if stored.len() <= 1 => (
+            point.label.as_str(),
+            history.get(point.index).copied().unwrap_or(0),
+        ),
+        _ => ("", rate),
+    };
+    let disk_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chart_chunks[2]);
+    let disk = [
+        (
+            "Disk Read",
+            &selected_process.disk_read_history,
+            selected_process.disk_read_rate,
+            Colors::CPU,
+        ),
+        (
+            "Disk Write",
+            &selected_process.disk_write_history,
+            selected_process.disk_write_rate,
+            Colors::MEMORY,
+        ),
+    ];
+    for ((title, history, rate, color), area) in disk.into_iter().zip(disk_chunks.iter()) {
+        let (label, rate) = scrubbed(history, rate);
+        let title = format!(" {}: {}{} ", title, app.config.units.rate(rate), label);
+        draw_history_sparkline(f, *area, title, history, color);
+    }
 
-    let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+    // The kernel statistics that are being collected, side by side
+    if let Some(&area) = chart_chunks.get(3) {
+        let kernel_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![
+                Constraint::Ratio(1, kernel.len() as u32);
+                kernel.len()
+            ])
+            .split(area);
+        for ((title, history, rate, unit, color), area) in
+            kernel.into_iter().zip(kernel_chunks.iter())
+        {
+            let (label, rate) = scrubbed(history, rate);
+            let title = format!(" {}: {}{}{} ", title, rate, unit, label);
+            draw_history_sparkline(f, *area, title, history, color);
+        }
+    }
+}
 
-    // Add a fancy dimming overlay for the entire screen with high opacity
-    let dim_overlay = Block::default().style(
-        Style::default()
-            .bg(Color::Rgb(20, 20, 30))
-            .fg(Color::Rgb(20, 20, 30)),
-    );
-    f.render_widget(dim_overlay, area);
+// A rate's recent history under a title, showing the newest samples that fit
+fn draw_history_sparkline<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    title: String,
+    history: &VecDeque<u64>,
+    color: Color,
+) {
+    let width = area.width.saturating_sub(2) as usize;
+    let data: Vec<u64> = history
+        .iter()
+        .skip(history.len().saturating_sub(width))
+        .copied()
+        .collect();
+    let sparkline = Sparkline::default()
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    title,
+                    Style::default()
+                        .fg(Colors::HEADER)
+                        .add_modifier(Modifier::BOLD),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Colors::BORDER)),
+        )
+        .data(&data)
+        .style(Style::default().fg(color));
+    f.render_widget(sparkline, area);
+}
 
-    // Create artistic header with logo - ensure proper centering
-    let title_text = "PSR - Process Status Reporter";
-    let padding_left = (popup_width as usize - title_text.len() - 4) / 2;
-    let padding_right = popup_width as usize - 4 - padding_left - title_text.len();
+// One argument per line, with long ones wrapped under their index
+fn draw_arguments<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let Some(selected_process) = app.selected_process() else {
+        return;
+    };
 
-    let header = vec![
-        Spans::from(vec![
-            Span::styled("╭", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::styled(
-                "─".repeat(popup_width as usize - 2),
-                Style::default().fg(Color::Rgb(108, 111, 132)),
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " Arguments: {} ({}) | Ctrl+y: Copy ",
+                selected_process.name,
+                selected_process.cmd.len()
             ),
-            Span::styled("╮", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    if selected_process.cmd.is_empty() {
+        let message = Paragraph::new(Span::styled(
+            "No command line (kernel thread or access denied)",
+            Style::default().fg(Color::DarkGray),
+        ))
+        .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let index_width = selected_process.cmd.len().to_string().len();
+    let text_width = (area.width.saturating_sub(2) as usize)
+        .saturating_sub(index_width + 2)
+        .max(1);
+
+    let mut lines = Vec::new();
+    for (index, arg) in selected_process
+        .cmd
+        .iter()
+        .enumerate()
+        .skip(app.detail_scroll)
+    {
+        let chars: Vec<char> = arg.chars().collect();
+        let pieces: Vec<String> = if chars.is_empty() {
+            vec![String::new()]
+        } else {
+            chars
+                .chunks(text_width)
+                .map(|piece| piece.iter().collect())
+                .collect()
+        };
+        for (i, piece) in pieces.into_iter().enumerate() {
+            let label = if i == 0 {
+                format!("{:>1$}  ", index, index_width)
+            } else {
+                " ".repeat(index_width + 2)
+            };
+            lines.push(Spans::from(vec![
+                Span::styled(label, Style::default().fg(Colors::HEADER)),
+                Span::styled(piece, Style::default().fg(Colors::TEXT)),
+            ]));
+        }
+        if lines.len() >= area.height as usize {
+            break;
+        }
+    }
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+// Capabilities, seccomp and the LSM label, with what leaves a process
+// unconfined in warning color
+fn draw_security<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let Some(selected_process) = app.selected_process() else {
+        return;
+    };
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" Security: {} ", selected_process.name),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    let security = match (&app.detail_error, &app.security) {
+        (Some(error), _) => {
+            let message = Span::styled(
+                format!("Unable to read the security context: {}", error),
+                Style::default().fg(Colors::ERROR),
+            );
+            f.render_widget(Paragraph::new(message).block(block), area);
+            return;
+        }
+        (None, Some(security)) => security,
+        (None, None) => {
+            f.render_widget(block, area);
+            return;
+        }
+    };
+
+    let text = Style::default().fg(Colors::TEXT);
+    let warning = Style::default().fg(Colors::WARNING);
+    let line = |label: &str, value: String, style: Style| {
         Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::styled(
-                " ".repeat(padding_left),
-                Style::default().fg(Color::Rgb(248, 248, 242)),
-            ),
-            Span::styled(
-                "P",
-                Style::default()
-                    .fg(Color::Rgb(255, 85, 85))
-                    .add_modifier(Modifier::BOLD),
+            Span::styled(format!("{}: ", label), Style::default().fg(Colors::HEADER)),
+            Span::styled(value, style),
+        ])
+    };
+    // Any one of these is as good as root
+    let powerful = ["sys_admin", "sys_module", "sys_ptrace", "sys_rawio"];
+    let capabilities = if security.capabilities.is_empty() {
+        line("Capabilities", "none".to_string(), text)
+    } else if security.all_capabilities {
+        let count = security.capabilities.len();
+        line(
+            "Capabilities",
+            format!("all {} (unrestricted)", count),
+            warning,
+        )
+    } else if security
+        .capabilities
+        .iter()
+        .any(|c| powerful.contains(&c.as_str()))
+    {
+        line("Capabilities", security.capabilities.join(", "), warning)
+    } else {
+        line("Capabilities", security.capabilities.join(", "), text)
+    };
+    let seccomp = match security.seccomp {
+        Seccomp::Disabled => line("Seccomp", "disabled".to_string(), warning),
+        Seccomp::Strict => line("Seccomp", "strict".to_string(), text),
+        Seccomp::Filter => line("Seccomp", "filter".to_string(), text),
+        Seccomp::Unknown => line("Seccomp", "unknown".to_string(), text),
+    };
+    let no_new_privs = if security.no_new_privs { "yes" } else { "no" };
+    let label = match &security.label {
+        Some((module, label)) if label.contains("unconfined") => {
+            line(module, label.clone(), warning)
+        }
+        Some((module, label)) => line(module, label.clone(), text),
+        None => line("Label", "none (no SELinux or AppArmor)".to_string(), text),
+    };
+
+    let lines = vec![
+        capabilities,
+        seccomp,
+        line("No New Privileges", no_new_privs.to_string(), text),
+        label,
+    ];
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
+// Soft and hard resource limits, with open files compared to how many are in
+// use since running out of those is the usual way services hit a limit
+fn draw_limits<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let Some(selected_process) = app.selected_process() else {
+        return;
+    };
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" Limits: {} ", selected_process.name),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    if let Some(error) = &app.detail_error {
+        let message = Paragraph::new(Span::styled(
+            format!("Unable to read limits: {}", error),
+            Style::default().fg(Colors::ERROR),
+        ))
+        .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    // Byte limits are scaled, so their units go without saying
+    let format = |limit: Option<u64>, units: &str| match limit {
+        None => "unlimited".to_string(),
+        Some(bytes) if units == "bytes" => app.config.units.bytes(bytes),
+        Some(value) => value.to_string(),
+    };
+    let rows = app.limits.iter().skip(app.detail_scroll).map(|limit| {
+        let in_use = (limit.name == "nofile")
+            .then_some(selected_process.fds)
+            .flatten();
+        let style = match (in_use, limit.soft) {
+            (Some(used), Some(soft)) if used * 10 >= soft * 9 => Style::default().fg(Colors::ERROR),
+            (Some(used), Some(soft)) if used * 10 >= soft * 7 => {
+                Style::default().fg(Colors::WARNING)
+            }
+            _ => Style::default().fg(Colors::TEXT),
+        };
+        let units = if limit.units == "bytes" {
+            String::new()
+        } else {
+            limit.units.clone()
+        };
+        Row::new(vec![
+            Cell::from(limit.name).style(Style::default().fg(Colors::HEADER)),
+            Cell::from(limit.description.clone()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(format(limit.soft, &limit.units)).style(style),
+            Cell::from(format(limit.hard, &limit.units)).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(in_use.map_or(String::new(), |used| used.to_string())).style(style),
+            Cell::from(units).style(Style::default().fg(Colors::TEXT)),
+        ])
+    });
+
+    let table = Table::new(rows)
+        .header(
+            Row::new(vec![
+                Cell::from("Name").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Limit").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Soft").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Hard").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("In Use").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Units").style(Style::default().fg(Colors::HEADER)),
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(block)
+        .widths(&[
+            Constraint::Length(11),
+            Constraint::Length(22),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(8),
+            Constraint::Length(10),
+        ])
+        .column_spacing(1);
+
+    f.render_widget(table, area);
+}
+
+fn draw_journal<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let Some(selected_process) = app.selected_process() else {
+        return;
+    };
+    let source = match selected_process
+        .unit
+        .as_deref()
+        .filter(|u| u.ends_with(".service"))
+    {
+        Some(unit) => unit.to_string(),
+        None => format!("PID {}", selected_process.pid),
+    };
+
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" Journal: {} (newest first) ", source),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    let message = if let Some(error) = &app.detail_error {
+        Some(Span::styled(
+            format!("Unable to read the journal: {}", error),
+            Style::default().fg(Colors::ERROR),
+        ))
+    } else if app.journal.is_empty() {
+        Some(Span::styled(
+            "No journal entries",
+            Style::default().fg(Color::DarkGray),
+        ))
+    } else {
+        None
+    };
+    if let Some(message) = message {
+        f.render_widget(Paragraph::new(message).block(block), area);
+        return;
+    }
+
+    let lines: Vec<Spans> = app
+        .journal
+        .iter()
+        .skip(app.detail_scroll)
+        .take(area.height as usize)
+        .map(|line| {
+            // short-iso lines start with the timestamp
+            match line.split_once(' ') {
+                Some((time, rest)) => Spans::from(vec![
+                    Span::styled(time, Style::default().fg(Colors::HEADER)),
+                    Span::raw(" "),
+                    Span::styled(rest, Style::default().fg(Colors::TEXT)),
+                ]),
+                None => Spans::from(Span::styled(
+                    line.as_str(),
+                    Style::default().fg(Colors::TEXT),
+                )),
+            }
+        })
+        .collect();
+
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn draw_output<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let Some(selected_process) = app.selected_process() else {
+        return;
+    };
+
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " Output: {} ({}) - newest first ",
+                selected_process.name, selected_process.pid
             ),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    let message = if let Some(error) = &app.detail_error {
+        Some(Span::styled(
+            format!("Unable to read the output targets: {}", error),
+            Style::default().fg(Colors::ERROR),
+        ))
+    } else if app.output.is_empty() {
+        Some(Span::styled(
+            "stdout and stderr are closed",
+            Style::default().fg(Color::DarkGray),
+        ))
+    } else {
+        None
+    };
+    if let Some(message) = message {
+        f.render_widget(Paragraph::new(message).block(block), area);
+        return;
+    }
+
+    // Each stream's target, then the end of what was written to it
+    let mut lines = Vec::new();
+    for stream in &app.output {
+        lines.push(Spans::from(vec![
             Span::styled(
-                "S",
+                stream.streams,
                 Style::default()
-                    .fg(Color::Rgb(255, 121, 198))
+                    .fg(Colors::HEADER)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(
-                "R",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
+            Span::raw(" → "),
+            Span::styled(stream.target.as_str(), Style::default().fg(Colors::TEXT)),
+        ]));
+        match &stream.tail {
+            Ok(tail) if tail.is_empty() => lines.push(Spans::from(Span::styled(
+                "  Nothing written yet",
+                Style::default().fg(Color::DarkGray),
+            ))),
+            Ok(tail) => lines.extend(tail.iter().rev().map(|line| {
+                Spans::from(Span::styled(
+                    format!("  {}", line),
+                    Style::default().fg(Colors::TEXT),
+                ))
+            })),
+            Err(reason) => lines.push(Spans::from(Span::styled(
+                format!("  Can't be tailed: {}", reason),
+                Style::default().fg(Color::DarkGray),
+            ))),
+        }
+    }
+
+    let lines: Vec<Spans> = lines
+        .into_iter()
+        .skip(app.detail_scroll)
+        .take(area.height as usize)
+        .collect();
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_open_files<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let Some(selected_process) = app.selected_process() else {
+        return;
+    };
+
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " Open Files: {} ({}) ",
+                selected_process.name,
+                app.open_files.len()
             ),
-            Span::styled(" - ", Style::default().fg(Color::Rgb(248, 248, 242))),
-            Span::styled(
-                "Process Status Reporter",
-                Style::default()
-                    .fg(Color::Rgb(139, 233, 253))
-                    .add_modifier(Modifier::BOLD),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    // Usually a permission error for processes owned by other users
+    if let Some(error) = &app.detail_error {
+        let message = Paragraph::new(Span::styled(
+            format!("Unable to read open files: {}", error),
+            Style::default().fg(Colors::ERROR),
+        ))
+        .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let rows = app.open_files.iter().skip(app.detail_scroll).map(|file| {
+        Row::new(vec![
+            Cell::from(file.fd.clone()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(file.kind).style(Style::default().fg(Colors::MEMORY)),
+            Cell::from(file.target.clone()).style(Style::default().fg(Colors::TEXT)),
+        ])
+    });
+
+    let table = Table::new(rows)
+        .header(
+            Row::new(vec![
+                Cell::from("FD").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Type").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Target").style(Style::default().fg(Colors::HEADER)),
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(block)
+        .widths(&[
+            Constraint::Length(6),
+            Constraint::Length(8),
+            Constraint::Percentage(80),
+        ])
+        .column_spacing(1);
+
+    f.render_widget(table, area);
+}
+
+fn draw_connections<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let Some(selected_process) = app.selected_process() else {
+        return;
+    };
+
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " Connections: {} ({}) ",
+                selected_process.name,
+                app.connections.len()
             ),
-            Span::styled(
-                " ".repeat(padding_right),
-                Style::default().fg(Color::Rgb(248, 248, 242)),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    if let Some(error) = &app.detail_error {
+        let message = Paragraph::new(Span::styled(
+            format!("Unable to read connections: {}", error),
+            Style::default().fg(Colors::ERROR),
+        ))
+        .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let rows = app.connections.iter().skip(app.detail_scroll).map(|c| {
+        let state_style = match c.state.as_str() {
+            "ESTABLISHED" => Style::default().fg(Colors::CPU),
+            "LISTEN" => Style::default().fg(Colors::MEMORY),
+            _ => Style::default().fg(Colors::TEXT),
+        };
+        // Names replace the numbers as they're found
+        let udp = c.protocol.starts_with("udp");
+        Row::new(vec![
+            Cell::from(c.protocol).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(app.resolver.describe(&c.local, udp))
+                .style(Style::default().fg(Colors::TEXT)),
+            Cell::from(app.resolver.describe(&c.remote, udp))
+                .style(Style::default().fg(Colors::TEXT)),
+            Cell::from(c.state.clone()).style(state_style),
+        ])
+    });
+
+    let table = Table::new(rows)
+        .header(
+            Row::new(vec![
+                Cell::from("Proto").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Local Address").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Remote Address").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("State").style(Style::default().fg(Colors::HEADER)),
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(block)
+        .widths(&[
+            Constraint::Length(6),
+            Constraint::Percentage(40),
+            Constraint::Percentage(40),
+            Constraint::Length(12),
+        ])
+        .column_spacing(1);
+
+    f.render_widget(table, area);
+}
+
+fn draw_memory_maps<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let Some(selected_process) = app.selected_process() else {
+        return;
+    };
+    let total: u64 = app.memory_maps.iter().map(|m| m.size).sum();
+
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " Memory Map: {} ({} mapped) ",
+                selected_process.name,
+                app.config.units.bytes(total)
             ),
-            Span::styled("  │", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::styled(
-                "─".repeat(popup_width as usize - 2),
-                Style::default().fg(Color::Rgb(68, 71, 90)),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    if let Some(error) = &app.detail_error {
+        let message = Paragraph::new(Span::styled(
+            format!("Unable to read memory map: {}", error),
+            Style::default().fg(Colors::ERROR),
+        ))
+        .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let rows = app.memory_maps.iter().skip(app.detail_scroll).map(|m| {
+        let name = if m.name.is_empty() {
+            "[anonymous]".to_string()
+        } else {
+            m.name.clone()
+        };
+        Row::new(vec![
+            Cell::from(app.config.units.bytes(m.size)).style(Style::default().fg(Colors::MEMORY)),
+            Cell::from(m.regions.to_string()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(m.kind).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(name).style(Style::default().fg(Colors::TEXT)),
+        ])
+    });
+
+    let table = Table::new(rows)
+        .header(
+            Row::new(vec![
+                Cell::from("Size").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Regions").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Type").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Mapping").style(Style::default().fg(Colors::HEADER)),
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(block)
+        .widths(&[
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Percentage(80),
+        ])
+        .column_spacing(1);
+
+    f.render_widget(table, area);
+}
+
+fn draw_threads<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let Some(selected_process) = app.selected_process() else {
+        return;
+    };
+
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " Threads: {} ({}) ",
+                selected_process.name,
+                app.threads.len()
             ),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-    ];
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    if let Some(error) = &app.detail_error {
+        let message = Paragraph::new(Span::styled(
+            format!("Unable to read threads: {}", error),
+            Style::default().fg(Colors::ERROR),
+        ))
+        .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let rows = app.threads.iter().skip(app.detail_scroll).map(|t| {
+        Row::new(vec![
+            Cell::from(t.tid.to_string()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(t.name.clone()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(t.state.to_string()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(format!("{:.1}", t.cpu_usage / app.cpu_divisor()))
+                .style(Style::default().fg(Colors::CPU)),
+            Cell::from(format!("{:.2}s", t.cpu_time)).style(Style::default().fg(Colors::TEXT)),
+        ])
+    });
+
+    let table = Table::new(rows)
+        .header(
+            Row::new(vec![
+                Cell::from("TID").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Name").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("State").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("CPU%").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("CPU Time").style(Style::default().fg(Colors::HEADER)),
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(block)
+        .widths(&[
+            Constraint::Length(8),
+            Constraint::Percentage(40),
+            Constraint::Length(6),
+            Constraint::Length(7),
+            Constraint::Length(12),
+        ])
+        .column_spacing(1);
+
+    f.render_widget(table, area);
+}
+
+fn draw_network_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let stats = &app.network_stats;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(stats.interfaces.len() as u16 + 3),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    // Interface summary table
+    let rows = stats.interfaces.iter().map(|i| {
+        Row::new(vec![
+            Cell::from(i.name.clone()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(app.config.units.rate(i.rx_rate)).style(Style::default().fg(Colors::CPU)),
+            Cell::from(app.config.units.rate(i.tx_rate)).style(Style::default().fg(Colors::MEMORY)),
+            Cell::from(app.config.units.bytes(i.total_received))
+                .style(Style::default().fg(Colors::TEXT)),
+            Cell::from(app.config.units.bytes(i.total_transmitted))
+                .style(Style::default().fg(Colors::TEXT)),
+        ])
+    });
+
+    let table = Table::new(rows)
+        .header(
+            Row::new(vec![
+                Cell::from("Interface").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("RX/s").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("TX/s").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Total RX").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Total TX").style(Style::default().fg(Colors::HEADER)),
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    format!(" Network Interfaces ({}) ", stats.interfaces.len()),
+                    Style::default()
+                        .fg(Colors::HEADER)
+                        .add_modifier(Modifier::BOLD),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Colors::BORDER)),
+        )
+        .widths(&[
+            Constraint::Percentage(30),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(12),
+        ])
+        .column_spacing(1);
+
+    f.render_widget(table, chunks[0]);
+
+    // One row of RX/TX sparklines per interface, as many as fit
+    let series: Vec<_> = stats
+        .interfaces
+        .iter()
+        .map(|i| {
+            (
+                i.name.as_str(),
+                format!(" {} RX: {} ", i.name, app.config.units.rate(i.rx_rate)),
+                format!(" {} TX: {} ", i.name, app.config.units.rate(i.tx_rate)),
+            )
+        })
+        .collect();
+    draw_rate_sparklines(f, &stats.history, &series, chunks[1]);
+}
+
+fn draw_disks_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let stats = &app.disk_stats;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(stats.disks.len() as u16 + 3),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    // Filesystem summary table
+    let rows = stats.disks.iter().map(|d| {
+        let used_percent = if d.total_space > 0 {
+            d.used_space() as f64 / d.total_space as f64 * 100.0
+        } else {
+            0.0
+        };
+        let usage_style = if used_percent > 90.0 {
+            Style::default().fg(Colors::ERROR)
+        } else if used_percent > 75.0 {
+            Style::default().fg(Colors::WARNING)
+        } else {
+            Style::default().fg(Colors::TEXT)
+        };
+
+        Row::new(vec![
+            Cell::from(d.mount_point.clone()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(d.name.clone()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(d.file_system.clone()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(app.config.units.bytes(d.total_space))
+                .style(Style::default().fg(Colors::TEXT)),
+            Cell::from(app.config.units.bytes(d.used_space()))
+                .style(Style::default().fg(Colors::TEXT)),
+            Cell::from(format!("{:.1}%", used_percent)).style(usage_style),
+            Cell::from(app.config.units.rate(d.read_rate)).style(Style::default().fg(Colors::CPU)),
+            Cell::from(app.config.units.rate(d.write_rate))
+                .style(Style::default().fg(Colors::MEMORY)),
+        ])
+    });
+
+    let table = Table::new(rows)
+        .header(
+            Row::new(vec![
+                Cell::from("Mount").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Device").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("FS").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Size").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Used").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Use%").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Read/s").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Write/s").style(Style::default().fg(Colors::HEADER)),
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    format!(" Disks ({}) ", stats.disks.len()),
+                    Style::default()
+                        .fg(Colors::HEADER)
+                        .add_modifier(Modifier::BOLD),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Colors::BORDER)),
+        )
+        .widths(&[
+            Constraint::Percentage(20),
+            Constraint::Percentage(20),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(7),
+            Constraint::Length(12),
+            Constraint::Length(12),
+        ])
+        .column_spacing(1);
+
+    f.render_widget(table, chunks[0]);
+
+    // One row of read/write sparklines per filesystem, as many as fit
+    let series: Vec<_> = stats
+        .disks
+        .iter()
+        .map(|d| {
+            (
+                d.mount_point.as_str(),
+                format!(
+                    " {} Read: {} ",
+                    d.mount_point,
+                    app.config.units.rate(d.read_rate)
+                ),
+                format!(
+                    " {} Write: {} ",
+                    d.mount_point,
+                    app.config.units.rate(d.write_rate)
+                ),
+            )
+        })
+        .collect();
+    draw_rate_sparklines(f, &stats.history, &series, chunks[1]);
+}
+
+// A row per GPU, then every process holding GPU memory, largest first
+fn draw_gpu_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    if app.gpus.is_empty() {
+        let message = Paragraph::new(
+            "No GPU found (no nvidia-smi, and no AMD or Intel card under /sys/class/drm)",
+        )
+        .style(Style::default().fg(Colors::TEXT))
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    " GPU ",
+                    Style::default()
+                        .fg(Colors::HEADER)
+                        .add_modifier(Modifier::BOLD),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Colors::BORDER)),
+        );
+        f.render_widget(message, area);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(app.gpus.len() as u16 + 3),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    let level_style = |percent: f64, warning: f64, error: f64| {
+        if percent > error {
+            Style::default().fg(Colors::ERROR)
+        } else if percent > warning {
+            Style::default().fg(Colors::WARNING)
+        } else {
+            Style::default().fg(Colors::TEXT)
+        }
+    };
+    let rows = app.gpus.iter().map(|gpu| {
+        // Integrated GPUs share system memory, so there's no total to hold
+        // what's in use against
+        let (memory, memory_percent) = if gpu.memory_total > 0 {
+            let percent = gpu.memory_used as f64 / gpu.memory_total as f64 * 100.0;
+            (
+                app.config.units.fraction(gpu.memory_used, gpu.memory_total),
+                Cell::from(format!("{:.1}%", percent)).style(level_style(percent, 75.0, 90.0)),
+            )
+        } else {
+            (
+                format!("{} shared", app.config.units.bytes(gpu.memory_used)),
+                Cell::from("-").style(Style::default().fg(Colors::TEXT)),
+            )
+        };
+        let utilization = match gpu.utilization {
+            Some(percent) => Cell::from(format!("{:.0}%", percent)).style(level_style(
+                percent as f64,
+                70.0,
+                90.0,
+            )),
+            None => Cell::from("-").style(Style::default().fg(Colors::TEXT)),
+        };
+        let temperature = match gpu.temperature {
+            Some(celsius) => {
+                Cell::from(format!("{}°C", celsius)).style(level_style(celsius as f64, 75.0, 85.0))
+            }
+            None => Cell::from("-").style(Style::default().fg(Colors::TEXT)),
+        };
+        Row::new(vec![
+            Cell::from(gpu.index.to_string()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(gpu.vendor.to_string()).style(Style::default().fg(Colors::TEXT)),
+            Cell::from(gpu.name.clone()).style(Style::default().fg(Colors::TEXT)),
+            utilization,
+            Cell::from(memory).style(Style::default().fg(Colors::MEMORY)),
+            memory_percent,
+            temperature,
+            Cell::from(
+                gpu.power
+                    .map_or("-".to_string(), |watts| format!("{:.0} W", watts)),
+            )
+            .style(Style::default().fg(Colors::TEXT)),
+            Cell::from(gpu.processes.len().to_string()).style(Style::default().fg(Colors::TEXT)),
+        ])
+    });
+    let table = Table::new(rows)
+        .header(
+            Row::new(vec![
+                Cell::from("#").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Vendor").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Name").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Util").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Memory").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Mem%").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Temp").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Power").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Procs").style(Style::default().fg(Colors::HEADER)),
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    format!(" GPUs ({}) ", app.gpus.len()),
+                    Style::default()
+                        .fg(Colors::HEADER)
+                        .add_modifier(Modifier::BOLD),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Colors::BORDER)),
+        )
+        .widths(&[
+            Constraint::Length(3),
+            Constraint::Length(6),
+            Constraint::Percentage(30),
+            Constraint::Length(6),
+            Constraint::Length(18),
+            Constraint::Length(7),
+            Constraint::Length(6),
+            Constraint::Length(7),
+            Constraint::Length(6),
+        ])
+        .column_spacing(1);
+    f.render_widget(table, chunks[0]);
+
+    // Processes by GPU, named from the process table where psr knows them
+    let mut holders: Vec<(u32, u32, u64)> = app
+        .gpus
+        .iter()
+        .flat_map(|gpu| {
+            gpu.processes
+                .iter()
+                .map(move |&(pid, memory)| (gpu.index, pid, memory))
+        })
+        .collect();
+    holders.sort_by_key(|&(_, _, memory)| std::cmp::Reverse(memory));
+    let rows = holders.iter().map(|&(index, pid, memory)| {
+        let process = app.all_processes.get(&pid);
+        let text = |value: String| Cell::from(value).style(Style::default().fg(Colors::TEXT));
+        Row::new(vec![
+            text(pid.to_string()),
+            text(process.map_or("?".to_string(), |p| p.name.clone())),
+            text(process.map_or("?".to_string(), |p| p.user.clone())),
+            text(index.to_string()),
+            Cell::from(app.config.units.bytes(memory)).style(Style::default().fg(Colors::MEMORY)),
+            text(process.map_or(String::new(), |p| p.cmd.join(" "))),
+        ])
+    });
+    let table = Table::new(rows)
+        .header(
+            Row::new(vec![
+                Cell::from("PID").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Name").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("User").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("GPU").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("GPU Mem").style(Style::default().fg(Colors::HEADER)),
+                Cell::from("Command").style(Style::default().fg(Colors::HEADER)),
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    format!(" GPU Processes ({}) ", holders.len()),
+                    Style::default()
+                        .fg(Colors::HEADER)
+                        .add_modifier(Modifier::BOLD),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Colors::BORDER)),
+        )
+        .widths(&[
+            Constraint::Length(8),
+            Constraint::Percentage(20),
+            Constraint::Length(12),
+            Constraint::Length(4),
+            Constraint::Length(11),
+            Constraint::Percentage(50),
+        ])
+        .column_spacing(1);
+    f.render_widget(table, chunks[1]);
+}
+
+// Draw side-by-side in/out sparklines for each (history key, in title, out title)
+fn draw_rate_sparklines<B: Backend>(
+    f: &mut Frame<B>,
+    history: &RateHistory,
+    series: &[(&str, String, String)],
+    area: Rect,
+) {
+    let visible = (area.height / 3) as usize;
+    let series: Vec<_> = series.iter().take(visible).collect();
+    if series.is_empty() {
+        return;
+    }
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            series
+                .iter()
+                .map(|_| Constraint::Length(3))
+                .chain(std::iter::once(Constraint::Min(0)))
+                .collect::<Vec<_>>(),
+        )
+        .split(area);
+
+    // Sparklines take a slice, so the ring buffers are copied out for the few on screen
+    let data = |history: &HashMap<String, VecDeque<u64>>, key: &str| -> Vec<u64> {
+        history
+            .get(key)
+            .map_or_else(Vec::new, |h| h.iter().copied().collect())
+    };
+    for ((key, in_title, out_title), row) in series.iter().zip(rows.iter()) {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(*row);
+        let (inbound, outbound) = (data(&history.inbound, key), data(&history.outbound, key));
+
+        let in_sparkline = Sparkline::default()
+            .block(
+                Block::default()
+                    .title(Span::styled(
+                        in_title.as_str(),
+                        Style::default().fg(Colors::HEADER),
+                    ))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Colors::BORDER)),
+            )
+            .data(&inbound)
+            .style(Style::default().fg(Colors::CPU));
+
+        let out_sparkline = Sparkline::default()
+            .block(
+                Block::default()
+                    .title(Span::styled(
+                        out_title.as_str(),
+                        Style::default().fg(Colors::HEADER),
+                    ))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Colors::BORDER)),
+            )
+            .data(&outbound)
+            .style(Style::default().fg(Colors::MEMORY));
+
+        f.render_widget(in_sparkline, halves[0]);
+        f.render_widget(out_sparkline, halves[1]);
+    }
+}
 
-    // For the sections, ensure consistent spacing
-    let kb_text = "KEYBOARD SHORTCUTS";
-    let kb_padding_left = (popup_width as usize - kb_text.len() - 2) / 2;
-    let kb_padding_right = popup_width as usize - 2 - kb_padding_left - kb_text.len();
+// The keymap's help, scrollable and narrowed down with `/`, so it lists the
+// bindings actually in effect and fits any terminal
+fn draw_help_popup<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    let Some(help) = &mut app.help else {
+        return;
+    };
+    let border = Style::default().fg(Color::Rgb(88, 91, 112));
+    let text = Style::default().fg(Color::Rgb(248, 248, 242));
+    let accent = Style::default()
+        .fg(Color::Rgb(255, 121, 198))
+        .add_modifier(Modifier::BOLD);
 
-    // Create the help text with improved styling and consistent alignment
-    let help_text = vec![
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::styled(" ".repeat(kb_padding_left), Style::default()),
-            Span::styled(
-                kb_text,
-                Style::default()
-                    .fg(Color::Rgb(241, 250, 140))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(" ".repeat(kb_padding_right), Style::default()),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw(" "),
-            Span::styled(
-                "─".repeat(popup_width as usize - 4),
-                Style::default().fg(Color::Rgb(108, 111, 132)),
-            ),
-            Span::raw(" "),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        // Navigation section - ensure consistent column alignment
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw(" "),
-            Span::styled(
-                "NAVIGATION:",
-                Style::default()
-                    .fg(Color::Rgb(255, 121, 198))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 14)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "↑/↓        - Navigate through the list of processes",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 55)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "←/→, Tab   - Switch to the next tab",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 42)),
-            Span::styled("   │", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "Shift+Tab  - Switch to the previous tab",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            // Span::raw("  - Switch to the previous tab"),
-            Span::raw(" ".repeat(popup_width as usize - 44)),
-            Span::styled(" │", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        // Add a space between sections with a subtle separator
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw(" "),
-            Span::styled(
-                "┄".repeat(popup_width as usize - 4),
-                Style::default().fg(Color::Rgb(68, 71, 90)),
-            ),
-            Span::raw(" "),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        // Sorting section - maintain consistent column alignment
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw(" "),
-            Span::styled(
-                "SORTING:",
-                Style::default()
-                    .fg(Color::Rgb(255, 121, 198))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 11)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "Space      - Toggle between ascending and descending sort",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 61)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "Ctrl+1     - Sort processes by Process ID (PID)",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 51)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "Ctrl+2     - Sort processes by Name alphabetically",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 54)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "Ctrl+3     - Sort processes by CPU usage percentage",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 55)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "Ctrl+4     - Sort processes by Memory consumption",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 53)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        // Separator
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw(" "),
-            Span::styled(
-                "┄".repeat(popup_width as usize - 4),
-                Style::default().fg(Color::Rgb(68, 71, 90)),
-            ),
-            Span::raw(" "),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        // Process actions section - keep aligned with previous sections
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw(" "),
-            Span::styled(
-                "PROCESS ACTIONS:",
-                Style::default()
-                    .fg(Color::Rgb(255, 121, 198))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 19)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "Ctrl+r     - Force refresh all process information",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 54)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "Ctrl+k     - Terminate (kill) the currently selected process",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 64)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "Esc        - Clear filter or close this help screen",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 55)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "Ctrl+q     - Exit the application",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 37)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        // Separator
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw(" "),
-            Span::styled(
-                "┄".repeat(popup_width as usize - 4),
-                Style::default().fg(Color::Rgb(68, 71, 90)),
-            ),
-            Span::raw(" "),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        // Filtering section - maintain column alignment
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw(" "),
-            Span::styled(
-                "FILTERING:",
-                Style::default()
-                    .fg(Color::Rgb(255, 121, 198))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 13)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "Any char   - Type characters to filter processes by name",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 60)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw("  "),
-            Span::styled(
-                "Backspace  - Delete the last character from the filter",
-                Style::default()
-                    .fg(Color::Rgb(189, 147, 249))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" ".repeat(popup_width as usize - 58)),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        // Bottom separator
-        Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw(" "),
-            Span::styled(
-                "┄".repeat(popup_width as usize - 4),
-                Style::default().fg(Color::Rgb(68, 71, 90)),
-            ),
-            Span::raw(" "),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        // Footer with close instruction - centered properly
+    let width = 72.min(area.width);
+    let rule = "┄".repeat(width.saturating_sub(4) as usize);
+    let mut lines = Vec::new();
+    for line in app.keymap.help(&help.search) {
+        match line {
+            HelpLine::Heading(heading) => {
+                if !lines.is_empty() {
+                    lines.push(Spans::from(Span::styled(
+                        format!(" {}", rule),
+                        Style::default().fg(Color::Rgb(68, 71, 90)),
+                    )));
+                }
+                lines.push(Spans::from(Span::styled(format!(" {}:", heading), accent)));
+            }
+            HelpLine::Entry(keys, description) => {
+                let keys = if keys.is_empty() {
+                    " ".repeat(13)
+                } else {
+                    format!("{:<10} - ", keys)
+                };
+                lines.push(Spans::from(vec![
+                    Span::raw(" "),
+                    Span::styled(
+                        keys,
+                        Style::default()
+                            .fg(Color::Rgb(189, 147, 249))
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(description, text),
+                ]));
+            }
+        }
+    }
+    if lines.is_empty() {
+        lines.push(Spans::from(Span::styled(" No matching keys", text)));
+    }
+
+    // As tall as the content allows, keeping the borders and footer on screen
+    let height = (lines.len() as u16 + 3).min(area.height);
+    let popup_area = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let total = lines.len();
+    let page = height.saturating_sub(3) as usize;
+    help.page = page;
+    help.scroll = help.scroll.min(total.saturating_sub(page));
+
+    let title = Spans::from(vec![
+        Span::raw(" "),
+        Span::styled(
+            "P",
+            Style::default()
+                .fg(Color::Rgb(255, 85, 85))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            "S",
+            Style::default()
+                .fg(Color::Rgb(255, 121, 198))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            "R",
+            Style::default()
+                .fg(Color::Rgb(189, 147, 249))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(" - ", text),
+        Span::styled(
+            "Keyboard Shortcuts ",
+            Style::default()
+                .fg(Color::Rgb(139, 233, 253))
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]);
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(border)
+        .style(Style::default().bg(Color::Rgb(40, 42, 54)));
+    let inner = block.inner(popup_area);
+    f.render_widget(Clear, popup_area);
+    f.render_widget(block, popup_area);
+
+    let body = Rect::new(
+        inner.x,
+        inner.y,
+        inner.width,
+        inner.height.saturating_sub(1),
+    );
+    f.render_widget(Paragraph::new(lines).scroll((help.scroll as u16, 0)), body);
+
+    // The search being typed, or where the view is and how to move it
+    let footer = if help.searching || !help.search.is_empty() {
         Spans::from(vec![
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-            Span::raw(" "),
-            Span::styled(
-                " ".repeat((popup_width as usize - 40) / 2),
-                Style::default(),
-            ),
-            Span::styled("Press ", Style::default().fg(Color::Rgb(248, 248, 242))),
-            Span::styled(
-                "Esc",
-                Style::default()
-                    .fg(Color::Rgb(255, 121, 198))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(" or ", Style::default().fg(Color::Rgb(248, 248, 242))),
+            Span::styled(" /", accent),
             Span::styled(
-                "Ctrl+h",
-                Style::default()
-                    .fg(Color::Rgb(255, 121, 198))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                " to close this help",
-                Style::default().fg(Color::Rgb(248, 248, 242)),
+                format!("{}{}", help.search, if help.searching { "_" } else { "" }),
+                text,
             ),
-            Span::styled(
-                " ".repeat((popup_width as usize - 44) / 2),
-                Style::default(),
-            ),
-            Span::raw(" "),
-            Span::styled("│", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-        // Bottom border
+        ])
+    } else {
+        let last = (help.scroll + page).min(total);
         Spans::from(vec![
-            Span::styled("╰", Style::default().fg(Color::Rgb(88, 91, 112))),
+            Span::styled(" ↑/↓ PgUp/PgDn", accent),
+            Span::styled(" scroll  ", text),
+            Span::styled("/", accent),
+            Span::styled(" search  ", text),
+            Span::styled("Esc", accent),
+            Span::styled(" close  ", text),
             Span::styled(
-                "─".repeat(popup_width as usize - 2),
-                Style::default().fg(Color::Rgb(108, 111, 132)),
+                format!("{}-{} of {}", help.scroll + 1, last, total),
+                Style::default().fg(Color::DarkGray),
             ),
-            Span::styled("╯", Style::default().fg(Color::Rgb(88, 91, 112))),
-        ]),
-    ];
-
-    // Combine header and content with properly aligned rows
-    let all_content = [header, help_text].concat();
-
-    // Create the help panel with visible styling
-    let help_paragraph = Paragraph::new(all_content)
-        .alignment(ratatui::layout::Alignment::Left)
-        .style(Style::default().bg(Color::Rgb(40, 42, 54))); // Dark background for the help panel
-
-    // Render the help panel
-    f.render_widget(help_paragraph, popup_area);
+        ])
+    };
+    f.render_widget(
+        Paragraph::new(footer),
+        Rect::new(inner.x, inner.y + body.height, inner.width, 1),
+    );
 }
 
-fn format_duration(duration: Duration) -> String {
+pub fn format_duration(duration: Duration) -> String {
     let total_secs = duration.as_secs();
 
     if total_secs < 60 {
@@ -1323,3 +4372,184 @@ fn format_duration(duration: Duration) -> String {
         format!("{}m {}s", minutes, seconds)
     }
 }
+
+// Machine uptime in days, hours and minutes, e.g. "3d 4h 12m"
+pub fn format_uptime(secs: u64) -> String {
+    let days = secs / 86_400;
+    let hours = (secs % 86_400) / 3600;
+    let minutes = (secs % 3600) / 60;
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else {
+        format!("{}h {}m", hours, minutes)
+    }
+}
+
+// When a process that has run for `run_time` started, in local time, e.g.
+// "Mar 3 14:02"
+pub fn format_started_at(started_at: u64) -> String {
+    match Local.timestamp_opt(started_at as i64, 0).single() {
+        Some(started) if started_at > 0 => started.format("%b %-d %H:%M").to_string(),
+        _ => String::new(),
+    }
+}
+
+// One row per counter: its rate over the last second, the total since the
+// process started, and a sparkline of the rate over the last minute
+fn draw_activity<B: Backend>(f: &mut Frame<B>, app: &App, totals: &Activity, area: Rect) {
+    let block = Block::default()
+        .title(Span::styled(
+            " Context Switches & Page Faults ",
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let counters = [
+        ("Voluntary", Colors::CPU),
+        ("Involuntary", Colors::WARNING),
+        ("Minor faults", Colors::MEMORY),
+        ("Major faults", Colors::ERROR),
+    ];
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1); 4])
+        .split(inner);
+
+    for (index, ((label, color), row)) in counters.into_iter().zip(rows.iter()).enumerate() {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(37), Constraint::Min(0)])
+            .split(*row);
+        let current = app
+            .activity_rates
+            .back()
+            .map_or(0, |rates| rates.counts()[index]);
+        let text = Paragraph::new(Spans::from(vec![
+            Span::styled(
+                format!("{:<13}", label),
+                Style::default().fg(Colors::HEADER),
+            ),
+            Span::styled(
+                format!("{:>8}/s {:>12} ", current, totals.counts()[index]),
+                Style::default().fg(Colors::TEXT),
+            ),
+        ]));
+        f.render_widget(text, columns[0]);
+
+        let data: Vec<u64> = app
+            .activity_rates
+            .iter()
+            .map(|rates| rates.counts()[index])
+            .collect();
+        let sparkline = Sparkline::default()
+            .data(&data)
+            .style(Style::default().fg(color));
+        f.render_widget(sparkline, columns[1]);
+    }
+}
+
+// Realtime processes stand out, since they preempt everything else
+fn scheduling_style(p: &ProcessInfo, normal: Style) -> Style {
+    if p.sched_policy.is_some_and(|policy| policy.is_realtime()) {
+        Style::default().fg(Colors::WARNING)
+    } else {
+        normal
+    }
+}
+
+fn format_power(watts: f64) -> String {
+    if watts >= 1.0 {
+        format!("{:.1} W", watts)
+    } else {
+        format!("{:.0} mW", watts * 1000.0)
+    }
+}
+
+fn draw_ports_tab<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let ports = app.visible_ports();
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " Listening Ports ({}) - Enter to inspect the owner ",
+                ports.len()
+            ),
+            Style::default()
+                .fg(Colors::HEADER)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Colors::BORDER));
+
+    if let Some(error) = &app.ports_error {
+        let message = Paragraph::new(format!("Ports unavailable: {}", error))
+            .style(Style::default().fg(Colors::ERROR))
+            .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let header = Row::new(
+        ["Proto", "Address", "Port", "PID", "Process", "User"]
+            .iter()
+            .map(|title| {
+                Cell::from(*title).style(
+                    Style::default()
+                        .fg(Colors::HEADER)
+                        .add_modifier(Modifier::BOLD),
+                )
+            }),
+    );
+
+    // Keep the selection in view; the border and header take three rows
+    let visible = area.height.saturating_sub(3).max(1) as usize;
+    let offset = app.port_selected.saturating_sub(visible - 1);
+    let rows = ports
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(visible)
+        .map(|(index, port)| {
+            // Sockets shared after a fork belong to several processes; the
+            // first one stands in for the rest
+            let pid = match port.pids.as_slice() {
+                [] => "-".to_string(),
+                [pid] => pid.to_string(),
+                [pid, rest @ ..] => format!("{} +{}", pid, rest.len()),
+            };
+            let owner = port.pids.first().and_then(|pid| app.all_processes.get(pid));
+            let style = if index == app.port_selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                Cell::from(port.protocol).style(Style::default().fg(Colors::TEXT)),
+                Cell::from(port.address.clone()).style(Style::default().fg(Colors::TEXT)),
+                Cell::from(port.port.to_string()).style(Style::default().fg(Colors::HIGHLIGHT)),
+                Cell::from(pid).style(Style::default().fg(Colors::TEXT)),
+                Cell::from(owner.map_or("-".to_string(), |p| p.name.clone()))
+                    .style(Style::default().fg(Colors::TEXT)),
+                Cell::from(owner.map_or("-".to_string(), |p| p.user.clone()))
+                    .style(Style::default().fg(Colors::TEXT)),
+            ])
+            .style(style)
+        });
+
+    let widths = [
+        Constraint::Length(6),
+        Constraint::Length(40),
+        Constraint::Length(7),
+        Constraint::Length(12),
+        Constraint::Min(16),
+        Constraint::Length(12),
+    ];
+    let table = Table::new(rows).header(header).block(block).widths(&widths);
+    f.render_widget(table, area);
+}
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+// How byte counts are shown across tables, chart titles and details, set by
+// the `[units]` table in the config:
+//
+//   [units]
+//   system = "si"    # "binary" (KiB, MiB, ...) or "si" (kB, MB, ...)
+//   precision = 2    # decimal places once a value is scaled past bytes
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnitSystem {
+    // Powers of 1024
+    #[default]
+    Binary,
+    // Powers of 1000
+    Si,
+}
+
+impl UnitSystem {
+    fn base(self) -> f64 {
+        match self {
+            UnitSystem::Binary => 1024.0,
+            UnitSystem::Si => 1000.0,
+        }
+    }
+
+    fn suffixes(self) -> [&'static str; 6] {
+        match self {
+            UnitSystem::Binary => ["B", "KiB", "MiB", "GiB", "TiB", "PiB"],
+            UnitSystem::Si => ["B", "kB", "MB", "GB", "TB", "PB"],
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Units {
+    pub system: UnitSystem,
+    pub precision: usize,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Self {
+            system: UnitSystem::Binary,
+            precision: 1,
+        }
+    }
+}
+
+impl Units {
+    // Scales to the largest unit that keeps the value at or above 1, e.g.
+    // "512 B", "1.5 MiB" or "3.2 GB"
+    pub fn bytes(&self, bytes: u64) -> String {
+        let base = self.system.base();
+        let suffixes = self.system.suffixes();
+        let mut value = bytes as f64;
+        let mut unit = 0;
+        while value >= base && unit < suffixes.len() - 1 {
+            value /= base;
+            unit += 1;
+        }
+
+        if unit == 0 {
+            format!("{} {}", bytes, suffixes[0])
+        } else {
+            format!("{:.*} {}", self.precision, value, suffixes[unit])
+        }
+    }
+
+    pub fn rate(&self, bytes_per_sec: u64) -> String {
+        format!("{}/s", self.bytes(bytes_per_sec))
+    }
+
+    // Used and total memory as "3.2/15.5 GiB", both in the total's unit so
+    // the two read against each other
+    pub fn fraction(&self, used: u64, total: u64) -> String {
+        let base = self.system.base();
+        let suffixes = self.system.suffixes();
+        let mut scale = 1.0;
+        let mut unit = 0;
+        while total as f64 / scale >= base && unit < suffixes.len() - 1 {
+            scale *= base;
+            unit += 1;
+        }
+
+        let precision = if unit == 0 { 0 } else { self.precision };
+        format!(
+            "{:.*}/{:.*} {}",
+            precision,
+            used as f64 / scale,
+            precision,
+            total as f64 / scale,
+            suffixes[unit]
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_scale_to_the_largest_whole_unit() {
+        let binary = Units::default();
+        assert_eq!(binary.bytes(0), "0 B");
+        assert_eq!(binary.bytes(1023), "1023 B");
+        assert_eq!(binary.bytes(1024), "1.0 KiB");
+        assert_eq!(binary.bytes(3 << 19), "1.5 MiB");
+        assert_eq!(binary.bytes(u64::MAX), "16384.0 PiB");
+        assert_eq!(binary.rate(2048), "2.0 KiB/s");
+
+        let si = Units {
+            system: UnitSystem::Si,
+            precision: 2,
+        };
+        assert_eq!(si.bytes(999), "999 B");
+        assert_eq!(si.bytes(1000), "1.00 kB");
+        assert_eq!(si.bytes(3_210_000_000), "3.21 GB");
+    }
+
+    #[test]
+    fn fractions_share_the_totals_unit() {
+        let units = Units::default();
+        assert_eq!(units.fraction(512 << 20, 16 << 30), "0.5/16.0 GiB");
+        assert_eq!(units.fraction(100, 1000), "100/1000 B");
+    }
+
+    #[test]
+    fn units_read_from_the_config() {
+        let units: Units = toml::from_str("system = \"si\"\nprecision = 0").unwrap();
+        assert_eq!(units.system, UnitSystem::Si);
+        assert_eq!(units.bytes(1500), "2 kB");
+
+        let units: Units = toml::from_str("precision = 3").unwrap();
+        assert_eq!(units.system, UnitSystem::Binary);
+        assert!(toml::from_str::<Units>("system = \"decimal\"").is_err());
+    }
+}